@@ -0,0 +1,23 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+struct RadialBlur {
+    strength: f32,
+}
+@group(0) @binding(2) var<uniform> settings: RadialBlur;
+
+const SAMPLES: i32 = 8;
+
+// 画面中心から外へ向かってぼかす (中心ほど弱い)
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let offset = in.uv - vec2<f32>(0.5, 0.5);
+    var color = vec4<f32>(0.0);
+    for (var i = 0; i < SAMPLES; i++) {
+        let t = f32(i) / f32(SAMPLES - 1);
+        color += textureSample(screen_texture, texture_sampler, in.uv - offset * settings.strength * t);
+    }
+    return color / f32(SAMPLES);
+}
@@ -0,0 +1,272 @@
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use serde_json::{json, Value};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::Message;
+
+use crate::core::admin::{
+    handshake_token, is_valid_preset_name, merge_json, origin_allowed, token_matches, AdminCommand, AdminReply,
+};
+use crate::display::Paused;
+use crate::error::{AppError, Health};
+use crate::governor::PhysicsGovernor;
+use crate::packet::ClientRegistry;
+use crate::search::{search, Highlight, Searchable};
+use crate::settings::Settings;
+use crate::snapshot::SceneCommand;
+use crate::stats::SessionStats;
+use crate::HandStates;
+
+// 場面のプリセット (scene.json と同じ形式) を置くディレクトリ
+const PRESETS_DIR: &str = "presets";
+// アプリが止まっていても接続側のスレッドが返事を待ち続けないようにする
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+struct AdminRequest {
+    command: AdminCommand,
+    reply: Sender<AdminReply>,
+}
+
+#[derive(Resource)]
+struct AdminChannel(Mutex<Receiver<AdminRequest>>);
+
+// 既定ではこの機械からしか繋げない。--admin-bind で広げる
+pub const DEFAULT_ADMIN_BIND: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+pub struct AdminPlugin {
+    pub port: Option<u16>,
+    pub bind: IpAddr,
+    // パケットの署名と同じ共有の秘密 (--secret)。あれば接続のときに合言葉として求める
+    pub token: Option<Vec<u8>>,
+    // ブラウザから繋いでよいページの Origin (--admin-origin, カンマ区切り)。
+    // 見ているだけのよそのページが localhost に繋いで場面を消すことを防ぐ
+    pub origins: Vec<String>,
+}
+
+impl Plugin for AdminPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(port) = self.port else {
+            return;
+        };
+        let addr = format!("{}:{port}", self.bind);
+        app.init_resource::<Health>();
+        // 場面を消したり設定を書き換えたりできるので、合言葉なしでほかの機械へは開かない
+        if self.token.is_none() && !self.bind.is_loopback() {
+            let message = format!("refusing to listen on {addr} without --secret");
+            app.world_mut().resource_mut::<Health>().failed("admin", message);
+            return;
+        }
+        let listener = match TcpListener::bind(&addr) {
+            Ok(listener) => listener,
+            Err(source) => {
+                let e = AppError::Bind { addr, source };
+                app.world_mut().resource_mut::<Health>().failed("admin", e.to_string());
+                return;
+            }
+        };
+        // 無人の展示をタブレットから操作する想定
+        match self.token {
+            Some(_) => info!("admin WebSocket listening on ws://{addr} (token required)"),
+            None => warn!(
+                "admin WebSocket listening on ws://{addr} without a token; only this machine and pages in --admin-origin can connect"
+            ),
+        }
+        let (requests, receiver) = mpsc::channel();
+        let check = Arc::new(HandshakeCheck {
+            token: self.token.clone(),
+            origins: self.origins.clone(),
+        });
+        std::thread::spawn(move || serve(listener, requests, check));
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.insert_resource(AdminChannel(Mutex::new(receiver)))
+            .add_systems(Update, handle_admin_requests);
+    }
+}
+
+fn serve(listener: TcpListener, requests: Sender<AdminRequest>, check: Arc<HandshakeCheck>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let requests = requests.clone();
+                let check = check.clone();
+                std::thread::spawn(move || handle_connection(stream, requests, check));
+            }
+            Err(e) => warn!("admin connection failed: {e}"),
+        }
+    }
+}
+
+// 許可していないページからの接続は 403、合言葉が合わない接続は 401 で、WebSocket に切り替える前に断る
+struct HandshakeCheck {
+    token: Option<Vec<u8>>,
+    origins: Vec<String>,
+}
+
+impl HandshakeCheck {
+    fn check(&self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+        if !origin_allowed(header("origin"), &self.origins) {
+            return Err(refuse(StatusCode::FORBIDDEN, "origin not allowed"));
+        }
+        let Some(secret) = &self.token else {
+            return Ok(response);
+        };
+        match handshake_token(request.uri().query(), header("authorization")) {
+            Some(given) if token_matches(secret, &given) => Ok(response),
+            _ => Err(refuse(StatusCode::UNAUTHORIZED, "admin token required")),
+        }
+    }
+}
+
+fn refuse(status: StatusCode, message: &str) -> ErrorResponse {
+    let mut refused = ErrorResponse::new(Some(message.to_string()));
+    *refused.status_mut() = status;
+    refused
+}
+
+fn handle_connection(stream: TcpStream, requests: Sender<AdminRequest>, check: Arc<HandshakeCheck>) {
+    let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+    let mut socket = match tungstenite::accept_hdr(stream, |request: &Request, response: Response| check.check(request, response)) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!(%peer, "admin handshake failed: {e}");
+            return;
+        }
+    };
+    info!(%peer, "admin client connected");
+    while let Ok(message) = socket.read() {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let reply = match serde_json::from_str::<AdminCommand>(&text) {
+            Ok(command) => {
+                let (reply, answer) = mpsc::channel();
+                if requests.send(AdminRequest { command, reply }).is_err() {
+                    break;
+                }
+                answer.recv_timeout(REPLY_TIMEOUT).unwrap_or_else(|_| AdminReply::error("timed out waiting for the app"))
+            }
+            Err(e) => AdminReply::error(format!("bad command: {e}")),
+        };
+        let text = serde_json::to_string(&reply).unwrap_or_default();
+        if socket.send(Message::text(text)).is_err() {
+            break;
+        }
+    }
+    info!(%peer, "admin client disconnected");
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_admin_requests(
+    channel: Res<AdminChannel>,
+    mut paused: ResMut<Paused>,
+    mut settings: ResMut<Settings>,
+    mut scene: EventWriter<SceneCommand>,
+    stats: Res<SessionStats>,
+    registry: Res<ClientRegistry>,
+    hand_states: Res<HandStates>,
+    governor: Res<PhysicsGovernor>,
+    diagnostics: Res<DiagnosticsStore>,
+    (mut highlight, objects, game_time): (ResMut<Highlight>, Query<Searchable, Without<Camera3d>>, Res<Time>),
+    time: Res<Time<Real>>,
+) {
+    let Ok(receiver) = channel.0.lock() else {
+        return;
+    };
+    for request in receiver.try_iter() {
+        debug!(command = ?request.command, "admin command");
+        let reply = match request.command {
+            AdminCommand::Pause { paused: value } => {
+                paused.0 = value.unwrap_or(!paused.0);
+                info!("paused by admin: {}", paused.0);
+                AdminReply::ok(Some(json!({ "paused": paused.0 })))
+            }
+            AdminCommand::Reset => {
+                scene.send(SceneCommand::Clear);
+                AdminReply::ok(None)
+            }
+            AdminCommand::Preset { name } => {
+                let path = PathBuf::from(PRESETS_DIR).join(format!("{name}.json"));
+                if !is_valid_preset_name(&name) {
+                    AdminReply::error(format!("invalid preset name {name:?}"))
+                } else if !path.is_file() {
+                    AdminReply::error(format!("no preset named {name:?}"))
+                } else {
+                    scene.send(SceneCommand::Load(path));
+                    AdminReply::ok(None)
+                }
+            }
+            AdminCommand::ListPresets => AdminReply::ok(Some(json!({ "presets": list_presets() }))),
+            AdminCommand::Settings { patch, save } => match patched_settings(&settings, &patch) {
+                Ok(patched) => {
+                    *settings = patched;
+                    if save {
+                        settings.save();
+                    }
+                    AdminReply::ok(serde_json::to_value(&*settings).ok())
+                }
+                Err(e) => AdminReply::error(e),
+            },
+            AdminCommand::Metrics => {
+                let fps = diagnostics
+                    .get(&FrameTimeDiagnosticsPlugin::FPS)
+                    .and_then(|d| d.smoothed());
+                let summary = stats.summary(time.elapsed_seconds(), registry.sources.len());
+                AdminReply::ok(Some(json!({
+                    "fps": fps,
+                    "paused": paused.0,
+                    "hands": hand_states.hands.len(),
+                    "bodies": governor.body_count,
+                    "load_level": format!("{:?}", governor.level),
+                    "session": summary,
+                })))
+            }
+            AdminCommand::Highlight { query, focus } => {
+                let matches = search(&query, &objects, game_time.elapsed_seconds());
+                info!(count = matches.len(), "highlighting objects");
+                let positions: Vec<[f32; 3]> = matches.iter().map(|(_, p, _)| p.to_array()).collect();
+                highlight.set(query, focus);
+                AdminReply::ok(Some(json!({ "count": matches.len(), "positions": positions })))
+            }
+            AdminCommand::ClearHighlight => {
+                highlight.clear();
+                AdminReply::ok(None)
+            }
+        };
+        let _ = request.reply.send(reply);
+    }
+}
+
+fn patched_settings(settings: &Settings, patch: &Value) -> Result<Settings, String> {
+    if !patch.is_object() {
+        return Err("settings patch must be a JSON object".to_string());
+    }
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    merge_json(&mut value, patch);
+    serde_json::from_value(value).map_err(|e| format!("invalid settings: {e}"))
+}
+
+fn list_presets() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(PRESETS_DIR) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| Some(p.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::arena::ArenaConfig;
+use crate::floor::FLOOR_Y;
+
+// 今の場面の壁と天井。scene.json を読み込むと置き換わる (snapshot.rs)
+#[derive(Resource, Default)]
+pub struct Arena {
+    pub config: ArenaConfig,
+}
+
+#[derive(Component)]
+struct ArenaWall;
+
+pub struct ArenaPlugin;
+
+impl Plugin for ArenaPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Arena::default()).add_systems(Update, rebuild_walls);
+    }
+}
+
+// 設定が変わったら壁を作り直す。跳ね返りは壁の値を優先する
+fn rebuild_walls(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    walls: Query<Entity, With<ArenaWall>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !arena.is_changed() {
+        return;
+    }
+    for entity in walls.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let glass = arena.config.glass.then(|| {
+        materials.add(StandardMaterial {
+            base_color: Color::srgba(0.7, 0.85, 1.0, 0.12),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.05,
+            reflectance: 0.8,
+            ..default()
+        })
+    });
+    for wall in arena.config.boxes(FLOOR_Y) {
+        let transform = Transform::from_translation(wall.center);
+        let mut entity = commands.spawn((
+            TransformBundle::from_transform(transform),
+            RigidBody::Fixed,
+            Collider::cuboid(wall.half_extents.x, wall.half_extents.y, wall.half_extents.z),
+            Restitution { coefficient: wall.restitution, combine_rule: CoefficientCombineRule::Max },
+            ArenaWall,
+        ));
+        if let Some(material) = &glass {
+            entity.insert((
+                meshes.add(Cuboid::from_size(wall.half_extents * 2.0)),
+                material.clone(),
+                VisibilityBundle::default(),
+            ));
+        }
+    }
+    if !arena.config.is_empty() {
+        info!(walls = arena.config.boxes(FLOOR_Y).len(), glass = arena.config.glass, "arena walls placed");
+    }
+}
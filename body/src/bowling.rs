@@ -0,0 +1,274 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::grab::{Grabbable, Held};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::HandStates;
+
+// レーンは手前 (z = 14) から奥へ伸ばす
+const LANE_HALF_WIDTH: f32 = 2.0;
+const LANE_NEAR_Z: f32 = 14.0;
+const LANE_FAR_Z: f32 = -12.0;
+const LANE_TOP: f32 = -4.9;
+const BALL_RADIUS: f32 = 0.8;
+const BALL_HOME: Vec3 = Vec3::new(0.0, LANE_TOP + BALL_RADIUS, 11.0);
+const PIN_RADIUS: f32 = 0.22;
+const PIN_HALF_HEIGHT: f32 = 0.75;
+const HEAD_PIN_Z: f32 = -6.0;
+const PIN_SPACING: f32 = 0.9;
+// これ以上傾いたピンは倒れたとみなす
+const FALLEN_TILT: f32 = 0.5;
+// 投球は手前のこの位置を越えたら始まり、止まるか奥まで行ったら終わる
+const ROLL_START_Z: f32 = BALL_HOME.z - 2.0;
+const ROLL_SETTLE_TIME: f32 = 1.5;
+const MAX_ROLL_TIME: f32 = 8.0;
+// 両手を開いて同じ向きに素早く払うとピンを並べ直す
+const SWEEP_SPEED: f32 = 12.0;
+const SWEEP_COOLDOWN: f32 = 1.5;
+
+#[derive(Component)]
+struct BowlingProp;
+
+#[derive(Component)]
+struct Pin;
+
+#[derive(Component)]
+struct BowlingBall;
+
+#[derive(Resource, Default)]
+pub struct Bowling {
+    pub active: bool,
+    pub total: u32,
+    pub rolls: u32,
+    last_roll: Option<u32>,
+    // 前回の投球までに倒れていたピンの数
+    down_before: u32,
+    rolling_since: Option<f32>,
+    settled_since: Option<f32>,
+    last_sweep: f32,
+}
+
+pub struct BowlingPlugin;
+
+impl Plugin for BowlingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Bowling::default())
+            .add_systems(
+                Update,
+                (toggle_bowling, track_roll, sweep_to_reset.after(PalmPoseSet).in_set(InteractionSet)).chain(),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, bowling_ui);
+    }
+}
+
+fn pin_positions() -> impl Iterator<Item = Vec3> {
+    (0..4).flat_map(|row| {
+        (0..=row).map(move |i| {
+            let x = (i as f32 - row as f32 / 2.0) * PIN_SPACING;
+            Vec3::new(x, LANE_TOP + PIN_HALF_HEIGHT, HEAD_PIN_Z - row as f32 * PIN_SPACING)
+        })
+    })
+}
+
+fn spawn_pins(commands: &mut Commands, meshes: &mut Assets<Mesh>, materials: &mut Assets<StandardMaterial>) {
+    let mesh = meshes.add(Cylinder::new(PIN_RADIUS, PIN_HALF_HEIGHT * 2.0));
+    let material = materials.add(Color::srgb(0.95, 0.95, 0.92));
+    // ピンどうしはつながず、それぞれ独立した剛体として倒れる
+    for position in pin_positions() {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(position),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            Collider::cylinder(PIN_HALF_HEIGHT, PIN_RADIUS),
+            ColliderMassProperties::Density(0.8),
+            Restitution::coefficient(0.4),
+            Velocity::default(),
+            Pin,
+            BowlingProp,
+        ));
+    }
+}
+
+fn is_fallen(transform: &Transform) -> bool {
+    transform.up().dot(Vec3::Y) < 1.0 - FALLEN_TILT || transform.translation.y < LANE_TOP - 1.0
+}
+
+fn toggle_bowling(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut bowling: ResMut<Bowling>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<Entity, With<BowlingProp>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyL) {
+        return;
+    }
+    let active = !bowling.active;
+    *bowling = Bowling { active, ..default() };
+    if !active {
+        for entity in props.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let lane_length = LANE_NEAR_Z - LANE_FAR_Z;
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(LANE_HALF_WIDTH * 2.0, 0.1, lane_length)),
+            material: materials.add(Color::srgb(0.8, 0.62, 0.38)),
+            transform: Transform::from_xyz(0.0, LANE_TOP - 0.05, (LANE_NEAR_Z + LANE_FAR_Z) / 2.0),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid(LANE_HALF_WIDTH, 0.05, lane_length / 2.0),
+        Friction::coefficient(0.1),
+        BowlingProp,
+    ));
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Sphere::new(BALL_RADIUS)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.15, 0.2, 0.55),
+                perceptual_roughness: 0.15,
+                ..default()
+            }),
+            transform: Transform::from_translation(BALL_HOME),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Density(3.0),
+        Friction::coefficient(0.2),
+        ExternalForce::default(),
+        Velocity::default(),
+        Grabbable { radius: BALL_RADIUS },
+        BowlingBall,
+        BowlingProp,
+    ));
+    spawn_pins(&mut commands, &mut meshes, &mut materials);
+    info!("bowling lane ready");
+}
+
+// 手を離れた球がレーンを進んだら投球とみなし、止まったら倒れたピンを数えて球を戻す
+fn track_roll(
+    mut bowling: ResMut<Bowling>,
+    mut ball: Query<(&mut Transform, &mut Velocity, Has<Held>), With<BowlingBall>>,
+    pins: Query<&Transform, (With<Pin>, Without<BowlingBall>)>,
+    time: Res<Time>,
+) {
+    if !bowling.active {
+        return;
+    }
+    let Ok((mut transform, mut velocity, held)) = ball.get_single_mut() else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    let Some(since) = bowling.rolling_since else {
+        if !held && transform.translation.z < ROLL_START_Z {
+            bowling.rolling_since = Some(now);
+        }
+        return;
+    };
+
+    let stopped = velocity.linvel.length() < 0.5 && pins.iter().all(|p| !is_fallen(p) || p.translation.y < LANE_TOP + 0.5);
+    bowling.settled_since = if stopped { Some(bowling.settled_since.unwrap_or(now)) } else { None };
+    let finished = transform.translation.z < HEAD_PIN_Z - 6.0
+        || transform.translation.y < LANE_TOP - 3.0
+        || bowling.settled_since.is_some_and(|t| now - t > ROLL_SETTLE_TIME)
+        || now - since > MAX_ROLL_TIME;
+    if !finished {
+        return;
+    }
+
+    let down = pins.iter().filter(|p| is_fallen(p)).count() as u32;
+    let knocked = down.saturating_sub(bowling.down_before);
+    bowling.down_before = down;
+    bowling.total += knocked;
+    bowling.rolls += 1;
+    bowling.last_roll = Some(knocked);
+    bowling.rolling_since = None;
+    bowling.settled_since = None;
+    info!(knocked, total = bowling.total, "bowling roll");
+
+    *transform = Transform::from_translation(BALL_HOME);
+    velocity.linvel = Vec3::ZERO;
+    velocity.angvel = Vec3::ZERO;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn sweep_to_reset(
+    mut commands: Commands,
+    mut bowling: ResMut<Bowling>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    pins: Query<Entity, With<Pin>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    if !bowling.active || now - bowling.last_sweep < SWEEP_COOLDOWN {
+        return;
+    }
+    let sweeping: Vec<f32> = hand_states
+        .hands
+        .iter()
+        .filter(|(_, hand)| hand.gesture == Gesture::Open)
+        .filter_map(|(key, _)| palms.poses.get(key).map(|p| p.linear_velocity.x))
+        .filter(|vx| vx.abs() > SWEEP_SPEED)
+        .collect();
+    let [a, b] = sweeping[..] else {
+        return;
+    };
+    if a.signum() != b.signum() {
+        return;
+    }
+
+    for entity in pins.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_pins(&mut commands, &mut meshes, &mut materials);
+    bowling.down_before = 0;
+    bowling.last_sweep = now;
+    info!("pins reset");
+}
+
+#[cfg(feature = "ui")]
+fn bowling_ui(mut contexts: EguiContexts, bowling: Res<Bowling>) {
+    if !bowling.active {
+        return;
+    }
+    egui::Window::new("Bowling")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(format!("Score {}  ({} rolls)", bowling.total, bowling.rolls));
+            match bowling.last_roll {
+                Some(10) if bowling.down_before == 10 => {
+                    ui.label(egui::RichText::new("Strike!").size(28.0));
+                }
+                Some(knocked) => {
+                    ui.label(format!("Last roll: {knocked} pins"));
+                }
+                None => {
+                    ui.label("Grab the ball with a fist and roll it down the lane");
+                }
+            }
+            if bowling.rolling_since.is_some() {
+                ui.label("Rolling...");
+            }
+            ui.small("Sweep both open hands sideways to reset pins / L: quit");
+        });
+}
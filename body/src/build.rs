@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::confirm::Confirmations;
+use crate::core::gesture::Gesture;
+use crate::core::grid::{footprint, free_origin, span};
+use crate::display::InteractionSet;
+use crate::floor::FLOOR_Y;
+use crate::grab::Held;
+use crate::lifecycle::vanish;
+use crate::settings::Settings;
+use crate::spawn::SpawnedBox;
+use crate::{update_hands_and_physics, HandStates};
+
+const ERASE_REACH: f32 = 0.5;
+// 空いているセルを上へ探す最大段数
+const MAX_STACK: i32 = 32;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BuildSettings {
+    // 格子の一辺 (ワールド単位)
+    pub cell: f32,
+}
+
+impl Default for BuildSettings {
+    fn default() -> Self {
+        Self { cell: 2.0 }
+    }
+}
+
+impl BuildSettings {
+    // 格子の原点は床の上に一段目の中心が来る高さに置く
+    fn origin(&self) -> Vec3 {
+        Vec3::new(0.0, FLOOR_Y + self.cell / 2.0, 0.0)
+    }
+
+    fn cell_of(&self, position: Vec3) -> IVec3 {
+        ((position - self.origin()) / self.cell).round().as_ivec3()
+    }
+
+    fn center_of(&self, cell: IVec3) -> Vec3 {
+        self.origin() + cell.as_vec3() * self.cell
+    }
+
+    // 各軸 span セルを占める箱の中心が position に来るときの、一番下の隅のセル
+    fn corner_of(&self, position: Vec3, span: i32) -> IVec3 {
+        self.cell_of(position - Vec3::splat((span - 1) as f32 * self.cell / 2.0))
+    }
+
+    fn block_center(&self, corner: IVec3, span: i32) -> Vec3 {
+        self.center_of(corner) + Vec3::splat((span - 1) as f32 * self.cell / 2.0)
+    }
+}
+
+// 格子に置かれて固定された積み木。格子より大きい箱は各軸 span セルを占める
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Placed {
+    pub corner: IVec3,
+    pub span: i32,
+}
+
+impl Placed {
+    pub fn cells(&self) -> impl Iterator<Item = IVec3> {
+        footprint(self.corner, self.span)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct BuildMode {
+    pub active: bool,
+}
+
+pub struct BuildPlugin;
+
+impl Plugin for BuildPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BuildMode::default()).add_systems(
+            Update,
+            (toggle_build, (unpin_grabbed, preview_and_place, erase_blocks).chain().in_set(InteractionSet))
+                .chain()
+                .after(update_hands_and_physics),
+        );
+    }
+}
+
+fn toggle_build(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut build: ResMut<BuildMode>,
+    mut placed: Query<(Entity, &mut RigidBody), With<Placed>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyM) {
+        return;
+    }
+    build.active = !build.active;
+    info!("build mode: {}", build.active);
+    // 抜けるときは置いた積み木を普通の物体に戻す
+    if !build.active {
+        for (entity, mut body) in placed.iter_mut() {
+            *body = RigidBody::Dynamic;
+            commands.entity(entity).remove::<Placed>();
+        }
+    }
+}
+
+// 置いた積み木をもう一度掴んだら固定を外す
+#[allow(clippy::type_complexity)]
+fn unpin_grabbed(mut commands: Commands, mut grabbed: Query<(Entity, &mut RigidBody), (With<Placed>, Added<Held>)>) {
+    for (entity, mut body) in grabbed.iter_mut() {
+        *body = RigidBody::Dynamic;
+        commands.entity(entity).remove::<Placed>();
+    }
+}
+
+// 置ける一番近い場所。占めるセルのどれかが埋まっていれば上へ積む
+fn free_cell(settings: &BuildSettings, position: Vec3, size: f32, occupied: &HashSet<IVec3>) -> Placed {
+    let span = span(size, settings.cell);
+    Placed { corner: free_origin(settings.corner_of(position, span), span, occupied, MAX_STACK), span }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn preview_and_place(
+    mut commands: Commands,
+    build: Res<BuildMode>,
+    settings: Res<Settings>,
+    mut released: RemovedComponents<Held>,
+    held: Query<(&Transform, &SpawnedBox), With<Held>>,
+    mut boxes: Query<(&mut Transform, &mut Velocity, &mut RigidBody, &SpawnedBox), (Without<Held>, Without<Placed>)>,
+    placed: Query<&Placed>,
+    mut gizmos: Gizmos,
+) {
+    if !build.active {
+        released.clear();
+        return;
+    }
+    let grid = &settings.build;
+    let occupied: HashSet<IVec3> = placed.iter().flat_map(Placed::cells).collect();
+
+    // 手に持っている間は置かれる位置を半透明の枠で見せる
+    for (transform, spawned) in held.iter() {
+        let cell = free_cell(grid, transform.translation, spawned.size, &occupied);
+        let preview =
+            Transform::from_translation(grid.block_center(cell.corner, cell.span)).with_scale(Vec3::splat(spawned.size));
+        gizmos.cuboid(preview, Color::srgba(0.4, 0.9, 1.0, 0.6));
+    }
+
+    let mut occupied = occupied;
+    for entity in released.read() {
+        let Ok((mut transform, mut velocity, mut body, spawned)) = boxes.get_mut(entity) else {
+            continue;
+        };
+        let cell = free_cell(grid, transform.translation, spawned.size, &occupied);
+        occupied.extend(cell.cells());
+        *transform = Transform::from_translation(grid.block_center(cell.corner, cell.span));
+        *velocity = Velocity::zero();
+        *body = RigidBody::Fixed;
+        commands.entity(entity).insert(cell);
+    }
+}
+
+// 鷲づかみの手で積み木に触れたまま確認の時間だけ保つと消す
+fn erase_blocks(
+    mut commands: Commands,
+    build: Res<BuildMode>,
+    mut confirmations: ResMut<Confirmations>,
+    hand_states: Res<HandStates>,
+    blocks: Query<(Entity, &Transform, &SpawnedBox), With<Placed>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    if !build.active {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    for (key, hand) in &hand_states.hands {
+        if hand.gesture != Gesture::Claw || !hand_states.is_confident(*key) {
+            continue;
+        }
+        let block = blocks.iter().find(|(_, transform, spawned)| {
+            let local = (hand.center - transform.translation).abs();
+            local.max_element() < spawned.size / 2.0 + ERASE_REACH
+        });
+        if let Some((entity, transform, spawned)) = block {
+            gizmos.cuboid(
+                Transform::from_translation(transform.translation).with_scale(Vec3::splat(spawned.size * 1.05)),
+                Color::srgb(1.0, 0.35, 0.1),
+            );
+            if confirmations.hold(("erase_block", entity.to_bits()), transform.translation, now) {
+                vanish(&mut commands, entity);
+                info!("block erased");
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+
+use crate::console::{ConsoleExt, ConsoleResult};
+
+const CAPTURE_DIR: &str = "captures";
+const CAPTURE_FPS: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptureOutput {
+    PngSequence,
+    Ffmpeg,
+}
+
+struct FfmpegPipe {
+    frames: Sender<(UVec2, Vec<u8>)>,
+}
+
+#[derive(Resource, Default)]
+pub struct FrameCapture {
+    output: Option<CaptureOutput>,
+    dir: PathBuf,
+    frame: u32,
+    accumulator: f32,
+    pipe: Option<FfmpegPipe>,
+}
+
+impl FrameCapture {
+    pub fn is_recording(&self) -> bool {
+        self.output.is_some()
+    }
+
+    fn start(&mut self, output: CaptureOutput) {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.dir = PathBuf::from(CAPTURE_DIR);
+        self.frame = 0;
+        self.accumulator = 0.0;
+        match output {
+            CaptureOutput::PngSequence => {
+                self.dir = self.dir.join(format!("frames-{stamp}"));
+            }
+            CaptureOutput::Ffmpeg => {
+                self.pipe = Some(spawn_ffmpeg_writer(self.dir.join(format!("capture-{stamp}.mp4"))));
+            }
+        }
+        if let Err(e) = fs::create_dir_all(&self.dir) {
+            error!("failed to create {}: {e}", self.dir.display());
+            return;
+        }
+        info!("capture started ({output:?})");
+        self.output = Some(output);
+    }
+
+    fn stop(&mut self) {
+        self.output = None;
+        // 送信側が全て閉じると書き込みスレッドが ffmpeg の入力を閉じて終了を待つ
+        // (撮影待ちのコールバックが残っていることがあるのでここでは待たない)
+        self.pipe = None;
+        info!("capture stopped after {} frames", self.frame);
+    }
+}
+
+// 最初のフレームで解像度が分かってから ffmpeg を起動する
+fn spawn_ffmpeg_writer(path: PathBuf) -> FfmpegPipe {
+    let (frames, receiver) = mpsc::channel::<(UVec2, Vec<u8>)>();
+    std::thread::spawn(move || {
+        let mut child: Option<Child> = None;
+        for (size, rgb) in receiver {
+            if child.is_none() {
+                let spawned = Command::new("ffmpeg")
+                    .args(["-y", "-f", "rawvideo", "-pix_fmt", "rgb24", "-s"])
+                    .arg(format!("{}x{}", size.x, size.y))
+                    .args(["-r", &CAPTURE_FPS.to_string(), "-i", "-"])
+                    .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+                    .arg(&path)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn();
+                match spawned {
+                    Ok(c) => child = Some(c),
+                    Err(e) => {
+                        error!("failed to start ffmpeg: {e}");
+                        return;
+                    }
+                }
+            }
+            let Some(stdin) = child.as_mut().and_then(|c| c.stdin.as_mut()) else {
+                return;
+            };
+            if let Err(e) = stdin.write_all(&rgb) {
+                error!("ffmpeg pipe closed: {e}");
+                return;
+            }
+        }
+        if let Some(mut c) = child {
+            drop(c.stdin.take());
+            match c.wait() {
+                Ok(status) if status.success() => info!("video written to {}", path.display()),
+                Ok(status) => error!("ffmpeg exited with {status}"),
+                Err(e) => error!("failed to wait for ffmpeg: {e}"),
+            }
+        }
+    });
+    FfmpegPipe { frames }
+}
+
+// 画面を連番の PNG か ffmpeg の動画に書き出す (F7、Shift+F7 で動画)
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FrameCapture::default())
+            .add_console_command("record", "record start [png|video] | record stop", record_command)
+            .add_systems(Update, capture_keys)
+            .add_systems(PostUpdate, capture_frames);
+    }
+}
+
+fn capture_keys(keys: Res<ButtonInput<KeyCode>>, mut capture: ResMut<FrameCapture>) {
+    if !keys.just_pressed(KeyCode::F7) {
+        return;
+    }
+    if capture.is_recording() {
+        capture.stop();
+    } else if keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight) {
+        capture.start(CaptureOutput::Ffmpeg);
+    } else {
+        capture.start(CaptureOutput::PngSequence);
+    }
+}
+
+fn record_command(In(args): In<Vec<String>>, mut capture: ResMut<FrameCapture>) -> ConsoleResult {
+    match args.first().map(String::as_str) {
+        Some("start") => {
+            if capture.is_recording() {
+                return Err("already recording".to_string());
+            }
+            let output = match args.get(1).map(String::as_str) {
+                None | Some("png") => CaptureOutput::PngSequence,
+                Some("video") => CaptureOutput::Ffmpeg,
+                Some(other) => return Err(format!("unknown capture output {other:?}")),
+            };
+            capture.start(output);
+            if !capture.is_recording() {
+                return Err(format!("failed to create {}", capture.dir.display()));
+            }
+            Ok(format!("recording to {}", capture.dir.display()))
+        }
+        Some("stop") if capture.is_recording() => {
+            capture.stop();
+            Ok(format!("stopped after {} frames", capture.frame))
+        }
+        Some("stop") => Err("not recording".to_string()),
+        _ => Err("record start [png|video] | record stop".to_string()),
+    }
+}
+
+fn capture_frames(
+    mut capture: ResMut<FrameCapture>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let Some(output) = capture.output else {
+        return;
+    };
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    // 描画のフレームレートに関係なく一定間隔で撮る
+    capture.accumulator += time.delta_seconds();
+    if capture.accumulator < 1.0 / CAPTURE_FPS {
+        return;
+    }
+    capture.accumulator = (capture.accumulator - 1.0 / CAPTURE_FPS).min(1.0 / CAPTURE_FPS);
+
+    let requested = match output {
+        CaptureOutput::PngSequence => {
+            let path = capture.dir.join(format!("frame-{:05}.png", capture.frame));
+            screenshots.save_screenshot_to_disk(window, path)
+        }
+        CaptureOutput::Ffmpeg => {
+            let Some(pipe) = &capture.pipe else {
+                return;
+            };
+            let frames = pipe.frames.clone();
+            screenshots.take_screenshot(window, move |image| {
+                let size = image.size();
+                match image.try_into_dynamic() {
+                    Ok(dynamic) => {
+                        let _ = frames.send((size, dynamic.to_rgb8().into_raw()));
+                    }
+                    Err(e) => error!("unsupported screenshot format: {e:?}"),
+                }
+            })
+        }
+    };
+    if requested.is_ok() {
+        capture.frame += 1;
+    }
+}
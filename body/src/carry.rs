@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::carry::{damping_ratio, TwoHandCarry, HEAVY_RADIUS};
+use crate::core::gesture::Gesture;
+use crate::core::spring::DampedSpring;
+use crate::display::InteractionSet;
+use crate::grab::{is_closed, surface_radius, Grabbable, Held};
+use crate::overlap::{hand_group, HandOverlapSet};
+use crate::profile::ActiveProfile;
+use crate::settings::Settings;
+use crate::spawn::SpawnedBox;
+use crate::tuning::Tuning;
+use crate::versus::{can_touch, Team};
+use crate::{update_hands_and_physics, HandKey, HandStates};
+
+// 両手で運ぶ物体を追わせるばねの固有角振動数 (rad/秒)。片手で持つより重く感じるよう低めにする
+const CARRY_FREQUENCY: f32 = 8.0;
+const ANGULAR_GAIN: f32 = 6.0;
+// 手の間隔が掴んだときからこれだけ変わったら支えきれずに落とす
+const BREAK_STRAIN: f32 = 3.0;
+
+// 重い物体を握った手。もう一方の手が別の面を握るまでは持ち上がらない
+#[derive(Debug, Clone, Copy)]
+struct Grip {
+    entity: Entity,
+    // 物体の座標系での掴み点
+    local: Vec3,
+}
+
+#[derive(Resource, Default)]
+pub struct CarryState {
+    grips: HashMap<HandKey, Grip>,
+    previous: HashMap<HandKey, Gesture>,
+}
+
+// 両手で運んでいる物体
+#[derive(Component)]
+pub struct CoopCarried {
+    pub hands: [HandKey; 2],
+    carry: TwoHandCarry,
+    spring: DampedSpring,
+    rotation: Quat,
+    // 運ぶ前の衝突グループ。両手の関節とだけ当たらないようにし、下ろしたら戻す
+    groups: Option<CollisionGroups>,
+}
+
+pub struct CarryPlugin;
+
+impl Plugin for CarryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CarryState::default()).add_systems(
+            Update,
+            (grip_heavy_objects, carry_heavy_objects)
+                .chain()
+                .after(update_hands_and_physics)
+                .after(HandOverlapSet)
+                .in_set(InteractionSet),
+        );
+    }
+}
+
+fn drop_carry(commands: &mut Commands, entity: Entity, carried: &CoopCarried) {
+    let mut entity = commands.entity(entity);
+    entity.remove::<CoopCarried>();
+    match carried.groups {
+        Some(groups) => entity.insert(groups),
+        None => entity.remove::<CollisionGroups>(),
+    };
+}
+
+// 重い物体の近くで手を握ると掴み点を覚え、二つの手が同じ物体の別の面を握ったら運び始める
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn grip_heavy_objects(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    settings: Res<Settings>,
+    mut state: ResMut<CarryState>,
+    objects: Query<
+        (Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, Option<&CollisionGroups>, Option<&CoopCarried>),
+        (With<RigidBody>, Without<Held>),
+    >,
+    teams: Query<&Team>,
+    mut gizmos: Gizmos,
+) {
+    let assist = &settings.grab_assist;
+    let state = &mut *state;
+    // 手を開いた、消えた、物体がなくなった掴みを忘れる。手が重なっている間は保つ
+    state.grips.retain(|key, grip| {
+        objects.contains(grip.entity)
+            && match hand_states.hands.get(key) {
+                Some(hand) => !hand_states.is_confident(*key) || is_closed(hand.gesture, assist),
+                None => false,
+            }
+    });
+
+    for (key, hand) in &hand_states.hands {
+        let was_closed = state.previous.get(key).is_some_and(|g| is_closed(*g, assist));
+        if !is_closed(hand.gesture, assist) || was_closed || state.grips.contains_key(key) || !hand_states.is_confident(*key) {
+            continue;
+        }
+        let nearest = objects
+            .iter()
+            .filter(|(entity, _, b, _, carried)| {
+                carried.is_none() && surface_radius(*b) >= HEAVY_RADIUS && can_touch(teams.get(*entity).ok(), key.0)
+            })
+            .map(|(entity, transform, b, _, _)| {
+                (entity, transform, (transform.translation.distance(hand.center) - surface_radius(b)).max(0.0))
+            })
+            .filter(|(_, _, surface)| *surface < profile.grab_distance)
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((entity, transform, _)) = nearest {
+            let local = transform.rotation.inverse() * (hand.center - transform.translation);
+            state.grips.insert(*key, Grip { entity, local });
+        }
+    }
+    state.previous = hand_states.hands.iter().map(|(key, hand)| (*key, hand.gesture)).collect();
+
+    let mut by_entity: HashMap<Entity, Vec<(HandKey, Vec3)>> = HashMap::new();
+    for (key, grip) in &state.grips {
+        by_entity.entry(grip.entity).or_default().push((*key, grip.local));
+    }
+    for (entity, mut hands) in by_entity {
+        let Ok((_, transform, _, groups, carried)) = objects.get(entity) else {
+            continue;
+        };
+        if carried.is_some() {
+            continue;
+        }
+        // 片手だけでは持ち上がらないことを赤い印で知らせる
+        if hands.len() < 2 {
+            let (_, local) = hands[0];
+            gizmos.sphere(transform.transform_point(local), Quat::IDENTITY, 0.3, Color::srgb(1.0, 0.3, 0.2));
+            continue;
+        }
+        hands.sort_by_key(|(key, _)| *key);
+        let Some(carry) = TwoHandCarry::new(hands[0].1, hands[1].1) else {
+            continue;
+        };
+        let base = groups.copied().unwrap_or_default();
+        let filters = base.filters - hand_group(hands[0].0 .0, hands[0].0 .1) - hand_group(hands[1].0 .0, hands[1].0 .1);
+        commands.entity(entity).insert((
+            CoopCarried {
+                hands: [hands[0].0, hands[1].0],
+                carry,
+                spring: DampedSpring::new(transform.translation, Vec3::ZERO),
+                rotation: transform.rotation,
+                groups: groups.copied(),
+            },
+            CollisionGroups::new(base.memberships, filters),
+        ));
+        info!(?entity, hands = ?[hands[0].0, hands[1].0], "two-hand carry");
+    }
+}
+
+// 両手の位置から物体の姿勢を解き、減衰の弱いばねで追わせる。手の間隔が掴んだときとずれるほど揺れ、
+// ずれすぎるか片手を離したら落とす
+fn carry_heavy_objects(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    tuning: Res<Tuning>,
+    mut state: ResMut<CarryState>,
+    mut carried: Query<(Entity, &mut CoopCarried, &Transform, &mut Velocity, &mut ExternalForce)>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let grab = &tuning.0.grab;
+    let dt = time.delta_seconds();
+    for (entity, mut carried, transform, mut velocity, mut force) in carried.iter_mut() {
+        let [a, b] = carried.hands;
+        let gripping = |key| state.grips.get(&key).is_some_and(|g| g.entity == entity);
+        let (Some(hand_a), Some(hand_b)) = (hand_states.hands.get(&a), hand_states.hands.get(&b)) else {
+            drop_carry(&mut commands, entity, &carried);
+            continue;
+        };
+        let strain = carried.carry.strain(hand_a.center, hand_b.center);
+        if !gripping(a) || !gripping(b) || strain > BREAK_STRAIN {
+            drop_carry(&mut commands, entity, &carried);
+            state.grips.retain(|_, g| g.entity != entity);
+            info!(?entity, strain, "two-hand carry dropped");
+            continue;
+        }
+
+        let (target, rotation) = carried.carry.solve(hand_a.center, hand_b.center, carried.rotation);
+        carried.rotation = rotation;
+        carried.spring.step(target, CARRY_FREQUENCY, damping_ratio(strain), dt);
+        // ばねの速度に乗せ、ぶつかってずれた分だけ位置の差で引き戻す
+        let correction = (carried.spring.position - transform.translation) * grab.follow_gain;
+        velocity.linvel = (carried.spring.velocity + correction).clamp_length_max(grab.max_follow_speed);
+        // 短い回りで向きをそろえる
+        let turn = rotation * transform.rotation.inverse();
+        let turn = if turn.w < 0.0 { -turn } else { turn };
+        velocity.angvel = turn.to_scaled_axis() * ANGULAR_GAIN;
+        force.force = Vec3::ZERO;
+        gizmos.line(hand_a.center, hand_b.center, Color::srgb(1.0, 0.8, 0.3).with_alpha(1.0 - strain / BREAK_STRAIN));
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::gltf::GltfAssetLabel;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::retarget::{aim, aim_with_roll, apply_offset, calibration_offset, palm_frame, RigConfig, WRIST};
+use crate::error::{AppError, Health};
+use crate::packet::ClientId;
+use crate::{update_hands_and_physics, HandPoint, HandSide, HandStates};
+
+const CONFIG_PATH: &str = "character.json";
+const ASSET_DIR: &str = "assets";
+// 手が見えなくなったとき休止姿勢へ戻す速さ (1/秒)
+const RELAX_RATE: f32 = 4.0;
+
+// 名前から見つけた骨と、その休止姿勢
+struct BoundBone {
+    entity: Entity,
+    parent: Option<Entity>,
+    rest: Quat,
+    name: String,
+    from: usize,
+    to: usize,
+}
+
+struct BoundHand {
+    wrist: BoundBone,
+    fingers: Vec<BoundBone>,
+}
+
+impl BoundHand {
+    fn bones(&self) -> impl Iterator<Item = &BoundBone> {
+        std::iter::once(&self.wrist).chain(&self.fingers)
+    }
+}
+
+// 読み込んだキャラクターのシーンの根に付けると、設定にある手の骨を追跡した手で動かす
+#[derive(Component)]
+pub struct RetargetRig {
+    pub config: RigConfig,
+    pub client: ClientId,
+    // calibrate で補正を書き戻すファイル
+    pub save_to: Option<PathBuf>,
+    // シーンが出来てから骨を名前で探して埋める
+    hands: Option<HashMap<HandSide, BoundHand>>,
+}
+
+impl RetargetRig {
+    pub fn new(config: RigConfig, client: ClientId) -> Self {
+        Self { config, client, save_to: None, hands: None }
+    }
+}
+
+pub struct CharacterPlugin;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command("character", "character calibrate", character_command)
+            .add_systems(Startup, spawn_character)
+            .add_systems(Update, (bind_bones, drive_hands).chain().after(update_hands_and_physics));
+    }
+}
+
+fn load_config(path: &Path) -> Result<Option<RigConfig>, AppError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(AppError::Read { path: path.into(), source }),
+    };
+    serde_json::from_str(&text).map(Some).map_err(|source| AppError::Parse { path: path.into(), source })
+}
+
+// character.json があればそのキャラクターを置き、ローカルの手で動かす
+fn spawn_character(mut commands: Commands, asset_server: Res<AssetServer>, mut health: ResMut<Health>) {
+    let config = match load_config(Path::new(CONFIG_PATH)) {
+        Ok(Some(config)) => config,
+        Ok(None) => return,
+        Err(e) => {
+            health.degraded("character", e.to_string());
+            return;
+        }
+    };
+    if !Path::new(ASSET_DIR).join(&config.model).exists() {
+        health.failed("character", format!("model not found: {ASSET_DIR}/{}", config.model));
+        return;
+    }
+    info!(model = config.model, "loading character");
+    let transform =
+        Transform::from_translation(Vec3::from_array(config.position)).with_scale(Vec3::splat(config.scale));
+    commands.spawn((
+        SceneBundle {
+            scene: asset_server.load(GltfAssetLabel::Scene(0).from_asset(config.model.clone())),
+            transform,
+            ..default()
+        },
+        RetargetRig { save_to: Some(CONFIG_PATH.into()), ..RetargetRig::new(config, 0) },
+    ));
+}
+
+// シーンの子孫から骨を名前で探す。見つからない骨は動かさず、その名前を画面に出す
+fn bind_bones(
+    mut rigs: Query<(Entity, &mut RetargetRig)>,
+    children: Query<&Children>,
+    nodes: Query<(&Name, &Transform, Option<&Parent>)>,
+    mut health: ResMut<Health>,
+) {
+    for (root, mut rig) in rigs.iter_mut() {
+        if rig.hands.is_some() {
+            continue;
+        }
+        let by_name: HashMap<&str, Entity> = children
+            .iter_descendants(root)
+            .filter_map(|entity| nodes.get(entity).ok().map(|(name, ..)| (name.as_str(), entity)))
+            .collect();
+        // シーンがまだ出来ていない
+        if by_name.is_empty() {
+            continue;
+        }
+        let mut missing = Vec::new();
+        let mut bind = |name: &str, from: usize, to: usize| {
+            let bone = by_name.get(name).and_then(|entity| {
+                let (_, transform, parent) = nodes.get(*entity).ok()?;
+                let parent = parent.map(|p| p.get());
+                Some(BoundBone { entity: *entity, parent, rest: transform.rotation, name: name.to_string(), from, to })
+            });
+            if bone.is_none() {
+                missing.push(name.to_string());
+            }
+            bone
+        };
+        let mut hands = HashMap::new();
+        for side in [HandSide::Right, HandSide::Left] {
+            let Some(hand) = rig.config.hands.get(side.label()) else {
+                continue;
+            };
+            let Some(wrist) = bind(&hand.wrist, WRIST, WRIST) else {
+                continue;
+            };
+            let fingers = hand.fingers.iter().filter_map(|f| bind(&f.bone, f.from, f.to)).collect();
+            hands.insert(side, BoundHand { wrist, fingers });
+        }
+        if missing.is_empty() {
+            health.ok("character");
+        } else {
+            health.degraded("character", format!("bones not found: {}", missing.join(", ")));
+        }
+        rig.hands = Some(hands);
+    }
+}
+
+// 親から子の順に、ランドマークの向きへ骨を向けたローカル回転を求める。
+// settle は求めた回転 (手が無ければ None) から実際に使う回転を決める
+fn walk_hand(
+    hand: &BoundHand,
+    config: &RigConfig,
+    landmarks: &[Option<Vec3>; 21],
+    globals: &Query<&GlobalTransform>,
+    mut settle: impl FnMut(&BoundBone, Option<Quat>) -> Quat,
+) {
+    let axes = (Vec3::from_array(config.bone_axis), Vec3::from_array(config.side_axis));
+    let mut world: HashMap<Entity, Quat> = HashMap::new();
+    for bone in hand.bones() {
+        let parent = bone.parent.and_then(|p| {
+            world.get(&p).copied().or_else(|| globals.get(p).ok().map(|g| g.to_scale_rotation_translation().1))
+        });
+        let parent = parent.unwrap_or(Quat::IDENTITY);
+        let posed = if bone.entity == hand.wrist.entity {
+            palm_frame(landmarks).and_then(|(forward, side)| aim_with_roll(parent, bone.rest, axes, forward, side))
+        } else {
+            landmarks[bone.from].zip(landmarks[bone.to]).and_then(|(a, b)| aim(parent, bone.rest, axes.0, b - a))
+        };
+        let local = settle(bone, posed);
+        world.insert(bone.entity, parent * local);
+    }
+}
+
+fn landmarks_of(points: &Query<(&HandPoint, &Transform)>, client: ClientId, side: HandSide) -> [Option<Vec3>; 21] {
+    let mut landmarks = [None; 21];
+    for (point, transform) in points.iter() {
+        if point.client == client && point.side == side && point.id < 21 {
+            landmarks[point.id] = Some(transform.translation);
+        }
+    }
+    landmarks
+}
+
+fn drive_hands(
+    rigs: Query<&RetargetRig>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    globals: Query<&GlobalTransform>,
+    mut bones: Query<&mut Transform, (With<Name>, Without<HandPoint>)>,
+    time: Res<Time>,
+) {
+    let relax = 1.0 - (-RELAX_RATE * time.delta_seconds()).exp();
+    for rig in rigs.iter() {
+        let Some(hands) = &rig.hands else {
+            continue;
+        };
+        for (&side, hand) in hands {
+            let visible = hand_states.hands.contains_key(&(rig.client, side));
+            let landmarks = if visible { landmarks_of(&points, rig.client, side) } else { [None; 21] };
+            walk_hand(hand, &rig.config, &landmarks, &globals, |bone, posed| {
+                let Ok(mut transform) = bones.get_mut(bone.entity) else {
+                    return bone.rest;
+                };
+                transform.rotation = match posed {
+                    Some(posed) => apply_offset(rig.config.offset(&bone.name), posed),
+                    None => transform.rotation.slerp(bone.rest, relax),
+                };
+                transform.rotation
+            });
+        }
+    }
+}
+
+// 手を開いてモデルの休止姿勢と同じ形にしたまま calibrate すると、その形が休止姿勢になるよう骨ごとの補正を取る
+fn character_command(
+    In(args): In<Vec<String>>,
+    mut rigs: Query<&mut RetargetRig>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    globals: Query<&GlobalTransform>,
+) -> ConsoleResult {
+    if args.first().map(String::as_str) != Some("calibrate") {
+        return Err("usage: character calibrate".to_string());
+    }
+    let mut calibrated = 0;
+    for mut rig in rigs.iter_mut() {
+        let Some(hands) = &rig.hands else {
+            continue;
+        };
+        let mut offsets = Vec::new();
+        for (&side, hand) in hands {
+            if !hand_states.hands.contains_key(&(rig.client, side)) {
+                continue;
+            }
+            let landmarks = landmarks_of(&points, rig.client, side);
+            walk_hand(hand, &rig.config, &landmarks, &globals, |bone, posed| {
+                if let Some(posed) = posed {
+                    offsets.push((bone.name.clone(), calibration_offset(bone.rest, posed).to_array()));
+                }
+                bone.rest
+            });
+        }
+        calibrated += offsets.len();
+        rig.config.offsets.extend(offsets);
+        if let Some(path) = &rig.save_to {
+            let text = serde_json::to_string_pretty(&rig.config).map_err(|e| e.to_string())?;
+            fs::write(path, text).map_err(|e| format!("failed to save {}: {e}", path.display()))?;
+        }
+    }
+    if calibrated == 0 {
+        return Err("no character hand is in view".to_string());
+    }
+    Ok(format!("calibrated {calibrated} bones"))
+}
@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::checkers::{is_dark, on_board, Checkers, IllegalMove, Move, Player, Square, SIZE};
+use crate::floor::FLOOR_Y;
+use crate::grab::{Grabbable, Held};
+use crate::lifecycle::vanish;
+
+// 盤は床の上、カメラから見て手前 (+z) が先手 (黒) の陣
+const SQUARE_SIZE: f32 = 1.4;
+const BOARD_THICKNESS: f32 = 0.2;
+const BOARD_TOP: f32 = FLOOR_Y + BOARD_THICKNESS;
+const PIECE_RADIUS: f32 = 0.55;
+const PIECE_HALF_HEIGHT: f32 = 0.15;
+// 置けない場所へ離した駒が元のマスへ跳ねて戻る時間と高さ
+const HOP_TIME: f32 = 0.4;
+const HOP_HEIGHT: f32 = 1.2;
+
+#[derive(Component)]
+struct CheckersProp;
+
+// 盤の上での駒の居場所。ルール上の盤面 (Checkers) と同じマスを指す
+#[derive(Component, Debug, Clone, Copy)]
+pub struct CheckerPiece {
+    pub square: Square,
+}
+
+// 置けない手を戻している途中の駒。戻る間は手や他の駒に押されないよう位置で動かす
+#[derive(Component)]
+struct Hopping {
+    from: Vec3,
+    to: Vec3,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct CheckersGame {
+    pub active: bool,
+    pub game: Checkers,
+    // 直前に戻した手の理由
+    pub message: Option<&'static str>,
+}
+
+#[derive(Resource)]
+struct CheckersAssets {
+    piece: Handle<Mesh>,
+    dark: Handle<StandardMaterial>,
+    light: Handle<StandardMaterial>,
+    dark_king: Handle<StandardMaterial>,
+    light_king: Handle<StandardMaterial>,
+}
+
+impl CheckersAssets {
+    fn material(&self, owner: Player, king: bool) -> Handle<StandardMaterial> {
+        match (owner, king) {
+            (Player::Dark, false) => self.dark.clone(),
+            (Player::Light, false) => self.light.clone(),
+            (Player::Dark, true) => self.dark_king.clone(),
+            (Player::Light, true) => self.light_king.clone(),
+        }
+    }
+}
+
+pub struct CheckersPlugin;
+
+impl Plugin for CheckersPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CheckersGame::default())
+            .add_systems(Startup, load_checkers_assets)
+            .add_systems(
+                Update,
+                (toggle_checkers, refuse_wrong_turn, show_targets, place_released, hop_back, show_turn).chain(),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, checkers_ui);
+    }
+}
+
+fn square_center((column, row): Square) -> Vec3 {
+    let half = (SIZE - 1) as f32 / 2.0;
+    Vec3::new((column as f32 - half) * SQUARE_SIZE, BOARD_TOP + PIECE_HALF_HEIGHT, (half - row as f32) * SQUARE_SIZE)
+}
+
+fn square_at(position: Vec3) -> Option<Square> {
+    let half = (SIZE - 1) as f32 / 2.0;
+    let square = ((position.x / SQUARE_SIZE + half).round() as i32, (half - position.z / SQUARE_SIZE).round() as i32);
+    on_board(square).then_some(square)
+}
+
+fn load_checkers_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // 成った駒は金色に光らせる
+    let king = |base: Color| StandardMaterial {
+        base_color: base,
+        emissive: LinearRgba::rgb(0.6, 0.45, 0.0),
+        ..default()
+    };
+    commands.insert_resource(CheckersAssets {
+        piece: meshes.add(Cylinder::new(PIECE_RADIUS, PIECE_HALF_HEIGHT * 2.0)),
+        dark: materials.add(Color::srgb(0.55, 0.08, 0.08)),
+        light: materials.add(Color::srgb(0.92, 0.88, 0.78)),
+        dark_king: materials.add(king(Color::srgb(0.55, 0.08, 0.08))),
+        light_king: materials.add(king(Color::srgb(0.92, 0.88, 0.78))),
+    });
+}
+
+fn spawn_piece(commands: &mut Commands, assets: &CheckersAssets, square: Square, owner: Player) {
+    commands.spawn((
+        PbrBundle {
+            mesh: assets.piece.clone(),
+            material: assets.material(owner, false),
+            transform: Transform::from_translation(square_center(square)),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::cylinder(PIECE_HALF_HEIGHT, PIECE_RADIUS),
+        Friction::coefficient(0.8),
+        ExternalForce::default(),
+        Velocity::default(),
+        Grabbable { radius: PIECE_RADIUS },
+        CheckerPiece { square },
+        CheckersProp,
+    ));
+}
+
+// E で盤を出し入れする。出すたびに初めからにする
+fn toggle_checkers(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut checkers: ResMut<CheckersGame>,
+    assets: Res<CheckersAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<Entity, With<CheckersProp>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyE) {
+        return;
+    }
+    let active = !checkers.active;
+    *checkers = CheckersGame { active, ..default() };
+    if !active {
+        for entity in props.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let width = SIZE as f32 * SQUARE_SIZE;
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(width + 0.6, BOARD_THICKNESS, width + 0.6)),
+            material: materials.add(Color::srgb(0.85, 0.72, 0.5)),
+            transform: Transform::from_xyz(0.0, FLOOR_Y + BOARD_THICKNESS / 2.0, 0.0),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid((width + 0.6) / 2.0, BOARD_THICKNESS / 2.0, (width + 0.6) / 2.0),
+        CheckersProp,
+    ));
+    // 暗いマスは見た目だけの薄い板。当たり判定は盤の板にまかせる
+    let tile = meshes.add(Cuboid::new(SQUARE_SIZE, 0.01, SQUARE_SIZE));
+    let dark = materials.add(Color::srgb(0.3, 0.2, 0.12));
+    for square in (0..SIZE).flat_map(|c| (0..SIZE).map(move |r| (c, r))).filter(|s| is_dark(*s)) {
+        let center = square_center(square).with_y(BOARD_TOP + 0.005);
+        commands.spawn((
+            PbrBundle {
+                mesh: tile.clone(),
+                material: dark.clone(),
+                transform: Transform::from_translation(center),
+                ..default()
+            },
+            CheckersProp,
+        ));
+    }
+    for (square, piece) in checkers.game.pieces() {
+        spawn_piece(&mut commands, &assets, square, piece.owner);
+    }
+    info!("checkers board ready");
+}
+
+// 手番でない側の駒は掴んでもすぐ放させる。放した駒は place_released で元のマスへ戻る
+fn refuse_wrong_turn(
+    mut commands: Commands,
+    mut checkers: ResMut<CheckersGame>,
+    grabbed: Query<(Entity, &CheckerPiece), Added<Held>>,
+) {
+    for (entity, piece) in grabbed.iter() {
+        if let Err(reason) = checkers.game.can_lift(piece.square) {
+            commands.entity(entity).remove::<Held>();
+            checkers.message = Some(reason.message());
+        }
+    }
+}
+
+// 持ち上げている駒を置けるマスを緑、今の真下のマスを白 (置けなければ赤) の枠で見せる
+fn show_targets(
+    checkers: Res<CheckersGame>,
+    held: Query<(&CheckerPiece, &Transform), With<Held>>,
+    mut gizmos: Gizmos,
+) {
+    let outline = |gizmos: &mut Gizmos, square: Square, color: Color| {
+        let center = square_center(square).with_y(BOARD_TOP + 0.03);
+        gizmos.rect(center, Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2), Vec2::splat(SQUARE_SIZE * 0.9), color);
+    };
+    for (piece, transform) in held.iter() {
+        let targets = checkers.game.legal_targets(piece.square);
+        for square in &targets {
+            outline(&mut gizmos, *square, Color::srgb(0.3, 1.0, 0.4));
+        }
+        if let Some(square) = square_at(transform.translation)
+            && square != piece.square
+        {
+            let color = if targets.contains(&square) { Color::WHITE } else { Color::srgb(1.0, 0.3, 0.2) };
+            outline(&mut gizmos, square, color);
+        }
+    }
+}
+
+// 離した駒を一番近いマスに当てはめる。置ける手なら吸い付かせ、置けなければ元のマスへ跳ねて戻す
+#[allow(clippy::type_complexity)]
+fn place_released(
+    mut commands: Commands,
+    mut checkers: ResMut<CheckersGame>,
+    assets: Res<CheckersAssets>,
+    mut released: RemovedComponents<Held>,
+    mut pieces: Query<
+        (Entity, &mut CheckerPiece, &mut Transform, &mut Velocity, &mut Handle<StandardMaterial>),
+        Without<Hopping>,
+    >,
+) {
+    if !checkers.active {
+        released.clear();
+        return;
+    }
+    for entity in released.read() {
+        let Ok((_, piece, transform, ..)) = pieces.get(entity) else {
+            continue;
+        };
+        let (from, position) = (piece.square, transform.translation);
+        let result = match square_at(position) {
+            Some(to) if to == from => Err(None),
+            Some(to) => checkers.game.play(from, to).map(|kind| (to, kind)).map_err(Some),
+            None => Err(Some(IllegalMove::NotALegalSquare)),
+        };
+        let (to, kind) = match result {
+            Ok(placed) => placed,
+            Err(reason) => {
+                if let Some(reason) = reason {
+                    checkers.message = Some(reason.message());
+                }
+                commands.entity(entity).insert((
+                    Hopping { from: position, to: square_center(from), elapsed: 0.0 },
+                    RigidBody::KinematicPositionBased,
+                ));
+                continue;
+            }
+        };
+        checkers.message = None;
+        if let Move::Jump { captured } = kind
+            && let Some((taken, ..)) = pieces.iter().find(|(_, p, ..)| p.square == captured)
+        {
+            vanish(&mut commands, taken);
+            commands.entity(taken).remove::<CheckerPiece>();
+        }
+        let Ok((_, mut piece, mut transform, mut velocity, mut material)) = pieces.get_mut(entity) else {
+            continue;
+        };
+        piece.square = to;
+        *transform = Transform::from_translation(square_center(to));
+        *velocity = Velocity::zero();
+        if let Some(placed) = checkers.game.piece(to) {
+            *material = assets.material(placed.owner, placed.king);
+        }
+        info!(?from, ?to, turn = checkers.game.turn.label(), "checkers move");
+    }
+}
+
+fn hop_back(
+    mut commands: Commands,
+    mut hopping: Query<(Entity, &mut Hopping, &mut Transform, &mut Velocity)>,
+    time: Res<Time>,
+) {
+    for (entity, mut hop, mut transform, mut velocity) in hopping.iter_mut() {
+        hop.elapsed += time.delta_seconds();
+        let t = (hop.elapsed / HOP_TIME).min(1.0);
+        let arc = Vec3::Y * HOP_HEIGHT * 4.0 * t * (1.0 - t);
+        *transform = Transform::from_translation(hop.from.lerp(hop.to, t) + arc);
+        if t >= 1.0 {
+            *velocity = Velocity::zero();
+            commands.entity(entity).remove::<Hopping>().insert(RigidBody::Dynamic);
+        }
+    }
+}
+
+// 手番の側の盤の縁を、その側の駒の色で光らせる
+fn show_turn(checkers: Res<CheckersGame>, mut gizmos: Gizmos) {
+    if !checkers.active {
+        return;
+    }
+    let game = &checkers.game;
+    let player = game.winner.unwrap_or(game.turn);
+    let edge = (SIZE as f32 / 2.0) * SQUARE_SIZE + 0.3;
+    let z = if player == Player::Dark { edge } else { -edge };
+    let color = match player {
+        Player::Dark => Color::srgb(1.0, 0.25, 0.2),
+        Player::Light => Color::srgb(1.0, 0.95, 0.8),
+    };
+    let y = BOARD_TOP + 0.05;
+    gizmos.line(Vec3::new(-edge, y, z), Vec3::new(edge, y, z), color);
+    if let Some(square) = game.chaining {
+        gizmos.circle(square_center(square).with_y(y), Dir3::Y, PIECE_RADIUS * 1.3, color);
+    }
+}
+
+#[cfg(feature = "ui")]
+fn checkers_ui(mut contexts: EguiContexts, checkers: Res<CheckersGame>) {
+    if !checkers.active {
+        return;
+    }
+    let game = &checkers.game;
+    egui::Window::new("Checkers")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            match game.winner {
+                Some(winner) => ui.heading(format!("{} wins!", winner.label())),
+                None => ui.heading(format!("{} to move", game.turn.label())),
+            };
+            if game.chaining.is_some() {
+                ui.label("Keep jumping with the same piece");
+            }
+            if let Some(message) = checkers.message {
+                ui.label(egui::RichText::new(message).color(egui::Color32::from_rgb(255, 120, 90)));
+            }
+            ui.small("Pick up a piece and drop it on a green square / E: quit");
+        });
+}
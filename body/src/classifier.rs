@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::classifier::{best_label, one_hot, GestureClassifier, HeuristicClassifier, LandmarkTensor};
+use crate::core::gesture::{classify_visible, Finger, Gesture};
+use crate::error::Health;
+use crate::packet::OneHand;
+use crate::settings::{ClassifierKind, GestureSettings, Settings};
+use crate::HandKey;
+
+#[derive(Resource)]
+pub struct ActiveClassifier {
+    classifier: Box<dyn GestureClassifier>,
+    // 今の分類器を作ったときの設定 (変わったら作り直す)
+    built_from: Option<GestureSettings>,
+    // 手ごとに最後に出したラベルごとの確率。手の周りの輪に出す (gesture_ring.rs)
+    latest: HashMap<HandKey, Vec<(String, f32)>>,
+}
+
+impl Default for ActiveClassifier {
+    fn default() -> Self {
+        Self { classifier: Box::new(HeuristicClassifier), built_from: None, latest: HashMap::new() }
+    }
+}
+
+impl ActiveClassifier {
+    // ランドマークが欠けているときや推論に失敗したときは送信側の判定を使う。
+    // 隠れた指 (occlusion.rs) があれば、補った点を分類器に渡さず見えている指だけで決める
+    pub fn gesture(&mut self, key: HandKey, hand: &OneHand, occluded: &[Finger]) -> Gesture {
+        if !occluded.is_empty() {
+            let gesture =
+                classify_visible(&hand.landmarks, occluded).unwrap_or_else(|| Gesture::from_label(&hand.gesture));
+            self.latest.insert(key, one_hot(gesture));
+            return gesture;
+        }
+        let Some(input) = LandmarkTensor::from_landmarks(&hand.landmarks) else {
+            self.latest.remove(&key);
+            return Gesture::from_label(&hand.gesture);
+        };
+        let probabilities = self.classifier.classify(&input);
+        let gesture = match best_label(&probabilities) {
+            Some(label) => Gesture::from_label(label),
+            None => Gesture::from_label(&hand.gesture),
+        };
+        self.latest.insert(key, probabilities);
+        gesture
+    }
+
+    pub fn probabilities(&self, key: HandKey) -> Option<&[(String, f32)]> {
+        self.latest.get(&key).map(Vec::as_slice)
+    }
+}
+
+pub struct ClassifierPlugin;
+
+impl Plugin for ClassifierPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActiveClassifier::default())
+            .add_systems(PreUpdate, rebuild_classifier);
+    }
+}
+
+// ONNX のモデルが使えないときは、手元の判定に切り替えてそのことを画面に出す
+fn rebuild_classifier(settings: Res<Settings>, mut active: ResMut<ActiveClassifier>, mut health: ResMut<Health>) {
+    if active.built_from.as_ref() == Some(&settings.gesture) {
+        return;
+    }
+    let gesture = settings.gesture.clone();
+    active.classifier = match build_classifier(&gesture) {
+        Ok(classifier) => {
+            health.ok("classifier");
+            classifier
+        }
+        Err(e) => {
+            health.degraded("classifier", format!("{e}; using the heuristic classifier"));
+            Box::new(HeuristicClassifier)
+        }
+    };
+    info!(classifier = active.classifier.name(), "gesture classifier ready");
+    active.built_from = Some(gesture);
+}
+
+fn build_classifier(settings: &GestureSettings) -> Result<Box<dyn GestureClassifier>, String> {
+    match settings.classifier {
+        ClassifierKind::Heuristic => Ok(Box::new(HeuristicClassifier)),
+        ClassifierKind::Onnx => load_onnx(settings),
+    }
+}
+
+#[cfg(feature = "onnx")]
+fn load_onnx(settings: &GestureSettings) -> Result<Box<dyn GestureClassifier>, String> {
+    let path = std::path::Path::new(&settings.model_path);
+    match crate::core::onnx::OnnxClassifier::load(path, settings.labels.clone()) {
+        Ok(classifier) => Ok(Box::new(classifier)),
+        Err(e) => Err(format!("failed to load {}: {e}", path.display())),
+    }
+}
+
+#[cfg(not(feature = "onnx"))]
+fn load_onnx(_settings: &GestureSettings) -> Result<Box<dyn GestureClassifier>, String> {
+    Err("built without the onnx feature".to_string())
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::confirm::{Confirm, HoldToConfirm, CONFIRM_HOLD};
+
+const RING_RADIUS: f32 = 0.9;
+
+// 確認中の操作を見分ける名前と番号 (物体や項目ごとに別の確認にする)
+pub type ConfirmKey = (&'static str, u64);
+
+struct Pending {
+    hold: HoldToConfirm,
+    position: Vec3,
+    // このフレームに hold が呼ばれた
+    seen: bool,
+}
+
+// 取り消せない操作の確認。操作を起こすジェスチャーを保っている間、毎フレーム hold を呼ぶと
+// 進み具合の輪を出し、CONFIRM_HOLD 秒保ちきったフレームだけ true を返す。呼ばれなくなったら取り消す
+#[derive(Resource, Default)]
+pub struct Confirmations {
+    pending: HashMap<ConfirmKey, Pending>,
+}
+
+impl Confirmations {
+    pub fn hold(&mut self, key: ConfirmKey, position: Vec3, now: f32) -> bool {
+        let pending = self.pending.entry(key).or_insert_with(|| Pending {
+            hold: HoldToConfirm::start(now),
+            position,
+            seen: true,
+        });
+        pending.position = position;
+        pending.seen = true;
+        pending.hold.update(now, CONFIRM_HOLD) == Confirm::Confirmed
+    }
+}
+
+pub struct ConfirmPlugin;
+
+impl Plugin for ConfirmPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Confirmations::default()).add_systems(PostUpdate, draw_and_expire);
+    }
+}
+
+// Update の間に hold が呼ばれなかった確認は、ジェスチャーを崩したとみなして捨てる
+fn draw_and_expire(
+    mut confirmations: ResMut<Confirmations>,
+    camera: Query<&GlobalTransform, With<Camera3d>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let eye = camera.get_single().map_or(Vec3::Z * 30.0, |t| t.translation());
+    confirmations.pending.retain(|_, pending| std::mem::take(&mut pending.seen));
+    for pending in confirmations.pending.values() {
+        let progress = pending.hold.progress(now, CONFIRM_HOLD);
+        // 輪はカメラに向ける
+        let normal = (eye - pending.position).normalize_or(Vec3::Z);
+        let rotation = Quat::from_rotation_arc(Vec3::Y, normal);
+        let faint = Color::srgba(1.0, 1.0, 1.0, 0.25);
+        gizmos.circle(pending.position, Dir3::new(normal).unwrap_or(Dir3::Z), RING_RADIUS, faint);
+        gizmos
+            .arc_3d(progress * std::f32::consts::TAU, RING_RADIUS, pending.position, rotation, Color::srgb(1.0, 0.35, 0.2))
+            .resolution(48);
+    }
+}
@@ -0,0 +1,246 @@
+use bevy::ecs::system::SystemId;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+use std::collections::VecDeque;
+
+use crate::core::console::{arg, arg_or, split_line};
+#[cfg(feature = "props")]
+use crate::props::PropCatalog;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::SpawnRequest;
+
+// 残しておく出力の行数
+const LOG_LINES: usize = 200;
+
+// 命令の実行結果。Ok は出力、Err はエラーとして表示する
+pub type ConsoleResult = Result<String, String>;
+
+struct ConsoleCommand {
+    name: String,
+    usage: String,
+    system: SystemId<Vec<String>, ConsoleResult>,
+}
+
+// コンソールで使える命令。ほかのプラグインは add_console_command で命令を足す
+#[derive(Resource, Default)]
+pub struct ConsoleCommands {
+    commands: Vec<ConsoleCommand>,
+}
+
+pub trait ConsoleExt {
+    fn add_console_command<M>(
+        &mut self,
+        name: &str,
+        usage: &str,
+        system: impl IntoSystem<Vec<String>, ConsoleResult, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl ConsoleExt for App {
+    fn add_console_command<M>(
+        &mut self,
+        name: &str,
+        usage: &str,
+        system: impl IntoSystem<Vec<String>, ConsoleResult, M> + 'static,
+    ) -> &mut Self {
+        let system = self.world_mut().register_system(system);
+        let mut commands = self.world_mut().get_resource_or_insert_with(ConsoleCommands::default);
+        if commands.commands.iter().any(|c| c.name == name) {
+            warn!("console command {name:?} registered twice; keeping the first");
+            return self;
+        }
+        commands.commands.push(ConsoleCommand { name: name.to_string(), usage: usage.to_string(), system });
+        self
+    }
+}
+
+// ~ で開く落ちてくるコンソール
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    #[cfg(feature = "ui")]
+    input: String,
+    log: VecDeque<(bool, String)>,
+    // 入力した行。上下の矢印でたどる
+    #[cfg(feature = "ui")]
+    history: Vec<String>,
+    #[cfg(feature = "ui")]
+    browsing: Option<usize>,
+    pending: Vec<String>,
+}
+
+impl Console {
+    // 管理用 API などから 1 行を実行させる
+    #[cfg(feature = "ui")]
+    pub fn submit(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        self.browsing = None;
+        self.pending.push(line);
+    }
+
+    fn print(&mut self, error: bool, text: impl Into<String>) {
+        for line in text.into().lines() {
+            self.log.push_back((error, line.to_string()));
+        }
+        while self.log.len() > LOG_LINES {
+            self.log.pop_front();
+        }
+    }
+}
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConsoleCommands>()
+            .insert_resource(Console::default())
+            .add_console_command("spawn", "spawn cube [size] [wood|metal|rubber] | spawn <prop>", spawn_command)
+            .add_console_command("set", "set gravity <y> | set gravity <x> <y> <z>", set_command)
+            .add_systems(PreUpdate, toggle_console.after(InputSystem))
+            .add_systems(Update, run_console_lines);
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, console_ui.before(run_console_lines));
+    }
+}
+
+// 開いている間は打った文字でほかのキー操作が動かないよう、キー入力を消す
+fn toggle_console(mut keys: ResMut<ButtonInput<KeyCode>>, mut console: ResMut<Console>) {
+    if keys.just_pressed(KeyCode::Backquote) {
+        console.open = !console.open;
+    }
+    if console.open {
+        keys.reset_all();
+    }
+}
+
+fn run_console_lines(world: &mut World) {
+    let lines = std::mem::take(&mut world.resource_mut::<Console>().pending);
+    for line in lines {
+        let Some((name, args)) = split_line(&line) else {
+            continue;
+        };
+        world.resource_mut::<Console>().print(false, format!("> {line}"));
+        let result = run_command(world, &name, args);
+        info!(command = line, ok = result.is_ok(), "console");
+        let mut console = world.resource_mut::<Console>();
+        match result {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => console.print(false, output),
+            Err(error) => console.print(true, error),
+        }
+    }
+}
+
+fn run_command(world: &mut World, name: &str, args: Vec<String>) -> ConsoleResult {
+    let commands = world.resource::<ConsoleCommands>();
+    if name == "help" {
+        let mut usages: Vec<&str> = commands.commands.iter().map(|c| c.usage.as_str()).collect();
+        usages.sort();
+        return Ok(format!("help\n{}", usages.join("\n")));
+    }
+    let Some(command) = commands.commands.iter().find(|c| c.name == name) else {
+        return Err(format!("unknown command {name:?} (try help)"));
+    };
+    let system = command.system;
+    world.run_system_with_input(system, args).map_err(|e| format!("{name} failed: {e:?}"))?
+}
+
+fn spawn_command(
+    In(args): In<Vec<String>>,
+    mut spawns: EventWriter<SpawnRequest>,
+    #[cfg(feature = "props")] mut props: ResMut<PropCatalog>,
+) -> ConsoleResult {
+    let shape = args.first().map_or("cube", String::as_str);
+    if !matches!(shape, "cube" | "box") {
+        // assets/props に置いた glTF (props.rs)
+        #[cfg(feature = "props")]
+        {
+            if props.request(shape) {
+                return Ok(format!("spawned a {shape}"));
+            }
+            return Err(format!("cannot spawn {shape:?}; known props: {}", props.names().join(", ")));
+        }
+        #[cfg(not(feature = "props"))]
+        return Err(format!("cannot spawn {shape:?}; only cubes come from the spawn registry"));
+    }
+    let size: f32 = arg_or(&args, 1, "size", 2.0)?;
+    if !(size > 0.0 && size <= 20.0) {
+        return Err("size must be between 0 and 20".to_string());
+    }
+    let material = match args.get(2) {
+        Some(name) => *SurfaceMaterial::ALL
+            .iter()
+            .find(|m| m.name() == name.to_lowercase())
+            .ok_or_else(|| format!("unknown material {name:?}"))?,
+        None => SurfaceMaterial::Wood,
+    };
+    spawns.send(SpawnRequest { position: Vec3::new(0.0, 10.0, 0.0), size, material });
+    Ok(format!("spawned a {} cube of size {size}", material.name()))
+}
+
+fn set_command(In(args): In<Vec<String>>, mut config: ResMut<RapierConfiguration>) -> ConsoleResult {
+    let name = args.first().ok_or("missing what to set (gravity)")?;
+    match name.as_str() {
+        "gravity" => {
+            config.gravity = match args.len() {
+                2 => Vec3::Y * arg::<f32>(&args, 1, "gravity")?,
+                4 => Vec3::new(arg(&args, 1, "x")?, arg(&args, 2, "y")?, arg(&args, 3, "z")?),
+                _ => return Err("set gravity <y> or set gravity <x> <y> <z>".to_string()),
+            };
+            Ok(format!("gravity = {}", config.gravity))
+        }
+        other => Err(format!("cannot set {other:?}")),
+    }
+}
+
+#[cfg(feature = "ui")]
+fn console_ui(mut contexts: EguiContexts, mut console: ResMut<Console>) {
+    if !console.open {
+        return;
+    }
+    let console = &mut *console;
+    egui::TopBottomPanel::top("console").resizable(true).show(contexts.ctx_mut(), |ui| {
+        egui::ScrollArea::vertical().max_height(240.0).stick_to_bottom(true).show(ui, |ui| {
+            for (error, line) in &console.log {
+                let color = if *error { egui::Color32::from_rgb(255, 110, 90) } else { egui::Color32::LIGHT_GRAY };
+                ui.label(egui::RichText::new(line).monospace().color(color));
+            }
+        });
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut console.input)
+                .font(egui::TextStyle::Monospace)
+                .desired_width(f32::INFINITY)
+                .hint_text("help"),
+        );
+        // 開いたときの ~ が入力に混ざらないようにする
+        console.input.retain(|c| c != '`' && c != '~');
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let line = std::mem::take(&mut console.input);
+            if !line.trim().is_empty() {
+                console.submit(line);
+            }
+        }
+        if response.has_focus() && !console.history.is_empty() {
+            let (up, down) = ui.input(|i| (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+            let last = console.history.len() - 1;
+            let browsing = match (console.browsing, up, down) {
+                (None, true, _) => Some(last),
+                (Some(i), true, _) => Some(i.saturating_sub(1)),
+                (Some(i), false, true) if i < last => Some(i + 1),
+                (Some(_), false, true) => None,
+                (current, ..) => current,
+            };
+            if browsing != console.browsing {
+                console.browsing = browsing;
+                console.input = browsing.map_or_else(String::new, |i| console.history[i].clone());
+            }
+        }
+        response.request_focus();
+    });
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::search::ObjectQuery;
+
+// 管理用 WebSocket で受け付ける命令。1 メッセージに 1 つの JSON
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum AdminCommand {
+    // paused を省くと切り替え
+    Pause {
+        #[serde(default)]
+        paused: Option<bool>,
+    },
+    Reset,
+    Preset {
+        name: String,
+    },
+    ListPresets,
+    // settings.json と同じ形の一部だけを送れば、その項目だけ変わる
+    Settings {
+        #[serde(default)]
+        patch: Value,
+        #[serde(default)]
+        save: bool,
+    },
+    Metrics,
+    // 条件に合う物体を枠で囲む。focus ならカメラを向ける
+    Highlight {
+        #[serde(default)]
+        query: ObjectQuery,
+        #[serde(default)]
+        focus: bool,
+    },
+    ClearHighlight,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AdminReply {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl AdminReply {
+    pub fn ok(data: Option<Value>) -> Self {
+        Self { ok: true, error: None, data }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(message.into()), data: None }
+    }
+}
+
+// オブジェクト同士は項目ごとに再帰して重ね、それ以外は patch の値で置き換える
+pub fn merge_json(base: &mut Value, patch: &Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(base.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch.clone(),
+    }
+}
+
+// プリセット名はファイル名に使うので、ディレクトリをまたげる文字は通さない
+pub fn is_valid_preset_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// 接続の URL の ?token=... か、Authorization: Bearer ... で渡された合言葉。
+// URL のほうはエスケープされているので戻してから返す
+pub fn handshake_token(query: Option<&str>, authorization: Option<&str>) -> Option<Vec<u8>> {
+    let from_query = query.and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    match from_query {
+        Some(token) => percent_decode(token),
+        None => Some(authorization?.strip_prefix("Bearer ")?.trim().as_bytes().to_vec()),
+    }
+}
+
+// application/x-www-form-urlencoded の値を戻す。%XX が壊れていれば None
+fn percent_decode(value: &str) -> Option<Vec<u8>> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            b => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    Some(decoded)
+}
+
+// かかる時間から合言葉を推測されないよう、長さが同じなら最後まで比べる
+pub fn token_matches(secret: &[u8], token: &[u8]) -> bool {
+    secret.len() == token.len() && secret.iter().zip(token).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// ブラウザは WebSocket を開いたページの Origin を必ず付ける。付いていなければブラウザ以外なので通し、
+// 付いていれば許可したページ (--admin-origin) からだけ受け付ける
+pub fn origin_allowed(origin: Option<&str>, allowed: &[String]) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let origin = origin.trim().trim_end_matches('/');
+    allowed.iter().any(|a| a.trim_end_matches('/').eq_ignore_ascii_case(origin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn commands_parse_from_tagged_json() {
+        let pause: AdminCommand = serde_json::from_str(r#"{"cmd":"pause"}"#).unwrap();
+        assert_eq!(pause, AdminCommand::Pause { paused: None });
+        let preset: AdminCommand = serde_json::from_str(r#"{"cmd":"preset","name":"lobby"}"#).unwrap();
+        assert_eq!(preset, AdminCommand::Preset { name: "lobby".to_string() });
+        assert!(serde_json::from_str::<AdminCommand>(r#"{"cmd":"format_disk"}"#).is_err());
+        let highlight: AdminCommand =
+            serde_json::from_str(r#"{"cmd":"highlight","query":{"sleeping":true},"focus":true}"#).unwrap();
+        let query = ObjectQuery { sleeping: Some(true), ..Default::default() };
+        assert_eq!(highlight, AdminCommand::Highlight { query, focus: true });
+    }
+
+    #[test]
+    fn merge_replaces_only_patched_fields() {
+        let mut base = json!({"display": {"vsync": true, "fullscreen": false}, "build": {"cell": 2.0}});
+        merge_json(&mut base, &json!({"display": {"fullscreen": true}}));
+        assert_eq!(base, json!({"display": {"vsync": true, "fullscreen": true}, "build": {"cell": 2.0}}));
+    }
+
+    #[test]
+    fn preset_names_cannot_escape_the_directory() {
+        assert!(is_valid_preset_name("night_mode-2"));
+        assert!(!is_valid_preset_name("../settings"));
+        assert!(!is_valid_preset_name(""));
+    }
+
+    #[test]
+    fn token_comes_from_the_query_or_the_header() {
+        assert_eq!(handshake_token(Some("a=1&token=abc"), None), Some(b"abc".to_vec()));
+        assert_eq!(handshake_token(None, Some("Bearer abc ")), Some(b"abc".to_vec()));
+        assert_eq!(handshake_token(Some("a=1"), Some("Basic abc")), None);
+        assert!(token_matches(b"abc", b"abc"));
+        assert!(!token_matches(b"abc", b"abd"));
+        assert!(!token_matches(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn query_token_is_percent_decoded() {
+        assert_eq!(handshake_token(Some("token=a%2Bb%26c%3D"), None), Some(b"a+b&c=".to_vec()));
+        assert_eq!(handshake_token(Some("token=two+words"), None), Some(b"two words".to_vec()));
+        assert_eq!(handshake_token(Some("token=bad%2"), None), None);
+        assert_eq!(handshake_token(Some("token=bad%zz"), None), None);
+    }
+
+    #[test]
+    fn browser_origins_must_be_listed() {
+        let allowed = vec!["http://tablet.local:8080".to_string()];
+        assert!(origin_allowed(None, &[]));
+        assert!(origin_allowed(Some("http://tablet.local:8080/"), &allowed));
+        assert!(!origin_allowed(Some("https://evil.example"), &allowed));
+        assert!(!origin_allowed(Some("http://localhost"), &[]));
+    }
+}
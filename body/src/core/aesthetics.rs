@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+// タップの間隔がこれより空いたらテンポを測り直す
+const TAP_RESET: f32 = 2.0;
+const MAX_TAPS: usize = 8;
+
+// 手の量 (高さ・つまみ具合など) を見た目の値に写す。input.0 > input.1 なら逆向きに写る
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ParamMapping {
+    pub input: (f32, f32),
+    pub output: (f32, f32),
+    // 1 で線形。大きいほど入力の上のほうで急に変わる
+    pub exponent: f32,
+}
+
+impl ParamMapping {
+    pub fn apply(&self, value: f32) -> f32 {
+        let span = self.input.1 - self.input.0;
+        if span == 0.0 {
+            return self.output.0;
+        }
+        let t = ((value - self.input.0) / span).clamp(0.0, 1.0).powf(self.exponent);
+        self.output.0 + (self.output.1 - self.output.0) * t
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AestheticsMapping {
+    // 手のひらの高さ (ワールド y) → 環境光の明るさ
+    pub ambient: ParamMapping,
+    // 親指と人差し指の距離 → ブルームの強さ
+    pub bloom: ParamMapping,
+    // 拍ごとに小道具を光らせる強さと、その減衰 (1/秒)
+    pub pulse_strength: f32,
+    pub pulse_decay: f32,
+}
+
+impl Default for AestheticsMapping {
+    fn default() -> Self {
+        Self {
+            ambient: ParamMapping { input: (-4.0, 14.0), output: (0.0, 1500.0), exponent: 1.5 },
+            bloom: ParamMapping { input: (2.0, 0.3), output: (0.0, 0.6), exponent: 1.0 },
+            pulse_strength: 4.0,
+            pulse_decay: 6.0,
+        }
+    }
+}
+
+// タップした間隔の平均からテンポを求める
+#[derive(Debug, Clone, Default)]
+pub struct TapTempo {
+    taps: Vec<f32>,
+}
+
+impl TapTempo {
+    pub fn tap(&mut self, now: f32) {
+        if self.taps.last().is_some_and(|last| now - last > TAP_RESET) {
+            self.taps.clear();
+        }
+        self.taps.push(now);
+        if self.taps.len() > MAX_TAPS {
+            self.taps.remove(0);
+        }
+    }
+
+    // 拍の間隔 (秒)。2 回以上タップするまでは None
+    pub fn period(&self) -> Option<f32> {
+        let (first, last) = (self.taps.first()?, self.taps.last()?);
+        (self.taps.len() > 1).then(|| (last - first) / (self.taps.len() - 1) as f32)
+    }
+
+    #[cfg(any(test, feature = "ui"))]
+    pub fn bpm(&self) -> Option<f32> {
+        self.period().map(|p| 60.0 / p)
+    }
+
+    // 最後のタップを拍の頭として、now までに何拍目に入ったか
+    pub fn beat_index(&self, now: f32) -> Option<u64> {
+        let period = self.period()?;
+        let last = *self.taps.last()?;
+        (now >= last).then(|| ((now - last) / period) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_clamps_and_curves() {
+        let mapping = ParamMapping { input: (0.0, 10.0), output: (100.0, 200.0), exponent: 2.0 };
+        assert_eq!(mapping.apply(-5.0), 100.0);
+        assert_eq!(mapping.apply(20.0), 200.0);
+        assert!((mapping.apply(5.0) - 125.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn reversed_input_inverts_mapping() {
+        let bloom = AestheticsMapping::default().bloom;
+        assert_eq!(bloom.apply(0.1), bloom.output.1);
+        assert_eq!(bloom.apply(3.0), bloom.output.0);
+    }
+
+    #[test]
+    fn tap_tempo_from_regular_taps() {
+        let mut tempo = TapTempo::default();
+        assert_eq!(tempo.bpm(), None);
+        for i in 0..4 {
+            tempo.tap(10.0 + i as f32 * 0.5);
+        }
+        assert!((tempo.bpm().unwrap() - 120.0).abs() < 1e-3);
+        assert_eq!(tempo.beat_index(11.5 + 1.2), Some(2));
+
+        // 間が空いたら最初から測り直す
+        tempo.tap(20.0);
+        assert_eq!(tempo.bpm(), None);
+    }
+}
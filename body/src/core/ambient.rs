@@ -0,0 +1,99 @@
+use glam::Vec3;
+
+// 中心のまわりを回る輪の半径と、輪へ引き戻す強さ
+const RING_RADIUS: f32 = 7.0;
+const RING_STIFFNESS: f32 = 0.6;
+// 輪に沿って回す力と、上下にゆっくり漂わせる幅・速さ
+const SWIRL: f32 = 1.2;
+const BOB_HEIGHT: f32 = 1.5;
+const BOB_RATE: f32 = 0.4;
+const BOB_STIFFNESS: f32 = 0.8;
+// 近づきすぎた玉同士を離す距離と強さ
+const SEPARATION: f32 = 2.0;
+const REPULSION: f32 = 1.5;
+const DRAG: f32 = 0.5;
+const MAX_SPEED: f32 = 4.0;
+
+// 休止中に漂わせる光る玉の一つ
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orb {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    phase: f32,
+}
+
+// 中心のまわりの輪に等間隔で並べる
+pub fn ring_of_orbs(count: usize, center: Vec3) -> Vec<Orb> {
+    (0..count)
+        .map(|i| {
+            let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+            Orb {
+                position: center + Vec3::new(angle.cos(), 0.0, angle.sin()) * RING_RADIUS,
+                velocity: Vec3::ZERO,
+                phase: angle * 2.0,
+            }
+        })
+        .collect()
+}
+
+// 輪へ引き戻す力・輪に沿う力・上下の揺れ・玉同士の反発を弱くかけて進める
+pub fn step_orbs(orbs: &mut [Orb], center: Vec3, elapsed: f32, dt: f32) {
+    let positions: Vec<Vec3> = orbs.iter().map(|orb| orb.position).collect();
+    for (i, orb) in orbs.iter_mut().enumerate() {
+        let offset = orb.position - center;
+        let flat = Vec3::new(offset.x, 0.0, offset.z);
+        let radial = flat.try_normalize().unwrap_or(Vec3::X);
+        let mut force = -radial * (flat.length() - RING_RADIUS) * RING_STIFFNESS;
+        force += Vec3::Y.cross(radial) * -SWIRL;
+        let height = BOB_HEIGHT * (elapsed * BOB_RATE + orb.phase).sin();
+        force += Vec3::Y * (height - offset.y) * BOB_STIFFNESS;
+        for (j, other) in positions.iter().enumerate() {
+            let away = orb.position - *other;
+            let distance = away.length();
+            if j != i && distance < SEPARATION && distance > f32::EPSILON {
+                force += away / distance * (SEPARATION - distance) * REPULSION;
+            }
+        }
+        force -= orb.velocity * DRAG;
+        orb.velocity = (orb.velocity + force * dt).clamp_length_max(MAX_SPEED);
+        orb.position += orb.velocity * dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orbs_keep_circling_near_the_ring() {
+        let center = Vec3::new(0.0, 5.0, 0.0);
+        let mut orbs = ring_of_orbs(8, center);
+        let start = orbs[0].position;
+        let mut travelled = 0.0;
+        for frame in 0..3600 {
+            let before = orbs[0].position;
+            step_orbs(&mut orbs, center, frame as f32 / 60.0, 1.0 / 60.0);
+            travelled += before.distance(orbs[0].position);
+        }
+        for orb in &orbs {
+            let offset = orb.position - center;
+            let radius = Vec3::new(offset.x, 0.0, offset.z).length();
+            assert!((radius - RING_RADIUS).abs() < 2.0, "{radius}");
+            assert!(offset.y.abs() < BOB_HEIGHT + 1.0, "{}", offset.y);
+            assert!(orb.velocity.length() <= MAX_SPEED + 1e-4);
+        }
+        // 止まらずに回り続けている
+        assert!(travelled > 20.0, "{travelled} from {start}");
+    }
+
+    #[test]
+    fn overlapping_orbs_push_apart() {
+        let mut orbs = ring_of_orbs(2, Vec3::ZERO);
+        orbs[1].position = orbs[0].position + Vec3::Y * 0.5;
+        let gap = orbs[0].position.distance(orbs[1].position);
+        for _ in 0..30 {
+            step_orbs(&mut orbs, Vec3::ZERO, 0.0, 1.0 / 60.0);
+        }
+        assert!(orbs[0].position.distance(orbs[1].position) > gap);
+    }
+}
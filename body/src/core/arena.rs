@@ -0,0 +1,133 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+// 壁の厚さの半分。速く投げた物体がすり抜けないよう厚めにする
+pub const HALF_THICKNESS: f32 = 1.0;
+// 向かい合う壁が無い側は、ここまで伸ばす
+const OPEN_EXTENT: f32 = 40.0;
+// 天井が無いときの壁の高さ (床から)
+const OPEN_HEIGHT: f32 = 30.0;
+
+// 壁 1 枚。position は内側の面の座標 (左右は x、奥と手前は z、天井は y)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Wall {
+    pub position: f32,
+    #[serde(default = "default_restitution")]
+    pub restitution: f32,
+}
+
+fn default_restitution() -> f32 {
+    0.6
+}
+
+// 投げた物体が画面の外へ飛んでいかないよう囲む壁と天井 (scene.json の arena)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ArenaConfig {
+    #[serde(default)]
+    pub left: Option<Wall>,
+    #[serde(default)]
+    pub right: Option<Wall>,
+    #[serde(default)]
+    pub back: Option<Wall>,
+    #[serde(default)]
+    pub front: Option<Wall>,
+    #[serde(default)]
+    pub ceiling: Option<Wall>,
+    // 薄いガラスとして見せる。false なら見えない壁
+    #[serde(default)]
+    pub glass: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WallBox {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    pub restitution: f32,
+}
+
+impl ArenaConfig {
+    pub fn is_empty(&self) -> bool {
+        self.walls().next().is_none()
+    }
+
+    fn walls(&self) -> impl Iterator<Item = Wall> {
+        [self.left, self.right, self.back, self.front, self.ceiling].into_iter().flatten()
+    }
+
+    // 壁ごとの箱。内側の面が position に来るよう外側へ厚みを持たせ、隣の壁の厚みまで伸ばして角をふさぐ
+    pub fn boxes(&self, floor_y: f32) -> Vec<WallBox> {
+        let span = |low: Option<Wall>, high: Option<Wall>, open_low: f32, open_high: f32| {
+            let low = low.map_or(open_low, |w| w.position - HALF_THICKNESS * 2.0);
+            let high = high.map_or(open_high, |w| w.position + HALF_THICKNESS * 2.0);
+            ((low + high) / 2.0, (high - low) / 2.0)
+        };
+        let (x, half_x) = span(self.left, self.right, -OPEN_EXTENT, OPEN_EXTENT);
+        let (z, half_z) = span(self.back, self.front, -OPEN_EXTENT, OPEN_EXTENT);
+        let top = self.ceiling.map_or(floor_y + OPEN_HEIGHT, |w| w.position);
+        let (y, half_y) = ((floor_y + top) / 2.0, (top - floor_y) / 2.0);
+        let (middle, half) = (Vec3::new(x, y, z), Vec3::new(half_x, half_y, half_z));
+        let sides = [
+            (self.left, Vec3::NEG_X),
+            (self.right, Vec3::X),
+            (self.back, Vec3::NEG_Z),
+            (self.front, Vec3::Z),
+            (self.ceiling, Vec3::Y),
+        ];
+        let mut boxes = Vec::new();
+        for (wall, outward) in sides {
+            let Some(wall) = wall else {
+                continue;
+            };
+            // 壁に垂直な軸だけを壁の位置と厚みに置き換える
+            let axis = outward.abs();
+            let across = wall.position + outward.dot(axis) * HALF_THICKNESS;
+            boxes.push(WallBox {
+                center: middle * (Vec3::ONE - axis) + axis * across,
+                half_extents: half * (Vec3::ONE - axis) + axis * HALF_THICKNESS,
+                restitution: wall.restitution,
+            });
+        }
+        boxes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wall(position: f32) -> Option<Wall> {
+        Some(Wall { position, restitution: 0.8 })
+    }
+
+    #[test]
+    fn inner_faces_sit_on_the_configured_planes() {
+        let arena = ArenaConfig { left: wall(-10.0), right: wall(12.0), ceiling: wall(15.0), ..Default::default() };
+        let boxes = arena.boxes(-5.0);
+        assert_eq!(boxes.len(), 3);
+        assert_eq!(boxes[0].center.x + boxes[0].half_extents.x, -10.0);
+        assert_eq!(boxes[1].center.x - boxes[1].half_extents.x, 12.0);
+        assert_eq!(boxes[2].center.y - boxes[2].half_extents.y, 15.0);
+        // 左右の壁は床から天井まで
+        assert_eq!(boxes[0].center.y - boxes[0].half_extents.y, -5.0);
+        assert_eq!(boxes[0].center.y + boxes[0].half_extents.y, 15.0);
+        assert_eq!(boxes[0].restitution, 0.8);
+    }
+
+    #[test]
+    fn walls_reach_past_their_neighbours_to_close_the_corners() {
+        let arena = ArenaConfig { left: wall(-10.0), back: wall(-8.0), ..Default::default() };
+        let boxes = arena.boxes(0.0);
+        let (left, back) = (boxes[0], boxes[1]);
+        assert!(left.center.z - left.half_extents.z <= back.center.z - back.half_extents.z);
+        assert!(back.center.x - back.half_extents.x <= left.center.x - left.half_extents.x);
+    }
+
+    #[test]
+    fn missing_walls_and_restitution_default() {
+        let arena: ArenaConfig = serde_json::from_str(r#"{"ceiling": {"position": 20.0}}"#).unwrap();
+        assert!(!arena.is_empty());
+        assert_eq!(arena.ceiling.unwrap().restitution, 0.6);
+        assert!(ArenaConfig::default().is_empty());
+        assert!(ArenaConfig::default().boxes(0.0).is_empty());
+    }
+}
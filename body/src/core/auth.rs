@@ -0,0 +1,133 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeSet;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// 署名つきのパケットは、署名 (HMAC-SHA256 の 16 進 64 文字)、通し番号 (16 進 16 文字)、本文の順に並べたもの。
+// 署名は通し番号と本文をつないだものにかける
+pub const TAG_LEN: usize = 64;
+pub const SEQUENCE_LEN: usize = 16;
+// 通し番号は送った時刻 (UNIX 時刻のマイクロ秒)。受け取った時刻とこれ以上離れたものは捨てる。機械どうしの時計のずれもこの中に収める
+pub const FRESHNESS_WINDOW: u64 = 30_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Signed<'a> {
+    pub sequence: u64,
+    pub tag: [u8; 32],
+    pub payload: &'a [u8],
+}
+
+// 署名が正しければ通し番号と本文を返す
+pub fn verify<'a>(key: &[u8], datagram: &'a [u8]) -> Option<Signed<'a>> {
+    if datagram.len() < TAG_LEN + SEQUENCE_LEN {
+        return None;
+    }
+    let (tag, signed) = datagram.split_at(TAG_LEN);
+    let tag = decode_hex(tag)?;
+    let mut mac = HmacSha256::new_from_slice(key).ok()?;
+    mac.update(signed);
+    // 比較にかかる時間から署名を推測されないよう、一致判定はライブラリに任せる
+    mac.verify_slice(&tag).ok()?;
+    let (sequence, payload) = signed.split_at(SEQUENCE_LEN);
+    let sequence = u64::from_str_radix(std::str::from_utf8(sequence).ok()?, 16).ok()?;
+    Some(Signed { sequence, tag: tag.try_into().ok()?, payload })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    // 時刻が窓の外 (古い録画の流し直しか、時計のずれ)
+    Stale,
+    // 窓の中で同じパケットが二度来た
+    Replayed,
+}
+
+// 正しく署名されたパケットでも、録って流し直されたものは受け付けない
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    // 窓の中で受け付けたパケット。送り手が複数でも区別できるよう署名ごと覚える
+    seen: BTreeSet<(u64, [u8; 32])>,
+}
+
+impl ReplayGuard {
+    pub fn check(&mut self, signed: &Signed, now: u64) -> Result<(), Rejection> {
+        if signed.sequence.abs_diff(now) > FRESHNESS_WINDOW {
+            return Err(Rejection::Stale);
+        }
+        self.seen = self.seen.split_off(&(now.saturating_sub(FRESHNESS_WINDOW), [0; 32]));
+        if !self.seen.insert((signed.sequence, signed.tag)) {
+            return Err(Rejection::Replayed);
+        }
+        Ok(())
+    }
+}
+
+fn decode_hex(text: &[u8]) -> Option<Vec<u8>> {
+    let digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    text.chunks(2).map(|pair| Some((digit(pair[0])? << 4) | digit(*pair.get(1)?)?)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000_000_000;
+
+    // 送る側 (vision/main.py) と同じ署名
+    fn sign(key: &[u8], payload: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac.finalize().into_bytes().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn datagram(key: &[u8], sequence: u64, payload: &[u8]) -> Vec<u8> {
+        let signed = [format!("{sequence:016x}").as_bytes(), payload].concat();
+        [sign(key, &signed).as_bytes(), &signed].concat()
+    }
+
+    #[test]
+    fn matches_rfc4231_vector() {
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn signed_packet_round_trips() {
+        let payload = br#"{"hands":[]}"#;
+        let datagram = datagram(b"secret", NOW, payload);
+        let signed = verify(b"secret", &datagram).unwrap();
+        assert_eq!((signed.sequence, signed.payload), (NOW, &payload[..]));
+    }
+
+    #[test]
+    fn tampered_or_unsigned_packets_are_rejected() {
+        let payload = br#"{"hands":[]}"#;
+        let mut datagram = datagram(b"secret", NOW, payload);
+        assert_eq!(verify(b"other", &datagram), None);
+        assert_eq!(verify(b"secret", payload), None);
+        // 通し番号だけ書き換えても署名が合わない
+        datagram[TAG_LEN] = b'f';
+        assert_eq!(verify(b"secret", &datagram), None);
+        let mut datagram = self::datagram(b"secret", NOW, payload);
+        *datagram.last_mut().unwrap() = b' ';
+        assert_eq!(verify(b"secret", &datagram), None);
+    }
+
+    #[test]
+    fn repeated_and_stale_packets_are_rejected() {
+        let mut guard = ReplayGuard::default();
+        let first = datagram(b"secret", NOW, b"{}");
+        let first = verify(b"secret", &first).unwrap();
+        assert_eq!(guard.check(&first, NOW), Ok(()));
+        assert_eq!(guard.check(&first, NOW + 1_000), Err(Rejection::Replayed));
+        // 同じ時刻でも別の送り手の別のパケットは通す
+        let other = datagram(b"secret", NOW, br#"{"hands":[]}"#);
+        assert_eq!(guard.check(&verify(b"secret", &other).unwrap(), NOW), Ok(()));
+        // 窓を過ぎて覚えていなくても、古いので捨てる
+        assert_eq!(guard.check(&first, NOW + FRESHNESS_WINDOW + 1), Err(Rejection::Stale));
+        let early = datagram(b"secret", NOW + FRESHNESS_WINDOW * 2, b"{}");
+        assert_eq!(guard.check(&verify(b"secret", &early).unwrap(), NOW), Err(Rejection::Stale));
+    }
+}
@@ -0,0 +1,79 @@
+use glam::{Quat, Vec3};
+
+// 中心から表面までがこれ以上の物体は重く、片手では持てない
+pub const HEAVY_RADIUS: f32 = 2.0;
+// 掴み点の向きのなす角の cos がこれ以下なら別の面を掴んでいる
+const MAX_FACE_DOT: f32 = 0.0;
+// 手の間隔が掴んだときからこれだけ変わるとぐらつきが最大になる
+const WOBBLE_STRAIN: f32 = 1.5;
+const STEADY_DAMPING: f32 = 1.0;
+const WOBBLY_DAMPING: f32 = 0.15;
+
+// 物体の中心から見た二つの掴み点 (物体の座標系)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwoHandCarry {
+    pub local_a: Vec3,
+    pub local_b: Vec3,
+}
+
+impl TwoHandCarry {
+    // 同じ面を両手で掴んでも支えにならないので、中心を挟んだ別の面のときだけ運べる
+    pub fn new(local_a: Vec3, local_b: Vec3) -> Option<Self> {
+        let (a, b) = (local_a.try_normalize()?, local_b.try_normalize()?);
+        (a.dot(b) <= MAX_FACE_DOT).then_some(Self { local_a, local_b })
+    }
+
+    // 両手の位置から物体の中心と向きを求める。掴み点を結ぶ向きを手を結ぶ向きに合わせ、
+    // その軸まわりのねじれは前の向きを引き継ぐ
+    pub fn solve(&self, hand_a: Vec3, hand_b: Vec3, previous: Quat) -> (Vec3, Quat) {
+        let grips = previous * (self.local_b - self.local_a);
+        let rotation = match ((hand_b - hand_a).try_normalize(), grips.try_normalize()) {
+            (Some(hands), Some(grips)) => (Quat::from_rotation_arc(grips, hands) * previous).normalize(),
+            _ => previous,
+        };
+        let center = (hand_a + hand_b) / 2.0 - rotation * (self.local_a + self.local_b) / 2.0;
+        (center, rotation)
+    }
+
+    // 手の間隔と掴み点の間隔の差。両手がばらばらに動くほど大きい
+    pub fn strain(&self, hand_a: Vec3, hand_b: Vec3) -> f32 {
+        (hand_a.distance(hand_b) - self.local_a.distance(self.local_b)).abs()
+    }
+}
+
+// 手のずれが大きいほど減衰を弱めて物体を揺らす
+pub fn damping_ratio(strain: f32) -> f32 {
+    let t = (strain / WOBBLE_STRAIN).clamp(0.0, 1.0);
+    STEADY_DAMPING + (WOBBLY_DAMPING - STEADY_DAMPING) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_grips_on_different_faces() {
+        assert!(TwoHandCarry::new(Vec3::NEG_X * 2.5, Vec3::X * 2.5).is_some());
+        assert!(TwoHandCarry::new(Vec3::NEG_X * 2.5, Vec3::Z * 2.5).is_some());
+        assert!(TwoHandCarry::new(Vec3::new(2.5, 0.5, 0.0), Vec3::new(2.5, -0.5, 0.0)).is_none());
+        assert!(TwoHandCarry::new(Vec3::ZERO, Vec3::X).is_none());
+    }
+
+    #[test]
+    fn solves_pose_between_hands() {
+        let carry = TwoHandCarry::new(Vec3::NEG_X * 2.5, Vec3::X * 2.5).unwrap();
+        let (center, rotation) = carry.solve(Vec3::new(0.0, 3.0, -2.5), Vec3::new(0.0, 3.0, 2.5), Quat::IDENTITY);
+        assert!(center.distance(Vec3::new(0.0, 3.0, 0.0)) < 1e-5);
+        assert!((rotation * Vec3::X - Vec3::Z).length() < 1e-5);
+        assert!(carry.strain(Vec3::new(0.0, 3.0, -2.5), Vec3::new(0.0, 3.0, 2.5)) < 1e-5);
+    }
+
+    #[test]
+    fn wobbles_more_as_hands_disagree() {
+        let carry = TwoHandCarry::new(Vec3::NEG_X, Vec3::X).unwrap();
+        let strain = carry.strain(Vec3::NEG_X * 2.0, Vec3::X * 2.0);
+        assert!((strain - 2.0).abs() < 1e-5);
+        assert_eq!(damping_ratio(0.0), 1.0);
+        assert!(damping_ratio(strain) < damping_ratio(0.5));
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+pub const SIZE: i32 = 8;
+// 列と行。行 0 が先手 (黒) の手前
+pub type Square = (i32, i32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Player {
+    Dark,
+    Light,
+}
+
+impl Player {
+    pub fn other(self) -> Player {
+        match self {
+            Player::Dark => Player::Light,
+            Player::Light => Player::Dark,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Player::Dark => "Dark",
+            Player::Light => "Light",
+        }
+    }
+
+    // 前へ進むときの行の増え方
+    fn forward(self) -> i32 {
+        match self {
+            Player::Dark => 1,
+            Player::Light => -1,
+        }
+    }
+
+    fn king_row(self) -> i32 {
+        match self {
+            Player::Dark => SIZE - 1,
+            Player::Light => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub owner: Player,
+    pub king: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Step,
+    Jump { captured: Square },
+}
+
+// 動かせなかった理由。画面にそのまま出す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMove {
+    GameOver,
+    NoPiece,
+    NotYourTurn,
+    // 連続で跳んでいる途中の駒しか動かせない
+    MustContinueJump,
+    // 取れる駒があるときは取らなければならない
+    MustCapture,
+    NotALegalSquare,
+}
+
+impl IllegalMove {
+    pub fn message(self) -> &'static str {
+        match self {
+            IllegalMove::GameOver => "The game is over",
+            IllegalMove::NoPiece => "There is no piece there",
+            IllegalMove::NotYourTurn => "Not your turn",
+            IllegalMove::MustContinueJump => "Keep jumping with the same piece",
+            IllegalMove::MustCapture => "A capture is available and must be taken",
+            IllegalMove::NotALegalSquare => "That square is not a legal move",
+        }
+    }
+}
+
+pub fn on_board((column, row): Square) -> bool {
+    (0..SIZE).contains(&column) && (0..SIZE).contains(&row)
+}
+
+// 駒を置くのは暗いマスだけ
+pub fn is_dark(square: Square) -> bool {
+    (square.0 + square.1) % 2 == 0
+}
+
+// 取れる駒があれば取らなければならない、取った駒がまだ跳べれば続けて跳ぶ、の 2 つだけ入れたチェッカー
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkers {
+    pieces: HashMap<Square, Piece>,
+    pub turn: Player,
+    pub chaining: Option<Square>,
+    pub winner: Option<Player>,
+}
+
+impl Default for Checkers {
+    fn default() -> Self {
+        let mut pieces = HashMap::new();
+        for row in 0..SIZE {
+            let owner = match row {
+                0..=2 => Player::Dark,
+                5..=7 => Player::Light,
+                _ => continue,
+            };
+            for column in (0..SIZE).filter(|c| is_dark((*c, row))) {
+                pieces.insert((column, row), Piece { owner, king: false });
+            }
+        }
+        Self { pieces, turn: Player::Dark, chaining: None, winner: None }
+    }
+}
+
+impl Checkers {
+    pub fn piece(&self, square: Square) -> Option<Piece> {
+        self.pieces.get(&square).copied()
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        self.pieces.iter().map(|(s, p)| (*s, *p))
+    }
+
+    fn directions(piece: Piece) -> Vec<(i32, i32)> {
+        let forward = piece.owner.forward();
+        let mut directions = vec![(-1, forward), (1, forward)];
+        if piece.king {
+            directions.extend([(-1, -forward), (1, -forward)]);
+        }
+        directions
+    }
+
+    // (跳んだ先, 取る駒)
+    fn jumps_from(&self, from: Square) -> Vec<(Square, Square)> {
+        let Some(piece) = self.piece(from) else {
+            return Vec::new();
+        };
+        Self::directions(piece)
+            .into_iter()
+            .filter_map(|(dc, dr)| {
+                let over = (from.0 + dc, from.1 + dr);
+                let to = (from.0 + dc * 2, from.1 + dr * 2);
+                let enemy = self.piece(over).is_some_and(|p| p.owner != piece.owner);
+                (enemy && on_board(to) && self.piece(to).is_none()).then_some((to, over))
+            })
+            .collect()
+    }
+
+    fn steps_from(&self, from: Square) -> Vec<Square> {
+        let Some(piece) = self.piece(from) else {
+            return Vec::new();
+        };
+        Self::directions(piece)
+            .into_iter()
+            .map(|(dc, dr)| (from.0 + dc, from.1 + dr))
+            .filter(|to| on_board(*to) && self.piece(*to).is_none())
+            .collect()
+    }
+
+    fn can_capture(&self, player: Player) -> bool {
+        self.pieces.iter().any(|(square, piece)| piece.owner == player && !self.jumps_from(*square).is_empty())
+    }
+
+    // 手番の側がこの駒を持ち上げてよいか
+    pub fn can_lift(&self, from: Square) -> Result<(), IllegalMove> {
+        if self.winner.is_some() {
+            return Err(IllegalMove::GameOver);
+        }
+        let piece = self.piece(from).ok_or(IllegalMove::NoPiece)?;
+        if piece.owner != self.turn {
+            return Err(IllegalMove::NotYourTurn);
+        }
+        if self.chaining.is_some_and(|s| s != from) {
+            return Err(IllegalMove::MustContinueJump);
+        }
+        Ok(())
+    }
+
+    pub fn check(&self, from: Square, to: Square) -> Result<Move, IllegalMove> {
+        self.can_lift(from)?;
+        if let Some((_, captured)) = self.jumps_from(from).into_iter().find(|(t, _)| *t == to) {
+            return Ok(Move::Jump { captured });
+        }
+        if self.chaining.is_some() || self.can_capture(self.turn) {
+            return Err(IllegalMove::MustCapture);
+        }
+        if self.steps_from(from).contains(&to) {
+            return Ok(Move::Step);
+        }
+        Err(IllegalMove::NotALegalSquare)
+    }
+
+    // 持ち上げた駒を置けるマス
+    pub fn legal_targets(&self, from: Square) -> Vec<Square> {
+        let candidates = self.jumps_from(from).into_iter().map(|(to, _)| to).chain(self.steps_from(from));
+        candidates.filter(|to| self.check(from, *to).is_ok()).collect()
+    }
+
+    // 動かして、成り・連続跳び・手番の交代・勝敗まで進める
+    pub fn play(&mut self, from: Square, to: Square) -> Result<Move, IllegalMove> {
+        let kind = self.check(from, to)?;
+        let mut piece = self.pieces.remove(&from).ok_or(IllegalMove::NoPiece)?;
+        let promoted = !piece.king && to.1 == piece.owner.king_row();
+        piece.king |= promoted;
+        self.pieces.insert(to, piece);
+        // 成ったらそこで手番を終える
+        self.chaining = None;
+        if let Move::Jump { captured } = kind {
+            self.pieces.remove(&captured);
+            if !promoted && !self.jumps_from(to).is_empty() {
+                self.chaining = Some(to);
+                return Ok(kind);
+            }
+        }
+        self.turn = self.turn.other();
+        let can_move = self
+            .pieces
+            .iter()
+            .filter(|(_, p)| p.owner == self.turn)
+            .any(|(s, _)| !self.jumps_from(*s).is_empty() || !self.steps_from(*s).is_empty());
+        if !can_move {
+            self.winner = Some(self.turn.other());
+        }
+        Ok(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(pieces: &[(Square, Player, bool)], turn: Player) -> Checkers {
+        let pieces = pieces.iter().map(|(s, owner, king)| (*s, Piece { owner: *owner, king: *king })).collect();
+        Checkers { pieces, turn, chaining: None, winner: None }
+    }
+
+    #[test]
+    fn opening_has_twelve_pieces_each_and_dark_moves_first() {
+        let game = Checkers::default();
+        for player in [Player::Dark, Player::Light] {
+            assert_eq!(game.pieces().filter(|(_, p)| p.owner == player).count(), 12);
+        }
+        assert_eq!(game.check((1, 5), (0, 4)), Err(IllegalMove::NotYourTurn));
+        assert_eq!(game.check((2, 2), (3, 3)), Ok(Move::Step));
+        assert_eq!(game.check((2, 2), (2, 3)), Err(IllegalMove::NotALegalSquare));
+    }
+
+    #[test]
+    fn capture_is_forced_and_chains() {
+        let mut game = board(
+            &[((2, 2), Player::Dark, false), ((3, 3), Player::Light, false), ((5, 5), Player::Light, false)],
+            Player::Dark,
+        );
+        assert_eq!(game.check((2, 2), (1, 3)), Err(IllegalMove::MustCapture));
+        assert_eq!(game.play((2, 2), (4, 4)), Ok(Move::Jump { captured: (3, 3) }));
+        assert_eq!(game.chaining, Some((4, 4)));
+        assert_eq!(game.turn, Player::Dark);
+        assert_eq!(game.play((4, 4), (6, 6)), Ok(Move::Jump { captured: (5, 5) }));
+        assert_eq!(game.winner, Some(Player::Dark));
+    }
+
+    #[test]
+    fn reaching_the_far_row_crowns_and_kings_move_back() {
+        let mut game = board(&[((2, 6), Player::Dark, false), ((5, 1), Player::Light, false)], Player::Dark);
+        game.play((2, 6), (1, 7)).unwrap();
+        assert!(game.piece((1, 7)).unwrap().king);
+        game.play((5, 1), (4, 0)).unwrap();
+        assert_eq!(game.check((1, 7), (2, 6)), Ok(Move::Step));
+    }
+}
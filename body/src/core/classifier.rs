@@ -0,0 +1,100 @@
+use crate::core::gesture::{classify_landmarks, Gesture, WRIST};
+use crate::core::mapping::landmark;
+use crate::packet::Landmark;
+
+pub const LANDMARK_COUNT: usize = 21;
+pub const TENSOR_LEN: usize = LANDMARK_COUNT * 3;
+
+// 中指の付け根
+const MIDDLE_MCP: usize = 9;
+
+// 分類器への入力。手首を原点にし、手首〜中指付け根の長さで割った座標を
+// id 順に (x, y, z) で 21 点分並べたもの。手の位置や大きさに左右されない
+#[derive(Debug, Clone, PartialEq)]
+pub struct LandmarkTensor(pub [f32; TENSOR_LEN]);
+
+impl LandmarkTensor {
+    pub fn from_landmarks(landmarks: &[Landmark]) -> Option<Self> {
+        let wrist = landmark(landmarks, WRIST)?;
+        let mcp = landmark(landmarks, MIDDLE_MCP)?;
+        let scale = ((mcp.x - wrist.x).powi(2) + (mcp.y - wrist.y).powi(2) + (mcp.z - wrist.z).powi(2)).sqrt();
+        if scale <= f32::EPSILON {
+            return None;
+        }
+        let mut values = [0.0; TENSOR_LEN];
+        for id in 0..LANDMARK_COUNT {
+            let lm = landmark(landmarks, id)?;
+            values[id * 3] = (lm.x - wrist.x) / scale;
+            values[id * 3 + 1] = (lm.y - wrist.y) / scale;
+            values[id * 3 + 2] = (lm.z - wrist.z) / scale;
+        }
+        Some(Self(values))
+    }
+
+    pub fn landmarks(&self) -> Vec<Landmark> {
+        self.0
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(id, v)| Landmark { id, x: v[0], y: v[1], z: v[2] })
+            .collect()
+    }
+}
+
+// ラベルごとの確率 (合計 1) を返す
+pub trait GestureClassifier: Send + Sync {
+    fn name(&self) -> &str;
+    fn classify(&self, input: &LandmarkTensor) -> Vec<(String, f32)>;
+}
+
+pub fn best_label(probabilities: &[(String, f32)]) -> Option<&str> {
+    probabilities
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(label, _)| label.as_str())
+}
+
+// 指の折れ具合による従来の判定
+pub struct HeuristicClassifier;
+
+impl GestureClassifier for HeuristicClassifier {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn classify(&self, input: &LandmarkTensor) -> Vec<(String, f32)> {
+        one_hot(classify_landmarks(&input.landmarks()))
+    }
+}
+
+// 1 つのジェスチャーだけを確率 1 とする
+pub fn one_hot(gesture: Gesture) -> Vec<(String, f32)> {
+    Gesture::ALL
+        .iter()
+        .map(|g| (g.label().to_string(), if *g == gesture { 1.0 } else { 0.0 }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    #[test]
+    fn tensor_is_wrist_relative_and_scaled() {
+        let hand = fixtures::hand("open_right");
+        let tensor = LandmarkTensor::from_landmarks(&hand.landmarks).unwrap();
+        assert_eq!(&tensor.0[0..3], &[0.0, 0.0, 0.0]);
+        let mcp = &tensor.0[MIDDLE_MCP * 3..MIDDLE_MCP * 3 + 3];
+        let length = (mcp[0].powi(2) + mcp[1].powi(2) + mcp[2].powi(2)).sqrt();
+        assert!((length - 1.0).abs() < 1e-5, "length {length}");
+    }
+
+    #[test]
+    fn heuristic_matches_landmark_rules() {
+        for (name, expected) in [("open_right", Gesture::Open), ("fist_right", Gesture::Fist), ("claw_right", Gesture::Claw)] {
+            let tensor = LandmarkTensor::from_landmarks(&fixtures::hand(name).landmarks).unwrap();
+            let probabilities = HeuristicClassifier.classify(&tensor);
+            assert_eq!(best_label(&probabilities), Some(expected.label()), "{name}");
+        }
+    }
+}
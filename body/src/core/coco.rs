@@ -0,0 +1,133 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+pub const HAND_CATEGORY: u32 = 1;
+
+// COCO のキーポイント形式に合わせた注釈。ジェスチャーなどは独自の項目として足す
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CocoDataset {
+    pub info: CocoInfo,
+    pub images: Vec<CocoImage>,
+    pub annotations: Vec<CocoAnnotation>,
+    pub categories: Vec<CocoCategory>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CocoInfo {
+    pub description: String,
+    pub date_created: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CocoImage {
+    pub id: u64,
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CocoAnnotation {
+    pub id: u64,
+    pub image_id: u64,
+    pub category_id: u32,
+    // x, y, v の並び。v は 0: 写っていない (カメラの後ろ), 1: 画面外, 2: 画面内
+    pub keypoints: Vec<f32>,
+    pub num_keypoints: usize,
+    pub bbox: [f32; 4],
+    pub area: f32,
+    pub iscrowd: u8,
+    pub gesture: String,
+    pub side: String,
+    pub client: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CocoCategory {
+    pub id: u32,
+    pub name: String,
+    pub supercategory: String,
+    pub keypoints: Vec<String>,
+    // 1 始まりのキーポイント番号の組
+    pub skeleton: Vec<[usize; 2]>,
+}
+
+impl CocoCategory {
+    pub fn hand(names: &[&str], connections: &[(usize, usize)]) -> Self {
+        Self {
+            id: HAND_CATEGORY,
+            name: "hand".to_string(),
+            supercategory: "person".to_string(),
+            keypoints: names.iter().map(|n| n.to_string()).collect(),
+            skeleton: connections.iter().map(|(a, b)| [a + 1, b + 1]).collect(),
+        }
+    }
+}
+
+// 画面上の位置 (画面外なら in_view = false)。カメラの後ろにある点は None
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectedPoint {
+    pub position: Vec2,
+    pub in_view: bool,
+}
+
+impl CocoAnnotation {
+    pub fn from_points(id: u64, image_id: u64, points: &[Option<ProjectedPoint>]) -> Self {
+        let mut keypoints = Vec::with_capacity(points.len() * 3);
+        for point in points {
+            match point {
+                Some(p) => keypoints.extend([p.position.x, p.position.y, if p.in_view { 2.0 } else { 1.0 }]),
+                None => keypoints.extend([0.0, 0.0, 0.0]),
+            }
+        }
+        // 枠は画面内に写っている点だけから求める
+        let visible: Vec<Vec2> = points.iter().flatten().filter(|p| p.in_view).map(|p| p.position).collect();
+        let bbox = match visible.first() {
+            Some(first) => {
+                let (min, max) = visible.iter().fold((*first, *first), |(min, max), p| (min.min(*p), max.max(*p)));
+                [min.x, min.y, max.x - min.x, max.y - min.y]
+            }
+            None => [0.0; 4],
+        };
+        Self {
+            id,
+            image_id,
+            category_id: HAND_CATEGORY,
+            keypoints,
+            num_keypoints: visible.len(),
+            bbox,
+            area: bbox[2] * bbox[3],
+            iscrowd: 0,
+            gesture: String::new(),
+            side: String::new(),
+            client: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotation_bbox_covers_visible_points_only() {
+        let points = [
+            Some(ProjectedPoint { position: Vec2::new(10.0, 20.0), in_view: true }),
+            Some(ProjectedPoint { position: Vec2::new(30.0, 5.0), in_view: true }),
+            Some(ProjectedPoint { position: Vec2::new(-50.0, 5.0), in_view: false }),
+            None,
+        ];
+        let annotation = CocoAnnotation::from_points(7, 3, &points);
+        assert_eq!(annotation.num_keypoints, 2);
+        assert_eq!(annotation.bbox, [10.0, 5.0, 20.0, 15.0]);
+        assert_eq!(annotation.area, 300.0);
+        assert_eq!(&annotation.keypoints[6..], &[-50.0, 5.0, 1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn skeleton_is_one_based() {
+        let category = CocoCategory::hand(&["a", "b", "c"], &[(0, 1), (1, 2)]);
+        assert_eq!(category.skeleton, vec![[1, 2], [2, 3]]);
+        assert_eq!(category.keypoints.len(), 3);
+    }
+}
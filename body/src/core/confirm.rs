@@ -0,0 +1,63 @@
+// 取り消せない操作 (シーンの消去・物体の削除など) を実行する前に、ジェスチャーを保たせる時間 (秒)
+pub const CONFIRM_HOLD: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Confirm {
+    // 保っている途中。0..1 の進み具合
+    Holding(f32),
+    // この呼び出しで保ちきった。以後は保ち直すまで返さない
+    Confirmed,
+    // 保ちきって実行済み
+    Done,
+}
+
+// ジェスチャーを一定時間保ったら一度だけ実行させる
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HoldToConfirm {
+    since: f32,
+    done: bool,
+}
+
+impl HoldToConfirm {
+    pub fn start(now: f32) -> Self {
+        Self { since: now, done: false }
+    }
+
+    pub fn update(&mut self, now: f32, hold: f32) -> Confirm {
+        if self.done {
+            return Confirm::Done;
+        }
+        let progress = if hold > 0.0 { ((now - self.since) / hold).clamp(0.0, 1.0) } else { 1.0 };
+        if progress >= 1.0 {
+            self.done = true;
+            Confirm::Confirmed
+        } else {
+            Confirm::Holding(progress)
+        }
+    }
+
+    pub fn progress(&self, now: f32, hold: f32) -> f32 {
+        if self.done || hold <= 0.0 { 1.0 } else { ((now - self.since) / hold).clamp(0.0, 1.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirms_once_after_hold() {
+        let mut hold = HoldToConfirm::start(10.0);
+        assert_eq!(hold.update(10.0, CONFIRM_HOLD), Confirm::Holding(0.0));
+        assert!(matches!(hold.update(10.75, CONFIRM_HOLD), Confirm::Holding(p) if (p - 0.5).abs() < 1e-5));
+        assert_eq!(hold.update(11.5, CONFIRM_HOLD), Confirm::Confirmed);
+        assert_eq!(hold.update(12.0, CONFIRM_HOLD), Confirm::Done);
+        assert_eq!(hold.progress(12.0, CONFIRM_HOLD), 1.0);
+    }
+
+    #[test]
+    fn zero_hold_confirms_immediately() {
+        let mut hold = HoldToConfirm::start(3.0);
+        assert_eq!(hold.update(3.0, 0.0), Confirm::Confirmed);
+    }
+}
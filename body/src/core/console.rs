@@ -0,0 +1,74 @@
+use std::str::FromStr;
+
+// 1 行を命令名と引数に分ける。"..." で囲めば空白を含む引数にできる
+pub fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(current);
+    }
+    let mut words = words.into_iter();
+    let name = words.next()?.to_lowercase();
+    Some((name, words.collect()))
+}
+
+// index 番目の引数を読む。無ければ default、読めなければ何が悪いかを返す
+pub fn arg_or<T: FromStr>(args: &[String], index: usize, name: &str, default: T) -> Result<T, String> {
+    match args.get(index) {
+        Some(text) => text.parse().map_err(|_| format!("{name}: cannot read {text:?}")),
+        None => Ok(default),
+    }
+}
+
+pub fn arg<T: FromStr>(args: &[String], index: usize, name: &str) -> Result<T, String> {
+    let text = args.get(index).ok_or_else(|| format!("missing {name}"))?;
+    text.parse().map_err(|_| format!("{name}: cannot read {text:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_name_and_arguments() {
+        let (name, args) = split_line("  Spawn cube   2 ").unwrap();
+        assert_eq!(name, "spawn");
+        assert_eq!(args, vec!["cube", "2"]);
+        assert!(split_line("   ").is_none());
+    }
+
+    #[test]
+    fn quotes_keep_spaces_together() {
+        let (_, args) = split_line(r#"load "my scene" """#).unwrap();
+        assert_eq!(args, vec!["my scene", ""]);
+    }
+
+    #[test]
+    fn reads_typed_arguments() {
+        let args = vec!["-4".to_string(), "big".to_string()];
+        assert_eq!(arg::<f32>(&args, 0, "gravity"), Ok(-4.0));
+        assert!(arg::<f32>(&args, 1, "size").unwrap_err().contains("big"));
+        assert!(arg::<f32>(&args, 2, "size").unwrap_err().contains("missing"));
+        assert_eq!(arg_or(&args, 2, "size", 2.0), Ok(2.0));
+    }
+}
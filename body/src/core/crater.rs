@@ -0,0 +1,128 @@
+// 床タイル 1 枚分の凹み。格子点ごとの高さ (負で凹み、正で縁の盛り上がり) を持つ
+
+// 1 辺あたりの格子点の数
+pub const RESOLUTION: usize = 33;
+const MAX_DEPTH: f32 = 0.8;
+const RIM_HEIGHT: f32 = 0.3;
+// 凹みの外側でこの倍率の半径まで縁を盛り上げる
+const RIM_WIDTH: f32 = 1.5;
+// これより小さい起伏は平らとみなす
+const FLAT: f32 = 1e-3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CraterTile {
+    size: f32,
+    heights: Vec<f32>,
+}
+
+impl CraterTile {
+    pub fn new(size: f32) -> Self {
+        Self { size, heights: vec![0.0; RESOLUTION * RESOLUTION] }
+    }
+
+    // 格子点 (i, j) のタイル中心からの位置 (x, z)
+    pub fn point(&self, i: usize, j: usize) -> [f32; 2] {
+        let step = self.size / (RESOLUTION - 1) as f32;
+        [-self.size / 2.0 + i as f32 * step, -self.size / 2.0 + j as f32 * step]
+    }
+
+    pub fn height(&self, i: usize, j: usize) -> f32 {
+        self.heights[j * RESOLUTION + i]
+    }
+
+    // タイル中心から center だけずれた位置に、半径 radius・深さ depth のお椀形の凹みを押す
+    pub fn stamp(&mut self, center: [f32; 2], radius: f32, depth: f32) {
+        if radius <= 0.0 {
+            return;
+        }
+        for j in 0..RESOLUTION {
+            for i in 0..RESOLUTION {
+                let [x, z] = self.point(i, j);
+                let r = ((x - center[0]).powi(2) + (z - center[1]).powi(2)).sqrt() / radius;
+                let offset = if r < 1.0 {
+                    -depth * (1.0 - r * r)
+                } else if r < RIM_WIDTH {
+                    let t = (r - 1.0) / (RIM_WIDTH - 1.0);
+                    depth * RIM_HEIGHT * (t * std::f32::consts::PI).sin()
+                } else {
+                    continue;
+                };
+                let height = &mut self.heights[j * RESOLUTION + i];
+                *height = (*height + offset).clamp(-MAX_DEPTH, MAX_DEPTH * RIM_HEIGHT);
+            }
+        }
+    }
+
+    // 起伏を時定数 1 / rate で平らに戻す。まだ起伏が残っていれば true
+    pub fn heal(&mut self, rate: f32, dt: f32) -> bool {
+        let keep = (-rate * dt).exp();
+        let mut deformed = false;
+        for height in &mut self.heights {
+            *height *= keep;
+            if height.abs() < FLAT {
+                *height = 0.0;
+            } else {
+                deformed = true;
+            }
+        }
+        deformed
+    }
+
+    // 隣の格子点との高さの差から求めた上向きの法線
+    pub fn normal(&self, i: usize, j: usize) -> [f32; 3] {
+        let step = self.size / (RESOLUTION - 1) as f32;
+        let h = |i: usize, j: usize| self.height(i.min(RESOLUTION - 1), j.min(RESOLUTION - 1));
+        let dx = (h(i + 1, j) - h(i.saturating_sub(1), j)) / (step * 2.0);
+        let dz = (h(i, j + 1) - h(i, j.saturating_sub(1))) / (step * 2.0);
+        let length = (dx * dx + 1.0 + dz * dz).sqrt();
+        [-dx / length, 1.0 / length, -dz / length]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CENTER: usize = RESOLUTION / 2;
+
+    #[test]
+    fn stamp_is_deepest_at_center_with_raised_rim() {
+        let mut tile = CraterTile::new(10.0);
+        tile.stamp([0.0, 0.0], 2.0, 0.4);
+        assert!((tile.height(CENTER, CENTER) + 0.4).abs() < 1e-5);
+        // 中心から 2.5 離れた点は縁の上
+        let step = 10.0 / (RESOLUTION - 1) as f32;
+        let rim = (2.5 / step).round() as usize;
+        assert!(tile.height(CENTER + rim, CENTER) > 0.0);
+        assert_eq!(tile.height(0, 0), 0.0);
+    }
+
+    #[test]
+    fn repeated_impacts_are_capped() {
+        let mut tile = CraterTile::new(10.0);
+        for _ in 0..20 {
+            tile.stamp([0.0, 0.0], 2.0, 0.4);
+        }
+        assert!(tile.height(CENTER, CENTER) >= -MAX_DEPTH);
+    }
+
+    #[test]
+    fn heals_back_to_flat() {
+        let mut tile = CraterTile::new(10.0);
+        tile.stamp([1.0, -1.0], 2.0, 0.4);
+        assert!(tile.heal(0.5, 0.1));
+        let mut steps = 0;
+        while tile.heal(0.5, 0.1) {
+            steps += 1;
+            assert!(steps < 1000);
+        }
+        assert_eq!(tile, CraterTile::new(10.0));
+    }
+
+    #[test]
+    fn flat_tile_points_up() {
+        let tile = CraterTile::new(10.0);
+        assert_eq!(tile.normal(CENTER, CENTER), [0.0, 1.0, 0.0]);
+        assert_eq!(tile.normal(0, RESOLUTION - 1), [0.0, 1.0, 0.0]);
+    }
+}
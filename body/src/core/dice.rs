@@ -0,0 +1,65 @@
+use glam::{Quat, Vec3};
+
+// 各面の外向き法線 (さいころの座標系) と目。向かい合う面の和は 7
+pub const FACES: [(Vec3, u8); 6] = [
+    (Vec3::Y, 1),
+    (Vec3::NEG_Y, 6),
+    (Vec3::X, 2),
+    (Vec3::NEG_X, 5),
+    (Vec3::Z, 3),
+    (Vec3::NEG_Z, 4),
+];
+
+// 上を向いた面の法線と鉛直の内積がこれ未満なら、何かに立てかかっていて目が決まらない
+const FLAT_ALIGNMENT: f32 = 0.9;
+
+// 止まったさいころの姿勢から上を向いている目を求める
+pub fn face_up(rotation: Quat) -> Option<u8> {
+    let (alignment, value) = FACES
+        .iter()
+        .map(|(normal, value)| ((rotation * *normal).dot(Vec3::Y), *value))
+        .max_by(|a, b| a.0.total_cmp(&b.0))?;
+    (alignment >= FLAT_ALIGNMENT).then_some(value)
+}
+
+// 面の中心を原点とし、辺の半分を 1 とした目の位置
+pub fn pip_pattern(value: u8) -> &'static [(f32, f32)] {
+    match value {
+        1 => &[(0.0, 0.0)],
+        2 => &[(-1.0, -1.0), (1.0, 1.0)],
+        3 => &[(-1.0, -1.0), (0.0, 0.0), (1.0, 1.0)],
+        4 => &[(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)],
+        5 => &[(-1.0, -1.0), (1.0, -1.0), (0.0, 0.0), (-1.0, 1.0), (1.0, 1.0)],
+        6 => &[(-1.0, -1.0), (1.0, -1.0), (-1.0, 0.0), (1.0, 0.0), (-1.0, 1.0), (1.0, 1.0)],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn upright_and_rotated_faces() {
+        assert_eq!(face_up(Quat::IDENTITY), Some(1));
+        assert_eq!(face_up(Quat::from_rotation_x(PI)), Some(6));
+        // x 軸まわりに -90° 回すと +z の面が上に来る
+        assert_eq!(face_up(Quat::from_rotation_x(-FRAC_PI_2)), Some(3));
+        assert_eq!(face_up(Quat::from_rotation_z(FRAC_PI_2)), Some(2));
+    }
+
+    #[test]
+    fn cocked_die_has_no_value() {
+        assert_eq!(face_up(Quat::from_rotation_x(FRAC_PI_4)), None);
+    }
+
+    #[test]
+    fn opposite_faces_sum_to_seven_and_patterns_match_values() {
+        for (normal, value) in FACES {
+            let opposite = FACES.iter().find(|(n, _)| *n == -normal).unwrap().1;
+            assert_eq!(value + opposite, 7);
+            assert_eq!(pip_pattern(value).len(), value as usize);
+        }
+    }
+}
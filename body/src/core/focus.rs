@@ -0,0 +1,72 @@
+// 手の動きの激しさ。手のひらの速さと回る速さを足したものを、急に変わらないようにならす
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Activity {
+    level: f32,
+}
+
+// 1 rad/秒 の回転を何ワールド単位/秒の動きとみなすか
+const SPIN_WEIGHT: f32 = 2.0;
+
+impl Activity {
+    // rate は 1 秒あたりどれだけ新しい値に寄せるか
+    pub fn update(&mut self, speed: f32, spin: f32, dt: f32, rate: f32) -> f32 {
+        let sample = speed + spin * SPIN_WEIGHT;
+        self.level += (sample - self.level) * (1.0 - (-rate * dt).exp());
+        self.level
+    }
+
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+}
+
+// 焦点を合わせる相手を選ぶ。今の相手より margin 以上激しく動く相手が出るまで乗り換えない
+pub fn choose_subject<K: Copy + PartialEq>(current: Option<K>, candidates: &[(K, f32)], margin: f32) -> Option<K> {
+    let (best, best_level) = candidates.iter().copied().max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let kept = current.and_then(|c| candidates.iter().find(|(k, _)| *k == c).copied());
+    match kept {
+        Some((kept, level)) if best_level < level + margin => Some(kept),
+        _ => Some(best),
+    }
+}
+
+// ピントの距離を target へ滑らかに寄せる
+pub fn pull_focus(current: f32, target: f32, dt: f32, rate: f32) -> f32 {
+    current + (target - current) * (1.0 - (-rate * dt).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_follows_motion_smoothly() {
+        let mut activity = Activity::default();
+        let first = activity.update(10.0, 0.0, 0.1, 5.0);
+        assert!(first > 0.0 && first < 10.0);
+        for _ in 0..50 {
+            activity.update(10.0, 1.0, 0.1, 5.0);
+        }
+        assert!((activity.level() - 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn subject_switches_only_past_the_margin() {
+        assert_eq!(choose_subject::<u8>(None, &[], 1.0), None);
+        assert_eq!(choose_subject(None, &[(1, 2.0), (2, 3.0)], 1.0), Some(2));
+        assert_eq!(choose_subject(Some(1), &[(1, 2.0), (2, 2.5)], 1.0), Some(1));
+        assert_eq!(choose_subject(Some(1), &[(1, 2.0), (2, 3.5)], 1.0), Some(2));
+        // 今の相手が見えなくなったら一番激しい相手へ
+        assert_eq!(choose_subject(Some(3), &[(1, 2.0), (2, 0.5)], 1.0), Some(1));
+    }
+
+    #[test]
+    fn focus_converges_without_overshooting() {
+        let mut distance = 5.0;
+        for _ in 0..100 {
+            distance = pull_focus(distance, 20.0, 1.0 / 60.0, 4.0);
+            assert!(distance <= 20.0);
+        }
+        assert!((distance - 20.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,112 @@
+use glam::{Vec2, Vec3};
+
+use super::mapping::{hand_size, landmark, Region, WORLD_SCALE};
+use crate::packet::Landmark;
+
+// 前腕 (手首〜肘) の長さは手首〜中指の付け根の約 2.6 倍
+pub const FOREARM_RATIO: f32 = 2.6;
+// 画像上の前腕と手の大きさの比を覚える速さ
+const RATIO_BLEND: f32 = 0.05;
+// 前腕から予想した手の大きさとの食い違いがこれを超え、しかも腕は動いていなければ指が隠れたとみなす
+const OCCLUSION_TOLERANCE: f32 = 0.35;
+const STABLE_FOREARM: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandSizeEstimate {
+    pub size: f32,
+    pub occluded: bool,
+}
+
+// 手がよく見えている間に画像上の前腕の長さと手の大きさの比を覚え、
+// 指が隠れて手の大きさが崩れたときは前腕から手の大きさ (= 奥行き) を出す
+#[derive(Debug, Clone, Default)]
+pub struct ForearmCalibration {
+    ratio: Option<f32>,
+    last_forearm: Option<f32>,
+}
+
+impl ForearmCalibration {
+    pub fn hand_size(&mut self, landmarks: &[Landmark], elbow: &Landmark) -> Option<HandSizeEstimate> {
+        let wrist = landmark(landmarks, 0)?;
+        let forearm = Vec2::new(elbow.x - wrist.x, elbow.y - wrist.y).length();
+        let stable = self.last_forearm.is_some_and(|last| last > 0.0 && (forearm / last - 1.0).abs() < STABLE_FOREARM);
+        self.last_forearm = Some(forearm);
+
+        let measured = hand_size(landmarks).filter(|s| *s > f32::EPSILON);
+        let predicted = self.ratio.map(|r| forearm / r);
+        let complete = landmarks.len() >= 21;
+        let jumped = matches!((measured, predicted), (Some(m), Some(p)) if (m / p - 1.0).abs() > OCCLUSION_TOLERANCE);
+
+        match (measured, predicted) {
+            // 手の大きさが急に変わっても腕ごと動いたのなら本当に奥行きが変わった
+            (Some(size), _) if complete && !(jumped && stable) => {
+                let ratio = forearm / size;
+                self.ratio = Some(self.ratio.map_or(ratio, |r| r + (ratio - r) * RATIO_BLEND));
+                Some(HandSizeEstimate { size, occluded: false })
+            }
+            (_, Some(size)) => Some(HandSizeEstimate { size, occluded: true }),
+            (Some(size), None) => Some(HandSizeEstimate { size, occluded: false }),
+            (None, None) => None,
+        }
+    }
+}
+
+// 手首から肘へのワールド座標のずれ。画像上の向きはそのまま使い、
+// 奥行き方向は前腕の長さ length が保たれるように解く (肘は手首より奥か手前かを z で決める)
+pub fn elbow_offset(wrist: &Landmark, elbow: &Landmark, region: &Region, scale: f32, length: f32) -> Vec3 {
+    let planar = (Vec2::new((elbow.x - wrist.x) * region.width(), (wrist.y - elbow.y) * WORLD_SCALE) * scale)
+        .clamp_length_max(length);
+    let depth = (length * length - planar.length_squared()).max(0.0).sqrt();
+    let sign = if elbow.z > wrist.z { 1.0 } else { -1.0 };
+    Vec3::new(planar.x, planar.y, depth * sign)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::mapping::LEGACY_REGION;
+
+    fn lm(id: usize, x: f32, y: f32) -> Landmark {
+        Landmark { id, x, y, z: 0.0 }
+    }
+
+    // 手首 (0) と中指の付け根 (9) だけを size 離した手。complete なら 21 点にする
+    fn hand(size: f32, complete: bool) -> Vec<Landmark> {
+        let count = if complete { 21 } else { 10 };
+        (0..count).map(|id| if id == 9 { lm(9, 0.5, 0.5 - size) } else { lm(id, 0.5, 0.5) }).collect()
+    }
+
+    #[test]
+    fn occluded_fingers_fall_back_to_forearm() {
+        let elbow = lm(99, 0.5, 0.75);
+        let mut calibration = ForearmCalibration::default();
+        let seen = calibration.hand_size(&hand(0.1, true), &elbow).unwrap();
+        assert!(!seen.occluded && (seen.size - 0.1).abs() < 1e-4);
+        // 腕は動かずに手だけ半分に縮んだ
+        let shrunk = calibration.hand_size(&hand(0.05, true), &elbow).unwrap();
+        assert!(shrunk.occluded);
+        assert!((shrunk.size - 0.1).abs() < 1e-4);
+        let missing = calibration.hand_size(&hand(0.05, false), &elbow).unwrap();
+        assert!(missing.occluded);
+    }
+
+    #[test]
+    fn moving_arm_is_trusted() {
+        let mut calibration = ForearmCalibration::default();
+        calibration.hand_size(&hand(0.1, true), &lm(99, 0.5, 0.75));
+        let nearer = calibration.hand_size(&hand(0.2, true), &lm(99, 0.5, 1.0)).unwrap();
+        assert!(!nearer.occluded && (nearer.size - 0.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn elbow_keeps_forearm_length() {
+        let wrist = lm(0, 0.5, 0.5);
+        let elbow = Landmark { z: -0.1, ..lm(99, 0.5, 0.6) };
+        let offset = elbow_offset(&wrist, &elbow, &LEGACY_REGION, 1.0, 5.0);
+        assert!((offset.length() - 5.0).abs() < 1e-4);
+        assert!(offset.y < 0.0 && offset.z < 0.0);
+        // 画像上で長すぎるときは奥行き 0 で長さに切り詰める
+        let far = elbow_offset(&wrist, &lm(99, 0.5, 1.0), &LEGACY_REGION, 1.0, 5.0);
+        assert!((far - Vec3::new(0.0, -5.0, 0.0)).length() < 1e-4);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::core::mapping::landmark;
+use crate::packet::{Landmark, OneHand};
+
+pub const WRIST: usize = 0;
+pub const FINGER_TIPS: [usize; 4] = [8, 12, 16, 20];
+pub const FINGER_MCPS: [usize; 4] = [5, 9, 13, 17];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Finger {
+    Index,
+    Middle,
+    Ring,
+    Pinky,
+}
+
+impl Finger {
+    pub fn tip(self) -> usize {
+        FINGER_TIPS[self as usize]
+    }
+
+    pub fn mcp(self) -> usize {
+        FINGER_MCPS[self as usize]
+    }
+
+    pub const ALL: [Finger; 4] = [Finger::Index, Finger::Middle, Finger::Ring, Finger::Pinky];
+}
+
+fn dist_sq(a: &Landmark, b: &Landmark) -> f32 {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)
+}
+
+// vision 側の get_gesture と同じ判定: 指先が付け根より手首に近ければ折れている
+pub fn finger_extended(landmarks: &[Landmark], finger: Finger) -> Option<bool> {
+    let wrist = landmark(landmarks, WRIST)?;
+    let tip = landmark(landmarks, finger.tip())?;
+    let mcp = landmark(landmarks, finger.mcp())?;
+    Some(dist_sq(tip, wrist) > dist_sq(mcp, wrist))
+}
+
+// 指先〜付け根の直線距離と関節に沿った長さの比 (伸びていれば 1 に近い)
+pub fn finger_straightness(landmarks: &[Landmark], finger: Finger) -> Option<f32> {
+    let mcp = landmark(landmarks, finger.mcp())?;
+    let pip = landmark(landmarks, finger.mcp() + 1)?;
+    let dip = landmark(landmarks, finger.mcp() + 2)?;
+    let tip = landmark(landmarks, finger.tip())?;
+    let along = dist_sq(mcp, pip).sqrt() + dist_sq(pip, dip).sqrt() + dist_sq(dip, tip).sqrt();
+    Some(dist_sq(mcp, tip).sqrt() / along.max(f32::EPSILON))
+}
+
+const CLAW_STRAIGHTNESS: f32 = 0.75;
+
+// 指は開いたまま第二・第三関節だけを曲げた形 (鷲づかみ)
+pub fn is_claw(landmarks: &[Landmark]) -> bool {
+    let curled = Finger::ALL
+        .iter()
+        .filter(|f| {
+            finger_extended(landmarks, **f) == Some(true)
+                && finger_straightness(landmarks, **f).is_some_and(|s| s < CLAW_STRAIGHTNESS)
+        })
+        .count();
+    curled >= 3
+}
+
+// 人差し指だけを伸ばした形 (指さし)
+pub fn is_pointing(landmarks: &[Landmark]) -> bool {
+    let extended = |finger| finger_extended(landmarks, finger);
+    extended(Finger::Index) == Some(true)
+        && [Finger::Middle, Finger::Ring, Finger::Pinky].iter().all(|f| extended(*f) == Some(false))
+}
+
+// 人差し指と中指だけを伸ばした形 (2 本指)
+pub fn is_two_fingers(landmarks: &[Landmark]) -> bool {
+    let extended = |finger| finger_extended(landmarks, finger);
+    extended(Finger::Index) == Some(true)
+        && extended(Finger::Middle) == Some(true)
+        && [Finger::Ring, Finger::Pinky].iter().all(|f| extended(*f) == Some(false))
+}
+
+// vision 側の get_gesture と同じ規則に Claw を加えたもの
+pub fn classify_landmarks(landmarks: &[Landmark]) -> Gesture {
+    let folded = Finger::ALL
+        .iter()
+        .filter(|f| finger_extended(landmarks, **f) == Some(false))
+        .count();
+    if folded >= 3 {
+        Gesture::Fist
+    } else if is_claw(landmarks) {
+        Gesture::Claw
+    } else if folded == 0 && Finger::ALL.iter().all(|f| finger_extended(landmarks, *f).is_some()) {
+        Gesture::Open
+    } else {
+        Gesture::Neutral
+    }
+}
+
+// 隠れた指を除いて判定する。補った点で折れた・伸びたを決めると形を取り違えるので、
+// 見えている指だけで決め、見えている指が 2 本未満なら決めない
+pub fn classify_visible(landmarks: &[Landmark], hidden: &[Finger]) -> Option<Gesture> {
+    let visible: Vec<Option<bool>> = Finger::ALL
+        .iter()
+        .filter(|f| !hidden.contains(f))
+        .map(|f| finger_extended(landmarks, *f))
+        .collect();
+    let known = visible.iter().flatten().count();
+    let folded = visible.iter().filter(|e| **e == Some(false)).count();
+    if known < 2 {
+        return None;
+    }
+    let gesture = if folded >= 3 || folded == known {
+        Gesture::Fist
+    } else if folded == 0 && known == visible.len() {
+        Gesture::Open
+    } else {
+        Gesture::Neutral
+    };
+    Some(gesture)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Gesture {
+    #[default]
+    Neutral,
+    Open,
+    Fist,
+    Claw,
+}
+
+impl Gesture {
+    pub const ALL: [Gesture; 4] = [Gesture::Neutral, Gesture::Open, Gesture::Fist, Gesture::Claw];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Gesture::Neutral => "Neutral",
+            Gesture::Open => "Open",
+            Gesture::Fist => "Fist",
+            Gesture::Claw => "Claw",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Self {
+        Gesture::ALL
+            .into_iter()
+            .find(|g| g.label().eq_ignore_ascii_case(label))
+            .unwrap_or_default()
+    }
+
+    // 磁石としての極性 (+1 で引き寄せ、-1 で反発)
+    pub fn polarity(self) -> Option<f32> {
+        match self {
+            Gesture::Fist => Some(1.0),
+            Gesture::Claw => Some(-1.0),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpsShape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl RpsShape {
+    pub const ALL: [RpsShape; 3] = [RpsShape::Rock, RpsShape::Paper, RpsShape::Scissors];
+
+    pub fn beats(self, other: RpsShape) -> bool {
+        matches!(
+            (self, other),
+            (RpsShape::Rock, RpsShape::Scissors)
+                | (RpsShape::Paper, RpsShape::Rock)
+                | (RpsShape::Scissors, RpsShape::Paper)
+        )
+    }
+
+    pub fn counter(self) -> RpsShape {
+        match self {
+            RpsShape::Rock => RpsShape::Paper,
+            RpsShape::Paper => RpsShape::Scissors,
+            RpsShape::Scissors => RpsShape::Rock,
+        }
+    }
+}
+
+pub fn classify_rps(hand: &OneHand) -> Option<RpsShape> {
+    let extended = |finger| finger_extended(&hand.landmarks, finger);
+    let index = extended(Finger::Index)?;
+    let middle = extended(Finger::Middle)?;
+    let ring = extended(Finger::Ring)?;
+    let pinky = extended(Finger::Pinky)?;
+
+    match (index, middle, ring, pinky) {
+        (false, false, false, false) => Some(RpsShape::Rock),
+        (true, true, true, true) => Some(RpsShape::Paper),
+        (true, true, false, false) => Some(RpsShape::Scissors),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    #[test]
+    fn open_hand_has_all_fingers_extended() {
+        let hand = fixtures::hand("open_right");
+        for finger in Finger::ALL {
+            assert_eq!(finger_extended(&hand.landmarks, finger), Some(true), "{finger:?}");
+        }
+        assert!(!is_claw(&hand.landmarks));
+    }
+
+    #[test]
+    fn fist_has_all_fingers_folded() {
+        let hand = fixtures::hand("fist_right");
+        for finger in Finger::ALL {
+            assert_eq!(finger_extended(&hand.landmarks, finger), Some(false), "{finger:?}");
+        }
+    }
+
+    #[test]
+    fn pointing_needs_only_the_index_finger() {
+        for name in ["open_right", "fist_right", "scissors_right"] {
+            assert!(!is_pointing(&fixtures::hand(name).landmarks), "{name}");
+        }
+        // 握りこぶしから人差し指だけを伸ばす
+        let mut hand = fixtures::hand("fist_right");
+        let wrist = landmark(&hand.landmarks, WRIST).unwrap().clone();
+        let mcp = landmark(&hand.landmarks, Finger::Index.mcp()).unwrap().clone();
+        let tip = hand.landmarks.iter_mut().find(|l| l.id == Finger::Index.tip()).unwrap();
+        tip.x = mcp.x + (mcp.x - wrist.x);
+        tip.y = mcp.y + (mcp.y - wrist.y);
+        tip.z = mcp.z + (mcp.z - wrist.z);
+        assert!(is_pointing(&hand.landmarks));
+    }
+
+    #[test]
+    fn two_fingers_is_index_and_middle_only() {
+        assert!(is_two_fingers(&fixtures::hand("scissors_right").landmarks));
+        for name in ["open_right", "fist_right", "pointing_right"] {
+            assert!(!is_two_fingers(&fixtures::hand(name).landmarks), "{name}");
+        }
+    }
+
+    #[test]
+    fn rps_shapes_from_fixtures() {
+        assert_eq!(classify_rps(&fixtures::hand("fist_right")), Some(RpsShape::Rock));
+        assert_eq!(classify_rps(&fixtures::hand("open_right")), Some(RpsShape::Paper));
+        assert_eq!(classify_rps(&fixtures::hand("scissors_right")), Some(RpsShape::Scissors));
+    }
+
+    #[test]
+    fn claw_is_detected_from_landmarks() {
+        let hand = fixtures::hand("claw_right");
+        assert!(is_claw(&hand.landmarks));
+        assert_eq!(classify_landmarks(&hand.landmarks), Gesture::Claw);
+    }
+
+    #[test]
+    fn gestures_from_fixtures() {
+        let classify = |name| classify_landmarks(&fixtures::hand(name).landmarks);
+        assert_eq!(classify("fist_right"), Gesture::Fist);
+        assert_eq!(classify("open_right"), Gesture::Open);
+        assert_eq!(classify("scissors_right"), Gesture::Neutral);
+    }
+
+    #[test]
+    fn hidden_fingers_do_not_decide_the_shape() {
+        let open = fixtures::hand("open_right");
+        assert_eq!(classify_visible(&open.landmarks, &[Finger::Ring]), Some(Gesture::Open));
+        let fist = fixtures::hand("fist_right");
+        assert_eq!(classify_visible(&fist.landmarks, &[Finger::Index, Finger::Middle]), Some(Gesture::Fist));
+        // 隠れていると言われた指は、点が残っていても見ない
+        assert_eq!(classify_visible(&fist.landmarks, &Finger::ALL[..3]), None);
+    }
+
+    #[test]
+    fn labels_round_trip() {
+        for gesture in Gesture::ALL {
+            assert_eq!(Gesture::from_label(gesture.label()), gesture);
+        }
+        assert_eq!(Gesture::from_label("thumbs_up"), Gesture::Neutral);
+    }
+
+    #[test]
+    fn polarity_of_magnet_gestures() {
+        assert_eq!(Gesture::Fist.polarity(), Some(1.0));
+        assert_eq!(Gesture::Claw.polarity(), Some(-1.0));
+        assert_eq!(Gesture::Open.polarity(), None);
+    }
+
+    #[test]
+    fn missing_landmarks_give_no_shape() {
+        let mut hand = fixtures::hand("open_right");
+        hand.landmarks.retain(|l| l.id != 12);
+        assert_eq!(finger_extended(&hand.landmarks, Finger::Middle), None);
+        assert_eq!(classify_rps(&hand), None);
+    }
+
+    #[test]
+    fn every_shape_is_beaten_by_its_counter() {
+        for shape in RpsShape::ALL {
+            assert!(shape.counter().beats(shape));
+            assert!(!shape.beats(shape));
+        }
+    }
+}
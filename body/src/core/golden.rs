@@ -0,0 +1,133 @@
+// フィクスチャの手に対するジェスチャー判定・座標変換・法線の結果を golden.json と突き合わせ、
+// 計算の変更で結果が黙って変わらないようにする。
+// 意図して結果を変えたときは UPDATE_GOLDEN=1 cargo test golden で書き直し、差分を確かめてからコミットする
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::fixtures;
+use crate::core::gesture::{classify_landmarks, classify_rps, is_claw, is_pointing};
+use crate::core::mapping::{estimate_depth, map_hand, palm_normal, Reach, LEGACY_REGION};
+use crate::packet::OneHand;
+
+const GOLDEN: &str = include_str!("../../tests/fixtures/golden.json");
+const TOLERANCE: f32 = 1e-4;
+// 座標を記録する関節 (手首・親指の先・人差し指の先・手のひら)
+const TRACKED: [usize; 4] = [0, 4, 8, 9];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Expected {
+    gesture: String,
+    pointing: bool,
+    claw: bool,
+    rps: Option<String>,
+    depth: f32,
+    palm_normal: Option<[f32; 3]>,
+    // 従来の領域に増幅なしで写したワールド座標
+    mapped: BTreeMap<usize, [f32; 3]>,
+    pinch_gap: Option<f32>,
+}
+
+fn compute(hand: &OneHand) -> Expected {
+    let landmarks = &hand.landmarks;
+    let mapped = map_hand(landmarks, &LEGACY_REGION, &Reach::default());
+    Expected {
+        gesture: classify_landmarks(landmarks).label().to_string(),
+        pointing: is_pointing(landmarks),
+        claw: is_claw(landmarks),
+        rps: classify_rps(hand).map(|shape| format!("{shape:?}")),
+        depth: estimate_depth(landmarks),
+        palm_normal: palm_normal(landmarks, hand.label == "Right").map(|normal| normal.to_array()),
+        mapped: TRACKED.iter().filter_map(|id| Some((*id, mapped.get(id)?.to_array()))).collect(),
+        pinch_gap: mapped.get(&4).zip(mapped.get(&8)).map(|(thumb, index)| thumb.distance(*index)),
+    }
+}
+
+fn cases() -> Vec<(String, OneHand)> {
+    let mut cases: Vec<(String, OneHand)> = fixtures::HANDS.iter().map(|name| (name.to_string(), fixtures::hand(name))).collect();
+    for (i, hand) in fixtures::packet("crossed_hands").hands.into_iter().enumerate() {
+        cases.push((format!("crossed_hands/{i}"), hand));
+    }
+    cases
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= TOLERANCE
+}
+
+// 数値は許容誤差の範囲で、それ以外は完全一致で比べる。食い違った項目を返す
+fn differences(expected: &Expected, actual: &Expected) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut check = |field: &str, same: bool| {
+        if !same {
+            found.push(field.to_string());
+        }
+    };
+    check("gesture", expected.gesture == actual.gesture);
+    check("pointing", expected.pointing == actual.pointing);
+    check("claw", expected.claw == actual.claw);
+    check("rps", expected.rps == actual.rps);
+    check("depth", close(expected.depth, actual.depth));
+    let vectors_close = |a: &[f32; 3], b: &[f32; 3]| a.iter().zip(b).all(|(x, y)| close(*x, *y));
+    check(
+        "palm_normal",
+        match (&expected.palm_normal, &actual.palm_normal) {
+            (Some(a), Some(b)) => vectors_close(a, b),
+            (a, b) => a == b,
+        },
+    );
+    check(
+        "mapped",
+        expected.mapped.len() == actual.mapped.len()
+            && expected.mapped.iter().all(|(id, a)| actual.mapped.get(id).is_some_and(|b| vectors_close(a, b))),
+    );
+    check(
+        "pinch_gap",
+        match (expected.pinch_gap, actual.pinch_gap) {
+            (Some(a), Some(b)) => close(a, b),
+            (a, b) => a == b,
+        },
+    );
+    found
+}
+
+#[test]
+fn fixtures_match_golden_outputs() {
+    let actual: BTreeMap<String, Expected> = cases().iter().map(|(name, hand)| (name.clone(), compute(hand))).collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden.json");
+        let text = serde_json::to_string_pretty(&actual).expect("golden outputs should serialize");
+        std::fs::write(path, text + "\n").expect("golden file should be writable");
+        return;
+    }
+
+    let expected: BTreeMap<String, Expected> = serde_json::from_str(GOLDEN).expect("golden file should parse");
+    let mut failures = Vec::new();
+    for (name, actual) in &actual {
+        match expected.get(name) {
+            Some(expected) => {
+                let fields = differences(expected, actual);
+                if !fields.is_empty() {
+                    failures.push(format!("{name}: {} (expected {expected:?}, got {actual:?})", fields.join(", ")));
+                }
+            }
+            None => failures.push(format!("{name}: missing from golden.json")),
+        }
+    }
+    for name in expected.keys().filter(|name| !actual.contains_key(*name)) {
+        failures.push(format!("{name}: no longer produced by any fixture"));
+    }
+    assert!(failures.is_empty(), "golden outputs changed:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn golden_file_covers_expected_shapes() {
+    // フィクスチャの名前どおりの形に判定されていることも確かめる (golden を書き直したときの見落とし防止)
+    let expected: BTreeMap<String, Expected> = serde_json::from_str(GOLDEN).expect("golden file should parse");
+    assert_eq!(expected["open_right"].gesture, "Open");
+    assert_eq!(expected["fist_right"].gesture, "Fist");
+    assert_eq!(expected["claw_right"].gesture, "Claw");
+    assert!(expected["pointing_right"].pointing);
+    assert!(expected["pinch_right"].pinch_gap.is_some_and(|gap| gap < expected["open_right"].pinch_gap.unwrap()));
+    assert_eq!(expected["crossed_hands/0"].gesture, "Open");
+    assert_eq!(expected["crossed_hands/1"].gesture, "Open");
+}
@@ -0,0 +1,59 @@
+use glam::IVec3;
+use std::collections::HashSet;
+
+// 一辺 size の箱が、一辺 cell の格子の各軸で何セル分を占めるか。格子より小さい箱も 1 セルは使う
+pub fn span(size: f32, cell: f32) -> i32 {
+    if cell <= 0.0 {
+        return 1;
+    }
+    // ちょうど倍数の大きさが誤差で一つ多く数えられないようにする
+    ((size / cell - 1e-4).ceil() as i32).max(1)
+}
+
+// 一番下の隅のセル origin から各軸 span 個ずつ並ぶ、箱が占めるセル
+pub fn footprint(origin: IVec3, span: i32) -> impl Iterator<Item = IVec3> {
+    let span = span.max(1);
+    (0..span).flat_map(move |x| (0..span).flat_map(move |y| (0..span).map(move |z| origin + IVec3::new(x, y, z))))
+}
+
+// 置ける一番近い場所 (一番下の隅のセル)。占めるセルのどれかが埋まっていれば上へ積む。
+// 床より下には置かない
+pub fn free_origin(nearest: IVec3, span: i32, occupied: &HashSet<IVec3>, max_stack: i32) -> IVec3 {
+    let mut origin = IVec3::new(nearest.x, nearest.y.max(0), nearest.z);
+    for _ in 0..max_stack {
+        if !footprint(origin, span).any(|cell| occupied.contains(&cell)) {
+            break;
+        }
+        origin.y += 1;
+    }
+    origin
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn larger_boxes_span_several_cells() {
+        assert_eq!(span(1.0, 2.0), 1);
+        assert_eq!(span(2.0, 2.0), 1);
+        assert_eq!(span(2.5, 2.0), 2);
+        assert_eq!(span(6.0, 2.0), 3);
+        assert_eq!(span(3.0, 0.0), 1);
+        assert_eq!(footprint(IVec3::ZERO, 2).count(), 8);
+        assert!(footprint(IVec3::new(1, 0, 1), 2).any(|c| c == IVec3::new(2, 1, 2)));
+    }
+
+    #[test]
+    fn big_blocks_stack_above_anything_they_would_overlap() {
+        // 小さい積み木が (1, 0, 1) にあるとき、2 セル分の箱を (0, 0, 0) に置くと重なるので上へ
+        let occupied: HashSet<IVec3> = [IVec3::new(1, 0, 1)].into();
+        assert_eq!(free_origin(IVec3::ZERO, 2, &occupied, 32), IVec3::new(0, 1, 0));
+        // 1 セルの箱なら隣は空いている
+        assert_eq!(free_origin(IVec3::ZERO, 1, &occupied, 32), IVec3::ZERO);
+        // 大きい箱の占めるセルをすべて覚えていれば、その上の段に小さい箱は置かれない
+        let occupied: HashSet<IVec3> = footprint(IVec3::ZERO, 2).collect();
+        assert_eq!(free_origin(IVec3::new(1, 1, 1), 1, &occupied, 32), IVec3::new(1, 2, 1));
+        assert_eq!(free_origin(IVec3::new(3, -2, 0), 1, &occupied, 32), IVec3::new(3, 0, 0));
+    }
+}
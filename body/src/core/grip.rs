@@ -0,0 +1,110 @@
+use glam::Vec3;
+
+use crate::core::gesture::{finger_straightness, Finger};
+use crate::packet::Landmark;
+
+// 握りきった指の、付け根〜指先の直線距離と関節に沿った長さの比
+const CURLED_STRAIGHTNESS: f32 = 0.3;
+// 握ったとみなされる程度の曲がりでは力を出さず、そこから先を握力とする
+const GRIP_ONSET: f32 = 0.6;
+// 指を曲げる速さ (1/秒) を握り込みの強さに換算する係数と、その抜ける速さ
+const SQUEEZE_GAIN: f32 = 0.15;
+const SQUEEZE_DECAY: f32 = 4.0;
+// 押しつぶしたときの縦方向の縮みの上限
+const MAX_SQUASH: f32 = 0.5;
+
+// 四本の指の曲がり具合の平均 (0 で伸びきり、1 で握りきり)
+pub fn curl(landmarks: &[Landmark]) -> Option<f32> {
+    let mut total = 0.0;
+    for finger in Finger::ALL {
+        let straightness = finger_straightness(landmarks, finger)?;
+        total += ((1.0 - straightness) / (1.0 - CURLED_STRAIGHTNESS)).clamp(0.0, 1.0);
+    }
+    Some(total / Finger::ALL.len() as f32)
+}
+
+// 握力の推定。圧力は測れないので、握り込みの深さと握り込む速さで代わりにする
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GripMeter {
+    curl: f32,
+    squeeze: f32,
+    sampled_at: Option<f32>,
+}
+
+impl GripMeter {
+    pub fn sample(&mut self, curl: f32, now: f32) {
+        if let Some(previous) = self.sampled_at {
+            let dt = now - previous;
+            if dt > 0.0 {
+                let rate = (curl - self.curl) / dt;
+                self.squeeze = self.squeeze_at(now).max(rate * SQUEEZE_GAIN);
+            }
+        }
+        self.curl = curl;
+        self.sampled_at = Some(now);
+    }
+
+    fn squeeze_at(&self, now: f32) -> f32 {
+        let elapsed = self.sampled_at.map_or(0.0, |t| (now - t).max(0.0));
+        self.squeeze * (-elapsed * SQUEEZE_DECAY).exp()
+    }
+
+    // 0..1 の握力。開いた手や軽く握っただけの手では 0
+    pub fn strength(&self, now: f32) -> f32 {
+        let depth = ((self.curl - GRIP_ONSET) / (1.0 - GRIP_ONSET)).max(0.0);
+        (depth + self.squeeze_at(now)).clamp(0.0, 1.0)
+    }
+}
+
+// つぶれ具合 amount (0..1) に応じた大きさ。縦に縮んだ分だけ横へ膨らんで体積を保つ
+pub fn squash_scale(amount: f32) -> Vec3 {
+    let height = 1.0 - amount.clamp(0.0, 1.0) * MAX_SQUASH;
+    let side = 1.0 / height.sqrt();
+    Vec3::new(side, height, side)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    #[test]
+    fn fist_curls_more_than_open_hand() {
+        let open = curl(&fixtures::hand("open_right").landmarks).unwrap();
+        let fist = curl(&fixtures::hand("fist_right").landmarks).unwrap();
+        assert!(open < 0.2, "{open}");
+        assert!(fist > open + 0.3, "{open} {fist}");
+    }
+
+    #[test]
+    fn holding_still_gives_depth_only() {
+        let mut meter = GripMeter::default();
+        meter.sample(0.5, 0.0);
+        meter.sample(0.5, 0.1);
+        assert_eq!(meter.strength(0.1), 0.0);
+        meter.sample(1.0, 10.0);
+        meter.sample(1.0, 10.1);
+        assert!((meter.strength(10.1) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn squeezing_fast_adds_strength_that_fades() {
+        let mut meter = GripMeter::default();
+        meter.sample(0.6, 0.0);
+        meter.sample(0.8, 0.05);
+        let squeezed = meter.strength(0.05);
+        assert!(squeezed > 0.9, "{squeezed}");
+        let later = meter.strength(2.0);
+        assert!((later - 0.5).abs() < 0.01, "{later}");
+    }
+
+    #[test]
+    fn squash_keeps_volume() {
+        assert_eq!(squash_scale(0.0), Vec3::ONE);
+        for amount in [0.3, 1.0, 2.0] {
+            let scale = squash_scale(amount);
+            assert!(scale.y < 1.0 && scale.x > 1.0);
+            assert!((scale.x * scale.y * scale.z - 1.0).abs() < 1e-5);
+        }
+    }
+}
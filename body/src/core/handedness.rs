@@ -0,0 +1,153 @@
+use glam::Vec2;
+
+use crate::core::mapping::landmark;
+use crate::packet::OneHand;
+
+const LABELS: [&str; 2] = ["Right", "Left"];
+const MIDDLE_MCP: usize = 9;
+// この時間より前に見た位置は手が入れ替わった可能性があるので比べない
+const MEMORY: f32 = 0.5;
+// 1 フレームでこれより大きく跳んだときだけラベルを疑う (正規化座標)
+const MIN_JUMP: f32 = 0.15;
+// 入れ替えた方が移動量がこの割合以下になるときだけ直す
+const SWAP_RATIO: f32 = 0.5;
+
+// 手のひらの中心 (中指の付け根) の画像上の位置
+fn palm(hand: &OneHand) -> Option<Vec2> {
+    landmark(&hand.landmarks, MIDDLE_MCP).map(|l| Vec2::new(l.x, l.y))
+}
+
+fn index_of(label: &str) -> Option<usize> {
+    LABELS.iter().position(|l| l.eq_ignore_ascii_case(label))
+}
+
+// 送信元ごとに、直前に左右それぞれの手がいた位置を覚えておき、
+// 位置のつながりと合わない左右のラベルを付け替える
+#[derive(Debug, Clone, Default)]
+pub struct HandednessTracker {
+    last: [Option<(Vec2, f32)>; 2],
+}
+
+impl HandednessTracker {
+    fn recent(&self, index: usize, now: f32) -> Option<Vec2> {
+        self.last[index].filter(|(_, seen)| now - seen <= MEMORY).map(|(p, _)| p)
+    }
+
+    // ラベルを付け替えた手の数を返す
+    pub fn correct(&mut self, hands: &mut [OneHand], now: f32) -> usize {
+        let palms: Vec<Option<Vec2>> = hands.iter().map(palm).collect();
+        let mut corrected = 0;
+        match hands {
+            [a, b] => {
+                let (Some(pa), Some(pb)) = (palms[0], palms[1]) else {
+                    return 0;
+                };
+                let previous = (self.recent(0, now), self.recent(1, now));
+                if let (Some(right), Some(left)) = previous {
+                    // 今のラベルどおりの移動量と、左右を入れ替えたときの移動量を比べる
+                    let cost = |first: usize, second: usize| {
+                        let at = |i: usize| if i == 0 { right } else { left };
+                        pa.distance(at(first)) + pb.distance(at(second))
+                    };
+                    let (ia, ib) = (index_of(&a.label), index_of(&b.label));
+                    let keep = match (ia, ib) {
+                        (Some(x), Some(y)) if x != y => cost(x, y),
+                        // 両方に同じラベルが付いていれば近い方の並びを選ぶ
+                        _ => f32::INFINITY,
+                    };
+                    let straight = cost(0, 1);
+                    let crossed = cost(1, 0);
+                    let best = if straight <= crossed { [0, 1] } else { [1, 0] };
+                    let best_cost = straight.min(crossed);
+                    if keep.is_infinite() || (keep > MIN_JUMP && best_cost < keep * SWAP_RATIO) {
+                        for (hand, index) in [&mut *a, &mut *b].into_iter().zip(best) {
+                            if index_of(&hand.label) != Some(index) {
+                                hand.label = LABELS[index].to_string();
+                                corrected += 1;
+                            }
+                        }
+                    }
+                }
+            }
+            [hand] => {
+                if let (Some(p), Some(index)) = (palms[0], index_of(&hand.label)) {
+                    let other = 1 - index;
+                    let same = self.recent(index, now).map_or(f32::INFINITY, |q| p.distance(q));
+                    // もう片方の手が直前までそこにいたなら、ラベルが裏返ったとみなす
+                    if let Some(q) = self.recent(other, now) {
+                        let moved = p.distance(q);
+                        if same > MIN_JUMP && moved < MIN_JUMP && moved < same * SWAP_RATIO {
+                            hand.label = LABELS[other].to_string();
+                            corrected += 1;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for (hand, p) in hands.iter().zip(palms) {
+            if let (Some(index), Some(p)) = (index_of(&hand.label), p) {
+                self.last[index] = Some((p, now));
+            }
+        }
+        corrected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures::hand;
+
+    fn shifted(name: &str, label: &str, dx: f32) -> OneHand {
+        let mut h = hand(name);
+        h.label = label.to_string();
+        for l in &mut h.landmarks {
+            l.x += dx;
+        }
+        h
+    }
+
+    #[test]
+    fn swaps_flipped_pair() {
+        let mut tracker = HandednessTracker::default();
+        let mut hands = vec![shifted("open_right", "Right", 0.3), shifted("open_left", "Left", -0.3)];
+        assert_eq!(tracker.correct(&mut hands, 0.0), 0);
+
+        let mut flipped = vec![shifted("open_right", "Left", 0.3), shifted("open_left", "Right", -0.3)];
+        assert_eq!(tracker.correct(&mut flipped, 0.03), 2);
+        assert_eq!(flipped[0].label, "Right");
+        assert_eq!(flipped[1].label, "Left");
+    }
+
+    #[test]
+    fn keeps_labels_of_hands_that_cross_slowly() {
+        let mut tracker = HandednessTracker::default();
+        let mut hands = vec![shifted("open_right", "Right", 0.02), shifted("open_left", "Left", -0.02)];
+        tracker.correct(&mut hands, 0.0);
+        let mut next = vec![shifted("open_right", "Right", 0.0), shifted("open_left", "Left", 0.0)];
+        assert_eq!(tracker.correct(&mut next, 0.03), 0);
+    }
+
+    #[test]
+    fn relabels_lone_hand_that_jumps_sides() {
+        let mut tracker = HandednessTracker::default();
+        let mut hands = vec![shifted("open_right", "Right", 0.3), shifted("open_right", "Left", -0.3)];
+        tracker.correct(&mut hands, 0.0);
+
+        let mut lone = vec![shifted("open_right", "Left", 0.3)];
+        assert_eq!(tracker.correct(&mut lone, 0.03), 1);
+        assert_eq!(lone[0].label, "Right");
+    }
+
+    #[test]
+    fn trusts_labels_after_hands_were_gone() {
+        let mut tracker = HandednessTracker::default();
+        let mut hands = vec![shifted("open_right", "Right", 0.3), shifted("open_left", "Left", -0.3)];
+        tracker.correct(&mut hands, 0.0);
+
+        let mut flipped = vec![shifted("open_right", "Left", 0.3), shifted("open_left", "Right", -0.3)];
+        assert_eq!(tracker.correct(&mut flipped, MEMORY + 1.0), 0);
+    }
+}
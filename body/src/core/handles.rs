@@ -0,0 +1,88 @@
+use glam::{Quat, Vec3};
+use std::f32::consts::{PI, TAU};
+
+// 回転の角度を揃える刻み (15°)
+pub const SNAP_STEP: f32 = PI / 12.0;
+
+// 真上から見た向き。Quat::from_rotation_y(yaw) が +X を向ける先と同じ角度になる
+pub fn yaw_of(offset: Vec3) -> f32 {
+    (-offset.z).atan2(offset.x)
+}
+
+pub fn rotation_yaw(rotation: Quat) -> f32 {
+    yaw_of(rotation * Vec3::X)
+}
+
+// -π..π に収める
+pub fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped <= -PI { wrapped + TAU } else { wrapped }
+}
+
+pub fn snap_angle(angle: f32, step: f32) -> f32 {
+    if step <= 0.0 { angle } else { (angle / step).round() * step }
+}
+
+// 角のつまみを掴んで中心のまわりに回す操作。指が何周回っても角度が飛ばないよう、回した量を積み上げる
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotateDrag {
+    pub start_yaw: f32,
+    last_pointer: f32,
+    turned: f32,
+}
+
+impl RotateDrag {
+    // pointer は物体の中心からつまんだ位置へのずれ
+    pub fn new(pointer: Vec3, start_yaw: f32) -> Self {
+        Self { start_yaw, last_pointer: yaw_of(pointer), turned: 0.0 }
+    }
+
+    // 刻みに揃えた新しい向き
+    pub fn update(&mut self, pointer: Vec3) -> f32 {
+        let now = yaw_of(pointer);
+        self.turned += wrap_angle(now - self.last_pointer);
+        self.last_pointer = now;
+        snap_angle(self.start_yaw + self.turned, SNAP_STEP)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(angle: f32) -> Vec3 {
+        Quat::from_rotation_y(angle) * Vec3::X * 2.0
+    }
+
+    #[test]
+    fn yaw_matches_rotation() {
+        for angle in [0.0, 0.5, -1.2, 3.0] {
+            assert!((rotation_yaw(Quat::from_rotation_y(angle)) - angle).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn drag_snaps_to_fifteen_degrees() {
+        let mut drag = RotateDrag::new(at(0.0), 0.0);
+        assert_eq!(drag.update(at(0.1)), 0.0);
+        assert!((drag.update(at(0.2)) - SNAP_STEP).abs() < 1e-5);
+        assert!((drag.update(at(-0.5)) + 2.0 * SNAP_STEP).abs() < 1e-5);
+    }
+
+    #[test]
+    fn turning_past_half_a_circle_keeps_going() {
+        let mut drag = RotateDrag::new(at(0.0), 0.0);
+        let mut yaw = 0.0;
+        for i in 1..=40 {
+            yaw = drag.update(at(i as f32 * 0.1));
+        }
+        assert!((yaw - snap_angle(4.0, SNAP_STEP)).abs() < 1e-4, "{yaw}");
+    }
+
+    #[test]
+    fn wraps_into_half_turns() {
+        assert!((wrap_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-5);
+        assert!((wrap_angle(-PI) - PI).abs() < 1e-5);
+        assert_eq!(wrap_angle(0.25), 0.25);
+    }
+}
@@ -0,0 +1,161 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// 形の大きさに対する、平面の表裏を決めるときの許容誤差
+const EPSILON_SCALE: f32 = 1e-5;
+
+// 凸包。面は外から見て反時計回り
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConvexHull {
+    pub vertices: Vec<[f32; 3]>,
+    pub faces: Vec<[u32; 3]>,
+}
+
+impl ConvexHull {
+    // 点の集まりを包む凸包。すべての点がほぼ同じ平面に乗っていれば作れない
+    pub fn from_points(points: &[Vec3]) -> Option<ConvexHull> {
+        let (min, max) = points.iter().fold((Vec3::MAX, Vec3::MIN), |(lo, hi), p| (lo.min(*p), hi.max(*p)));
+        let epsilon = (max - min).max_element() * EPSILON_SCALE;
+        let mut faces = initial_tetrahedron(points, epsilon)?;
+        let normal = |f: &[usize; 3]| (points[f[1]] - points[f[0]]).cross(points[f[2]] - points[f[0]]);
+        let above = |f: &[usize; 3], p: Vec3| normal(f).normalize_or_zero().dot(p - points[f[0]]) > epsilon;
+
+        for (index, point) in points.iter().enumerate() {
+            let (visible, kept): (Vec<[usize; 3]>, Vec<[usize; 3]>) = faces.iter().partition(|f| above(f, *point));
+            if visible.is_empty() {
+                continue;
+            }
+            // 見える面の縁のうち、見える面どうしで共有していない辺が地平線。そこへ新しい点をつなぐ
+            let edges: HashSet<(usize, usize)> =
+                visible.iter().flat_map(|f| [(f[0], f[1]), (f[1], f[2]), (f[2], f[0])]).collect();
+            faces = kept;
+            for &(a, b) in &edges {
+                if !edges.contains(&(b, a)) {
+                    faces.push([a, b, index]);
+                }
+            }
+        }
+
+        // 凸包に残った点だけを詰め直す
+        let mut remap = vec![u32::MAX; points.len()];
+        let mut vertices = Vec::new();
+        let faces = faces
+            .iter()
+            .map(|f| {
+                f.map(|i| {
+                    if remap[i] == u32::MAX {
+                        remap[i] = vertices.len() as u32;
+                        vertices.push(points[i].to_array());
+                    }
+                    remap[i]
+                })
+            })
+            .collect();
+        Some(ConvexHull { vertices, faces })
+    }
+
+    pub fn points(&self) -> Vec<Vec3> {
+        self.vertices.iter().map(|v| Vec3::from_array(*v)).collect()
+    }
+
+    // 原点から一番遠い頂点までの距離
+    pub fn radius(&self) -> f32 {
+        self.vertices.iter().map(|v| Vec3::from_array(*v).length()).fold(0.0, f32::max)
+    }
+
+    pub fn scaled(&self, scale: f32) -> ConvexHull {
+        ConvexHull { vertices: self.vertices.iter().map(|v| v.map(|c| c * scale)).collect(), faces: self.faces.clone() }
+    }
+}
+
+// 互いに一番離れた 4 点で作る最初の四面体。面は重心から外へ向ける
+fn initial_tetrahedron(points: &[Vec3], epsilon: f32) -> Option<Vec<[usize; 3]>> {
+    let farthest = |score: &dyn Fn(Vec3) -> f32| {
+        (0..points.len()).max_by(|a, b| score(points[*a]).total_cmp(&score(points[*b])))
+    };
+    let a = farthest(&|p| -p.x)?;
+    let b = farthest(&|p| p.distance_squared(points[a]))?;
+    let line = (points[b] - points[a]).try_normalize()?;
+    let c = farthest(&|p| (p - points[a]).reject_from_normalized(line).length_squared())?;
+    let plane = (points[b] - points[a]).cross(points[c] - points[a]).try_normalize()?;
+    let d = farthest(&|p| plane.dot(p - points[a]).abs())?;
+    if plane.dot(points[d] - points[a]).abs() <= epsilon {
+        return None;
+    }
+    let center = (points[a] + points[b] + points[c] + points[d]) / 4.0;
+    let faces = [[a, b, c], [a, b, d], [a, c, d], [b, c, d]].map(|[i, j, k]| {
+        let normal = (points[j] - points[i]).cross(points[k] - points[i]);
+        if normal.dot(points[i] - center) < 0.0 { [i, k, j] } else { [i, j, k] }
+    });
+    Some(faces.to_vec())
+}
+
+// 読み込むたびに凸包を計算し直さないよう、元のファイルの大きさと更新時刻と一緒に保存する
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HullCache {
+    pub source_len: u64,
+    pub source_modified: u64,
+    pub hull: ConvexHull,
+}
+
+impl HullCache {
+    pub fn matches(&self, source_len: u64, source_modified: u64) -> bool {
+        self.source_len == source_len && self.source_modified == source_modified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube_with_inside_points() -> Vec<Vec3> {
+        let mut points = Vec::new();
+        for x in [-1.0, 1.0] {
+            for y in [-1.0, 1.0] {
+                for z in [-1.0, 1.0] {
+                    points.push(Vec3::new(x, y, z));
+                }
+            }
+        }
+        // 中の点と、面の上の点は凸包に残らない
+        points.extend([Vec3::ZERO, Vec3::new(0.3, -0.2, 0.5), Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.5, 0.5)]);
+        points
+    }
+
+    #[test]
+    fn cube_hull_keeps_only_the_corners() {
+        let hull = ConvexHull::from_points(&cube_with_inside_points()).unwrap();
+        assert_eq!(hull.vertices.len(), 8);
+        assert_eq!(hull.faces.len(), 12);
+        assert!((hull.radius() - 3f32.sqrt()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn faces_point_outward() {
+        let hull = ConvexHull::from_points(&cube_with_inside_points()).unwrap();
+        let points = hull.points();
+        for face in &hull.faces {
+            let [a, b, c] = face.map(|i| points[i as usize]);
+            let normal = (b - a).cross(c - a);
+            assert!(normal.dot(a) > 0.0, "{face:?}");
+        }
+    }
+
+    #[test]
+    fn flat_point_sets_have_no_hull() {
+        let flat = [Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::new(1.0, 0.0, 1.0)];
+        assert!(ConvexHull::from_points(&flat).is_none());
+        assert!(ConvexHull::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn cache_matches_only_the_same_source() {
+        let hull = ConvexHull::from_points(&cube_with_inside_points()).unwrap();
+        let cache = HullCache { source_len: 1024, source_modified: 1_700_000_000, hull };
+        assert!(cache.matches(1024, 1_700_000_000));
+        assert!(!cache.matches(1024, 1_700_000_001));
+        let text = serde_json::to_string(&cache).unwrap();
+        assert_eq!(serde_json::from_str::<HullCache>(&text).unwrap(), cache);
+    }
+}
@@ -0,0 +1,86 @@
+use glam::Vec3;
+use std::collections::HashMap;
+
+// 静止中の揺れの何倍までを不感帯に含めるか
+const DEAD_ZONE_SIGMAS: f32 = 3.0;
+const MIN_DEAD_ZONE: f32 = 0.03;
+const MAX_DEAD_ZONE: f32 = 1.0;
+
+// ランドマークごとの位置の記録。静止した手の揺れの大きさを求める
+#[derive(Debug, Clone, Default)]
+pub struct JitterSamples {
+    positions: HashMap<usize, Vec<Vec3>>,
+}
+
+impl JitterSamples {
+    pub fn push(&mut self, points: &HashMap<usize, Vec3>) {
+        for (id, position) in points {
+            self.positions.entry(*id).or_default().push(*position);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.values().map(Vec::len).max().unwrap_or(0)
+    }
+
+    fn means(&self) -> impl Iterator<Item = (Vec3, &Vec<Vec3>)> {
+        self.positions
+            .values()
+            .filter(|p| p.len() > 1)
+            .map(|p| (p.iter().sum::<Vec3>() / p.len() as f32, p))
+    }
+
+    // 平均位置からのずれの二乗平均平方根を、ランドマーク全体で平均したもの
+    pub fn std_dev(&self) -> Option<f32> {
+        let per_landmark: Vec<f32> = self
+            .means()
+            .map(|(mean, p)| (p.iter().map(|x| x.distance_squared(mean)).sum::<f32>() / p.len() as f32).sqrt())
+            .collect();
+        (!per_landmark.is_empty()).then(|| per_landmark.iter().sum::<f32>() / per_landmark.len() as f32)
+    }
+
+    // 平均位置から一番離れた点までの距離 (手が動いていないかの確認に使う)
+    pub fn max_excursion(&self) -> f32 {
+        self.means()
+            .flat_map(|(mean, p)| p.iter().map(move |x| x.distance(mean)))
+            .fold(0.0, f32::max)
+    }
+}
+
+pub fn propose_dead_zone(noise: f32) -> f32 {
+    (noise * DEAD_ZONE_SIGMAS).clamp(MIN_DEAD_ZONE, MAX_DEAD_ZONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(offsets: &[f32]) -> JitterSamples {
+        let mut samples = JitterSamples::default();
+        for x in offsets {
+            samples.push(&HashMap::from([(0, Vec3::new(*x, 0.0, 0.0)), (9, Vec3::new(5.0 + x, 1.0, 0.0))]));
+        }
+        samples
+    }
+
+    #[test]
+    fn std_dev_of_alternating_noise() {
+        let samples = samples(&[-0.1, 0.1, -0.1, 0.1]);
+        assert_eq!(samples.len(), 4);
+        assert!((samples.std_dev().unwrap() - 0.1).abs() < 1e-5);
+        assert!((samples.max_excursion() - 0.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn still_hand_has_no_noise() {
+        let samples = samples(&[0.0; 10]);
+        assert_eq!(samples.std_dev(), Some(0.0));
+        assert_eq!(propose_dead_zone(0.0), MIN_DEAD_ZONE);
+    }
+
+    #[test]
+    fn proposal_scales_with_noise() {
+        assert!((propose_dead_zone(0.05) - 0.15).abs() < 1e-5);
+        assert_eq!(propose_dead_zone(10.0), MAX_DEAD_ZONE);
+    }
+}
@@ -0,0 +1,119 @@
+// 物理パラメータ実験室のつまみ。値とつまみの位置 (0〜1) を行き来する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LabParam {
+    Gravity,
+    Friction,
+    Restitution,
+    Damping,
+}
+
+impl LabParam {
+    pub const ALL: [LabParam; 4] = [LabParam::Gravity, LabParam::Friction, LabParam::Restitution, LabParam::Damping];
+
+    #[cfg(any(test, feature = "ui"))]
+    pub fn label(self) -> &'static str {
+        match self {
+            LabParam::Gravity => "Gravity",
+            LabParam::Friction => "Friction",
+            LabParam::Restitution => "Restitution",
+            LabParam::Damping => "Air damping",
+        }
+    }
+
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            LabParam::Gravity => (0.0, 30.0),
+            LabParam::Friction => (0.0, 2.0),
+            LabParam::Restitution => (0.0, 1.0),
+            LabParam::Damping => (0.0, 5.0),
+        }
+    }
+
+    pub fn fraction(self, value: f32) -> f32 {
+        let (min, max) = self.range();
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    }
+
+    pub fn value_at(self, fraction: f32) -> f32 {
+        let (min, max) = self.range();
+        min + (max - min) * fraction.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabParams {
+    pub gravity: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub damping: f32,
+}
+
+impl Default for LabParams {
+    fn default() -> Self {
+        Self {
+            gravity: 9.81,
+            friction: 0.5,
+            restitution: 0.0,
+            damping: 0.0,
+        }
+    }
+}
+
+impl LabParams {
+    pub fn get(&self, param: LabParam) -> f32 {
+        match param {
+            LabParam::Gravity => self.gravity,
+            LabParam::Friction => self.friction,
+            LabParam::Restitution => self.restitution,
+            LabParam::Damping => self.damping,
+        }
+    }
+
+    pub fn set(&mut self, param: LabParam, value: f32) {
+        let (min, max) = param.range();
+        let value = value.clamp(min, max);
+        match param {
+            LabParam::Gravity => self.gravity = value,
+            LabParam::Friction => self.friction = value,
+            LabParam::Restitution => self.restitution = value,
+            LabParam::Damping => self.damping = value,
+        }
+    }
+}
+
+// 長さ length のレールの中心からの位置 offset を、つまみの位置 (0〜1) に直す
+pub fn track_fraction(offset: f32, length: f32) -> f32 {
+    (offset / length + 0.5).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction_round_trips() {
+        for param in LabParam::ALL {
+            let value = param.value_at(0.3);
+            assert!((param.fraction(value) - 0.3).abs() < 1e-5, "{}", param.label());
+        }
+    }
+
+    #[test]
+    fn defaults_sit_inside_ranges() {
+        let params = LabParams::default();
+        for param in LabParam::ALL {
+            let (min, max) = param.range();
+            assert!((min..=max).contains(&params.get(param)), "{}", param.label());
+        }
+    }
+
+    #[test]
+    fn clamps_past_track_ends() {
+        assert_eq!(track_fraction(0.0, 4.0), 0.5);
+        assert_eq!(track_fraction(-3.0, 4.0), 0.0);
+        assert_eq!(track_fraction(3.0, 4.0), 1.0);
+        let mut params = LabParams::default();
+        params.set(LabParam::Restitution, 2.0);
+        assert_eq!(params.restitution, 1.0);
+    }
+}
@@ -0,0 +1,34 @@
+// 出現と消滅のときの大きさの倍率。t は 0..1 の進み具合
+
+// 少し膨らんでから元の大きさに落ち着く (ease-out-back)
+pub fn appear_scale(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.7;
+    let t = t.clamp(0.0, 1.0) - 1.0;
+    1.0 + (OVERSHOOT + 1.0) * t * t * t + OVERSHOOT * t * t
+}
+
+// 一瞬だけ膨らんでから縮んで消える
+pub fn vanish_scale(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    (1.0 - t * t) * (1.0 + 0.3 * (t * std::f32::consts::PI).sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appear_grows_from_nothing_with_overshoot() {
+        assert!(appear_scale(0.0).abs() < 1e-6);
+        assert!((appear_scale(1.0) - 1.0).abs() < 1e-6);
+        let peak = (1..100).map(|i| appear_scale(i as f32 / 100.0)).fold(0.0, f32::max);
+        assert!(peak > 1.05 && peak < 1.2);
+    }
+
+    #[test]
+    fn vanish_ends_at_zero() {
+        assert!((vanish_scale(0.0) - 1.0).abs() < 1e-6);
+        assert!(vanish_scale(1.0).abs() < 1e-6);
+        assert!(vanish_scale(2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,278 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::packet::Landmark;
+
+pub const WORLD_SCALE: f32 = 20.0;
+pub const HEIGHT_OFFSET: f32 = 3.0;
+// 手が小さく (カメラから遠く) 写るほど奥に置く
+const DEPTH_BASE: f32 = 20.0;
+const DEPTH_GAIN: f32 = 80.0;
+
+// 1 人で使うときの従来のマッピング (正規化 x 0..1 → -10..10)
+pub const LEGACY_REGION: Region = Region { min_x: -10.0, max_x: 10.0 };
+
+// シーンを x 方向に区切った作業領域
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub min_x: f32,
+    pub max_x: f32,
+}
+
+impl Region {
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+
+    pub fn center(&self) -> f32 {
+        (self.min_x + self.max_x) / 2.0
+    }
+
+    pub fn map_x(&self, normalized: f32) -> f32 {
+        self.center() + (normalized - 0.5) * self.width()
+    }
+
+    pub fn clamp_x(&self, x: f32) -> f32 {
+        x.clamp(self.min_x, self.max_x)
+    }
+
+    pub fn contains_x(&self, x: f32) -> bool {
+        (self.min_x..=self.max_x).contains(&x)
+    }
+}
+
+// 手を置いてよい空間。奥行きの推定を誤った手が床下や小道具の中に入らないようにする
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+// 床 (y = -5, 30 x 30) の上でカメラ (z = 20) より手前に出ない範囲
+pub const DEFAULT_BOUNDS: Bounds = Bounds { min: [-15.0, -4.8, -15.0], max: [15.0, 20.0, 17.0] };
+
+impl Default for Bounds {
+    fn default() -> Self {
+        DEFAULT_BOUNDS
+    }
+}
+
+impl Bounds {
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(Vec3::from_array(self.min)).all() && point.cmple(Vec3::from_array(self.max)).all()
+    }
+
+    pub fn clamp(&self, point: Vec3) -> Vec3 {
+        point.clamp(Vec3::from_array(self.min), Vec3::from_array(self.max))
+    }
+}
+
+pub fn landmark(landmarks: &[Landmark], id: usize) -> Option<&Landmark> {
+    landmarks.iter().find(|l| l.id == id)
+}
+
+// 画像上の手首〜中指MCPの長さ
+pub fn hand_size(landmarks: &[Landmark]) -> Option<f32> {
+    let w = landmark(landmarks, 0)?;
+    let m = landmark(landmarks, 9)?;
+    let dx = w.x - m.x;
+    let dy = w.y - m.y;
+    Some((dx * dx + dy * dy).sqrt())
+}
+
+pub fn estimate_depth(landmarks: &[Landmark]) -> f32 {
+    hand_size(landmarks).map_or(0.0, depth_from_size)
+}
+
+pub fn depth_from_size(size: f32) -> f32 {
+    DEPTH_BASE - size * DEPTH_GAIN
+}
+
+pub fn landmark_to_world(lm: &Landmark, region: &Region, depth: f32) -> Vec3 {
+    Vec3::new(
+        region.map_x(lm.x),
+        (0.5 - lm.y) * WORLD_SCALE + HEIGHT_OFFSET,
+        depth + lm.z * WORLD_SCALE,
+    )
+}
+
+// 手の大きさの倍率と、画像の端に近いほど手の動きを大きくする到達距離の増幅
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Reach {
+    pub hand_scale: f32,
+    pub amplification: f32,
+}
+
+impl Default for Reach {
+    fn default() -> Self {
+        Self { hand_scale: 1.0, amplification: 0.0 }
+    }
+}
+
+impl Reach {
+    // 画像中心からのずれ u (-1..1) を u * (1 + a * u^2) に伸ばす。中心付近はほとんど変わらない
+    pub fn amplify(&self, normalized: f32) -> f32 {
+        let u = (normalized - 0.5) * 2.0;
+        0.5 + u * (1.0 + self.amplification * u * u) / 2.0
+    }
+
+    // 設定を変えたときに手が跳ばないよう、少しずつ近づける
+    pub fn blend_toward(&mut self, target: &Reach, t: f32) {
+        let t = t.clamp(0.0, 1.0);
+        self.hand_scale += (target.hand_scale - self.hand_scale) * t;
+        self.amplification += (target.amplification - self.amplification) * t;
+    }
+}
+
+// 手全体をワールド座標に写す。手のひら (中指の付け根) の位置だけを増幅し、
+// 手の形はそこを中心に hand_scale 倍するので指の形は歪まない
+pub fn map_hand(landmarks: &[Landmark], region: &Region, reach: &Reach) -> HashMap<usize, Vec3> {
+    map_hand_at_depth(landmarks, region, reach, estimate_depth(landmarks))
+}
+
+pub fn map_hand_at_depth(landmarks: &[Landmark], region: &Region, reach: &Reach, depth: f32) -> HashMap<usize, Vec3> {
+    let mut points: HashMap<usize, Vec3> = landmarks
+        .iter()
+        .map(|lm| (lm.id, landmark_to_world(lm, region, depth)))
+        .collect();
+    let Some(palm) = landmark(landmarks, 9) else {
+        return points;
+    };
+    let anchor = landmark_to_world(palm, region, depth);
+    let amplified = Landmark { x: reach.amplify(palm.x), y: reach.amplify(palm.y), ..palm.clone() };
+    let moved = landmark_to_world(&amplified, region, depth);
+    for point in points.values_mut() {
+        *point = moved + (*point - anchor) * reach.hand_scale;
+    }
+    points
+}
+
+// 手のひらの法線。左手は外積の順序を入れ替えて同じ向きにそろえる
+pub fn palm_normal(landmarks: &[Landmark], right_hand: bool) -> Option<Vec3> {
+    let w = landmark(landmarks, 0)?;
+    let i = landmark(landmarks, 5)?;
+    let p = landmark(landmarks, 17)?;
+    let to_index = Vec3::new(i.x - w.x, w.y - i.y, i.z - w.z);
+    let to_pinky = Vec3::new(p.x - w.x, w.y - p.y, p.z - w.z);
+
+    let mut normal = if right_hand {
+        to_index.cross(to_pinky).normalize_or_zero()
+    } else {
+        to_pinky.cross(to_index).normalize_or_zero()
+    };
+    normal.y *= -1.0;
+    Some(normal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    fn lm(id: usize, x: f32, y: f32, z: f32) -> Landmark {
+        Landmark { id, x, y, z }
+    }
+
+    #[test]
+    fn image_center_maps_to_region_center() {
+        let region = Region { min_x: 0.0, max_x: 10.0 };
+        let world = landmark_to_world(&lm(0, 0.5, 0.5, 0.0), &region, 4.0);
+        assert_eq!(world, Vec3::new(5.0, HEIGHT_OFFSET, 4.0));
+    }
+
+    #[test]
+    fn legacy_region_matches_original_mapping() {
+        for x in [0.0, 0.25, 0.5, 1.0] {
+            assert!((LEGACY_REGION.map_x(x) - (x - 0.5) * WORLD_SCALE).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn image_up_is_world_up() {
+        let top = landmark_to_world(&lm(0, 0.5, 0.1, 0.0), &LEGACY_REGION, 0.0);
+        let bottom = landmark_to_world(&lm(0, 0.5, 0.9, 0.0), &LEGACY_REGION, 0.0);
+        assert!(top.y > bottom.y);
+    }
+
+    #[test]
+    fn region_clamps_and_contains() {
+        let region = Region { min_x: -5.0, max_x: 5.0 };
+        assert_eq!(region.clamp_x(7.0), 5.0);
+        assert!(region.contains_x(-5.0));
+        assert!(!region.contains_x(5.1));
+    }
+
+    #[test]
+    fn bounds_clamp_points_inside() {
+        let under_floor = Vec3::new(0.0, -30.0, 5.0);
+        assert!(!DEFAULT_BOUNDS.contains(under_floor));
+        let clamped = DEFAULT_BOUNDS.clamp(under_floor);
+        assert!(DEFAULT_BOUNDS.contains(clamped));
+        assert_eq!(clamped, Vec3::new(0.0, DEFAULT_BOUNDS.min[1], 5.0));
+    }
+
+    #[test]
+    fn reach_amplifies_edges_more_than_center() {
+        let reach = Reach { hand_scale: 1.0, amplification: 1.0 };
+        assert_eq!(reach.amplify(0.5), 0.5);
+        assert!((reach.amplify(0.55) - 0.55).abs() < 0.001);
+        assert!((reach.amplify(1.0) - 1.5).abs() < 1e-5);
+        assert_eq!(Reach::default().amplify(0.9), 0.9);
+    }
+
+    #[test]
+    fn hand_scale_keeps_palm_in_place() {
+        let hand = fixtures::hand("open_right");
+        let normal = map_hand(&hand.landmarks, &LEGACY_REGION, &Reach::default());
+        let giant = map_hand(&hand.landmarks, &LEGACY_REGION, &Reach { hand_scale: 2.0, amplification: 0.0 });
+        assert_eq!(giant[&9], normal[&9]);
+        let span = |points: &HashMap<usize, Vec3>| points[&12].distance(points[&9]);
+        assert!((span(&giant) - 2.0 * span(&normal)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn blending_moves_part_way() {
+        let mut reach = Reach::default();
+        reach.blend_toward(&Reach { hand_scale: 3.0, amplification: 1.0 }, 0.5);
+        assert_eq!(reach, Reach { hand_scale: 2.0, amplification: 0.5 });
+    }
+
+    #[test]
+    fn hand_size_from_fixture() {
+        let hand = fixtures::hand("open_right");
+        let size = hand_size(&hand.landmarks).unwrap();
+        assert!((size - 0.084).abs() < 1e-3, "size {size}");
+    }
+
+    #[test]
+    fn larger_hand_is_nearer() {
+        let small = vec![lm(0, 0.5, 0.6, 0.0), lm(9, 0.5, 0.55, 0.0)];
+        let large = vec![lm(0, 0.5, 0.8, 0.0), lm(9, 0.5, 0.6, 0.0)];
+        assert!(estimate_depth(&large) < estimate_depth(&small));
+    }
+
+    #[test]
+    fn depth_defaults_without_landmarks() {
+        assert_eq!(estimate_depth(&[lm(5, 0.5, 0.5, 0.0)]), 0.0);
+    }
+
+    #[test]
+    fn palm_normal_is_unit_length() {
+        let hand = fixtures::hand("fist_right");
+        let normal = palm_normal(&hand.landmarks, true).unwrap();
+        assert!((normal.length() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn mirrored_left_hand_normal_is_mirrored() {
+        let right = palm_normal(&fixtures::hand("open_right").landmarks, true).unwrap();
+        let left = palm_normal(&fixtures::hand("open_left").landmarks, false).unwrap();
+        assert!(left.abs_diff_eq(Vec3::new(-right.x, right.y, right.z), 1e-4), "{left} vs {right}");
+    }
+
+    #[test]
+    fn palm_normal_needs_palm_landmarks() {
+        assert!(palm_normal(&[lm(0, 0.5, 0.5, 0.0)], true).is_none());
+    }
+}
@@ -0,0 +1,96 @@
+// Bevy に依存しない手の計算 (座標変換・奥行き推定・法線・姿勢・ジェスチャー判定)
+pub mod admin;
+pub mod ambient;
+pub mod arena;
+pub mod auth;
+pub mod carry;
+pub mod checkers;
+pub mod aesthetics;
+pub mod classifier;
+#[cfg(feature = "recording")]
+pub mod coco;
+pub mod confirm;
+pub mod console;
+pub mod crater;
+pub mod dice;
+pub mod focus;
+pub mod forearm;
+pub mod gesture;
+#[cfg(test)]
+mod golden;
+pub mod grid;
+pub mod grip;
+pub mod handedness;
+pub mod handles;
+#[cfg(feature = "props")]
+pub mod hull;
+pub mod jitter;
+pub mod lab;
+pub mod lifecycle;
+pub mod mapping;
+pub mod occlusion;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+pub mod pose;
+pub mod profile;
+pub mod punching_bag;
+pub mod puppet;
+pub mod resting;
+#[cfg(feature = "character")]
+pub mod retarget;
+pub mod reveal;
+pub mod scoop;
+pub mod search;
+pub mod sequence;
+pub mod session;
+pub mod smoothing;
+pub mod soft_body;
+pub mod silhouette;
+pub mod speed_gate;
+pub mod split_input;
+pub mod spring;
+pub mod stamp;
+pub mod substep;
+pub mod swipe;
+pub mod teaching;
+pub mod thinning;
+pub mod tool_belt;
+pub mod topology;
+pub mod trail;
+pub mod trajectory;
+pub mod trackpad;
+pub mod tuning;
+pub mod twist;
+pub mod versus;
+pub mod wind_zone;
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::packet::{HandPacket, OneHand};
+
+    // 1 つの手だけのフィクスチャ
+    pub const HANDS: &[&str] = &["open_right", "open_left", "fist_right", "claw_right", "scissors_right", "pinch_right", "pointing_right"];
+
+    pub fn hand(name: &str) -> OneHand {
+        let text = match name {
+            "open_right" => include_str!("../../tests/fixtures/open_right.json"),
+            "open_left" => include_str!("../../tests/fixtures/open_left.json"),
+            "fist_right" => include_str!("../../tests/fixtures/fist_right.json"),
+            "claw_right" => include_str!("../../tests/fixtures/claw_right.json"),
+            "scissors_right" => include_str!("../../tests/fixtures/scissors_right.json"),
+            "pinch_right" => include_str!("../../tests/fixtures/pinch_right.json"),
+            "pointing_right" => include_str!("../../tests/fixtures/pointing_right.json"),
+            _ => panic!("unknown fixture {name}"),
+        };
+        serde_json::from_str(text).expect("fixture should parse")
+    }
+
+    // 両手が写ったパケット全体のフィクスチャ
+    pub fn packet(name: &str) -> HandPacket {
+        let text = match name {
+            "crossed_hands" => include_str!("../../tests/fixtures/crossed_hands.json"),
+            _ => panic!("unknown fixture {name}"),
+        };
+        serde_json::from_str(text).expect("fixture should parse")
+    }
+}
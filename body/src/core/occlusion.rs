@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use crate::core::gesture::{Finger, WRIST};
+use crate::core::mapping::landmark;
+use crate::packet::Landmark;
+
+// 隠れた点を最後に見えた形のまま補う時間。これを過ぎたら補わない
+pub const HOLD_TIME: f32 = 0.6;
+// 補っている間に手首へ寄せる割合。長く隠れた指ほど縮めて、古い形のまま突き出さないようにする
+const DECAY: f32 = 0.3;
+
+// 手ごとに、各点の手首からのずれと最後に見えた時刻を覚えておき、
+// 指が隠れて点の抜けたパケットを直前の形で補う
+#[derive(Debug, Clone, Default)]
+pub struct FingerMemory {
+    last: HashMap<usize, ([f32; 3], f32)>,
+}
+
+impl FingerMemory {
+    // 抜けていた点を補い、点が 1 つでも抜けていた指を返す。補った点は判定に使わないこと
+    pub fn fill(&mut self, landmarks: &mut Vec<Landmark>, now: f32) -> Vec<Finger> {
+        let occluded: Vec<Finger> = Finger::ALL
+            .into_iter()
+            .filter(|f| (f.mcp()..=f.tip()).any(|id| landmark(landmarks, id).is_none()))
+            .collect();
+        // 手首が無ければどこに補えばよいか分からない
+        let Some(wrist) = landmark(landmarks, WRIST).cloned() else {
+            return occluded;
+        };
+        for l in landmarks.iter() {
+            self.last.insert(l.id, ([l.x - wrist.x, l.y - wrist.y, l.z - wrist.z], now));
+        }
+        self.last.retain(|_, (_, seen)| now - *seen <= HOLD_TIME);
+        let mut filled: Vec<Landmark> = self
+            .last
+            .iter()
+            .filter(|(_, (_, seen))| *seen < now)
+            .map(|(&id, &([x, y, z], seen))| {
+                let shrink = 1.0 - DECAY * (now - seen) / HOLD_TIME;
+                Landmark { id, x: wrist.x + x * shrink, y: wrist.y + y * shrink, z: wrist.z + z * shrink }
+            })
+            .collect();
+        filled.sort_by_key(|l| l.id);
+        landmarks.extend(filled);
+        occluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    fn without(landmarks: &[Landmark], finger: Finger) -> Vec<Landmark> {
+        landmarks.iter().filter(|l| !(finger.mcp()..=finger.tip()).contains(&l.id)).cloned().collect()
+    }
+
+    #[test]
+    fn full_hands_are_left_alone() {
+        let hand = fixtures::hand("open_right");
+        let mut memory = FingerMemory::default();
+        let mut landmarks = hand.landmarks.clone();
+        assert!(memory.fill(&mut landmarks, 0.0).is_empty());
+        assert_eq!(landmarks.len(), hand.landmarks.len());
+    }
+
+    #[test]
+    fn hidden_finger_is_held_relative_to_the_wrist_then_dropped() {
+        let hand = fixtures::hand("open_right");
+        let mut memory = FingerMemory::default();
+        memory.fill(&mut hand.landmarks.clone(), 0.0);
+
+        // 手が右へ動いている間に薬指が隠れる
+        let mut landmarks = without(&hand.landmarks, Finger::Ring);
+        for l in &mut landmarks {
+            l.x += 0.1;
+        }
+        assert_eq!(memory.fill(&mut landmarks, 0.1), vec![Finger::Ring]);
+        assert_eq!(landmarks.len(), hand.landmarks.len());
+        let original = landmark(&hand.landmarks, Finger::Ring.tip()).unwrap();
+        let held = landmark(&landmarks, Finger::Ring.tip()).unwrap();
+        let (wrist, start) = (landmark(&landmarks, WRIST).unwrap(), landmark(&hand.landmarks, WRIST).unwrap());
+        // 手首について動き、少しだけ手首へ縮む
+        assert!(held.x > original.x);
+        let reach = |l: &Landmark| (l.x - wrist.x).hypot(l.y - wrist.y);
+        let before = (original.x - start.x).hypot(original.y - start.y);
+        assert!(reach(held) < before && reach(held) > before * (1.0 - DECAY));
+
+        let mut later = without(&hand.landmarks, Finger::Ring);
+        assert_eq!(memory.fill(&mut later, HOLD_TIME + 0.1), vec![Finger::Ring]);
+        assert!(landmark(&later, Finger::Ring.tip()).is_none());
+    }
+
+    #[test]
+    fn nothing_is_filled_without_a_wrist() {
+        let hand = fixtures::hand("open_right");
+        let mut memory = FingerMemory::default();
+        memory.fill(&mut hand.landmarks.clone(), 0.0);
+        let mut landmarks: Vec<Landmark> = hand.landmarks.iter().filter(|l| l.id != WRIST).cloned().collect();
+        let count = landmarks.len();
+        assert!(memory.fill(&mut landmarks, 0.1).is_empty());
+        assert_eq!(landmarks.len(), count);
+    }
+}
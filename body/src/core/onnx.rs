@@ -0,0 +1,60 @@
+use std::path::Path;
+use tract_onnx::prelude::*;
+
+use crate::core::classifier::{GestureClassifier, LandmarkTensor, TENSOR_LEN};
+
+// 入力 [1, 63] の f32、出力 [1, ラベル数] のモデルを受け付ける
+pub struct OnnxClassifier {
+    name: String,
+    model: TypedRunnableModel<TypedModel>,
+    labels: Vec<String>,
+}
+
+impl OnnxClassifier {
+    pub fn load(path: &Path, labels: Vec<String>) -> TractResult<Self> {
+        let model = tract_onnx::onnx()
+            .model_for_path(path)?
+            .with_input_fact(0, f32::fact([1, TENSOR_LEN]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+        let name = path.file_name().map_or_else(|| "onnx".to_string(), |n| n.to_string_lossy().into_owned());
+        Ok(Self { name, model, labels })
+    }
+
+    fn run(&self, input: &LandmarkTensor) -> TractResult<Vec<f32>> {
+        let tensor = Tensor::from_shape(&[1, TENSOR_LEN], &input.0)?;
+        let outputs = self.model.run(tvec!(tensor.into()))?;
+        Ok(outputs[0].to_array_view::<f32>()?.iter().copied().collect())
+    }
+}
+
+impl GestureClassifier for OnnxClassifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn classify(&self, input: &LandmarkTensor) -> Vec<(String, f32)> {
+        // 推論に失敗したら空を返し、呼び出し側で送信側の判定に戻してもらう
+        let Ok(raw) = self.run(input) else {
+            return Vec::new();
+        };
+        // ラベルが足りない出力は番号で呼ぶ
+        to_probabilities(&raw)
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (self.labels.get(i).cloned().unwrap_or_else(|| i.to_string()), p))
+            .collect()
+    }
+}
+
+// モデルの出力が確率になっていなければ (ロジットなら) softmax をかける
+fn to_probabilities(raw: &[f32]) -> Vec<f32> {
+    let is_distribution = raw.iter().all(|v| (0.0..=1.0).contains(v)) && (raw.iter().sum::<f32>() - 1.0).abs() < 1e-3;
+    if is_distribution {
+        return raw.to_vec();
+    }
+    let max = raw.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exp: Vec<f32> = raw.iter().map(|v| (v - max).exp()).collect();
+    let sum: f32 = exp.iter().sum();
+    exp.iter().map(|v| v / sum).collect()
+}
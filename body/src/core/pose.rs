@@ -0,0 +1,69 @@
+use glam::{Mat3, Quat, Vec3};
+
+// 手のひらの向き。y は手首から中指の付け根、z は手のひらの法線、x は親指側。
+// 左手は x を反転させて右手と同じ右手系にそろえる
+pub fn palm_rotation(wrist: Vec3, index_mcp: Vec3, middle_mcp: Vec3, pinky_mcp: Vec3, right_hand: bool) -> Option<Quat> {
+    let up = (middle_mcp - wrist).try_normalize()?;
+    let across = if right_hand { index_mcp - pinky_mcp } else { pinky_mcp - index_mcp };
+    let normal = across.cross(up).try_normalize()?;
+    let side = up.cross(normal);
+    Some(Quat::from_mat3(&Mat3::from_cols(side, up, normal)).normalize())
+}
+
+// 回転と平行移動をまとめた剛体変換。補間や合成で位置と向きがずれない
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DualQuat {
+    pub real: Quat,
+    pub dual: Quat,
+}
+
+impl DualQuat {
+    pub fn from_rotation_translation(rotation: Quat, translation: Vec3) -> Self {
+        let t = Quat::from_xyzw(translation.x, translation.y, translation.z, 0.0);
+        Self { real: rotation, dual: (t * rotation) * 0.5 }
+    }
+
+    pub fn rotation(&self) -> Quat {
+        self.real
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        let t = (self.dual * 2.0) * self.real.conjugate();
+        Vec3::new(t.x, t.y, t.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_right_hand_has_identity_rotation() {
+        let rotation = palm_rotation(
+            Vec3::ZERO,
+            Vec3::new(0.5, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-0.5, 1.0, 0.0),
+            true,
+        )
+        .unwrap();
+        assert!((rotation * Vec3::Y).distance(Vec3::Y) < 1e-5);
+        assert!(rotation.angle_between(Quat::IDENTITY) < 1e-4);
+    }
+
+    #[test]
+    fn mirrored_left_hand_has_the_same_rotation() {
+        let right = palm_rotation(Vec3::ZERO, Vec3::new(0.5, 1.0, 0.0), Vec3::Y, Vec3::new(-0.5, 1.0, 0.0), true);
+        let left = palm_rotation(Vec3::ZERO, Vec3::new(-0.5, 1.0, 0.0), Vec3::Y, Vec3::new(0.5, 1.0, 0.0), false);
+        assert!(right.unwrap().angle_between(left.unwrap()) < 1e-4);
+    }
+
+    #[test]
+    fn dual_quat_round_trips_translation() {
+        let rotation = Quat::from_rotation_y(1.2);
+        let translation = Vec3::new(3.0, -1.0, 7.5);
+        let pose = DualQuat::from_rotation_translation(rotation, translation);
+        assert!(pose.translation().distance(translation) < 1e-5);
+        assert_eq!(pose.rotation(), rotation);
+    }
+}
@@ -0,0 +1,119 @@
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::core::gesture::{Finger, WRIST};
+use crate::packet::{Landmark, OneHand};
+
+const MIDDLE_MCP: usize = 9;
+
+fn point(landmarks: &[Landmark], id: usize) -> Option<Vec2> {
+    landmarks.iter().find(|l| l.id == id).map(|l| Vec2::new(l.x, l.y))
+}
+
+// 手首〜中指MCP の長さ (正規化座標)。カメラに近いほど大きく写る
+pub fn palm_length(hand: &OneHand) -> Option<f32> {
+    let length = point(&hand.landmarks, WRIST)?.distance(point(&hand.landmarks, MIDDLE_MCP)?);
+    (length > f32::EPSILON).then_some(length)
+}
+
+// 手の形の比率。長さはすべて手首〜中指MCP との比なので、カメラからの距離では変わらない
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HandProportions {
+    // 人差し指MCP〜小指MCP
+    pub palm_width: f32,
+    // 人差し指から小指まで、MCP から指先へ関節に沿った長さ
+    pub finger_lengths: [f32; 4],
+}
+
+impl HandProportions {
+    pub fn measure(hand: &OneHand) -> Option<Self> {
+        let palm = palm_length(hand)?;
+        let get = |id: usize| point(&hand.landmarks, id);
+        let palm_width = get(Finger::Index.mcp())?.distance(get(Finger::Pinky.mcp())?);
+        let mut finger_lengths = [0.0; 4];
+        for (length, finger) in finger_lengths.iter_mut().zip(Finger::ALL) {
+            let joints = [finger.mcp(), finger.mcp() + 1, finger.mcp() + 2, finger.tip()];
+            let mut along = 0.0;
+            for pair in joints.windows(2) {
+                along += get(pair[0])?.distance(get(pair[1])?);
+            }
+            *length = along / palm;
+        }
+        Some(Self { palm_width: palm_width / palm, finger_lengths })
+    }
+
+    // 各比率の相対誤差の最大値
+    pub fn difference(&self, other: &Self) -> f32 {
+        let rel = |a: f32, b: f32| (a - b).abs() / a.max(b).max(f32::EPSILON);
+        self.finger_lengths
+            .iter()
+            .zip(&other.finger_lengths)
+            .map(|(a, b)| rel(*a, *b))
+            .fold(rel(self.palm_width, other.palm_width), f32::max)
+    }
+
+    // 測った比率の項目ごとの中央値
+    pub fn median(samples: &[Self]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let median = |f: &dyn Fn(&Self) -> f32| {
+            let mut values: Vec<f32> = samples.iter().map(f).collect();
+            values.sort_by(f32::total_cmp);
+            values[values.len() / 2]
+        };
+        Some(Self {
+            palm_width: median(&|p| p.palm_width),
+            finger_lengths: std::array::from_fn(|i| median(&|p| p.finger_lengths[i])),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    fn moved(hand: &OneHand, scale: f32, offset: Vec2) -> OneHand {
+        let mut hand = hand.clone();
+        for l in &mut hand.landmarks {
+            l.x = l.x * scale + offset.x;
+            l.y = l.y * scale + offset.y;
+        }
+        hand
+    }
+
+    #[test]
+    fn proportions_do_not_change_with_distance_from_the_camera() {
+        let hand = fixtures::hand("open_right");
+        let near = HandProportions::measure(&hand).unwrap();
+        let far = HandProportions::measure(&moved(&hand, 0.5, Vec2::new(0.2, 0.1))).unwrap();
+        assert!(near.difference(&far) < 1e-4);
+        let far_palm = palm_length(&moved(&hand, 0.5, Vec2::ZERO)).unwrap();
+        assert!((far_palm - palm_length(&hand).unwrap() * 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn longer_fingers_are_a_different_hand() {
+        let hand = fixtures::hand("open_right");
+        let mut long = hand.clone();
+        for finger in Finger::ALL {
+            let mcp = point(&hand.landmarks, finger.mcp()).unwrap();
+            for l in long.landmarks.iter_mut().filter(|l| (finger.mcp() + 1..=finger.tip()).contains(&l.id)) {
+                let stretched = mcp + (Vec2::new(l.x, l.y) - mcp) * 1.3;
+                (l.x, l.y) = (stretched.x, stretched.y);
+            }
+        }
+        let a = HandProportions::measure(&hand).unwrap();
+        let b = HandProportions::measure(&long).unwrap();
+        assert!(a.difference(&b) > 0.2);
+    }
+
+    #[test]
+    fn median_is_taken_per_field() {
+        let p = |w: f32, f: f32| HandProportions { palm_width: w, finger_lengths: [f; 4] };
+        let median = HandProportions::median(&[p(0.7, 0.9), p(0.9, 0.7), p(0.8, 0.8)]).unwrap();
+        assert_eq!(median, p(0.8, 0.8));
+        assert_eq!(HandProportions::median(&[]), None);
+    }
+}
@@ -0,0 +1,103 @@
+use glam::Vec3;
+
+// この時間より間を空けずに近くへ続いた接触は同じ一発とみなし、最大値だけを残す
+const STRIKE_GAP: f32 = 0.15;
+const MERGE_DISTANCE: f32 = 1.5;
+// 最後の接触からこの時間で数字が消える
+pub const LIFETIME: f32 = 1.5;
+// 消えるまでに数字が浮き上がる高さ
+const RISE: f32 = 1.2;
+
+// 当たった場所に浮かべる一発ごとの最大の力
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Readout {
+    pub position: Vec3,
+    pub peak: f32,
+    last_hit: f32,
+}
+
+impl Readout {
+    // 浮き上がった位置と濃さ (1 から 0 へ)
+    pub fn shown(&self, now: f32) -> (Vec3, f32) {
+        let age = ((now - self.last_hit) / LIFETIME).clamp(0.0, 1.0);
+        (self.position + Vec3::Y * RISE * age, 1.0 - age)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImpactReadouts {
+    readouts: Vec<Readout>,
+    // 出してから一番強かった一発
+    pub best: f32,
+}
+
+impl ImpactReadouts {
+    // 一発の最大値が伸びたときは true
+    pub fn hit(&mut self, position: Vec3, force: f32, now: f32) -> bool {
+        self.best = self.best.max(force);
+        let strike = self
+            .readouts
+            .iter_mut()
+            .find(|r| now - r.last_hit <= STRIKE_GAP && r.position.distance(position) <= MERGE_DISTANCE);
+        match strike {
+            Some(readout) => {
+                readout.last_hit = now;
+                if force <= readout.peak {
+                    return false;
+                }
+                readout.peak = force;
+                readout.position = position;
+            }
+            None => self.readouts.push(Readout { position, peak: force, last_hit: now }),
+        }
+        true
+    }
+
+    pub fn expire(&mut self, now: f32) {
+        self.readouts.retain(|r| now - r.last_hit < LIFETIME);
+    }
+
+    pub fn readouts(&self) -> &[Readout] {
+        &self.readouts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contacts_of_one_strike_keep_the_peak() {
+        let mut readouts = ImpactReadouts::default();
+        assert!(readouts.hit(Vec3::ZERO, 100.0, 0.0));
+        assert!(readouts.hit(Vec3::X * 0.2, 400.0, 0.02));
+        assert!(!readouts.hit(Vec3::X * 0.3, 150.0, 0.04));
+        assert_eq!(readouts.readouts().len(), 1);
+        assert_eq!(readouts.readouts()[0].peak, 400.0);
+        assert_eq!(readouts.readouts()[0].position, Vec3::X * 0.2);
+    }
+
+    #[test]
+    fn separate_strikes_get_their_own_readout() {
+        let mut readouts = ImpactReadouts::default();
+        readouts.hit(Vec3::ZERO, 100.0, 0.0);
+        // 間を空けた次の一発と、同時に離れた場所への一発
+        readouts.hit(Vec3::ZERO, 50.0, 0.5);
+        readouts.hit(Vec3::Y * 3.0, 80.0, 0.5);
+        assert_eq!(readouts.readouts().len(), 3);
+        assert_eq!(readouts.best, 100.0);
+    }
+
+    #[test]
+    fn readouts_rise_fade_and_expire() {
+        let mut readouts = ImpactReadouts::default();
+        readouts.hit(Vec3::ZERO, 100.0, 0.0);
+        let (position, alpha) = readouts.readouts()[0].shown(LIFETIME / 2.0);
+        assert!(position.y > 0.0 && (alpha - 0.5).abs() < 1e-5);
+        readouts.expire(LIFETIME / 2.0);
+        assert_eq!(readouts.readouts().len(), 1);
+        readouts.expire(LIFETIME);
+        assert!(readouts.readouts().is_empty());
+        assert_eq!(readouts.best, 100.0);
+    }
+}
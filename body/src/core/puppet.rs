@@ -0,0 +1,138 @@
+use glam::Vec3;
+
+const THUMB_TIP: usize = 4;
+const INDEX_MCP: usize = 5;
+const INDEX_PIP: usize = 6;
+const INDEX_TIP: usize = 8;
+const MIDDLE_MCP: usize = 9;
+const MIDDLE_TIP: usize = 12;
+const PINKY_MCP: usize = 17;
+// 親指の先と人差し指・中指の先の間隔 (手のひらの幅に対する比)。この範囲を口の開き 0..1 に写す
+const CLOSED_GAP: f32 = 0.35;
+const OPEN_GAP: f32 = 1.4;
+// 口を開いた・閉じたとみなす開き。間を空けて、ぎりぎりの位置でぱくぱく鳴り続けないようにする
+#[cfg(any(test, feature = "audio"))]
+const FLAP_OPEN: f32 = 0.45;
+#[cfg(any(test, feature = "audio"))]
+const FLAP_CLOSED: f32 = 0.2;
+// 手のひらの幅に対する目の大きさと、指の甲から浮かせる高さ
+const EYE_RADIUS: f32 = 0.22;
+const EYE_LIFT: f32 = 0.25;
+
+// 親指を下あご、人差し指と中指を上あごにした手の人形の顔
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PuppetFace {
+    pub eyes: [Vec3; 2],
+    pub eye_radius: f32,
+    // 口の中心と、上あごから下あごへの向き
+    pub mouth: Vec3,
+    pub jaw: Vec3,
+    pub mouth_width: f32,
+    pub openness: f32,
+}
+
+fn upper_jaw(points: &[Vec3; 21]) -> Vec3 {
+    (points[INDEX_TIP] + points[MIDDLE_TIP]) / 2.0
+}
+
+// 口の開き (0: 閉じている、1: 大きく開いている)
+pub fn mouth_openness(points: &[Vec3; 21]) -> f32 {
+    let width = points[INDEX_MCP].distance(points[PINKY_MCP]);
+    if width <= f32::EPSILON {
+        return 0.0;
+    }
+    // 人差し指と中指のどちらか近いほうが親指に付けば閉じたとみなす
+    let thumb = points[THUMB_TIP];
+    let gap = thumb.distance(points[INDEX_TIP]).min(thumb.distance(points[MIDDLE_TIP])) / width;
+    ((gap - CLOSED_GAP) / (OPEN_GAP - CLOSED_GAP)).clamp(0.0, 1.0)
+}
+
+// back は手の甲の向き。目は人差し指と中指の付け根と第二関節の間の甲側に置く
+pub fn face(points: &[Vec3; 21], back: Vec3) -> Option<PuppetFace> {
+    let width = points[INDEX_MCP].distance(points[PINKY_MCP]);
+    let back = back.try_normalize()?;
+    if width <= f32::EPSILON {
+        return None;
+    }
+    let knuckles = (points[INDEX_MCP] + points[INDEX_PIP]) / 2.0;
+    let across = (points[INDEX_MCP] - points[MIDDLE_MCP]).reject_from(back).try_normalize()?;
+    let eye_center = knuckles + back * width * EYE_LIFT;
+    let eye_radius = width * EYE_RADIUS;
+    let upper = upper_jaw(points);
+    let thumb = points[THUMB_TIP];
+    Some(PuppetFace {
+        eyes: [eye_center + across * eye_radius * 1.2, eye_center - across * eye_radius * 1.2],
+        eye_radius,
+        mouth: (upper + thumb) / 2.0,
+        jaw: (thumb - upper).try_normalize().unwrap_or(-back),
+        mouth_width: width * 0.6,
+        openness: mouth_openness(points),
+    })
+}
+
+// 口を開いてから閉じた瞬間を拾う
+#[cfg(any(test, feature = "audio"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlapDetector {
+    open: bool,
+    // 開いていた間の一番大きな開き
+    widest: f32,
+}
+
+#[cfg(any(test, feature = "audio"))]
+impl FlapDetector {
+    // 閉じた瞬間に、それまでの一番大きな開きを返す
+    pub fn update(&mut self, openness: f32) -> Option<f32> {
+        if self.open {
+            self.widest = self.widest.max(openness);
+            if openness < FLAP_CLOSED {
+                self.open = false;
+                return Some(self.widest);
+            }
+        } else if openness > FLAP_OPEN {
+            self.open = true;
+            self.widest = openness;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+    use crate::core::mapping::{map_hand, Reach, LEGACY_REGION};
+
+    fn points(name: &str) -> [Vec3; 21] {
+        let mapped = map_hand(&fixtures::hand(name).landmarks, &LEGACY_REGION, &Reach::default());
+        std::array::from_fn(|i| mapped[&i])
+    }
+
+    #[test]
+    fn pinch_closes_mouth_and_open_hand_opens_it() {
+        let pinch = mouth_openness(&points("pinch_right"));
+        let open = mouth_openness(&points("open_right"));
+        assert!(pinch < 0.1, "{pinch}");
+        assert!(open > pinch + 0.3, "{open}");
+    }
+
+    #[test]
+    fn eyes_sit_on_back_of_hand() {
+        let points = points("open_right");
+        let back = (points[INDEX_MCP] - points[0]).cross(points[PINKY_MCP] - points[0]);
+        let face = face(&points, back).unwrap();
+        let knuckle = points[INDEX_MCP];
+        for eye in face.eyes {
+            assert!((eye - knuckle).dot(back.normalize()) > 0.0);
+        }
+        assert!(face.eyes[0].distance(face.eyes[1]) > face.eye_radius);
+    }
+
+    #[test]
+    fn flaps_once_per_open_close() {
+        let mut detector = FlapDetector::default();
+        let frames = [0.0, 0.3, 0.6, 0.9, 0.5, 0.3, 0.1, 0.05, 0.3, 0.1];
+        let flaps: Vec<f32> = frames.iter().filter_map(|o| detector.update(*o)).collect();
+        assert_eq!(flaps, vec![0.9]);
+    }
+}
@@ -0,0 +1,82 @@
+// 机に置いて休ませている手の判定の閾値。高さは仮想の机の面から測る
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestThresholds {
+    // これより低いと休ませ始めとみなす
+    pub rest_height: f32,
+    // 休ませた手はこれより高く持ち上げるまで休ませたままにする
+    pub lift_height: f32,
+    // 手のひらの向きと上下の向きの内積 (の絶対値) がこれ以上なら平ら
+    pub min_flatness: f32,
+    pub max_speed: f32,
+    // 低く・平らで・ゆっくりのままこの秒数たったら休ませる
+    pub dwell: f32,
+}
+
+// 机の上に手を置いて休ませている間は true を返す。置いてからしばらく待ってから休ませ、
+// 持ち上げたとき (lift_height を超えたとき) だけ戻す
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RestDetector {
+    settled_since: Option<f32>,
+    resting: bool,
+}
+
+impl RestDetector {
+    pub fn update(&mut self, height: f32, flatness: f32, speed: f32, now: f32, limits: &RestThresholds) -> bool {
+        if self.resting {
+            if height > limits.lift_height {
+                self.resting = false;
+                self.settled_since = None;
+            }
+            return self.resting;
+        }
+        let settled = height < limits.rest_height && flatness >= limits.min_flatness && speed < limits.max_speed;
+        if !settled {
+            self.settled_since = None;
+            return false;
+        }
+        let since = *self.settled_since.get_or_insert(now);
+        self.resting = now - since >= limits.dwell;
+        self.resting
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: RestThresholds =
+        RestThresholds { rest_height: 1.0, lift_height: 2.5, min_flatness: 0.8, max_speed: 2.0, dwell: 0.5 };
+
+    #[test]
+    fn rests_after_dwelling_low_flat_and_still() {
+        let mut detector = RestDetector::default();
+        assert!(!detector.update(0.5, 0.95, 0.1, 0.0, &LIMITS));
+        assert!(!detector.update(0.5, 0.95, 0.1, 0.3, &LIMITS));
+        assert!(detector.update(0.5, 0.95, 0.1, 0.6, &LIMITS));
+    }
+
+    #[test]
+    fn tilted_or_moving_hands_do_not_rest() {
+        let mut detector = RestDetector::default();
+        for i in 0..10 {
+            let now = i as f32 * 0.2;
+            assert!(!detector.update(0.5, 0.3, 0.1, now, &LIMITS));
+        }
+        assert!(!detector.update(0.5, 0.95, 0.1, 2.0, &LIMITS));
+        // 途中で動くと待ち時間をやり直す
+        assert!(!detector.update(0.5, 0.95, 5.0, 2.3, &LIMITS));
+        assert!(!detector.update(0.5, 0.95, 0.1, 2.6, &LIMITS));
+        assert!(detector.update(0.5, 0.95, 0.1, 3.1, &LIMITS));
+    }
+
+    #[test]
+    fn stays_resting_until_lifted_clear() {
+        let mut detector = RestDetector::default();
+        detector.update(0.5, 0.95, 0.1, 0.0, &LIMITS);
+        assert!(detector.update(0.5, 0.95, 0.1, 1.0, &LIMITS));
+        // 指を立てたり少し浮かせたりしても休ませたまま
+        assert!(detector.update(1.8, 0.2, 3.0, 1.1, &LIMITS));
+        assert!(!detector.update(3.0, 0.95, 0.1, 1.2, &LIMITS));
+        assert!(!detector.update(0.5, 0.95, 0.1, 1.3, &LIMITS));
+    }
+}
@@ -0,0 +1,160 @@
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const WRIST: usize = 0;
+const INDEX_MCP: usize = 5;
+const MIDDLE_MCP: usize = 9;
+const PINKY_MCP: usize = 17;
+
+// 1 本の骨と、その骨の向きを決めるランドマーク (from から to への向きに骨を向ける)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BoneTarget {
+    pub bone: String,
+    pub from: usize,
+    pub to: usize,
+}
+
+// 片手ぶんの骨の名前。fingers は親から子の順に並べる
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HandRig {
+    pub wrist: String,
+    #[serde(default)]
+    pub fingers: Vec<BoneTarget>,
+}
+
+// 読み込んだキャラクターの手の骨への対応づけ (character.json)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RigConfig {
+    // assets/ からの glTF のパス
+    pub model: String,
+    #[serde(default)]
+    pub position: [f32; 3],
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    // 骨の伸びる向き (骨のローカル座標)。多くの書き出し方では +Y
+    #[serde(default = "default_axis")]
+    pub bone_axis: [f32; 3],
+    // 手首の骨で、親指側を指すローカルの向き
+    #[serde(default = "default_side_axis")]
+    pub side_axis: [f32; 3],
+    // キーは "Right" / "Left" (追跡した手の左右)
+    #[serde(default)]
+    pub hands: HashMap<String, HandRig>,
+    // 骨ごとの補正 (x, y, z, w)。calibrate で書き込む
+    #[serde(default)]
+    pub offsets: HashMap<String, [f32; 4]>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_axis() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_side_axis() -> [f32; 3] {
+    [1.0, 0.0, 0.0]
+}
+
+impl RigConfig {
+    pub fn offset(&self, bone: &str) -> Quat {
+        self.offsets.get(bone).map_or(Quat::IDENTITY, |q| Quat::from_array(*q).normalize())
+    }
+}
+
+// 骨の休止姿勢 (rest、親に対するローカル回転) から、骨の軸 axis がワールドで target を向くよう
+// 最小の回転で振ったローカル回転。parent は親のワールド回転
+pub fn aim(parent: Quat, rest: Quat, axis: Vec3, target: Vec3) -> Option<Quat> {
+    let target = target.try_normalize()?;
+    let rest_world = parent * rest;
+    let current = (rest_world * axis).try_normalize()?;
+    let swing = Quat::from_rotation_arc(current, target);
+    Some((parent.inverse() * swing * rest_world).normalize())
+}
+
+// aim のあと、骨の軸まわりにひねって side_axis を target_side へ合わせる (手首用)
+pub fn aim_with_roll(
+    parent: Quat,
+    rest: Quat,
+    (axis, side_axis): (Vec3, Vec3),
+    target: Vec3,
+    target_side: Vec3,
+) -> Option<Quat> {
+    let local = aim(parent, rest, axis, target)?;
+    let world = parent * local;
+    let forward = (world * axis).normalize();
+    let side = (world * side_axis).reject_from(forward).try_normalize()?;
+    let wanted = target_side.reject_from(forward).try_normalize()?;
+    let angle = side.cross(wanted).dot(forward).atan2(side.dot(wanted));
+    Some((parent.inverse() * Quat::from_axis_angle(forward, angle) * world).normalize())
+}
+
+// 手首から中指の付け根への向きと、小指側から人差し指側への向き
+pub fn palm_frame(points: &[Option<Vec3>; 21]) -> Option<(Vec3, Vec3)> {
+    let wrist = points[WRIST]?;
+    let forward = points[MIDDLE_MCP]? - wrist;
+    let side = points[INDEX_MCP]? - points[PINKY_MCP]?;
+    Some((forward, side))
+}
+
+// 補正を掛けた回転 (offset * posed)
+pub fn apply_offset(offset: Quat, posed: Quat) -> Quat {
+    (offset * posed).normalize()
+}
+
+// 今の手の形で求めた回転 posed が休止姿勢 rest になるような補正。
+// 手を開いてモデルの休止姿勢と同じ形にした状態で取る
+pub fn calibration_offset(rest: Quat, posed: Quat) -> Quat {
+    (rest * posed.inverse()).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        a.distance(b) < 1e-4
+    }
+
+    #[test]
+    fn aim_points_the_bone_axis_at_the_target() {
+        let parent = Quat::from_rotation_z(0.4);
+        let rest = Quat::from_rotation_x(0.3);
+        let target = Vec3::new(1.0, 2.0, -0.5);
+        let local = aim(parent, rest, Vec3::Y, target).unwrap();
+        assert!(close(parent * local * Vec3::Y, target.normalize()));
+    }
+
+    #[test]
+    fn roll_lines_up_the_side_axis() {
+        let parent = Quat::from_rotation_y(1.0);
+        let target = Vec3::new(0.0, 0.0, -1.0);
+        let side = Vec3::new(0.0, 1.0, 0.0);
+        let local = aim_with_roll(parent, Quat::IDENTITY, (Vec3::Y, Vec3::X), target, side).unwrap();
+        let world = parent * local;
+        assert!(close(world * Vec3::Y, target));
+        assert!(close(world * Vec3::X, side));
+    }
+
+    #[test]
+    fn calibration_returns_rest_at_the_calibrated_pose() {
+        let rest = Quat::from_rotation_x(0.2);
+        let posed = Quat::from_euler(glam::EulerRot::XYZ, 0.5, -0.3, 0.8);
+        let offset = calibration_offset(rest, posed);
+        assert!(apply_offset(offset, posed).angle_between(rest) < 1e-4);
+    }
+
+    #[test]
+    fn config_fills_defaults() {
+        let config: RigConfig = serde_json::from_str(
+            r#"{"model": "characters/robot.glb", "hands": {"Right": {"wrist": "hand_r"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.scale, 1.0);
+        assert_eq!(config.bone_axis, [0.0, 1.0, 0.0]);
+        assert_eq!(config.offset("hand_r"), Quat::IDENTITY);
+        assert!(config.hands["Right"].fingers.is_empty());
+    }
+}
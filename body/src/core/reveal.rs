@@ -0,0 +1,70 @@
+use glam::Vec3;
+
+// 円すいの縁と先端からこの距離までは、離れるほど薄く見せる
+const SOFT_EDGE: f32 = 1.0;
+
+// 手のひらから伸ばす懐中電灯の光の円すい
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cone {
+    pub apex: Vec3,
+    // 長さ 1
+    pub direction: Vec3,
+    pub half_angle: f32,
+    pub range: f32,
+}
+
+impl Cone {
+    // 中心 point・半径 radius の物体がどれだけ照らされているか。0 (外) から 1 (中)。
+    // 物体の一部でも光に入っていれば 1
+    pub fn reveal(&self, point: Vec3, radius: f32) -> f32 {
+        let offset = point - self.apex;
+        let along = offset.dot(self.direction);
+        if along < -radius {
+            return 0.0;
+        }
+        let across = (offset - self.direction * along).length();
+        let cone_radius = along.max(0.0) * self.half_angle.tan();
+        // 円すいの側面までの距離 (おおよそ) と、先端を越えた距離
+        let outside = (across - cone_radius) * self.half_angle.cos() - radius;
+        let past = along - self.range - radius;
+        let fade = |gap: f32| (1.0 - gap.max(0.0) / SOFT_EDGE).clamp(0.0, 1.0);
+        fade(outside) * fade(past)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone() -> Cone {
+        Cone { apex: Vec3::ZERO, direction: Vec3::Z, half_angle: 0.4, range: 20.0 }
+    }
+
+    #[test]
+    fn objects_in_the_beam_are_revealed() {
+        let cone = cone();
+        assert_eq!(cone.reveal(Vec3::new(0.0, 0.0, 10.0), 0.5), 1.0);
+        // 軸から外れていても、円すいの中なら見える
+        assert_eq!(cone.reveal(Vec3::new(3.0, 0.0, 10.0), 0.5), 1.0);
+        // 中心は外でも、大きな物体が光にかかっていれば見える
+        assert_eq!(cone.reveal(Vec3::new(5.5, 0.0, 10.0), 2.0), 1.0);
+    }
+
+    #[test]
+    fn objects_outside_behind_or_beyond_stay_hidden() {
+        let cone = cone();
+        assert_eq!(cone.reveal(Vec3::new(10.0, 0.0, 5.0), 0.5), 0.0);
+        assert_eq!(cone.reveal(Vec3::new(0.0, 0.0, -3.0), 0.5), 0.0);
+        assert_eq!(cone.reveal(Vec3::new(0.0, 0.0, 25.0), 0.5), 0.0);
+    }
+
+    #[test]
+    fn the_edge_fades_instead_of_popping() {
+        let cone = cone();
+        let edge = 10.0 * 0.4f32.tan();
+        let shown: Vec<f32> = (0..6).map(|i| cone.reveal(Vec3::new(edge + i as f32 * 0.3, 0.0, 10.0), 0.0)).collect();
+        assert!(shown.windows(2).all(|w| w[0] >= w[1]), "{shown:?}");
+        assert!(shown[1] > 0.0 && shown[1] < 1.0, "{shown:?}");
+        assert_eq!(shown[5], 0.0);
+    }
+}
@@ -0,0 +1,88 @@
+use glam::{Quat, Vec3};
+
+// 両手のひらの間がこの範囲なら、すくう形とみなす
+const MIN_GAP: f32 = 0.8;
+const MAX_GAP: f32 = 5.0;
+// 手のひらの法線と、手のひら同士を結ぶ向きの cos。これ以上なら向かい合っている
+const MIN_FACING: f32 = 0.7;
+// 手のひらを結ぶ軸からこの距離までを両手の間とみなす
+pub const SCOOP_RADIUS: f32 = 1.5;
+
+// 向かい合った両手のひらの間の空間。a から b への軸に沿った円柱で表す
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scoop {
+    pub center: Vec3,
+    pub axis: Vec3,
+    pub gap: f32,
+}
+
+impl Scoop {
+    // 法線は手のひら側と甲側のどちらを向いていてもよい (左右で符号がそろわないため)
+    pub fn between(a: Vec3, normal_a: Vec3, b: Vec3, normal_b: Vec3) -> Option<Self> {
+        let gap = a.distance(b);
+        if !(MIN_GAP..=MAX_GAP).contains(&gap) {
+            return None;
+        }
+        let axis = (b - a) / gap;
+        if normal_a.normalize_or_zero().dot(axis).abs() < MIN_FACING
+            || normal_b.normalize_or_zero().dot(axis).abs() < MIN_FACING
+        {
+            return None;
+        }
+        Some(Self { center: (a + b) / 2.0, axis, gap })
+    }
+
+    // 半径 radius の物体がまるごと両手の間に入っているか
+    pub fn contains(&self, point: Vec3, radius: f32) -> bool {
+        let offset = point - self.center;
+        let along = offset.dot(self.axis);
+        let across = (offset - self.axis * along).length();
+        along.abs() + radius <= self.gap / 2.0 && across <= SCOOP_RADIUS
+    }
+
+    // 運ぶ物体の位置を表す座標系の向き。x が手のひらを結ぶ軸になる
+    pub fn rotation(&self, previous: Quat) -> Quat {
+        // 軸まわりのねじれは前の向きから引き継ぎ、軸の変化分だけ回す
+        Quat::from_rotation_arc(previous * Vec3::X, self.axis) * previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_palms_facing_each_other() {
+        let a = Vec3::new(-1.5, 0.0, 0.0);
+        let b = Vec3::new(1.5, 0.0, 0.0);
+        assert!(Scoop::between(a, Vec3::X, b, Vec3::NEG_X).is_some());
+        // 法線の符号はどちらでもよい
+        assert!(Scoop::between(a, Vec3::NEG_X, b, Vec3::NEG_X).is_some());
+        // 両手とも下を向いている
+        assert!(Scoop::between(a, Vec3::NEG_Y, b, Vec3::NEG_Y).is_none());
+        // 離れすぎ・近すぎ
+        assert!(Scoop::between(a * 4.0, Vec3::X, b * 4.0, Vec3::NEG_X).is_none());
+        assert!(Scoop::between(a * 0.1, Vec3::X, b * 0.1, Vec3::NEG_X).is_none());
+    }
+
+    #[test]
+    fn contains_only_objects_between_palms() {
+        let scoop = Scoop::between(Vec3::new(-2.0, 0.0, 0.0), Vec3::X, Vec3::new(2.0, 0.0, 0.0), Vec3::NEG_X).unwrap();
+        assert!(scoop.contains(Vec3::new(0.5, 0.3, 0.0), 0.4));
+        // 手のひらからはみ出している
+        assert!(!scoop.contains(Vec3::new(1.8, 0.0, 0.0), 0.4));
+        // 軸から遠い
+        assert!(!scoop.contains(Vec3::new(0.0, 2.0, 0.0), 0.2));
+    }
+
+    #[test]
+    fn rotation_follows_axis_keeping_twist() {
+        let scoop = Scoop::between(Vec3::ZERO, Vec3::Z, Vec3::new(0.0, 0.0, 3.0), Vec3::Z).unwrap();
+        let twisted = Quat::from_rotation_x(0.5);
+        let rotation = scoop.rotation(twisted);
+        assert!((rotation * Vec3::X - Vec3::Z).length() < 1e-5);
+        // 軸が変わらなければ向きもそのまま
+        let same = Scoop { axis: Vec3::X, ..scoop }.rotation(twisted);
+        assert!(same.angle_between(twisted) < 1e-4);
+    }
+}
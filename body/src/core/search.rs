@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+// 大きな場面を調べるときに物体を絞り込む条件。省いた項目は問わない
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObjectQuery {
+    pub heavier_than: Option<f32>,
+    pub lighter_than: Option<f32>,
+    // 中心から表面までの距離
+    pub larger_than: Option<f32>,
+    pub smaller_than: Option<f32>,
+    // 出てきてからこの秒数以内
+    pub spawned_within: Option<f32>,
+    pub sleeping: Option<bool>,
+    pub held: Option<bool>,
+}
+
+// 条件と照らし合わせる物体の様子
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ObjectFacts {
+    pub mass: f32,
+    pub radius: f32,
+    // 出てきてからの秒数。分からなければ None
+    pub age: Option<f32>,
+    pub sleeping: bool,
+    pub held: bool,
+}
+
+impl ObjectQuery {
+    pub fn matches(&self, facts: &ObjectFacts) -> bool {
+        self.heavier_than.is_none_or(|m| facts.mass > m)
+            && self.lighter_than.is_none_or(|m| facts.mass < m)
+            && self.larger_than.is_none_or(|r| facts.radius > r)
+            && self.smaller_than.is_none_or(|r| facts.radius < r)
+            && self.spawned_within.is_none_or(|s| facts.age.is_some_and(|age| age <= s))
+            && self.sleeping.is_none_or(|s| facts.sleeping == s)
+            && self.held.is_none_or(|h| facts.held == h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOX: ObjectFacts = ObjectFacts { mass: 10.0, radius: 1.0, age: Some(30.0), sleeping: true, held: false };
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(ObjectQuery::default().matches(&BOX));
+    }
+
+    #[test]
+    fn every_given_criterion_must_hold() {
+        let query = ObjectQuery { heavier_than: Some(5.0), sleeping: Some(true), ..Default::default() };
+        assert!(query.matches(&BOX));
+        assert!(!query.matches(&ObjectFacts { sleeping: false, ..BOX }));
+        assert!(!query.matches(&ObjectFacts { mass: 2.0, ..BOX }));
+    }
+
+    #[test]
+    fn unknown_age_is_not_recent() {
+        let query: ObjectQuery = serde_json::from_str(r#"{"spawned_within": 60}"#).unwrap();
+        assert!(query.matches(&BOX));
+        assert!(!query.matches(&ObjectFacts { age: Some(90.0), ..BOX }));
+        assert!(!query.matches(&ObjectFacts { age: None, ..BOX }));
+        assert!(serde_json::from_str::<ObjectQuery>(r#"{"heavy": 5}"#).is_err());
+    }
+}
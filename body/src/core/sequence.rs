@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+// sequences.json の中身。コードを書かずにデモの段取りを組むためのもの
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SequenceFile {
+    #[serde(default)]
+    pub sequences: Vec<Sequence>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sequence {
+    pub name: String,
+    pub trigger: Trigger,
+    pub keyframes: Vec<Keyframe>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    // 起動からの秒数
+    Time { at: f32 },
+    // このジェスチャーを hold 秒保ったとき。side を省くとどちらの手でもよい
+    Gesture {
+        gesture: String,
+        #[serde(default)]
+        side: Option<String>,
+        #[serde(default)]
+        hold: f32,
+    },
+}
+
+// at はシーケンスが始まってからの秒数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    pub at: f32,
+    pub event: SceneEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SceneEvent {
+    // center を中心に x 方向へ並べて箱を落とす。name を付けると move_prop で動かせる
+    SpawnWave {
+        count: usize,
+        center: [f32; 3],
+        #[serde(default = "default_spacing")]
+        spacing: f32,
+        #[serde(default = "default_size")]
+        size: f32,
+        #[serde(default)]
+        material: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+    // 省いた項目は変えない
+    Light {
+        #[serde(default)]
+        ambient: Option<f32>,
+        #[serde(default)]
+        intensity: Option<f32>,
+        #[serde(default)]
+        color: Option<[f32; 3]>,
+    },
+    // name を付けて出した箱を duration 秒かけて to へ運ぶ
+    MoveProp {
+        name: String,
+        to: [f32; 3],
+        #[serde(default = "default_duration")]
+        duration: f32,
+    },
+}
+
+fn default_spacing() -> f32 {
+    2.5
+}
+
+fn default_size() -> f32 {
+    2.0
+}
+
+fn default_duration() -> f32 {
+    1.0
+}
+
+impl Sequence {
+    // 前のフレームの経過時間 from から今の経過時間 to までに来たキーフレーム。
+    // 区間は半開なので、どのキーフレームもちょうど一度だけ返る
+    pub fn due(&self, from: f32, to: f32) -> impl Iterator<Item = &Keyframe> {
+        self.keyframes.iter().filter(move |k| k.at >= from && k.at < to)
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.keyframes.iter().map(|k| k.at).fold(0.0, f32::max)
+    }
+}
+
+// count 個を center を中心に x 方向へ spacing おきに並べる
+pub fn wave_positions(center: [f32; 3], count: usize, spacing: f32) -> Vec<[f32; 3]> {
+    let first = center[0] - (count.saturating_sub(1)) as f32 * spacing / 2.0;
+    (0..count).map(|i| [first + i as f32 * spacing, center[1], center[2]]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"{
+        "sequences": [{
+            "name": "intro",
+            "trigger": { "type": "gesture", "gesture": "Fist", "side": "Right", "hold": 0.5 },
+            "keyframes": [
+                { "at": 0.0, "event": { "type": "spawn_wave", "count": 3, "center": [0, 15, 0], "name": "wave" } },
+                { "at": 1.5, "event": { "type": "light", "ambient": 800 } },
+                { "at": 3.0, "event": { "type": "move_prop", "name": "wave", "to": [0, 5, 0] } }
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn parses_example_file() {
+        let file: SequenceFile = serde_json::from_str(EXAMPLE).unwrap();
+        let sequence = &file.sequences[0];
+        assert_eq!(
+            sequence.trigger,
+            Trigger::Gesture { gesture: "Fist".into(), side: Some("Right".into()), hold: 0.5 }
+        );
+        assert!(matches!(sequence.keyframes[0].event, SceneEvent::SpawnWave { spacing: 2.5, size: 2.0, .. }));
+        assert!(matches!(sequence.keyframes[2].event, SceneEvent::MoveProp { duration: 1.0, .. }));
+        assert_eq!(sequence.duration(), 3.0);
+    }
+
+    #[test]
+    fn each_keyframe_is_due_once() {
+        let file: SequenceFile = serde_json::from_str(EXAMPLE).unwrap();
+        let sequence = &file.sequences[0];
+        let mut fired = Vec::new();
+        let mut t = 0.0;
+        while t < 4.0 {
+            fired.extend(sequence.due(t, t + 0.1).map(|k| k.at));
+            t += 0.1;
+        }
+        assert_eq!(fired, vec![0.0, 1.5, 3.0]);
+    }
+
+    #[test]
+    fn wave_is_centered() {
+        assert_eq!(wave_positions([0.0, 10.0, 0.0], 3, 2.0), vec![[-2.0, 10.0, 0.0], [0.0, 10.0, 0.0], [2.0, 10.0, 0.0]]);
+        assert!(wave_positions([0.0; 3], 0, 2.0).is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+// 終了時に書き出す一回分の利用記録
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    // 開始時刻 (UNIX 秒)
+    pub started_at: u64,
+    pub duration_secs: f32,
+    pub clients: usize,
+    // ジェスチャーに入った回数。Neutral は数えない
+    pub gestures: BTreeMap<String, u32>,
+    pub objects_spawned: u32,
+    pub packets_received: u64,
+    // 署名がない・合わないために捨てたパケットの数
+    #[serde(default)]
+    pub rejected_packets: u64,
+    // 1 秒あたりのパケット数の最大
+    pub peak_packet_rate: u32,
+    // 撮影から表示までの遅れの平均。手が一度も映らなければ None
+    pub average_latency_ms: Option<f32>,
+}
+
+impl SessionSummary {
+    pub fn to_markdown(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "# Session summary\n");
+        let _ = writeln!(text, "| metric | value |\n| --- | --- |");
+        let _ = writeln!(text, "| started (unix) | {} |", self.started_at);
+        let _ = writeln!(text, "| duration | {} |", format_duration(self.duration_secs));
+        let _ = writeln!(text, "| clients | {} |", self.clients);
+        let _ = writeln!(text, "| objects spawned | {} |", self.objects_spawned);
+        let _ = writeln!(text, "| packets received | {} |", self.packets_received);
+        let _ = writeln!(text, "| packets rejected | {} |", self.rejected_packets);
+        let _ = writeln!(text, "| peak packet rate | {}/s |", self.peak_packet_rate);
+        let latency = self.average_latency_ms.map_or("n/a".to_string(), |ms| format!("{ms:.1} ms"));
+        let _ = writeln!(text, "| average latency | {latency} |");
+        let _ = writeln!(text, "\n## Gestures\n");
+        if self.gestures.is_empty() {
+            let _ = writeln!(text, "No gestures recorded.");
+        } else {
+            let _ = writeln!(text, "| gesture | count |\n| --- | --- |");
+            for (gesture, count) in &self.gestures {
+                let _ = writeln!(text, "| {gesture} | {count} |");
+            }
+        }
+        text
+    }
+}
+
+fn format_duration(secs: f32) -> String {
+    let total = secs.max(0.0) as u64;
+    format!("{}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+}
+
+// 直近 1 秒に届いた数を数え、その最大を覚えておく
+#[derive(Debug, Clone, Default)]
+pub struct RateMeter {
+    arrivals: VecDeque<(f64, u32)>,
+    in_window: u32,
+    pub peak: u32,
+}
+
+impl RateMeter {
+    pub fn record(&mut self, now: f64, count: u32) {
+        if count > 0 {
+            self.arrivals.push_back((now, count));
+            self.in_window += count;
+        }
+        while let Some(&(t, n)) = self.arrivals.front() {
+            if now - t < 1.0 {
+                break;
+            }
+            self.arrivals.pop_front();
+            self.in_window -= n;
+        }
+        self.peak = self.peak.max(self.in_window);
+    }
+}
+
+// 逐次平均
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunningMean {
+    sum: f64,
+    count: u64,
+}
+
+impl RunningMean {
+    pub fn add(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_meter_keeps_peak_of_one_second_window() {
+        let mut meter = RateMeter::default();
+        for i in 0..30 {
+            meter.record(i as f64 / 30.0, 1);
+        }
+        assert_eq!(meter.peak, 30);
+        // 間が空いたあとは窓が空になるが、最大は残る
+        meter.record(5.0, 2);
+        assert_eq!(meter.in_window, 2);
+        assert_eq!(meter.peak, 30);
+    }
+
+    #[test]
+    fn running_mean_is_none_without_samples() {
+        let mut mean = RunningMean::default();
+        assert_eq!(mean.mean(), None);
+        mean.add(10.0);
+        mean.add(20.0);
+        assert_eq!(mean.mean(), Some(15.0));
+    }
+
+    #[test]
+    fn markdown_lists_gestures_and_duration() {
+        let summary = SessionSummary {
+            duration_secs: 3725.0,
+            gestures: BTreeMap::from([("Fist".to_string(), 4), ("Open".to_string(), 2)]),
+            ..Default::default()
+        };
+        let text = summary.to_markdown();
+        assert!(text.contains("| duration | 1:02:05 |"));
+        assert!(text.contains("| Fist | 4 |"));
+        assert!(text.contains("| average latency | n/a |"));
+    }
+}
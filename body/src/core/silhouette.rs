@@ -0,0 +1,200 @@
+use glam::{Vec2, Vec3};
+use std::f32::consts::TAU;
+
+// 手のひらの輪郭に使う点 (手首・親指の付け根・各指の付け根)
+pub const PALM: [usize; 6] = [0, 1, 5, 9, 13, 17];
+// 付け根から指先への並び
+pub const FINGERS: [[usize; 4]; 5] = [[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 16], [17, 18, 19, 20]];
+
+const TUBE_SIDES: u32 = 8;
+// 手のひらの幅 (人差し指と小指の付け根の距離) に対する厚みと指の太さ
+const PALM_THICKNESS: f32 = 0.12;
+const FINGER_RADIUS: f32 = 0.11;
+// 指先に向かって細くする割合
+const FINGER_TAPER: f32 = 0.3;
+
+// 21 点から作る手の形。手のひらは輪郭を押し出した板、指は関節をつないだ管
+#[derive(Debug, Clone, Default)]
+pub struct SilhouetteMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl SilhouetteMesh {
+    pub fn from_landmarks(points: &[Vec3; 21]) -> Self {
+        let mut mesh = Self::default();
+        let width = points[5].distance(points[17]);
+        if !width.is_finite() || width <= f32::EPSILON {
+            return mesh;
+        }
+        mesh.add_palm(points, width * PALM_THICKNESS);
+        for finger in FINGERS {
+            mesh.add_tube(&finger.map(|i| points[i]), width * FINGER_RADIUS);
+        }
+        mesh
+    }
+
+    fn push(&mut self, position: Vec3, normal: Vec3) -> u32 {
+        self.positions.push(position.to_array());
+        self.normals.push(normal.to_array());
+        self.positions.len() as u32 - 1
+    }
+
+    fn add_palm(&mut self, points: &[Vec3; 21], thickness: f32) {
+        let origin = points[0];
+        let normal = (points[5] - origin).cross(points[17] - origin).normalize_or_zero();
+        let u = (points[9] - origin).reject_from(normal).normalize_or_zero();
+        if normal == Vec3::ZERO || u == Vec3::ZERO {
+            return;
+        }
+        // 手のひらの平面に落として輪郭を求める。u × v = normal なので反時計回りが表になる
+        let v = normal.cross(u);
+        let flat: Vec<Vec2> = PALM
+            .iter()
+            .map(|&i| {
+                let d = points[i] - origin;
+                Vec2::new(d.dot(u), d.dot(v))
+            })
+            .collect();
+        let hull = convex_hull(&flat);
+        if hull.len() < 3 {
+            return;
+        }
+        let corner = |p: Vec2| origin + u * p.x + v * p.y;
+        let half = normal * thickness * 0.5;
+        let n = hull.len() as u32;
+
+        for (face, offset) in [(normal, half), (-normal, -half)] {
+            let start = self.positions.len() as u32;
+            for &p in &hull {
+                self.push(corner(p) + offset, face);
+            }
+            for k in 1..n - 1 {
+                if face == normal {
+                    self.indices.extend([start, start + k, start + k + 1]);
+                } else {
+                    self.indices.extend([start, start + k + 1, start + k]);
+                }
+            }
+        }
+        for k in 0..hull.len() {
+            let (a, b) = (corner(hull[k]), corner(hull[(k + 1) % hull.len()]));
+            let outward = (b - a).cross(normal).normalize_or_zero();
+            let i = self.push(a + half, outward);
+            self.push(b + half, outward);
+            self.push(b - half, outward);
+            self.push(a - half, outward);
+            self.indices.extend([i, i + 3, i + 2, i, i + 2, i + 1]);
+        }
+    }
+
+    fn add_tube(&mut self, joints: &[Vec3], radius: f32) {
+        let last = joints.len() - 1;
+        let directions: Vec<Vec3> = (0..joints.len())
+            .map(|k| (joints[(k + 1).min(last)] - joints[k.saturating_sub(1)]).normalize_or_zero())
+            .collect();
+        if directions.contains(&Vec3::ZERO) {
+            return;
+        }
+        let first_ring = self.positions.len() as u32;
+        let mut side: Option<Vec3> = None;
+        for (k, (&joint, &dir)) in joints.iter().zip(&directions).enumerate() {
+            // 前の輪の向きを引き継いで、管がねじれないようにする
+            let u = side
+                .map(|s| s.reject_from_normalized(dir).normalize_or_zero())
+                .filter(|s| *s != Vec3::ZERO)
+                .unwrap_or_else(|| dir.any_orthonormal_vector());
+            side = Some(u);
+            let w = dir.cross(u);
+            let r = radius * (1.0 - FINGER_TAPER * k as f32 / last as f32);
+            for s in 0..TUBE_SIDES {
+                let angle = TAU * s as f32 / TUBE_SIDES as f32;
+                let out = u * angle.cos() + w * angle.sin();
+                self.push(joint + out * r, out);
+            }
+        }
+        for ring in 0..last as u32 {
+            for s in 0..TUBE_SIDES {
+                let a = first_ring + ring * TUBE_SIDES + s;
+                let b = first_ring + ring * TUBE_SIDES + (s + 1) % TUBE_SIDES;
+                let (c, d) = (a + TUBE_SIDES, b + TUBE_SIDES);
+                self.indices.extend([a, b, d, a, d, c]);
+            }
+        }
+        // 指先は尖らせて閉じる。付け根側は手のひらの中に隠れる
+        let tip_dir = directions[last];
+        let tip_ring = first_ring + last as u32 * TUBE_SIDES;
+        let tip_radius = radius * (1.0 - FINGER_TAPER);
+        let tip = self.push(joints[last] + tip_dir * tip_radius, tip_dir);
+        for s in 0..TUBE_SIDES {
+            self.indices.extend([tip_ring + s, tip_ring + (s + 1) % TUBE_SIDES, tip]);
+        }
+    }
+}
+
+// 反時計回りの凸包 (Andrew の単調連鎖法)
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let chain = |points: &mut dyn Iterator<Item = Vec2>| {
+        let mut chain: Vec<Vec2> = Vec::new();
+        for p in points {
+            while chain.len() >= 2 && (chain[chain.len() - 1] - chain[chain.len() - 2]).perp_dot(p - chain[chain.len() - 2]) <= 0.0 {
+                chain.pop();
+            }
+            chain.push(p);
+        }
+        chain.pop();
+        chain
+    };
+    let mut hull = chain(&mut sorted.iter().copied());
+    hull.extend(chain(&mut sorted.iter().rev().copied()));
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // xy 平面上に広げた手
+    fn flat_hand() -> [Vec3; 21] {
+        let mut points = [Vec3::ZERO; 21];
+        for (f, finger) in FINGERS.iter().enumerate() {
+            let x = f as f32 - 2.0;
+            for (k, &i) in finger.iter().enumerate() {
+                points[i] = Vec3::new(x * (1.0 + 0.2 * k as f32), 2.0 + k as f32, 0.0);
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn hull_drops_interior_points_and_winds_counterclockwise() {
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::splat(0.5)];
+        let hull = convex_hull(&square);
+        assert_eq!(hull.len(), 4);
+        let area: f32 = (0..hull.len()).map(|i| hull[i].perp_dot(hull[(i + 1) % hull.len()])).sum();
+        assert!(area > 0.0);
+    }
+
+    #[test]
+    fn hand_mesh_is_well_formed() {
+        let mesh = SilhouetteMesh::from_landmarks(&flat_hand());
+        assert!(!mesh.indices.is_empty());
+        assert_eq!(mesh.indices.len() % 3, 0);
+        assert_eq!(mesh.positions.len(), mesh.normals.len());
+        assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.positions.len()));
+        assert!(mesh.normals.iter().all(|n| (Vec3::from_array(*n).length() - 1.0).abs() < 1e-4));
+    }
+
+    #[test]
+    fn collapsed_hand_gives_empty_mesh() {
+        let mesh = SilhouetteMesh::from_landmarks(&[Vec3::ONE; 21]);
+        assert!(mesh.positions.is_empty() && mesh.indices.is_empty());
+    }
+}
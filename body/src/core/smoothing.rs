@@ -0,0 +1,301 @@
+use glam::Vec3;
+use std::collections::HashMap;
+
+pub const DEFAULT_MAX_CATCH_UP: f32 = 2.0;
+// 描画が引っかかっても一度に進める時間はこれまで
+const MAX_STEP: f32 = 0.1;
+// これ以上間が空いた (または時刻が戻った) パケットは補間せずにそこから再開する
+pub const MAX_GAP: f64 = 0.5;
+// 送信側と時計がずれているとみなす遅延。これを超えた測定値は捨てる
+const MAX_TRANSPORT_LATENCY: f64 = 1.0;
+// 速度と遅延の指数移動平均の重み
+const VELOCITY_BLEND: f32 = 0.5;
+const LATENCY_BLEND: f64 = 0.1;
+
+// パケットの時刻軸で直前の位置から最新の目標へ補間する
+#[derive(Default, Debug, Clone)]
+pub struct Track {
+    from: HashMap<usize, Vec3>,
+    to: HashMap<usize, Vec3>,
+    from_stamp: f64,
+    to_stamp: f64,
+    playhead: f64,
+    interval: f64,
+    // パケットから求めたランドマークの速度 (ワールド単位/秒)
+    velocity: HashMap<usize, Vec3>,
+    // 撮影から受信までの遅延 (秒)
+    latency: f64,
+}
+
+impl Track {
+    fn progress(&self) -> f32 {
+        let span = self.to_stamp - self.from_stamp;
+        if span <= 0.0 {
+            return 1.0;
+        }
+        ((self.playhead - self.from_stamp) / span).clamp(0.0, 1.0) as f32
+    }
+
+    pub fn sample(&self, id: usize) -> Option<Vec3> {
+        let to = self.to.get(&id)?;
+        let from = self.from.get(&id).unwrap_or(to);
+        Some(from.lerp(*to, self.progress()))
+    }
+
+    pub fn velocity(&self, id: usize) -> Vec3 {
+        self.velocity.get(&id).copied().unwrap_or(Vec3::ZERO)
+    }
+
+    // 撮影時刻から見て表示がどれだけ遅れているか (通信の遅延 + 再生の遅れ)
+    pub fn delay(&self) -> f64 {
+        self.latency + (self.to_stamp - self.playhead).max(0.0)
+    }
+
+    pub fn playhead(&self) -> f64 {
+        self.playhead
+    }
+
+    fn restart(&mut self, stamp: f64, targets: HashMap<usize, Vec3>) {
+        self.from.clone_from(&targets);
+        self.to = targets;
+        self.from_stamp = stamp;
+        self.to_stamp = stamp;
+        self.playhead = stamp;
+        self.velocity.clear();
+    }
+
+    pub fn push(&mut self, stamp: f64, targets: HashMap<usize, Vec3>) {
+        let gap = stamp - self.to_stamp;
+        if self.to.is_empty() || !(0.0..=MAX_GAP).contains(&gap) {
+            self.restart(stamp, targets);
+            return;
+        }
+        if gap == 0.0 {
+            self.to = targets;
+            return;
+        }
+
+        self.interval = if self.interval > 0.0 { self.interval * 0.9 + gap * 0.1 } else { gap };
+        for (id, target) in &targets {
+            if let Some(previous) = self.to.get(id) {
+                let measured = (*target - *previous) / gap as f32;
+                let velocity = self.velocity.entry(*id).or_insert(measured);
+                *velocity = velocity.lerp(measured, VELOCITY_BLEND);
+            }
+        }
+        self.from = targets.keys().filter_map(|id| self.sample(*id).map(|p| (*id, p))).collect();
+        self.from_stamp = self.playhead;
+        self.to = targets;
+        self.to_stamp = stamp;
+    }
+
+    // 通常は 1 パケット分遅れて再生し、それ以上遅れたら max_catch_up 倍速まで上げて追いつく。
+    // rate はパケットの時刻が実時間に対して進む速さ (リプレイの再生速度)。追いつく上限もこれに合わせる
+    pub fn advance(&mut self, dt: f32, rate: f32, max_catch_up: f32) {
+        let rate = rate.max(f32::EPSILON) as f64;
+        let step = dt.min(MAX_STEP) as f64 * rate;
+        let expected = self.interval.max(1e-3);
+        if self.to_stamp - self.playhead > MAX_GAP {
+            self.playhead = self.to_stamp - expected;
+        }
+        let lag = self.to_stamp - self.playhead;
+        let speed = (lag / expected).clamp(1.0, max_catch_up.max(1.0) as f64);
+        self.playhead = (self.playhead + step * speed).min(self.to_stamp);
+    }
+
+    // 撮影時刻付きのパケットを受け取ったときの通信遅延を記録する
+    pub fn record_latency(&mut self, latency: f64) {
+        if !(0.0..=MAX_TRANSPORT_LATENCY).contains(&latency) {
+            return;
+        }
+        self.latency = if self.latency > 0.0 { self.latency + (latency - self.latency) * LATENCY_BLEND } else { latency };
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DeadZone {
+    pub enabled: bool,
+    // この距離 (ワールド単位) 以内の揺れは止まっているとみなす
+    pub radius: f32,
+    // 止まったと判定するまでの時間
+    pub hold_time: f32,
+}
+
+impl Default for DeadZone {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            radius: 0.15,
+            hold_time: 0.2,
+        }
+    }
+}
+
+// 静止中は目標位置を固定し、radius を超えて動いた瞬間に解除する
+#[derive(Default, Debug, Clone)]
+pub struct Gate {
+    anchor: HashMap<usize, Vec3>,
+    still_since: f32,
+    frozen: bool,
+}
+
+impl Gate {
+    fn max_offset(&self, targets: &HashMap<usize, Vec3>) -> f32 {
+        targets
+            .iter()
+            .filter_map(|(id, pos)| self.anchor.get(id).map(|a| a.distance(*pos)))
+            .fold(0.0, f32::max)
+    }
+
+    fn reset(&mut self, targets: &HashMap<usize, Vec3>, now: f32) {
+        self.anchor.clone_from(targets);
+        self.still_since = now;
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn apply(&mut self, dead_zone: &DeadZone, targets: &mut HashMap<usize, Vec3>, now: f32) {
+        if !dead_zone.enabled || self.anchor.is_empty() || self.max_offset(targets) > dead_zone.radius {
+            self.reset(targets, now);
+            return;
+        }
+
+        if !self.frozen && now - self.still_since > dead_zone.hold_time {
+            // 止まり始めた姿勢ではなく今の姿勢で止める。ゆっくり流れていた分だけ引き戻さない
+            self.anchor.clone_from(targets);
+            self.frozen = true;
+        }
+        if self.frozen {
+            for (id, pos) in targets.iter_mut() {
+                if let Some(anchor) = self.anchor.get(id) {
+                    *pos = *anchor;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f32) -> HashMap<usize, Vec3> {
+        HashMap::from([(0, Vec3::new(x, 0.0, 0.0))])
+    }
+
+    // 60Hz の描画で、30Hz のパケットを t 秒ぶん流す
+    fn run(track: &mut Track, from: f64, seconds: f64, packet_rate: f64, rate: f32, max_catch_up: f32) -> f64 {
+        let frame = 1.0 / 60.0;
+        let mut stamp = from;
+        let mut next_packet = from;
+        let mut max_jump: f64 = 0.0;
+        let mut t = 0.0;
+        while t < seconds {
+            if t >= next_packet - from {
+                track.push(stamp, at(stamp as f32));
+                next_packet += 1.0 / packet_rate;
+            }
+            let before = track.playhead();
+            track.advance(frame as f32, rate, max_catch_up);
+            max_jump = max_jump.max(track.playhead() - before);
+            t += frame;
+            stamp = from + t * rate as f64;
+        }
+        max_jump
+    }
+
+    #[test]
+    fn catch_up_never_exceeds_the_limit() {
+        let mut track = Track::default();
+        track.push(0.0, at(0.0));
+        track.push(1.0 / 30.0, at(1.0));
+        // 0.4 秒遅れたパケットが届いても、1 フレームに進むのは max_catch_up 倍まで
+        track.push(0.4, at(2.0));
+        let frame = 1.0 / 60.0;
+        for _ in 0..30 {
+            let before = track.playhead();
+            track.advance(frame, 1.0, 2.0);
+            assert!(track.playhead() - before <= frame as f64 * 2.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn a_long_gap_restarts_the_track() {
+        let mut track = Track::default();
+        track.push(0.0, at(0.0));
+        track.push(0.05, at(1.0));
+        track.push(0.05 + MAX_GAP + 0.1, at(5.0));
+        // 補間せず新しい位置からやり直す
+        assert_eq!(track.sample(0), Some(Vec3::new(5.0, 0.0, 0.0)));
+        assert_eq!(track.playhead(), 0.05 + MAX_GAP + 0.1);
+        assert_eq!(track.velocity(0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn a_stamp_going_backwards_restarts_the_track() {
+        let mut track = Track::default();
+        track.push(1.0, at(0.0));
+        track.push(1.05, at(1.0));
+        track.push(0.5, at(3.0));
+        assert_eq!(track.sample(0), Some(Vec3::new(3.0, 0.0, 0.0)));
+        assert_eq!(track.playhead(), 0.5);
+    }
+
+    #[test]
+    fn fast_replay_does_not_keep_jumping_the_playhead() {
+        // 4 倍速のリプレイでもパケットの時刻に遅れず、MAX_GAP を超えて飛ばない
+        let mut track = Track::default();
+        let jump = run(&mut track, 0.0, 3.0, 30.0, 4.0, DEFAULT_MAX_CATCH_UP);
+        assert!(jump < MAX_GAP / 2.0, "playhead jumped {jump}");
+        assert!(track.delay() < MAX_GAP);
+    }
+
+    #[test]
+    fn the_gate_freezes_only_after_hold_time() {
+        let dead_zone = DeadZone { enabled: true, radius: 0.5, hold_time: 0.2 };
+        let mut gate = Gate::default();
+        let mut targets = at(0.0);
+        gate.apply(&dead_zone, &mut targets, 0.0);
+        let mut targets = at(0.1);
+        gate.apply(&dead_zone, &mut targets, 0.1);
+        assert!(!gate.is_frozen());
+        assert_eq!(targets, at(0.1));
+        let mut targets = at(0.15);
+        gate.apply(&dead_zone, &mut targets, 0.25);
+        assert!(gate.is_frozen());
+    }
+
+    #[test]
+    fn freezing_does_not_snap_back_to_where_the_hand_came_to_rest() {
+        let dead_zone = DeadZone { enabled: true, radius: 0.5, hold_time: 0.2 };
+        let mut gate = Gate::default();
+        // radius より小さくゆっくり流れていく手。止まった瞬間 (0.3 秒) の位置で固定し、0.0 へ引き戻さない
+        for (i, x) in [0.0, 0.1, 0.2, 0.3].into_iter().enumerate() {
+            let mut targets = at(x);
+            gate.apply(&dead_zone, &mut targets, i as f32 * 0.1);
+            assert_eq!(targets, at(x), "frame {i}");
+        }
+        assert!(gate.is_frozen());
+        let mut targets = at(0.32);
+        gate.apply(&dead_zone, &mut targets, 0.4);
+        assert_eq!(targets, at(0.3));
+    }
+
+    #[test]
+    fn moving_past_the_radius_releases_at_once() {
+        let dead_zone = DeadZone { enabled: true, radius: 0.5, hold_time: 0.2 };
+        let mut gate = Gate::default();
+        for (i, x) in [0.0, 0.0, 0.0, 0.0].into_iter().enumerate() {
+            let mut targets = at(x);
+            gate.apply(&dead_zone, &mut targets, i as f32 * 0.1);
+        }
+        assert!(gate.is_frozen());
+        let mut targets = at(0.6);
+        gate.apply(&dead_zone, &mut targets, 0.4);
+        assert!(!gate.is_frozen());
+        assert_eq!(targets, at(0.6));
+    }
+}
@@ -0,0 +1,118 @@
+use glam::Vec3;
+
+// 頂点ごとに動きを借りる節点の数
+const SKIN_NODES: usize = 3;
+
+// 球面にほぼ均等に並べた向き (黄金角のらせん)
+pub fn sphere_directions(count: usize) -> Vec<Vec3> {
+    let golden = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let ring = (1.0 - y * y).max(0.0).sqrt();
+            let angle = golden * i as f32;
+            Vec3::new(angle.cos() * ring, y, angle.sin() * ring)
+        })
+        .collect()
+}
+
+// 各節点を近い順に per_node 個の節点とばねでつなぐ。同じ組は一度だけ返す
+pub fn neighbor_pairs(points: &[Vec3], per_node: usize) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, point) in points.iter().enumerate() {
+        let mut others: Vec<usize> = (0..points.len()).filter(|j| *j != i).collect();
+        others.sort_by(|a, b| point.distance_squared(points[*a]).total_cmp(&point.distance_squared(points[*b])));
+        for j in others.into_iter().take(per_node) {
+            let pair = (i.min(j), i.max(j));
+            if !pairs.contains(&pair) {
+                pairs.push(pair);
+            }
+        }
+    }
+    pairs
+}
+
+// 見た目の頂点が、どの節点の動きをどれだけ受けるか
+#[derive(Debug, Clone)]
+pub struct Skin {
+    weights: Vec<Vec<(usize, f32)>>,
+}
+
+impl Skin {
+    // 近い節点ほど強く (距離の 2 乗に反比例して) 引かれる
+    pub fn bind(vertices: &[Vec3], nodes: &[Vec3]) -> Self {
+        let weights = vertices
+            .iter()
+            .map(|vertex| {
+                let mut nearest: Vec<(usize, f32)> =
+                    nodes.iter().enumerate().map(|(i, node)| (i, vertex.distance_squared(*node))).collect();
+                nearest.sort_by(|a, b| a.1.total_cmp(&b.1));
+                nearest.truncate(SKIN_NODES);
+                let raw: Vec<(usize, f32)> = nearest.iter().map(|(i, d)| (*i, 1.0 / d.max(1e-4))).collect();
+                let total: f32 = raw.iter().map(|(_, w)| w).sum();
+                raw.into_iter().map(|(i, w)| (i, w / total)).collect()
+            })
+            .collect();
+        Self { weights }
+    }
+
+    // 節点が静止時の位置から動いた分を混ぜて頂点を動かす。座標はどちらも玉の中心からの相対
+    pub fn deform(&self, rest: &[Vec3], nodes_rest: &[Vec3], nodes_now: &[Vec3]) -> Vec<Vec3> {
+        rest.iter()
+            .zip(&self.weights)
+            .map(|(vertex, weights)| {
+                *vertex + weights.iter().map(|(i, w)| (nodes_now[*i] - nodes_rest[*i]) * *w).sum::<Vec3>()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directions_cover_the_sphere() {
+        let directions = sphere_directions(14);
+        assert_eq!(directions.len(), 14);
+        for (i, a) in directions.iter().enumerate() {
+            assert!((a.length() - 1.0).abs() < 1e-5);
+            for b in &directions[i + 1..] {
+                assert!(a.distance(*b) > 0.5, "{a} and {b} bunch up");
+            }
+        }
+        let center: Vec3 = directions.iter().sum::<Vec3>() / 14.0;
+        assert!(center.length() < 0.1);
+    }
+
+    #[test]
+    fn pairs_are_unique_and_ordered() {
+        let pairs = neighbor_pairs(&sphere_directions(14), 3);
+        for (i, &(a, b)) in pairs.iter().enumerate() {
+            assert!(a < b);
+            assert!(!pairs[i + 1..].contains(&(a, b)));
+        }
+        assert!(pairs.len() >= 14 * 3 / 2);
+    }
+
+    #[test]
+    fn pressing_a_node_dents_only_nearby_vertices() {
+        let nodes = sphere_directions(14);
+        let vertices: Vec<Vec3> = sphere_directions(200);
+        let skin = Skin::bind(&vertices, &nodes);
+        assert_eq!(skin.deform(&vertices, &nodes, &nodes), vertices);
+
+        let mut pressed = nodes.clone();
+        pressed[0] *= 0.6;
+        let deformed = skin.deform(&vertices, &nodes, &pressed);
+        let near = (0..vertices.len()).min_by(|a, b| {
+            vertices[*a].distance(nodes[0]).total_cmp(&vertices[*b].distance(nodes[0]))
+        });
+        let far = (0..vertices.len()).max_by(|a, b| {
+            vertices[*a].distance(nodes[0]).total_cmp(&vertices[*b].distance(nodes[0]))
+        });
+        let (near, far) = (near.unwrap(), far.unwrap());
+        assert!(deformed[near].length() < 0.8);
+        assert!((deformed[far].length() - 1.0).abs() < 1e-5);
+    }
+}
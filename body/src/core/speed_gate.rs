@@ -0,0 +1,45 @@
+// 速すぎる手の動きを止める。速さが上限を超えたら、下回ってからも hold 秒は止めたままにする
+#[derive(Debug, Clone, Copy)]
+pub struct SpeedGate {
+    suspended_until: f32,
+}
+
+impl Default for SpeedGate {
+    fn default() -> Self {
+        Self { suspended_until: f32::NEG_INFINITY }
+    }
+}
+
+impl SpeedGate {
+    // 止めている間は true
+    pub fn update(&mut self, speed: f32, now: f32, limit: f32, hold: f32) -> bool {
+        if speed > limit {
+            self.suspended_until = now + hold;
+        }
+        now < self.suspended_until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_above_limit_and_holds() {
+        let mut gate = SpeedGate::default();
+        assert!(!gate.update(50.0, 0.0, 80.0, 0.5));
+        assert!(gate.update(120.0, 0.1, 80.0, 0.5));
+        assert!(gate.update(10.0, 0.4, 80.0, 0.5));
+        assert!(!gate.update(10.0, 0.7, 80.0, 0.5));
+    }
+
+    #[test]
+    fn staying_fast_keeps_it_suspended() {
+        let mut gate = SpeedGate::default();
+        for i in 0..20 {
+            assert!(gate.update(100.0, i as f32 * 0.1, 80.0, 0.2));
+        }
+        assert!(gate.update(0.0, 2.0, 80.0, 0.2));
+        assert!(!gate.update(0.0, 2.2, 80.0, 0.2));
+    }
+}
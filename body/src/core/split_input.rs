@@ -0,0 +1,145 @@
+use crate::packet::{HandPacket, OneHand};
+
+// 片手だけを追う追跡器を 2 台使うとき、それぞれが送ってくるポート。label があれば届いた手をその手とみなす
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitPort {
+    pub port: u16,
+    pub label: Option<String>,
+}
+
+// "5006:Left,5007:Right" の形。ラベルを省いたポートは送信側のラベルをそのまま使う
+pub fn parse_ports(spec: &str) -> Result<Vec<SplitPort>, String> {
+    let ports = spec
+        .split(',')
+        .map(|entry| {
+            let (port, label) = match entry.trim().split_once(':') {
+                Some((port, label)) => (port, Some(label.trim())),
+                None => (entry.trim(), None),
+            };
+            let port = port.parse().map_err(|_| format!("'{port}' is not a port number"))?;
+            if let Some(label) = label
+                && !matches!(label, "Left" | "Right")
+            {
+                return Err(format!("hand label must be Left or Right, not '{label}'"));
+            }
+            Ok(SplitPort { port, label: label.map(str::to_string) })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if ports.len() < 2 {
+        return Err("give at least two ports, e.g. 5006:Left,5007:Right".to_string());
+    }
+    Ok(ports)
+}
+
+// ラベルを上書きするポートでは、同じ手が 2 つにならないよう最初の 1 つだけ使う
+pub fn relabel(packet: &mut HandPacket, label: Option<&str>) {
+    if let Some(label) = label {
+        packet.hands.truncate(1);
+        for hand in &mut packet.hands {
+            hand.label = label.to_string();
+        }
+    }
+}
+
+struct Latest {
+    received: f32,
+    hands: Vec<OneHand>,
+}
+
+// ポートごとに最後に届いた手を覚えておき、まだ新しいものを 1 つのパケットにまとめる。
+// 片方の追跡器が止まっても、もう片方の手はそのまま出し続ける
+#[derive(Default)]
+pub struct SplitMerger {
+    latest: Vec<Option<Latest>>,
+    // 前回まとめてから届いたパケットの snap / beat / 撮影時刻
+    pending: Option<(bool, bool, Option<f64>)>,
+}
+
+impl SplitMerger {
+    pub fn new(ports: usize) -> Self {
+        Self { latest: (0..ports).map(|_| None).collect(), pending: None }
+    }
+
+    pub fn push(&mut self, index: usize, packet: HandPacket, now: f32) {
+        let (snap, beat, timestamp) = self.pending.unwrap_or((false, false, None));
+        let timestamp = match (timestamp, packet.timestamp) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        self.pending = Some((snap || packet.snap, beat || packet.beat, timestamp));
+        if let Some(slot) = self.latest.get_mut(index) {
+            *slot = Some(Latest { received: now, hands: packet.hands });
+        }
+    }
+
+    // 前回から何か届いていれば、timeout 秒以内に届いた手をまとめて返す
+    pub fn take(&mut self, now: f32, timeout: f32) -> Option<HandPacket> {
+        let (snap, beat, timestamp) = self.pending.take()?;
+        let mut hands: Vec<OneHand> = Vec::new();
+        for latest in self.latest.iter().flatten().filter(|l| now - l.received <= timeout) {
+            // 2 台が同じ手を送ってきたら先のポートを使う
+            for hand in &latest.hands {
+                if !hands.iter().any(|h| h.label == hand.label) {
+                    hands.push(hand.clone());
+                }
+            }
+        }
+        Some(HandPacket { hands, snap, beat, timestamp })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(labels: &[&str], timestamp: f64) -> HandPacket {
+        let hands = labels.iter().map(|l| format!(r#"{{"label": "{l}", "landmarks": []}}"#)).collect::<Vec<_>>();
+        let text = format!(r#"{{"hands": [{}], "timestamp": {timestamp}}}"#, hands.join(","));
+        serde_json::from_str(&text).unwrap()
+    }
+
+    fn labels(packet: &HandPacket) -> Vec<&str> {
+        packet.hands.iter().map(|h| h.label.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_ports_with_optional_labels() {
+        let ports = parse_ports("5006:Left, 5007").unwrap();
+        assert_eq!(ports[0], SplitPort { port: 5006, label: Some("Left".to_string()) });
+        assert_eq!(ports[1], SplitPort { port: 5007, label: None });
+        assert!(parse_ports("5006:Up,5007:Right").is_err());
+        assert!(parse_ports("5006:Left").is_err());
+        assert!(parse_ports("port:Left,5007").is_err());
+    }
+
+    #[test]
+    fn merges_both_trackers_with_label_override() {
+        let mut merger = SplitMerger::new(2);
+        assert!(merger.take(0.0, 0.3).is_none());
+        // 片手用の追跡器はどちらの手も "Right" と送ってくることがある
+        let (mut left, mut right) = (packet(&["Right", "Right"], 1.0), packet(&["Right"], 1.5));
+        relabel(&mut left, Some("Left"));
+        relabel(&mut right, Some("Right"));
+        merger.push(0, left, 0.0);
+        merger.push(1, right, 0.01);
+        let merged = merger.take(0.02, 0.3).unwrap();
+        assert_eq!(labels(&merged), ["Left", "Right"]);
+        assert_eq!(merged.timestamp, Some(1.5));
+        assert!(merger.take(0.03, 0.3).is_none());
+    }
+
+    #[test]
+    fn a_stalled_tracker_drops_only_its_own_hand() {
+        let mut merger = SplitMerger::new(2);
+        merger.push(0, packet(&["Left"], 0.0), 0.0);
+        merger.push(1, packet(&["Right"], 0.0), 0.0);
+        merger.take(0.0, 0.3);
+        merger.push(1, packet(&["Right"], 0.2), 0.2);
+        assert_eq!(labels(&merger.take(0.2, 0.3).unwrap()), ["Left", "Right"]);
+        merger.push(1, packet(&["Right"], 0.5), 0.5);
+        assert_eq!(labels(&merger.take(0.5, 0.3).unwrap()), ["Right"]);
+        // 手が写らなくなったポートは、届いた空のパケットでその手を消す
+        merger.push(1, packet(&[], 0.6), 0.6);
+        assert!(merger.take(0.6, 0.3).unwrap().hands.is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use glam::Vec3;
+
+// 臨界減衰ばね。行き過ぎずに最短で目標へ近づくので、持っている物体が手の震えで揺れ続けない
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriticalSpring {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+impl CriticalSpring {
+    pub fn new(position: Vec3, velocity: Vec3) -> Self {
+        Self { position, velocity }
+    }
+
+    // frequency は固有角振動数 (rad/秒)。dt によらず安定な厳密解で進める
+    pub fn step(&mut self, target: Vec3, frequency: f32, dt: f32) {
+        let offset = self.position - target;
+        let decay = (-frequency * dt).exp();
+        let temp = (self.velocity + offset * frequency) * dt;
+        self.velocity = (self.velocity - temp * frequency) * decay;
+        self.position = target + (offset + temp) * decay;
+    }
+}
+
+// 減衰比を選べるばね。1 未満にすると行き過ぎて揺れる (両手で運ぶ物体が手のずれでぐらつく表現に使う)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DampedSpring {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+impl DampedSpring {
+    pub fn new(position: Vec3, velocity: Vec3) -> Self {
+        Self { position, velocity }
+    }
+
+    // 半陰的オイラー法で進める。大きい dt で発散しないよう刻んで回す
+    pub fn step(&mut self, target: Vec3, frequency: f32, damping_ratio: f32, dt: f32) {
+        let steps = (dt * frequency / 0.2).ceil().max(1.0);
+        let h = dt / steps;
+        for _ in 0..steps as usize {
+            let accel = (target - self.position) * frequency * frequency - self.velocity * 2.0 * damping_ratio * frequency;
+            self.velocity += accel * h;
+            self.position += self.velocity * h;
+        }
+    }
+}
+
+// 時定数 time_constant (秒) の一次遅れ
+pub fn low_pass(previous: Vec3, current: Vec3, time_constant: f32, dt: f32) -> Vec3 {
+    if time_constant <= 0.0 {
+        return current;
+    }
+    previous.lerp(current, 1.0 - (-dt / time_constant).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spring_settles_without_overshoot() {
+        let mut spring = CriticalSpring::new(Vec3::ZERO, Vec3::ZERO);
+        let target = Vec3::X;
+        for _ in 0..120 {
+            spring.step(target, 12.0, 1.0 / 60.0);
+            assert!(spring.position.x <= 1.0 + 1e-5);
+        }
+        assert!(spring.position.distance(target) < 1e-3);
+        assert!(spring.velocity.length() < 1e-2);
+    }
+
+    #[test]
+    fn damped_spring_overshoots_only_when_underdamped() {
+        let overshoot = |ratio| {
+            let mut spring = DampedSpring::new(Vec3::ZERO, Vec3::ZERO);
+            let mut peak = 0.0f32;
+            for _ in 0..240 {
+                spring.step(Vec3::X, 10.0, ratio, 1.0 / 60.0);
+                peak = peak.max(spring.position.x);
+            }
+            assert!(spring.position.distance(Vec3::X) < 1e-2);
+            peak - 1.0
+        };
+        assert!(overshoot(1.0) < 1e-3);
+        assert!(overshoot(0.2) > 0.3);
+    }
+
+    #[test]
+    fn low_pass_moves_part_way() {
+        let filtered = low_pass(Vec3::ZERO, Vec3::ONE, 0.1, 0.1);
+        assert!(filtered.x > 0.6 && filtered.x < 0.65);
+        assert_eq!(low_pass(Vec3::ZERO, Vec3::ONE, 0.0, 0.1), Vec3::ONE);
+    }
+}
@@ -0,0 +1,64 @@
+// 持った物体の底と床の隙間がこれ以下で床に付いた、これ以上で離れたとみなす
+const TOUCH_CLEARANCE: f32 = 0.15;
+const LIFT_CLEARANCE: f32 = 0.6;
+// 1 回目に付いてから 2 回目に付くまでの最長の間隔 (秒)
+pub const DOUBLE_TAP_WINDOW: f32 = 0.7;
+
+// 持った物体で床を 2 回たたいた (はんこを押した) のを拾う。
+// 2 回目に付いたあと持ち上げた瞬間に押したとみなし、写しが持った物体と重ならないようにする
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StampDetector {
+    touching: bool,
+    last_tap: Option<f32>,
+    armed: bool,
+}
+
+impl StampDetector {
+    // clearance は物体の底から床までの高さ
+    pub fn update(&mut self, clearance: f32, now: f32) -> bool {
+        if !self.touching {
+            if clearance < TOUCH_CLEARANCE {
+                self.touching = true;
+                self.armed = self.last_tap.is_some_and(|t| now - t <= DOUBLE_TAP_WINDOW);
+                self.last_tap = if self.armed { None } else { Some(now) };
+            }
+            return false;
+        }
+        if clearance > LIFT_CLEARANCE {
+            self.touching = false;
+            return std::mem::take(&mut self.armed);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(detector: &mut StampDetector, frames: &[(f32, f32)]) -> Vec<f32> {
+        frames.iter().filter(|(clearance, now)| detector.update(*clearance, *now)).map(|(_, now)| *now).collect()
+    }
+
+    #[test]
+    fn double_tap_stamps_on_lift() {
+        let mut detector = StampDetector::default();
+        let frames = [(2.0, 0.0), (0.1, 0.1), (1.0, 0.2), (0.05, 0.4), (0.3, 0.5), (1.0, 0.6), (2.0, 0.7)];
+        assert_eq!(run(&mut detector, &frames), vec![0.6]);
+    }
+
+    #[test]
+    fn slow_taps_and_resting_do_not_stamp() {
+        let mut detector = StampDetector::default();
+        // 間が空いた 2 回と、床に置いたまま揺れるだけ
+        let frames = [(2.0, 0.0), (0.1, 0.1), (1.0, 0.2), (0.1, 1.5), (0.4, 1.6), (0.1, 1.7), (0.5, 1.8), (1.0, 3.0)];
+        assert!(run(&mut detector, &frames).is_empty());
+    }
+
+    #[test]
+    fn third_tap_starts_a_new_pair() {
+        let mut detector = StampDetector::default();
+        let frames = [(0.1, 0.0), (1.0, 0.1), (0.1, 0.2), (1.0, 0.3), (0.1, 0.4), (1.0, 0.5), (0.1, 0.6), (1.0, 0.7)];
+        assert_eq!(run(&mut detector, &frames), vec![0.3, 0.7]);
+    }
+}
@@ -0,0 +1,77 @@
+// 一回の物理ステップで手がこの距離 (max_travel) より進むと薄い物体をすり抜けうるので、
+// 手の速さに合わせてステップを細かく刻む
+pub fn substeps_for(speed: f32, step: f32, max_travel: f32, max_substeps: usize) -> usize {
+    let max_substeps = max_substeps.max(1);
+    if max_travel <= 0.0 {
+        return max_substeps;
+    }
+    let needed = (speed.max(0.0) * step / max_travel).ceil();
+    (needed as usize).clamp(1, max_substeps)
+}
+
+// 刻みを増やすのはすぐ、減らすのは手が遅いまま hold 秒たってから。振り抜いた直後の当たりも逃さない
+#[derive(Debug, Clone, Copy)]
+pub struct SubstepGovernor {
+    current: usize,
+    raised_at: f32,
+}
+
+impl Default for SubstepGovernor {
+    fn default() -> Self {
+        Self { current: 1, raised_at: f32::NEG_INFINITY }
+    }
+}
+
+impl SubstepGovernor {
+    pub fn update(&mut self, wanted: usize, now: f32, hold: f32) -> usize {
+        let wanted = wanted.max(1);
+        if wanted >= self.current {
+            self.current = wanted;
+            self.raised_at = now;
+        } else if now - self.raised_at >= hold {
+            self.current = wanted;
+        }
+        self.current
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faster_hands_get_more_substeps_up_to_the_limit() {
+        let step = 1.0 / 60.0;
+        assert_eq!(substeps_for(0.0, step, 0.5, 8), 1);
+        assert_eq!(substeps_for(20.0, step, 0.5, 8), 1);
+        // 1 ステップで 1.0 進むので 2 回に分ける
+        assert_eq!(substeps_for(60.0, step, 0.5, 8), 2);
+        assert_eq!(substeps_for(1000.0, step, 0.5, 8), 8);
+        assert_eq!(substeps_for(10.0, step, 0.0, 4), 4);
+        assert_eq!(substeps_for(-5.0, step, 0.5, 0), 1);
+    }
+
+    #[test]
+    fn slow_motion_does_not_ask_for_more_substeps() {
+        // time_scale 0.2 でもステップは実時間 1/60 秒ごとなので、等速のときと同じ数で足りる
+        let step = 1.0 / 60.0;
+        let time_scale = 0.2;
+        assert_eq!(substeps_for(60.0, step, 0.5, 8), 2);
+        assert!(substeps_for(60.0, step / time_scale, 0.5, 8) > 2);
+    }
+
+    #[test]
+    fn governor_rises_at_once_and_falls_after_hold() {
+        let mut governor = SubstepGovernor::default();
+        assert_eq!(governor.update(1, 0.0, 0.5), 1);
+        assert_eq!(governor.update(4, 0.1, 0.5), 4);
+        assert_eq!(governor.update(1, 0.3, 0.5), 4);
+        assert_eq!(governor.update(2, 0.5, 0.5), 4);
+        assert_eq!(governor.update(1, 0.7, 0.5), 1);
+        assert_eq!(governor.current(), 1);
+    }
+}
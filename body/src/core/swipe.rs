@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+// この時間のうちに、この距離だけ指先が左へ動いたら払ったとみなす
+const SWIPE_TIME: f32 = 0.35;
+const SWIPE_DISTANCE: f32 = 3.0;
+// 払っている間に上下へぶれてよい量 (左への移動に対する割合)
+const MAX_DRIFT: f32 = 0.6;
+
+// 2 本指を伸ばした形のまま左へ払う動き。1 回払ったら、形を崩すまで次は数えない
+#[derive(Debug, Clone, Default)]
+pub struct SwipeDetector {
+    samples: VecDeque<(f32, f32, f32)>,
+    fired: bool,
+}
+
+impl SwipeDetector {
+    // x は左右 (右が正)、y は上下の位置。払った瞬間だけ true
+    pub fn update(&mut self, posed: bool, x: f32, y: f32, now: f32) -> bool {
+        if !posed {
+            self.samples.clear();
+            self.fired = false;
+            return false;
+        }
+        self.samples.push_back((now, x, y));
+        while self.samples.front().is_some_and(|(t, ..)| now - t > SWIPE_TIME) {
+            self.samples.pop_front();
+        }
+        if self.fired {
+            return false;
+        }
+        let Some(&(_, start_x, start_y)) = self.samples.front() else {
+            return false;
+        };
+        let left = start_x - x;
+        if left >= SWIPE_DISTANCE && (y - start_y).abs() <= left * MAX_DRIFT {
+            self.fired = true;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_leftward_motion_fires_once() {
+        let mut swipe = SwipeDetector::default();
+        let fired: Vec<bool> = (0..10).map(|i| swipe.update(true, -(i as f32), 0.0, i as f32 * 0.03)).collect();
+        assert_eq!(fired.iter().filter(|f| **f).count(), 1);
+        // 形を崩してからもう一度払えば数える
+        swipe.update(false, 0.0, 0.0, 0.5);
+        let again = (0..10).any(|i| swipe.update(true, -(i as f32), 0.0, 0.6 + i as f32 * 0.03));
+        assert!(again);
+    }
+
+    #[test]
+    fn slow_rightward_or_diagonal_motion_does_not_fire() {
+        let mut swipe = SwipeDetector::default();
+        assert!(!(0..40).any(|i| swipe.update(true, -(i as f32) * 0.2, 0.0, i as f32 * 0.1)));
+        let mut swipe = SwipeDetector::default();
+        assert!(!(0..10).any(|i| swipe.update(true, i as f32, 0.0, i as f32 * 0.03)));
+        let mut swipe = SwipeDetector::default();
+        assert!(!(0..10).any(|i| swipe.update(true, -(i as f32), -(i as f32) * 1.5, i as f32 * 0.03)));
+        // 形を作っていなければ数えない
+        let mut swipe = SwipeDetector::default();
+        assert!(!(0..10).any(|i| swipe.update(false, -(i as f32), 0.0, i as f32 * 0.03)));
+    }
+}
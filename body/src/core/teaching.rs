@@ -0,0 +1,214 @@
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::core::pose::palm_rotation;
+
+const WRIST: usize = 0;
+const INDEX_MCP: usize = 5;
+const MIDDLE_MCP: usize = 9;
+const PINKY_MCP: usize = 17;
+// 比べる関節と、その関節から先へ伸びる骨の先の点 (MediaPipe の番号)
+const JOINTS: [(usize, usize); 15] = [
+    (1, 2), (2, 3), (3, 4),
+    (5, 6), (6, 7), (7, 8),
+    (9, 10), (10, 11), (11, 12),
+    (13, 14), (14, 15), (15, 16),
+    (17, 18), (18, 19), (19, 20),
+];
+// この角度以上ずれた関節は 0 点
+const MAX_ERROR: f32 = std::f32::consts::FRAC_PI_4;
+// 合っているとみなす点数
+pub const PASS_SCORE: f32 = 0.8;
+const DEFAULT_HOLD: f32 = 1.5;
+
+fn default_hold() -> f32 {
+    DEFAULT_HOLD
+}
+
+// まねる手の形。手のひらの向きにそろえてあるので、手をどこに置いても、どちらを向けても比べられる
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TargetPose {
+    pub name: String,
+    // 取り込んだ手 ("Right" / "Left")。反対の手でまねるときは鏡に映して比べる
+    pub side: String,
+    // 手首から各点への位置を手のひらの向きで見たもの (番号順)
+    pub points: Vec<[f32; 3]>,
+    // 合った形をこの秒数保てば次へ進む
+    #[serde(default = "default_hold")]
+    pub hold: f32,
+}
+
+impl TargetPose {
+    // right_hand の手でまねるときの点
+    pub fn points_for(&self, right_hand: bool) -> Vec<Vec3> {
+        let points: Vec<Vec3> = self.points.iter().map(|p| Vec3::from_array(*p)).collect();
+        if right_hand == (self.side == "Right") { points } else { mirrored(&points) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Lesson {
+    pub name: String,
+    #[serde(default)]
+    pub poses: Vec<TargetPose>,
+}
+
+// lessons.json の中身
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LessonFile {
+    #[serde(default)]
+    pub lessons: Vec<Lesson>,
+}
+
+// 手のひらの向きと、その向きで見た手首からの各点。points は番号順に並べる
+pub fn palm_local(points: &[Vec3], right_hand: bool) -> Option<(Quat, Vec<Vec3>)> {
+    let point = |id: usize| points.get(id).copied();
+    let wrist = point(WRIST)?;
+    let rotation = palm_rotation(wrist, point(INDEX_MCP)?, point(MIDDLE_MCP)?, point(PINKY_MCP)?, right_hand)?;
+    let inverse = rotation.inverse();
+    Some((rotation, points.iter().map(|p| inverse * (*p - wrist)).collect()))
+}
+
+// 左右を入れ替えた形。手のひらの座標は左右で x だけを反転させてそろえてあるので、鏡に映すと x の符号が変わる
+pub fn mirrored(points: &[Vec3]) -> Vec<Vec3> {
+    points.iter().map(|p| Vec3::new(-p.x, p.y, p.z)).collect()
+}
+
+// 関節ごとの、そこから先の骨の向きのずれ (ラジアン)。どちらかの点が足りない関節は飛ばす
+pub fn joint_errors(live: &[Vec3], target: &[Vec3]) -> Vec<(usize, f32)> {
+    JOINTS
+        .iter()
+        .filter_map(|&(joint, next)| {
+            let bone = |points: &[Vec3]| (*points.get(next)? - *points.get(joint)?).try_normalize();
+            Some((joint, bone(live)?.angle_between(bone(target)?)))
+        })
+        .collect()
+}
+
+// 0 (まったく違う) から 1 (同じ形) までの点数
+pub fn match_score(errors: &[(usize, f32)]) -> f32 {
+    if errors.is_empty() {
+        return 0.0;
+    }
+    errors.iter().map(|(_, e)| (1.0 - e / MAX_ERROR).clamp(0.0, 1.0)).sum::<f32>() / errors.len() as f32
+}
+
+// 一つのレッスンの進み具合。合った形を決められた時間保つと次の形へ進む
+#[derive(Debug, Clone, Default)]
+pub struct LessonRun {
+    pub pose: usize,
+    held: f32,
+    best: f32,
+    // 終えた形ごとの、保っている間の一番よい点数。飛ばした形は None
+    pub scores: Vec<Option<f32>>,
+}
+
+impl LessonRun {
+    // 手が見えなければ score は None。形を終えた瞬間だけ true
+    pub fn update(&mut self, score: Option<f32>, hold: f32, dt: f32) -> bool {
+        let Some(score) = score.filter(|s| *s >= PASS_SCORE) else {
+            self.held = 0.0;
+            self.best = 0.0;
+            return false;
+        };
+        self.held += dt;
+        self.best = self.best.max(score);
+        if self.held < hold {
+            return false;
+        }
+        self.advance(Some(self.best));
+        true
+    }
+
+    // 点数を付けずに次の形へ進む
+    pub fn skip(&mut self) {
+        self.advance(None);
+    }
+
+    fn advance(&mut self, score: Option<f32>) {
+        self.scores.push(score);
+        self.pose += 1;
+        self.held = 0.0;
+        self.best = 0.0;
+    }
+
+    #[cfg(feature = "ui")]
+    pub fn hold_fraction(&self, hold: f32) -> f32 {
+        if hold <= 0.0 { 1.0 } else { (self.held / hold).min(1.0) }
+    }
+
+    // 飛ばした形を除いた平均
+    pub fn average(&self) -> Option<f32> {
+        let scored: Vec<f32> = self.scores.iter().flatten().copied().collect();
+        (!scored.is_empty()).then(|| scored.iter().sum::<f32>() / scored.len() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::fixtures;
+
+    fn world_points(name: &str) -> Vec<Vec3> {
+        let mut landmarks = fixtures::hand(name).landmarks;
+        landmarks.sort_by_key(|l| l.id);
+        landmarks.iter().map(|l| Vec3::new(l.x, -l.y, l.z)).collect()
+    }
+
+    #[test]
+    fn same_shape_matches_wherever_the_hand_is() {
+        let points = world_points("open_right");
+        let (_, target) = palm_local(&points, true).unwrap();
+        // 動かして回しても同じ形
+        let turn = Quat::from_rotation_z(0.7) * Quat::from_rotation_x(0.3);
+        let moved: Vec<Vec3> = points.iter().map(|p| turn * *p + Vec3::new(4.0, -2.0, 1.0)).collect();
+        let (_, live) = palm_local(&moved, true).unwrap();
+        let errors = joint_errors(&live, &target);
+        assert_eq!(errors.len(), JOINTS.len());
+        assert!(match_score(&errors) > 0.99);
+    }
+
+    #[test]
+    fn different_shapes_score_low_at_the_bent_joints() {
+        let (_, open) = palm_local(&world_points("open_right"), true).unwrap();
+        let (_, fist) = palm_local(&world_points("fist_right"), true).unwrap();
+        let errors = joint_errors(&fist, &open);
+        assert!(match_score(&errors) < PASS_SCORE);
+        // 握った手では人差し指の第二関節が大きくずれる
+        let index_pip = errors.iter().find(|(joint, _)| *joint == 6).unwrap();
+        assert!(index_pip.1 > MAX_ERROR / 2.0, "{errors:?}");
+    }
+
+    #[test]
+    fn mirrored_left_hand_matches_a_right_hand_target() {
+        let right = world_points("pointing_right");
+        let left: Vec<Vec3> = right.iter().map(|p| Vec3::new(-p.x, p.y, p.z)).collect();
+        let (_, target) = palm_local(&right, true).unwrap();
+        let pose = TargetPose {
+            name: "point".to_string(),
+            side: "Right".to_string(),
+            points: target.iter().map(|p| p.to_array()).collect(),
+            hold: 1.0,
+        };
+        let (_, live) = palm_local(&left, false).unwrap();
+        assert!(match_score(&joint_errors(&live, &pose.points_for(false))) > 0.99);
+        assert!(match_score(&joint_errors(&live, &pose.points_for(true))) < 0.99);
+    }
+
+    #[test]
+    fn holding_a_match_advances_and_keeps_the_best_score() {
+        let mut run = LessonRun::default();
+        assert!(!run.update(Some(0.9), 1.0, 0.6));
+        // 外れたら最初から保ち直す
+        assert!(!run.update(Some(0.5), 1.0, 0.1));
+        assert!(!run.update(None, 1.0, 0.1));
+        assert!(!run.update(Some(0.85), 1.0, 0.6));
+        assert!(run.update(Some(0.95), 1.0, 0.6));
+        assert_eq!(run.pose, 1);
+        assert_eq!(run.scores, vec![Some(0.95)]);
+        run.skip();
+        assert_eq!(run.pose, 2);
+        assert_eq!(run.scores, vec![Some(0.95), None]);
+        assert_eq!(run.average(), Some(0.95));
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::packet::OneHand;
+
+// 受信側から送信側へ小さなデータグラムで返す希望。Wi-Fi 越しの送信側が混雑しないよう、
+// 送る頻度を落としたり奥行き (z) を省いたりさせる。古い送信側は読まずに捨てる
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ThinningRequest {
+    // 1 秒あたりのパケット数の上限。0 なら制限しない
+    pub rate: f32,
+    pub include_z: bool,
+    // 送信側に切り替えてほしいカメラ番号。None なら送信側の選択 (--camera や c キー) のまま
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera: Option<u32>,
+}
+
+impl ThinningRequest {
+    // 間引かずに全部送らせる
+    pub const FULL: ThinningRequest = ThinningRequest { rate: 0.0, include_z: true, camera: None };
+}
+
+// z を省いたパケットの奥行きを、同じ手が最後に z 付きで届いたときの値で埋める。
+// 一度も届いていなければ平らな手 (0) とみなす
+#[derive(Debug, Default)]
+pub struct DepthCache {
+    hands: HashMap<String, Vec<(usize, f32)>>,
+}
+
+impl DepthCache {
+    pub fn fill(&mut self, hand: &mut OneHand) {
+        if let Some(elbow) = &mut hand.elbow
+            && elbow.z.is_nan()
+        {
+            elbow.z = 0.0;
+        }
+        if hand.landmarks.iter().all(|l| !l.z.is_nan()) {
+            self.hands.insert(hand.label.clone(), hand.landmarks.iter().map(|l| (l.id, l.z)).collect());
+            return;
+        }
+        let known = self.hands.get(&hand.label);
+        for landmark in hand.landmarks.iter_mut().filter(|l| l.z.is_nan()) {
+            landmark.z = known.and_then(|k| k.iter().find(|(id, _)| *id == landmark.id)).map_or(0.0, |(_, z)| *z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::HandPacket;
+
+    fn thinned(label: &str) -> OneHand {
+        let text = format!(r#"{{"hands": [{{"label": "{label}", "landmarks": [{{"id": 0, "x": 0.5, "y": 0.5}}]}}]}}"#);
+        let packet: HandPacket = serde_json::from_str(&text).unwrap();
+        packet.hands.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn thinned_packet_parses_without_z() {
+        let mut hand = thinned("Right");
+        assert!(hand.landmarks[0].z.is_nan());
+        let mut cache = DepthCache::default();
+        cache.fill(&mut hand);
+        assert_eq!(hand.landmarks[0].z, 0.0);
+    }
+
+    #[test]
+    fn missing_depth_reuses_last_full_frame() {
+        let mut cache = DepthCache::default();
+        let mut full = crate::core::fixtures::hand("open_right");
+        let wrist_z = full.landmarks.iter().find(|l| l.id == 0).unwrap().z;
+        cache.fill(&mut full);
+        let mut right = thinned(&full.label);
+        cache.fill(&mut right);
+        assert_eq!(right.landmarks[0].z, wrist_z);
+        // 反対の手は別に覚える
+        let mut other = thinned("Other");
+        cache.fill(&mut other);
+        assert_eq!(other.landmarks[0].z, 0.0);
+    }
+
+    #[test]
+    fn request_round_trips() {
+        let request = ThinningRequest { rate: 20.0, include_z: false, camera: None };
+        let text = serde_json::to_string(&request).unwrap();
+        assert_eq!(text, r#"{"rate":20.0,"include_z":false}"#);
+        assert_eq!(serde_json::from_str::<ThinningRequest>(&text).unwrap(), request);
+    }
+
+    #[test]
+    fn camera_choice_is_sent_only_when_set() {
+        let request = ThinningRequest { camera: Some(2), ..ThinningRequest::FULL };
+        let text = serde_json::to_string(&request).unwrap();
+        assert_eq!(text, r#"{"rate":0.0,"include_z":true,"camera":2}"#);
+        assert_eq!(serde_json::from_str::<ThinningRequest>(&text).unwrap(), request);
+    }
+}
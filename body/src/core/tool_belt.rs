@@ -0,0 +1,92 @@
+// もう片方の人差し指で手首を叩いて切り替える、手ごとの道具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Tool {
+    // 掴む・握って引き寄せる (これまでどおりの操作)
+    #[default]
+    Grab,
+    // 開いた手のひらの前にある物体を押しやる
+    Push,
+    // つまんだ指先で宙に線を描く
+    Draw,
+    // つまんだ先の物体や線を消す
+    Delete,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 4] = [Tool::Grab, Tool::Push, Tool::Draw, Tool::Delete];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tool::Grab => "Grab",
+            Tool::Push => "Push",
+            Tool::Draw => "Draw",
+            Tool::Delete => "Delete",
+        }
+    }
+
+    pub fn next(self) -> Tool {
+        let index = Tool::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Tool::ALL[(index + 1) % Tool::ALL.len()]
+    }
+}
+
+// 指先が手首にこれより近づいたら触れた、これより離れたら離したとみなす
+const TOUCH: f32 = 0.5;
+const RELEASE: f32 = 0.9;
+// これより長く触れていたのは叩いたのではなく、手を重ねていただけとみなす
+const MAX_TOUCH_TIME: f32 = 0.6;
+
+// 手首に触れてすぐ離した瞬間を拾う
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WristTap {
+    touched_at: Option<f32>,
+}
+
+impl WristTap {
+    // distance はもう片方の人差し指の先から手首まで。叩いて離したフレームで true
+    pub fn update(&mut self, distance: f32, now: f32) -> bool {
+        match self.touched_at {
+            None if distance < TOUCH => {
+                self.touched_at = Some(now);
+                false
+            }
+            Some(at) if distance > RELEASE => {
+                self.touched_at = None;
+                now - at <= MAX_TOUCH_TIME
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycles_through_every_tool() {
+        let mut tool = Tool::default();
+        let mut seen = vec![tool];
+        for _ in 0..Tool::ALL.len() {
+            tool = tool.next();
+            seen.push(tool);
+        }
+        assert_eq!(seen, vec![Tool::Grab, Tool::Push, Tool::Draw, Tool::Delete, Tool::Grab]);
+    }
+
+    #[test]
+    fn quick_tap_counts_once() {
+        let mut tap = WristTap::default();
+        let frames = [(2.0, 0.0), (0.4, 0.1), (0.3, 0.2), (0.7, 0.3), (1.2, 0.4), (2.0, 0.5)];
+        let taps = frames.iter().filter(|(d, t)| tap.update(*d, *t)).count();
+        assert_eq!(taps, 1);
+    }
+
+    #[test]
+    fn resting_finger_on_wrist_is_not_a_tap() {
+        let mut tap = WristTap::default();
+        assert!(!tap.update(0.3, 0.0));
+        assert!(!tap.update(0.3, 1.0));
+        assert!(!tap.update(1.5, 1.5));
+    }
+}
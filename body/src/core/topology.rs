@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+// MediaPipe Hands の 21 点。手の計算 (ジェスチャー・姿勢・奥行き) はこの番号を前提にする
+pub const MEDIAPIPE_LANDMARKS: usize = 21;
+
+pub const MEDIAPIPE_NAMES: [&str; MEDIAPIPE_LANDMARKS] = [
+    "Wrist",
+    "Thumb_CMC", "Thumb_MCP", "Thumb_IP", "Thumb_Tip",
+    "Index_MCP", "Index_PIP", "Index_DIP", "Index_Tip",
+    "Middle_MCP", "Middle_PIP", "Middle_DIP", "Middle_Tip",
+    "Ring_MCP", "Ring_PIP", "Ring_DIP", "Ring_Tip",
+    "Pinky_MCP", "Pinky_PIP", "Pinky_DIP", "Pinky_Tip",
+];
+
+pub const MEDIAPIPE_CONNECTIONS: &[(usize, usize)] = &[
+    (0, 1), (1, 2), (2, 3), (3, 4),
+    (0, 5), (5, 6), (6, 7), (7, 8),
+    (9, 10), (10, 11), (11, 12),
+    (13, 14), (14, 15), (15, 16),
+    (0, 17), (17, 18), (18, 19), (19, 20),
+    (5, 9), (9, 13), (13, 17)
+];
+
+// 手の点の数とつなぎ方。MediaPipe より多くの点を出すトラッカーでは 21 番以降を追加の点として扱う
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HandTopology {
+    pub landmarks: usize,
+    pub connections: Vec<(usize, usize)>,
+    // 空なら MediaPipe の名前と "Landmark_<番号>" を使う
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+impl Default for HandTopology {
+    fn default() -> Self {
+        Self {
+            landmarks: MEDIAPIPE_LANDMARKS,
+            connections: MEDIAPIPE_CONNECTIONS.to_vec(),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl HandTopology {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.landmarks < MEDIAPIPE_LANDMARKS {
+            errors.push(format!(
+                "landmarks must be at least {MEDIAPIPE_LANDMARKS} so the MediaPipe points keep their ids (got {})",
+                self.landmarks
+            ));
+        }
+        for &(a, b) in &self.connections {
+            if a >= self.landmarks || b >= self.landmarks {
+                errors.push(format!("connection ({a}, {b}) refers to a landmark beyond {}", self.landmarks));
+            }
+        }
+        if !self.names.is_empty() && self.names.len() != self.landmarks {
+            errors.push(format!("names has {} entries but there are {} landmarks", self.names.len(), self.landmarks));
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    #[cfg(any(test, feature = "ui", feature = "recording"))]
+    pub fn name(&self, id: usize) -> String {
+        match (self.names.get(id), MEDIAPIPE_NAMES.get(id)) {
+            (Some(name), _) => name.clone(),
+            (None, Some(name)) => name.to_string(),
+            (None, None) => format!("Landmark_{id}"),
+        }
+    }
+
+    #[cfg(any(test, feature = "recording"))]
+    pub fn names(&self) -> Vec<String> {
+        (0..self.landmarks).map(|id| self.name(id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_mediapipe() {
+        let topology = HandTopology::default();
+        assert_eq!(topology.validate(), Ok(()));
+        assert_eq!(topology.name(0), "Wrist");
+        assert_eq!(topology.names().len(), MEDIAPIPE_LANDMARKS);
+    }
+
+    #[test]
+    fn extra_landmarks_get_generated_names() {
+        let mut topology = HandTopology { landmarks: 26, ..Default::default() };
+        topology.connections.push((20, 25));
+        assert_eq!(topology.validate(), Ok(()));
+        assert_eq!(topology.name(25), "Landmark_25");
+    }
+
+    #[test]
+    fn invalid_topologies_are_reported() {
+        let topology = HandTopology { landmarks: 10, connections: vec![(0, 30)], names: vec!["a".into()] };
+        assert_eq!(topology.validate().unwrap_err().len(), 3);
+    }
+}
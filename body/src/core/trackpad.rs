@@ -0,0 +1,90 @@
+use glam::{Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+// 空中に置いた四角い面。正面 (+z) から指を近づけるとその上の位置を画面の位置に写す
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TrackpadPlane {
+    pub center: [f32; 3],
+    pub width: f32,
+    pub height: f32,
+    // 面からこの距離以内の指だけを拾う
+    pub hover: f32,
+}
+
+impl Default for TrackpadPlane {
+    fn default() -> Self {
+        Self {
+            center: [0.0, 2.0, 2.0],
+            width: 16.0,
+            height: 9.0,
+            hover: 3.0,
+        }
+    }
+}
+
+impl TrackpadPlane {
+    pub fn center(&self) -> Vec3 {
+        Vec3::from_array(self.center)
+    }
+
+    // 面の左下を (0, 0)、右上を (1, 1) とした位置。面の外か離れすぎていれば None
+    pub fn project(&self, point: Vec3) -> Option<Vec2> {
+        let offset = point - self.center();
+        if offset.z.abs() > self.hover {
+            return None;
+        }
+        let uv = Vec2::new(offset.x / self.width + 0.5, offset.y / self.height + 0.5);
+        (uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all()).then_some(uv)
+    }
+}
+
+// カーソルを動かす範囲。origin はすべてのモニターをまとめた画面の中での左上のピクセル位置
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScreenArea {
+    #[serde(default)]
+    pub origin: [i32; 2],
+    pub size: [u32; 2],
+}
+
+// 面の上の位置を画面のピクセルに直す。画面は左上が原点で下向きに y が増える
+pub fn to_screen(uv: Vec2, area: ScreenArea) -> [i32; 2] {
+    let x = uv.x * (area.size[0].saturating_sub(1)) as f32;
+    let y = (1.0 - uv.y) * (area.size[1].saturating_sub(1)) as f32;
+    [area.origin[0] + x.round() as i32, area.origin[1] + y.round() as i32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_maps_to_middle() {
+        let plane = TrackpadPlane::default();
+        assert_eq!(plane.project(plane.center()), Some(Vec2::splat(0.5)));
+        let screen = ScreenArea { origin: [0, 0], size: [1921, 1081] };
+        assert_eq!(to_screen(Vec2::splat(0.5), screen), [960, 540]);
+    }
+
+    #[test]
+    fn corners_flip_vertically_on_screen() {
+        let screen = ScreenArea { origin: [0, 0], size: [1920, 1080] };
+        assert_eq!(to_screen(Vec2::new(0.0, 1.0), screen), [0, 0]);
+        assert_eq!(to_screen(Vec2::new(1.0, 0.0), screen), [1919, 1079]);
+    }
+
+    #[test]
+    fn a_monitor_right_of_another_is_offset() {
+        let screen = ScreenArea { origin: [2560, 180], size: [1920, 1080] };
+        assert_eq!(to_screen(Vec2::new(0.0, 1.0), screen), [2560, 180]);
+        assert_eq!(to_screen(Vec2::new(1.0, 0.0), screen), [4479, 1259]);
+    }
+
+    #[test]
+    fn ignores_points_off_the_plane() {
+        let plane = TrackpadPlane::default();
+        let center = plane.center();
+        assert_eq!(plane.project(center + Vec3::Z * (plane.hover + 0.1)), None);
+        assert_eq!(plane.project(center + Vec3::X * plane.width), None);
+        assert!(plane.project(center + Vec3::Z * plane.hover * 0.5).is_some());
+    }
+}
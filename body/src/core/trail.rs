@@ -0,0 +1,102 @@
+use glam::Vec3;
+use std::collections::VecDeque;
+
+// 速度を求めるのに使う軌跡の長さ (秒)
+const TRAIL_SPAN: f32 = 0.1;
+// 軌跡の最新点へ寄せる時定数。速度で先回りするので、ならしても遅れは小さい
+const TIME_CONSTANT: f32 = 0.04;
+// これ以上離れたら (手が消えて別の場所に現れたなど) ならさずに飛ぶ
+const SNAP_DISTANCE: f32 = 3.0;
+
+// 描画専用に一つの関節の位置をならす。物理の位置には戻さないので、物理の遅れは増えない
+#[derive(Debug, Clone)]
+pub struct LandmarkTrail {
+    samples: VecDeque<(f32, Vec3)>,
+    position: Vec3,
+    last_time: f32,
+}
+
+impl LandmarkTrail {
+    pub fn new(position: Vec3, now: f32) -> Self {
+        Self {
+            samples: VecDeque::from([(now, position)]),
+            position,
+            last_time: now,
+        }
+    }
+
+    // 軌跡に最小二乗で当てはめた直線の傾き。点ごとの差より揺れや更新の間隔のむらに強い
+    pub fn velocity(&self) -> Vec3 {
+        let n = self.samples.len() as f32;
+        let mean_t = self.samples.iter().map(|(t, _)| t).sum::<f32>() / n;
+        let mean_p = self.samples.iter().map(|(_, p)| *p).sum::<Vec3>() / n;
+        let (mut covariance, mut variance) = (Vec3::ZERO, 0.0);
+        for (t, p) in &self.samples {
+            covariance += (*p - mean_p) * (t - mean_t);
+            variance += (t - mean_t) * (t - mean_t);
+        }
+        if variance > f32::EPSILON * f32::EPSILON { covariance / variance } else { Vec3::ZERO }
+    }
+
+    // 物理の位置を記録し、描画する位置を返す
+    pub fn push(&mut self, target: Vec3, now: f32) -> Vec3 {
+        let dt = (now - self.last_time).max(0.0);
+        self.last_time = now;
+        if target.distance(self.position) > SNAP_DISTANCE {
+            *self = Self::new(target, now);
+            return target;
+        }
+        // 同じ位置が続くフレームは物理側が更新されていないので軌跡に入れない
+        if self.samples.back().is_none_or(|(_, p)| *p != target) {
+            self.samples.push_back((now, target));
+        }
+        while self.samples.len() > 2 && self.samples.front().is_some_and(|(t, _)| now - t > TRAIL_SPAN) {
+            self.samples.pop_front();
+        }
+        let predicted = self.position + self.velocity() * dt;
+        self.position = predicted.lerp(target, 1.0 - (-dt / TIME_CONSTANT).exp());
+        self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_steady_motion_without_lag_building_up() {
+        let speed = Vec3::new(5.0, 0.0, 0.0);
+        let mut trail = LandmarkTrail::new(Vec3::ZERO, 0.0);
+        let mut drawn = Vec3::ZERO;
+        for frame in 1..=120 {
+            let t = frame as f32 / 60.0;
+            drawn = trail.push(speed * t, t);
+        }
+        assert!((trail.velocity() - speed).length() < 0.01);
+        assert!(drawn.distance(speed * 2.0) < 0.05, "{drawn}");
+    }
+
+    #[test]
+    fn damps_alternating_jitter() {
+        let mut trail = LandmarkTrail::new(Vec3::ZERO, 0.0);
+        let mut largest = 0.0f32;
+        for frame in 1..=60 {
+            let t = frame as f32 / 60.0;
+            let noise = if frame % 2 == 0 { 0.1 } else { -0.1 };
+            let drawn = trail.push(Vec3::new(noise, 0.0, 0.0), t);
+            // 軌跡がたまるまでの最初の数フレームは除く
+            if frame > 10 {
+                largest = largest.max(drawn.x.abs());
+            }
+        }
+        assert!(largest < 0.05, "{largest}");
+    }
+
+    #[test]
+    fn snaps_across_large_jumps() {
+        let mut trail = LandmarkTrail::new(Vec3::ZERO, 0.0);
+        let far = Vec3::new(10.0, 0.0, 0.0);
+        assert_eq!(trail.push(far, 0.016), far);
+        assert_eq!(trail.velocity(), Vec3::ZERO);
+    }
+}
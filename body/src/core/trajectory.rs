@@ -0,0 +1,73 @@
+use glam::Vec3;
+
+// 投げた物体の軌跡の予測。物理エンジンと同じく速度を先に進める半陰的オイラー法で、
+// 重力と速度の減衰だけを見る軽い積分。床 (floor) より下に出たところで止める
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ballistic {
+    pub gravity: Vec3,
+    // 1 秒あたりの線形減衰 (Damping::linear_damping と同じ意味)
+    pub damping: f32,
+    pub step: f32,
+    pub max_time: f32,
+}
+
+impl Ballistic {
+    pub fn predict(&self, start: Vec3, velocity: Vec3, floor: f32) -> Vec<Vec3> {
+        let mut points = vec![start];
+        if self.step <= 0.0 {
+            return points;
+        }
+        let mut position = start;
+        let mut velocity = velocity;
+        let decay = 1.0 / (1.0 + self.step * self.damping.max(0.0));
+        let mut t = 0.0;
+        while t < self.max_time {
+            velocity = (velocity + self.gravity * self.step) * decay;
+            let next = position + velocity * self.step;
+            t += self.step;
+            if next.y < floor {
+                // 床を横切った点で止める
+                let fraction = (position.y - floor) / (position.y - next.y).max(f32::EPSILON);
+                points.push(position.lerp(next, fraction.clamp(0.0, 1.0)));
+                break;
+            }
+            position = next;
+            points.push(position);
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH: Ballistic = Ballistic { gravity: Vec3::new(0.0, -9.81, 0.0), damping: 0.0, step: 1.0 / 120.0, max_time: 3.0 };
+
+    #[test]
+    fn follows_the_parabola() {
+        let points = EARTH.predict(Vec3::ZERO, Vec3::new(4.0, 5.0, 0.0), -100.0);
+        let t = 1.0;
+        let index = (t / EARTH.step) as usize;
+        let expected = Vec3::new(4.0 * t, 5.0 * t - 0.5 * 9.81 * t * t, 0.0);
+        assert!(points[index].distance(expected) < 0.1, "{} vs {expected}", points[index]);
+    }
+
+    #[test]
+    fn stops_on_the_floor() {
+        let points = EARTH.predict(Vec3::new(0.0, 2.0, 0.0), Vec3::new(3.0, 0.0, 0.0), 0.0);
+        let landing = *points.last().unwrap();
+        assert!(landing.y.abs() < 1e-4);
+        // 2 m から落ちるのにかかる時間 × 横の速さ
+        let expected = 3.0 * (2.0 * 2.0 / 9.81_f32).sqrt();
+        assert!((landing.x - expected).abs() < 0.1, "{landing}");
+    }
+
+    #[test]
+    fn damping_shortens_the_throw() {
+        let damped = Ballistic { damping: 1.0, ..EARTH };
+        let far = EARTH.predict(Vec3::Y, Vec3::new(10.0, 3.0, 0.0), 0.0);
+        let near = damped.predict(Vec3::Y, Vec3::new(10.0, 3.0, 0.0), 0.0);
+        assert!(near.last().unwrap().x < far.last().unwrap().x);
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+// ジェスチャーごとの力の大きさ・届く距離・判定のしきい値。gestures.toml から読む
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GestureTuning {
+    pub magnet: MagnetTuning,
+    pub wind: WindTuning,
+    pub grab: GrabTuning,
+    pub bullet_time: BulletTimeTuning,
+    pub pinch: PinchTuning,
+}
+
+// 握った手 (引力) と鷲づかみの手 (斥力) の磁石
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MagnetTuning {
+    pub strength: f32,
+}
+
+impl Default for MagnetTuning {
+    fn default() -> Self {
+        Self { strength: 50000.0 }
+    }
+}
+
+// 両手を開いて同じ向きに向けると吹く風
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindTuning {
+    pub force: f32,
+    // 両手の法線の内積がこれを超えたら同じ向きとみなす (-1..1)
+    pub alignment: f32,
+}
+
+impl Default for WindTuning {
+    fn default() -> Self {
+        Self { force: 1500.0, alignment: 0.5 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrabTuning {
+    // 手の中心から物体の表面までこの距離以内なら掴める
+    pub distance: f32,
+    // 保持中の物体を手へ引き寄せる速さ (1/秒) と速度の上限
+    pub follow_gain: f32,
+    pub max_follow_speed: f32,
+    // 保持中は手の位置をこの時定数 (秒) でならし、固有角振動数 hold_frequency (rad/秒) のばねで追わせる
+    pub hold_smoothing: f32,
+    pub hold_frequency: f32,
+}
+
+impl Default for GrabTuning {
+    fn default() -> Self {
+        Self { distance: 1.0, follow_gain: 15.0, max_follow_speed: 60.0, hold_smoothing: 0.06, hold_frequency: 14.0 }
+    }
+}
+
+// 胸の前で両拳を合わせるとスローモーション
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletTimeTuning {
+    pub chest_radius: f32,
+    pub max_fist_gap: f32,
+    pub slow_motion_scale: f32,
+}
+
+impl Default for BulletTimeTuning {
+    fn default() -> Self {
+        Self { chest_radius: 6.0, max_fist_gap: 5.0, slow_motion_scale: 0.2 }
+    }
+}
+
+// 親指と人差し指の先がこの距離より近ければつまんでいる
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PinchTuning {
+    pub distance: f32,
+}
+
+impl Default for PinchTuning {
+    fn default() -> Self {
+        Self { distance: 0.8 }
+    }
+}
+
+impl GestureTuning {
+    pub fn parse(text: &str) -> Result<Self, Vec<String>> {
+        let tuning: Self = toml::from_str(text).map_err(|e| vec![e.to_string()])?;
+        tuning.validate()?;
+        Ok(tuning)
+    }
+
+    // 読み込んだ値の範囲を確かめ、問題のある項目をすべて挙げる
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut positive = |name: &str, value: f32| {
+            if !(value.is_finite() && value > 0.0) {
+                errors.push(format!("{name} must be a positive number (got {value})"));
+            }
+        };
+        positive("magnet.strength", self.magnet.strength);
+        positive("wind.force", self.wind.force);
+        positive("grab.distance", self.grab.distance);
+        positive("grab.follow_gain", self.grab.follow_gain);
+        positive("grab.max_follow_speed", self.grab.max_follow_speed);
+        positive("grab.hold_frequency", self.grab.hold_frequency);
+        positive("bullet_time.chest_radius", self.bullet_time.chest_radius);
+        positive("bullet_time.max_fist_gap", self.bullet_time.max_fist_gap);
+        positive("pinch.distance", self.pinch.distance);
+        if !(-1.0..=1.0).contains(&self.wind.alignment) {
+            errors.push(format!(
+                "wind.alignment is a dot product and must be between -1 and 1 (got {})",
+                self.wind.alignment
+            ));
+        }
+        if self.grab.hold_smoothing < 0.0 {
+            errors.push(format!("grab.hold_smoothing must not be negative (got {})", self.grab.hold_smoothing));
+        }
+        if !(self.bullet_time.slow_motion_scale > 0.0 && self.bullet_time.slow_motion_scale <= 1.0) {
+            errors.push(format!(
+                "bullet_time.slow_motion_scale must be in (0, 1] (got {})",
+                self.bullet_time.slow_motion_scale
+            ));
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_file_keeps_defaults() {
+        let tuning = GestureTuning::parse("[magnet]\nstrength = 1000.0\n").unwrap();
+        assert_eq!(tuning.magnet.strength, 1000.0);
+        assert_eq!(tuning.wind, WindTuning::default());
+    }
+
+    #[test]
+    fn defaults_round_trip() {
+        let text = toml::to_string_pretty(&GestureTuning::default()).unwrap();
+        assert_eq!(GestureTuning::parse(&text).unwrap(), GestureTuning::default());
+    }
+
+    #[test]
+    fn validation_lists_every_problem() {
+        let errors = GestureTuning::parse("[wind]\nalignment = 1.5\nforce = -2.0\n").unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.contains("wind.alignment")));
+        assert!(errors.iter().any(|e| e.contains("wind.force")));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(GestureTuning::parse("[magnet]\nstrenght = 1.0\n").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use glam::{Quat, Vec3};
+use std::f32::consts::{PI, TAU};
+
+// 1 回の更新でこれより大きくひねったら、向きの推定が裏返ったとみなして数えない
+const MAX_STEP: f32 = 1.2;
+
+fn wrap(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+// 回転を axis まわりのひねりとそれ以外の振りに分けたときの、ひねりの角度 (-PI..PI)
+pub fn twist_angle(rotation: Quat, axis: Vec3) -> f32 {
+    let Some(axis) = axis.try_normalize() else {
+        return 0.0;
+    };
+    let along = Vec3::new(rotation.x, rotation.y, rotation.z).dot(axis);
+    wrap(2.0 * along.atan2(rotation.w))
+}
+
+// 前腕まわりのひねりを積み重ねた角度。半回転を超えても途切れずに数え、
+// 止めている手の小さな揺れは dead_band の遊びで吸収する
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TwistTracker {
+    previous: Option<Quat>,
+    raw: f32,
+    angle: f32,
+    rate: f32,
+}
+
+impl TwistTracker {
+    // smoothing は角速度を新しい値に寄せる速さ (1/秒)
+    pub fn update(&mut self, rotation: Quat, axis: Vec3, dt: f32, dead_band: f32, smoothing: f32) -> f32 {
+        let before = self.angle;
+        if let Some(previous) = self.previous {
+            let step = twist_angle(rotation * previous.inverse(), axis);
+            if step.abs() <= MAX_STEP {
+                self.raw += step;
+            }
+        }
+        self.previous = Some(rotation);
+        self.angle = self.angle.clamp(self.raw - dead_band, self.raw + dead_band);
+        if dt > 0.0 {
+            let blend = 1.0 - (-smoothing * dt).exp();
+            self.rate += ((self.angle - before) / dt - self.rate) * blend;
+        }
+        self.angle
+    }
+
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    // rad/秒。右ねじの向き (axis の先から見て反時計回り) が正
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn twist_ignores_swing_around_other_axes() {
+        let twist = Quat::from_axis_angle(Vec3::Y, 0.7);
+        let swing = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 1.0).normalize(), 0.9);
+        assert!((twist_angle(twist, Vec3::Y) - 0.7).abs() < 1e-5);
+        assert!(twist_angle(swing, Vec3::Y).abs() < 1e-5);
+        assert!((twist_angle(swing * twist, Vec3::Y) - 0.7).abs() < 1e-5);
+        assert!((twist_angle(twist, Vec3::NEG_Y) + 0.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn continuous_roll_accumulates_past_half_a_turn() {
+        let mut tracker = TwistTracker::default();
+        let steps = 80;
+        let total = 400f32.to_radians();
+        for i in 0..=steps {
+            let rotation = Quat::from_axis_angle(Vec3::Y, total * i as f32 / steps as f32);
+            tracker.update(rotation, Vec3::Y, 1.0 / 60.0, 0.0, 20.0);
+        }
+        assert!((tracker.angle() - total).abs() < 1e-3);
+        assert!(tracker.rate() > 0.0);
+    }
+
+    #[test]
+    fn jitter_and_flips_do_not_turn() {
+        let mut tracker = TwistTracker::default();
+        for i in 0..60 {
+            let wobble = if i % 2 == 0 { 0.01 } else { -0.01 };
+            tracker.update(Quat::from_axis_angle(Vec3::Y, wobble), Vec3::Y, 1.0 / 60.0, 0.03, 20.0);
+        }
+        assert!(tracker.angle().abs() < 1e-6);
+        // 推定が裏返って一気に半回転しても数えない
+        tracker.update(Quat::from_axis_angle(Vec3::Y, 3.0), Vec3::Y, 1.0 / 60.0, 0.03, 20.0);
+        assert!(tracker.angle().abs() < 1e-6);
+    }
+}
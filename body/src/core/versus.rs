@@ -0,0 +1,66 @@
+// 対戦の得点と残り時間。0 番と 1 番の 2 人で戦う
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchState {
+    pub scores: [u32; 2],
+    pub remaining: f32,
+    pub finished: bool,
+}
+
+impl MatchState {
+    pub fn new(duration: f32) -> Self {
+        Self { scores: [0; 2], remaining: duration, finished: false }
+    }
+
+    // 時間切れになったフレームだけ true を返す
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.remaining = (self.remaining - dt).max(0.0);
+        self.finished = self.remaining == 0.0;
+        self.finished
+    }
+
+    pub fn score(&mut self, player: usize) {
+        if !self.finished
+            && let Some(score) = self.scores.get_mut(player)
+        {
+            *score += 1;
+        }
+    }
+
+    // 引き分けなら None
+    pub fn winner(&self) -> Option<usize> {
+        match self.scores[0].cmp(&self.scores[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_ends_once_and_freezes_scores() {
+        let mut state = MatchState::new(1.0);
+        state.score(1);
+        assert!(!state.tick(0.6));
+        assert!(state.tick(0.6));
+        assert!(!state.tick(0.6));
+        state.score(0);
+        assert_eq!(state.scores, [0, 1]);
+        assert_eq!(state.winner(), Some(1));
+    }
+
+    #[test]
+    fn equal_scores_are_a_draw() {
+        let mut state = MatchState::new(10.0);
+        state.score(0);
+        state.score(1);
+        state.score(5);
+        assert_eq!(state.winner(), None);
+    }
+}
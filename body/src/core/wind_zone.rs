@@ -0,0 +1,95 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+// 描いた領域の中の物体を一定の向きへ流し続ける風 (scene.json の wind_zones)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindZone {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+    // 中の物体に与える加速度
+    pub wind: [f32; 3],
+}
+
+impl WindZone {
+    pub fn contains(&self, position: Vec3) -> bool {
+        position.cmpge(Vec3::from_array(self.min)).all() && position.cmple(Vec3::from_array(self.max)).all()
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (Vec3::from_array(self.min) + Vec3::from_array(self.max)) / 2.0
+    }
+
+    pub fn size(&self) -> Vec3 {
+        Vec3::from_array(self.max) - Vec3::from_array(self.min)
+    }
+
+    pub fn wind(&self) -> Vec3 {
+        Vec3::from_array(self.wind)
+    }
+}
+
+// 描いている途中の風の領域。手のひらが通った場所を包む箱と、手のひらの向きの平均
+#[derive(Debug, Clone, Default)]
+pub struct ZoneBrush {
+    bounds: Option<(Vec3, Vec3)>,
+    directions: Vec3,
+}
+
+impl ZoneBrush {
+    pub fn stroke(&mut self, palm: Vec3, direction: Vec3) {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min(palm), max.max(palm)),
+            None => (palm, palm),
+        });
+        self.directions += direction.normalize_or_zero();
+    }
+
+    // 手のひらの通り道を radius だけ太らせた箱
+    pub fn bounds(&self, radius: f32) -> Option<(Vec3, Vec3)> {
+        self.bounds.map(|(min, max)| (min - Vec3::splat(radius), max + Vec3::splat(radius)))
+    }
+
+    // 向きが打ち消し合って決まらなければ領域を作らない
+    pub fn finish(&self, radius: f32, strength: f32) -> Option<WindZone> {
+        let (min, max) = self.bounds(radius)?;
+        let direction = self.directions.try_normalize()?;
+        Some(WindZone { min: min.to_array(), max: max.to_array(), wind: (direction * strength).to_array() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brush_wraps_the_palm_path_and_averages_the_direction() {
+        let mut brush = ZoneBrush::default();
+        brush.stroke(Vec3::new(0.0, 0.0, 0.0), Vec3::X);
+        brush.stroke(Vec3::new(4.0, 2.0, 0.0), Vec3::new(1.0, 0.2, 0.0));
+        let zone = brush.finish(1.0, 10.0).unwrap();
+        assert_eq!(zone.min, [-1.0, -1.0, -1.0]);
+        assert_eq!(zone.max, [5.0, 3.0, 1.0]);
+        assert!((zone.wind().length() - 10.0).abs() < 1e-4);
+        assert!(zone.wind().x > 9.0 && zone.wind().y > 0.0);
+        assert!(zone.contains(Vec3::new(2.0, 1.0, 0.5)));
+        assert!(!zone.contains(Vec3::new(2.0, 1.0, 1.5)));
+    }
+
+    #[test]
+    fn empty_or_cancelling_strokes_make_no_zone() {
+        assert!(ZoneBrush::default().finish(1.0, 10.0).is_none());
+        let mut brush = ZoneBrush::default();
+        brush.stroke(Vec3::ZERO, Vec3::X);
+        brush.stroke(Vec3::Y, Vec3::NEG_X);
+        assert!(brush.finish(1.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn zones_round_trip_through_json() {
+        let zone = WindZone { min: [-1.0, 0.0, -1.0], max: [1.0, 2.0, 1.0], wind: [0.0, 5.0, 0.0] };
+        let text = serde_json::to_string(&zone).unwrap();
+        assert_eq!(serde_json::from_str::<WindZone>(&text).unwrap(), zone);
+        assert_eq!(zone.center(), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(zone.size(), Vec3::new(2.0, 2.0, 2.0));
+    }
+}
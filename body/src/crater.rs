@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::crater::{CraterTile, RESOLUTION};
+use crate::floor::{FloorTile, TILE_SIZE};
+use crate::spawn::SpawnedBox;
+
+// この力積を超えて床に当たった箱だけが凹みを作る (画面の揺れより重い当たり)
+const CRATER_IMPULSE: f32 = 300.0;
+// 力積が閾値の何倍かで深さを決める
+const DEPTH_PER_IMPULSE: f32 = 0.12;
+const MAX_STAMP_DEPTH: f32 = 0.5;
+// 箱の大きさに対する凹みの半径
+const RADIUS_PER_SIZE: f32 = 0.6;
+// 凹みが戻る速さ (1/秒)。数秒かけてゆっくり平らになる
+const HEAL_RATE: f32 = 0.3;
+
+struct DentedTile {
+    crater: CraterTile,
+    mesh: Handle<Mesh>,
+    // 平らに戻ったら差し戻す共有のメッシュ
+    flat: Handle<Mesh>,
+}
+
+// 凹んでいる床タイル。当たり判定は平らなまま、見た目のメッシュだけを変形させる
+#[derive(Resource, Default)]
+struct Craters {
+    tiles: HashMap<Entity, DentedTile>,
+}
+
+pub struct CraterPlugin;
+
+impl Plugin for CraterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Craters::default())
+            .add_systems(Update, (stamp_craters, heal_craters).chain());
+    }
+}
+
+fn crater_mesh(crater: &CraterTile) -> Mesh {
+    let mut positions = Vec::with_capacity(RESOLUTION * RESOLUTION);
+    let mut normals = Vec::with_capacity(RESOLUTION * RESOLUTION);
+    let mut uvs = Vec::with_capacity(RESOLUTION * RESOLUTION);
+    for j in 0..RESOLUTION {
+        for i in 0..RESOLUTION {
+            let [x, z] = crater.point(i, j);
+            positions.push([x, crater.height(i, j), z]);
+            normals.push(crater.normal(i, j));
+            uvs.push([i as f32 / (RESOLUTION - 1) as f32, j as f32 / (RESOLUTION - 1) as f32]);
+        }
+    }
+    let mut indices = Vec::with_capacity((RESOLUTION - 1) * (RESOLUTION - 1) * 6);
+    for j in 0..RESOLUTION - 1 {
+        for i in 0..RESOLUTION - 1 {
+            let a = (j * RESOLUTION + i) as u32;
+            let b = a + RESOLUTION as u32;
+            indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+        }
+    }
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+fn stamp_craters(
+    mut craters: ResMut<Craters>,
+    mut contacts: EventReader<ContactForceEvent>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut tiles: Query<(Entity, &Transform, &mut Handle<Mesh>), With<FloorTile>>,
+    boxes: Query<(&Transform, &SpawnedBox)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for contact in contacts.read() {
+        let impulse = contact.total_force_magnitude * dt;
+        if impulse < CRATER_IMPULSE {
+            continue;
+        }
+        let landed = if tiles.contains(contact.collider1) {
+            contact.collider2
+        } else if tiles.contains(contact.collider2) {
+            contact.collider1
+        } else {
+            continue;
+        };
+        let Ok((box_transform, spawned)) = boxes.get(landed) else {
+            continue;
+        };
+        let impact = box_transform.translation;
+        let radius = spawned.size * RADIUS_PER_SIZE;
+        let depth = (impulse / CRATER_IMPULSE * DEPTH_PER_IMPULSE).min(MAX_STAMP_DEPTH);
+        debug!(?impact, impulse, depth, "crater");
+
+        // 縁がタイルの境目をまたぐ凹みは、となりのタイルにも押す
+        for (entity, transform, mut mesh) in tiles.iter_mut() {
+            let offset = impact - transform.translation;
+            let reach = TILE_SIZE / 2.0 + radius * 1.5;
+            if offset.x.abs() > reach || offset.z.abs() > reach {
+                continue;
+            }
+            let dented = craters.tiles.entry(entity).or_insert_with(|| DentedTile {
+                crater: CraterTile::new(TILE_SIZE),
+                mesh: meshes.add(crater_mesh(&CraterTile::new(TILE_SIZE))),
+                flat: mesh.clone(),
+            });
+            dented.crater.stamp([offset.x, offset.z], radius, depth);
+            *mesh = dented.mesh.clone();
+        }
+    }
+}
+
+fn heal_craters(
+    mut craters: ResMut<Craters>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut tiles: Query<&mut Handle<Mesh>, With<FloorTile>>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    craters.tiles.retain(|entity, dented| {
+        // 遠ざかって片付けられたタイルの凹みは忘れる
+        let Ok(mut mesh) = tiles.get_mut(*entity) else {
+            return false;
+        };
+        if !dented.crater.heal(HEAL_RATE, dt) {
+            *mesh = dented.flat.clone();
+            return false;
+        }
+        if let Some(deformed) = meshes.get_mut(&dented.mesh) {
+            *deformed = crater_mesh(&dented.crater);
+        }
+        true
+    });
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::grip::{curl, squash_scale, GripMeter};
+use crate::display::InteractionSet;
+use crate::grab::Held;
+use crate::juice::ShakeRequest;
+use crate::lifecycle::{vanish, Appearing, Vanishing};
+use crate::packet::{ClientPackets, IncomingPacket};
+use crate::sound::SurfaceMaterial;
+use crate::spawn::SpawnedBox;
+use crate::{update_hands_and_physics, HandKey, HandSide, HandStates};
+
+// つぶれ具合が握力に追いつくまでの時定数
+const CRUSH_TIME: f32 = 0.12;
+const CRUSH_TRAUMA: f32 = 0.25;
+
+// 握ると見た目がつぶれる物体
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Squashable {
+    // これ以下の握力ではつぶれない (0..1)
+    pub resistance: f32,
+    // ここまでつぶれると壊れる。None なら壊れず、離すと元の形に戻る
+    pub break_at: Option<f32>,
+    amount: f32,
+}
+
+impl Squashable {
+    pub fn new(resistance: f32, break_at: Option<f32>) -> Self {
+        Self { resistance, break_at, amount: 0.0 }
+    }
+
+    fn target(&self, strength: f32) -> f32 {
+        ((strength - self.resistance) / (1.0 - self.resistance).max(f32::EPSILON)).max(0.0)
+    }
+}
+
+// 握りつぶして壊した
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Crushed {
+    pub entity: Entity,
+    pub by: HandKey,
+    pub position: Vec3,
+}
+
+// 手ごとの握力の推定
+#[derive(Resource, Default)]
+pub struct GripStrengths {
+    meters: HashMap<HandKey, GripMeter>,
+}
+
+impl GripStrengths {
+    pub fn strength(&self, key: HandKey, now: f32) -> f32 {
+        self.meters.get(&key).map_or(0.0, |m| m.strength(now))
+    }
+}
+
+pub struct CrushPlugin;
+
+impl Plugin for CrushPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Crushed>()
+            .insert_resource(GripStrengths::default())
+            .add_systems(
+                Update,
+                (attach_squashable, measure_grips, crush_held, announce_crushes)
+                    .chain()
+                    .after(update_hands_and_physics)
+                    .in_set(InteractionSet),
+            );
+    }
+}
+
+// 木の箱は握りつぶせて、ゴムの箱はへこんでも戻る。金属はつぶれない
+#[allow(clippy::type_complexity)]
+fn attach_squashable(
+    mut commands: Commands,
+    added: Query<(Entity, &SurfaceMaterial), (Added<SpawnedBox>, Without<Squashable>)>,
+) {
+    for (entity, material) in added.iter() {
+        let squashable = match material {
+            SurfaceMaterial::Wood => Squashable::new(0.45, Some(0.9)),
+            SurfaceMaterial::Rubber => Squashable::new(0.1, None),
+            SurfaceMaterial::Metal => continue,
+        };
+        commands.entity(entity).insert(squashable);
+    }
+}
+
+// パケットが届いたフレームだけ指の曲がりを測り直す
+fn measure_grips(
+    mut grips: ResMut<GripStrengths>,
+    incoming: Res<IncomingPacket>,
+    clients: Res<ClientPackets>,
+    hand_states: Res<HandStates>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let packets = incoming.0.iter().map(|p| (0, p)).chain(clients.packets.iter().map(|(c, p)| (*c, p)));
+    for (client, packet) in packets {
+        for side in [HandSide::Right, HandSide::Left] {
+            let curl = packet.hands.iter().find(|h| h.label == side.label()).and_then(|h| curl(&h.landmarks));
+            if let Some(curl) = curl {
+                grips.meters.entry((client, side)).or_default().sample(curl, now);
+            }
+        }
+    }
+    grips.meters.retain(|key, _| hand_states.hands.contains_key(key));
+}
+
+#[allow(clippy::type_complexity)]
+fn crush_held(
+    mut commands: Commands,
+    grips: Res<GripStrengths>,
+    hand_states: Res<HandStates>,
+    mut squashables: Query<
+        (Entity, &mut Squashable, &mut Transform, Option<&Held>),
+        (Without<Appearing>, Without<Vanishing>),
+    >,
+    mut crushes: EventWriter<Crushed>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let dt = time.delta_seconds();
+    let blend = 1.0 - (-dt / CRUSH_TIME).exp();
+    for (entity, mut squashable, mut transform, held) in squashables.iter_mut() {
+        // 手が重なって指の形が怪しい間は握力を信じない
+        let grip = held
+            .filter(|h| hand_states.is_confident(h.by))
+            .map(|h| (h.by, grips.strength(h.by, now)));
+        let target = grip.map_or(0.0, |(_, strength)| squashable.target(strength));
+        let amount = squashable.amount;
+        // 壊れる物体はへこんだまま戻らない
+        let next = match squashable.break_at {
+            Some(_) if target <= amount => amount,
+            _ => amount + (target - amount) * blend,
+        };
+        if let Some((by, _)) = grip
+            && let Some(limit) = squashable.break_at
+            && next >= limit
+        {
+            crushes.send(Crushed { entity, by, position: transform.translation });
+            vanish(&mut commands, entity);
+            continue;
+        }
+        // ほぼ戻ったら元の大きさにそろえ、変化がなければ大きさに触らない
+        let next = if next < 1e-3 { 0.0 } else { next };
+        if next != amount {
+            squashable.amount = next;
+            transform.scale = squash_scale(next);
+        }
+    }
+}
+
+fn announce_crushes(mut crushes: EventReader<Crushed>, mut shakes: EventWriter<ShakeRequest>) {
+    for crush in crushes.read() {
+        info!(entity = ?crush.entity, by = ?crush.by, position = ?crush.position, "crushed");
+        shakes.send(ShakeRequest { trauma: CRUSH_TRAUMA });
+    }
+}
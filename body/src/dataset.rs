@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::transform::TransformSystem;
+use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::coco::{CocoAnnotation, CocoCategory, CocoDataset, CocoImage, CocoInfo, ProjectedPoint};
+use crate::core::topology::HandTopology;
+use crate::display::world_to_window;
+use crate::topology::Topology;
+use crate::{HandPoint, HandStates};
+
+const DATASET_DIR: &str = "datasets";
+// 連続するフレームはほぼ同じ絵になるので間引いて撮る
+const CAPTURE_FPS: f32 = 5.0;
+
+#[derive(Resource, Default)]
+pub struct DatasetCapture {
+    pub recording: bool,
+    dir: PathBuf,
+    dataset: CocoDataset,
+    accumulator: f32,
+}
+
+impl DatasetCapture {
+    fn start(&mut self, topology: &HandTopology) {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.dir = PathBuf::from(DATASET_DIR).join(format!("session-{stamp}"));
+        if let Err(e) = fs::create_dir_all(self.dir.join("images")) {
+            error!("failed to create {}: {e}", self.dir.display());
+            return;
+        }
+        let names = topology.names();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        self.dataset = CocoDataset {
+            info: CocoInfo { description: "MasterHand hand landmark capture".to_string(), date_created: stamp.to_string() },
+            categories: vec![CocoCategory::hand(&names, &topology.connections)],
+            ..default()
+        };
+        self.accumulator = 0.0;
+        self.recording = true;
+        info!("dataset capture started in {}", self.dir.display());
+    }
+
+    fn stop(&mut self) {
+        self.recording = false;
+        let path = self.dir.join("annotations.json");
+        let result = serde_json::to_string(&self.dataset)
+            .map_err(std::io::Error::other)
+            .and_then(|text| fs::write(&path, text));
+        match result {
+            Ok(()) => info!(
+                images = self.dataset.images.len(),
+                annotations = self.dataset.annotations.len(),
+                "dataset written to {}",
+                path.display()
+            ),
+            Err(e) => error!("failed to write {}: {e}", path.display()),
+        }
+    }
+}
+
+pub struct DatasetPlugin;
+
+impl Plugin for DatasetPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DatasetCapture::default())
+            .add_systems(Update, toggle_dataset_capture)
+            // 揺れを足したあとの、実際に描画されるカメラで投影する
+            .add_systems(PostUpdate, capture_dataset_frame.after(TransformSystem::TransformPropagate));
+    }
+}
+
+fn toggle_dataset_capture(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut capture: ResMut<DatasetCapture>,
+    topology: Res<Topology>,
+) {
+    if !keys.just_pressed(KeyCode::F2) {
+        return;
+    }
+    if capture.recording {
+        capture.stop();
+    } else {
+        capture.start(&topology.0);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn capture_dataset_frame(
+    mut capture: ResMut<DatasetCapture>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    points: Query<(&HandPoint, &GlobalTransform)>,
+    hand_states: Res<HandStates>,
+    topology: Res<Topology>,
+    time: Res<Time>,
+) {
+    if !capture.recording {
+        return;
+    }
+    capture.accumulator += time.delta_seconds();
+    if capture.accumulator < 1.0 / CAPTURE_FPS {
+        return;
+    }
+    capture.accumulator = (capture.accumulator - 1.0 / CAPTURE_FPS).min(1.0 / CAPTURE_FPS);
+    let (Ok((window_entity, window)), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    if hand_states.hands.is_empty() {
+        return;
+    }
+
+    let image_id = capture.dataset.images.len() as u64;
+    let file_name = format!("images/{image_id:06}.png");
+    if let Err(e) = screenshots.save_screenshot_to_disk(window_entity, capture.dir.join(&file_name)) {
+        error!("failed to request screenshot: {e}");
+        return;
+    }
+    // スクリーンショットは物理ピクセルなので座標も合わせる
+    let scale = window.scale_factor();
+    let (width, height) = (window.physical_width(), window.physical_height());
+    capture.dataset.images.push(CocoImage { id: image_id, file_name, width, height });
+
+    let count = topology.0.landmarks;
+    let mut by_hand: HashMap<_, Vec<Option<ProjectedPoint>>> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let key = (point.client, point.side);
+        if !hand_states.hands.contains_key(&key) || point.id >= count {
+            continue;
+        }
+        let projected = world_to_window(camera, camera_transform, window, transform.translation()).map(|p| {
+            let position = p * scale;
+            let in_view = position.x >= 0.0 && position.y >= 0.0 && position.x < width as f32 && position.y < height as f32;
+            ProjectedPoint { position, in_view }
+        });
+        by_hand.entry(key).or_insert_with(|| vec![None; count])[point.id] = projected;
+    }
+
+    let mut hands: Vec<_> = by_hand.into_iter().collect();
+    hands.sort_by_key(|(key, _)| *key);
+    for ((client, side), projected) in hands {
+        let id = capture.dataset.annotations.len() as u64;
+        let mut annotation = CocoAnnotation::from_points(id, image_id, &projected);
+        annotation.gesture = hand_states.hands[&(client, side)].gesture.label().to_string();
+        annotation.side = side.label().to_string();
+        annotation.client = client;
+        capture.dataset.annotations.push(annotation);
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(feature = "audio")]
+use bevy::audio::{GlobalVolume, Volume};
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_2;
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::palm::PalmPoses;
+use crate::twist::{track_palm_twist, PalmTwist};
+use crate::{HandKey, HandStates};
+
+const POSITION: Vec3 = Vec3::new(-11.0, 4.0, 4.0);
+const KNOB_RADIUS: f32 = 1.2;
+const KNOB_DEPTH: f32 = 0.8;
+// 手のひらがこの距離まで来て握ったらつまむ。離れすぎるか手を開いたら放す
+const GRAB_DISTANCE: f32 = 2.5;
+const RELEASE_DISTANCE: f32 = 4.0;
+// 回せる範囲 (rad)。真上を中心に左右へ半分ずつ
+const SWEEP: f32 = 270.0 * std::f32::consts::PI / 180.0;
+const START_VOLUME: f32 = 1.0;
+// この速さ (rad/秒) で回すと弧の色が一番濃くなる
+const TURNING_RATE: f32 = 3.0;
+
+// 手首をひねって回す音量のつまみ。カメラの方を向いて立ち、時計回りで大きくなる
+#[derive(Component)]
+struct VolumeDial {
+    angle: f32,
+    // つまんでいる手と、つまんだときのひねりとつまみの角度
+    grip: Option<(HandKey, f32, f32)>,
+}
+
+impl VolumeDial {
+    fn value(&self) -> f32 {
+        (self.angle / SWEEP + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Component)]
+struct DialKnob;
+
+pub struct DialPlugin;
+
+impl Plugin for DialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_console_command("dial", "dial (show or hide the volume dial)", dial_command)
+            .add_systems(Update, (turn_dials.after(track_palm_twist).in_set(InteractionSet), draw_dials).chain());
+    }
+}
+
+fn dial_command(
+    In(_): In<Vec<String>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    dials: Query<Entity, With<VolumeDial>>,
+) -> ConsoleResult {
+    if !dials.is_empty() {
+        for entity in dials.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return Ok("volume dial removed".to_string());
+    }
+    let knob = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.75, 0.75, 0.78),
+        metallic: 0.8,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let notch = materials.add(StandardMaterial { base_color: Color::srgb(1.0, 0.45, 0.1), ..default() });
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(POSITION)),
+            VolumeDial { angle: (START_VOLUME - 0.5) * SWEEP, grip: None },
+        ))
+        .with_children(|dial| {
+            // 円柱の軸 (y) をカメラへ向ける
+            dial.spawn((
+                PbrBundle {
+                    mesh: meshes.add(Cylinder::new(KNOB_RADIUS, KNOB_DEPTH)),
+                    material: knob,
+                    transform: Transform::from_rotation(Quat::from_rotation_x(FRAC_PI_2)),
+                    ..default()
+                },
+                DialKnob,
+            ))
+            .with_children(|knob| {
+                knob.spawn(PbrBundle {
+                    mesh: meshes.add(Sphere::new(0.18)),
+                    material: notch,
+                    transform: Transform::from_xyz(0.0, KNOB_DEPTH / 2.0, -KNOB_RADIUS * 0.7),
+                    ..default()
+                });
+            });
+        });
+    Ok("volume dial placed".to_string())
+}
+
+// 握った手のひねった分だけつまみを回す
+fn turn_dials(
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    twist: Res<PalmTwist>,
+    mut dials: Query<(&mut VolumeDial, &Transform)>,
+    #[cfg(feature = "audio")] mut volume: ResMut<GlobalVolume>,
+) {
+    let gripping = |key: HandKey| {
+        hand_states.hands.get(&key).is_some_and(|h| matches!(h.gesture, Gesture::Fist | Gesture::Claw))
+    };
+    for (mut dial, transform) in dials.iter_mut() {
+        let distance = |key: HandKey| palms.poses.get(&key).map(|p| p.position().distance(transform.translation));
+        if let Some((key, ..)) = dial.grip
+            && !(gripping(key) && distance(key).is_some_and(|d| d <= RELEASE_DISTANCE))
+        {
+            dial.grip = None;
+            info!(volume = dial.value(), "volume dial released");
+        }
+        if dial.grip.is_none() {
+            let grabbed = hand_states.hands.keys().copied().find(|key| {
+                hand_states.is_confident(*key) && gripping(*key) && distance(*key).is_some_and(|d| d <= GRAB_DISTANCE)
+            });
+            if let Some(key) = grabbed
+                && let Some(start) = twist.angle(key)
+            {
+                dial.grip = Some((key, start, dial.angle));
+            }
+        }
+        let Some((key, start, from)) = dial.grip else {
+            continue;
+        };
+        let Some(now) = twist.angle(key) else {
+            continue;
+        };
+        // 手前へ伸ばした前腕の軸まわりの正のひねりは、見ている側からは時計回りになる
+        dial.angle = (from + now - start).clamp(-SWEEP / 2.0, SWEEP / 2.0);
+        #[cfg(feature = "audio")]
+        {
+            volume.volume = Volume::new(dial.value());
+        }
+    }
+}
+
+fn draw_dials(
+    dials: Query<(&VolumeDial, &Children, &Transform)>,
+    mut knobs: Query<&mut Transform, (With<DialKnob>, Without<VolumeDial>)>,
+    twist: Res<PalmTwist>,
+    mut gizmos: Gizmos,
+) {
+    for (dial, children, transform) in dials.iter() {
+        for child in children.iter() {
+            if let Ok(mut knob) = knobs.get_mut(*child) {
+                knob.rotation = Quat::from_rotation_z(-dial.angle) * Quat::from_rotation_x(FRAC_PI_2);
+            }
+        }
+        // 目盛りの弧。今の音量までを明るくする
+        let face = transform.translation + Vec3::Z * (KNOB_DEPTH / 2.0 + 0.05);
+        let point = |angle: f32| face + Vec3::new(angle.sin(), angle.cos(), 0.0) * KNOB_RADIUS * 1.3;
+        let arc = |from: f32, to: f32| (0..=24).map(move |i| point(from + (to - from) * i as f32 / 24.0));
+        gizmos.linestrip(arc(-SWEEP / 2.0, SWEEP / 2.0), Color::srgba(0.6, 0.6, 0.6, 0.4));
+        // つまんでいる間はオレンジにし、速く回すほど濃くする
+        let lit = match dial.grip {
+            Some((key, ..)) => {
+                let turning = twist.rate(key).unwrap_or(0.0).abs() / TURNING_RATE;
+                Color::srgb(1.0, 0.75, 0.45).mix(&Color::srgb(1.0, 0.45, 0.0), turning.min(1.0))
+            }
+            None => Color::srgb(0.9, 0.9, 0.9),
+        };
+        gizmos.linestrip(arc(-SWEEP / 2.0, dial.angle), lit);
+    }
+}
@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::dice::{face_up, pip_pattern, FACES};
+use crate::grab::{Grabbable, Held};
+
+const DICE_COUNT: usize = 3;
+const DIE_HALF: f32 = 0.6;
+const PIP_RADIUS: f32 = 0.09;
+// 目の間隔 (面の中心から端の目まで)
+const PIP_SPREAD: f32 = DIE_HALF * 0.5;
+// これより速く動いたら振られたとみなし、これより遅い状態が続いたら止まったとみなす
+const ROLL_SPEED: f32 = 2.0;
+const SETTLE_SPEED: f32 = 0.1;
+const SETTLE_TIME: f32 = 0.5;
+// 立てかかって目が決まらないときに軽く揺する
+const NUDGE_IMPULSE: f32 = 0.5;
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DiceRolled(pub u8);
+
+#[derive(Component, Default)]
+pub struct Die {
+    pub value: Option<u8>,
+    rolling: bool,
+    still_since: Option<f32>,
+}
+
+#[derive(Resource, Default)]
+pub struct DiceTable {
+    pub active: bool,
+    pub last_total: Option<u32>,
+    pub rolls: u32,
+}
+
+pub struct DicePlugin;
+
+impl Plugin for DicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DiceRolled>()
+            .insert_resource(DiceTable::default())
+            .add_systems(Update, (toggle_dice, settle_dice, total_rolls).chain());
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, dice_ui);
+    }
+}
+
+fn toggle_dice(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut table: ResMut<DiceTable>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    dice: Query<Entity, With<Die>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+    let active = !table.active;
+    *table = DiceTable { active, ..default() };
+    if !active {
+        for entity in dice.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let body = meshes.add(Cuboid::new(DIE_HALF * 2.0, DIE_HALF * 2.0, DIE_HALF * 2.0));
+    let body_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.92, 0.9, 0.85),
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    let pip = meshes.add(Sphere::new(PIP_RADIUS));
+    let pip_material = materials.add(Color::srgb(0.08, 0.08, 0.1));
+    for i in 0..DICE_COUNT {
+        let x = (i as f32 - (DICE_COUNT - 1) as f32 / 2.0) * DIE_HALF * 3.0;
+        commands
+            .spawn((
+                PbrBundle {
+                    mesh: body.clone(),
+                    material: body_material.clone(),
+                    transform: Transform::from_xyz(x, 2.0, 4.0).with_rotation(Quat::from_rotation_y(i as f32)),
+                    ..default()
+                },
+                RigidBody::Dynamic,
+                Collider::round_cuboid(DIE_HALF * 0.9, DIE_HALF * 0.9, DIE_HALF * 0.9, DIE_HALF * 0.1),
+                ColliderMassProperties::Density(2.0),
+                Restitution::coefficient(0.3),
+                Friction::coefficient(0.6),
+                ExternalForce::default(),
+                Velocity::default(),
+                Grabbable { radius: DIE_HALF },
+                // 置いただけでも一度目を読む
+                Die { rolling: true, ..default() },
+            ))
+            .with_children(|die| {
+                for (normal, value) in FACES {
+                    let (u, w) = normal.any_orthonormal_pair();
+                    for &(a, b) in pip_pattern(value) {
+                        // 面から少しだけ沈めて半球に見せる
+                        let position = normal * (DIE_HALF - PIP_RADIUS * 0.4) + (u * a + w * b) * PIP_SPREAD;
+                        die.spawn(PbrBundle {
+                            mesh: pip.clone(),
+                            material: pip_material.clone(),
+                            transform: Transform::from_translation(position),
+                            ..default()
+                        });
+                    }
+                }
+            });
+    }
+    info!("dice ready");
+}
+
+fn settle_dice(
+    mut commands: Commands,
+    mut dice: Query<(Entity, &Transform, &Velocity, &mut Die, Has<Held>)>,
+    mut rolled: EventWriter<DiceRolled>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for (entity, transform, velocity, mut die, held) in dice.iter_mut() {
+        let speed = velocity.linvel.length().max(velocity.angvel.length() * DIE_HALF);
+        if held || speed > ROLL_SPEED {
+            die.rolling = true;
+            die.value = None;
+            die.still_since = None;
+            continue;
+        }
+        if !die.rolling {
+            continue;
+        }
+        if speed > SETTLE_SPEED {
+            die.still_since = None;
+            continue;
+        }
+        let since = *die.still_since.get_or_insert(now);
+        if now - since < SETTLE_TIME {
+            continue;
+        }
+        match face_up(transform.rotation) {
+            Some(value) => {
+                die.value = Some(value);
+                die.rolling = false;
+                rolled.send(DiceRolled(value));
+            }
+            None => {
+                die.still_since = None;
+                commands.entity(entity).insert(ExternalImpulse {
+                    impulse: Vec3::Y * NUDGE_IMPULSE,
+                    torque_impulse: transform.right() * NUDGE_IMPULSE * 0.2,
+                });
+            }
+        }
+    }
+}
+
+// すべてのさいころの目が出そろったら合計を出す
+fn total_rolls(mut table: ResMut<DiceTable>, mut rolled: EventReader<DiceRolled>, dice: Query<&Die>) {
+    let mut settled = false;
+    for DiceRolled(value) in rolled.read() {
+        debug!(value, "die settled");
+        settled = true;
+    }
+    if !settled {
+        return;
+    }
+    let values: Option<Vec<u8>> = dice.iter().map(|d| d.value).collect();
+    let Some(values) = values else {
+        return;
+    };
+    let total = values.iter().map(|v| *v as u32).sum();
+    table.last_total = Some(total);
+    table.rolls += 1;
+    info!(?values, total, "dice rolled");
+}
+
+#[cfg(feature = "ui")]
+fn dice_ui(mut contexts: EguiContexts, table: Res<DiceTable>, dice: Query<&Die>) {
+    if !table.active {
+        return;
+    }
+    egui::Window::new("Dice")
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let faces: Vec<String> = dice.iter().map(|d| d.value.map_or("?".to_string(), |v| v.to_string())).collect();
+            ui.label(faces.join("  "));
+            match table.last_total {
+                Some(total) => ui.heading(format!("Total {total}")),
+                None => ui.label("Grab the dice, shake and throw"),
+            };
+            ui.small(format!("{} rolls / R: put away", table.rolls));
+        });
+}
@@ -0,0 +1,241 @@
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::RenderLayers;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode, WindowRef};
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::idle::{Idle, IdleSettings};
+use crate::settings::Settings;
+use crate::silhouette::HandDisplayMode;
+
+// 縮小して描いた場面を引き伸ばすカメラと板だけが使う層。手の骨組みなどのギズモは載せない
+const PRESENT_LAYER: usize = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DisplaySettings {
+    pub fullscreen: bool,
+    pub vsync: bool,
+    // 3D の場面を描く解像度の倍率。ウィンドウの大きさは変えない
+    pub resolution_scale: f32,
+    #[serde(default)]
+    pub hands: HandDisplayMode,
+    #[serde(default)]
+    pub idle: IdleSettings,
+    // 持った物体を振りかぶると、離したときの軌跡を薄く描く
+    #[serde(default = "default_throw_preview")]
+    pub throw_preview: bool,
+    // 手の周りにジェスチャーごとの確からしさを輪で出す
+    #[serde(default)]
+    pub gesture_ring: bool,
+}
+
+fn default_throw_preview() -> bool {
+    true
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            vsync: true,
+            resolution_scale: 1.0,
+            hands: HandDisplayMode::default(),
+            idle: IdleSettings::default(),
+            throw_preview: true,
+            gesture_ring: false,
+        }
+    }
+}
+
+// 一時停止中は物理と手の入力を止め、描画と UI だけ続ける
+#[derive(Resource, Default)]
+pub struct Paused(pub bool);
+
+pub fn running(paused: Res<Paused>) -> bool {
+    !paused.0
+}
+
+// 手で物体や外部の入力を動かす系。一時停止中と休止中は丸ごと止める。
+// 手の状態そのものの更新は休止から戻るのに要るので入れない
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InteractionSet;
+
+pub fn interacting(paused: Res<Paused>, idle: Res<Idle>) -> bool {
+    !paused.0 && !idle.asleep
+}
+
+// resolution_scale が 1 でないとき、場面のカメラの描き先にする画像と、それをウィンドウへ引き伸ばすカメラと板
+struct ScaledTarget {
+    image: Handle<Image>,
+    presenter: Entity,
+    sprite: Entity,
+    size: Extent3d,
+    window_size: Vec2,
+}
+
+#[derive(Resource, Default)]
+struct RenderScale(Option<ScaledTarget>);
+
+fn scene_image(size: Extent3d) -> Image {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("scaled scene"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    image
+}
+
+// 場面のカメラから見た位置をウィンドウの論理ピクセルに直す。縮小して描いていても egui の重ね表示がずれない
+pub fn world_to_window(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    window: &Window,
+    position: Vec3,
+) -> Option<Vec2> {
+    let viewport = camera.world_to_viewport(camera_transform, position)?;
+    let target = camera.logical_viewport_size()?;
+    Some(viewport * window.size() / target)
+}
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Paused::default())
+            .init_resource::<RenderScale>()
+            .configure_sets(Update, InteractionSet.run_if(interacting))
+            .add_systems(Update, (display_hotkeys, apply_display_settings, apply_render_scale, apply_pause).chain());
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, pause_overlay.after(apply_pause));
+    }
+}
+
+fn display_hotkeys(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>, mut paused: ResMut<Paused>) {
+    if keys.just_pressed(KeyCode::F11) {
+        settings.display.fullscreen = !settings.display.fullscreen;
+    }
+    if keys.just_pressed(KeyCode::F8) {
+        paused.0 = !paused.0;
+        info!("paused: {}", paused.0);
+    }
+}
+
+// 手でウィンドウの大きさを変えても戻さないよう、設定が変わったときだけ当てる
+fn apply_display_settings(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let display = &settings.display;
+    let mode = if display.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let present_mode = if display.vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync };
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+}
+
+// 場面を倍率を掛けた大きさの画像に描き、2D のカメラでウィンドウいっぱいに引き伸ばす。
+// 倍率が 1 に戻ればウィンドウへ直接描く
+fn apply_render_scale(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<&mut Camera, With<Camera3d>>,
+    mut scaled: ResMut<RenderScale>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let scale = settings.display.resolution_scale.clamp(0.25, 2.0);
+    if (scale - 1.0).abs() < 0.01 {
+        if let Some(target) = scaled.0.take() {
+            commands.entity(target.presenter).despawn();
+            commands.entity(target.sprite).despawn();
+            images.remove(&target.image);
+            for mut camera in cameras.iter_mut() {
+                camera.target = RenderTarget::Window(WindowRef::Primary);
+            }
+        }
+        return;
+    }
+    let size = Extent3d {
+        width: ((window.physical_width() as f32 * scale).round() as u32).max(1),
+        height: ((window.physical_height() as f32 * scale).round() as u32).max(1),
+        depth_or_array_layers: 1,
+    };
+    let window_size = window.size();
+    if scaled.0.as_ref().is_some_and(|t| t.size == size && t.window_size == window_size) {
+        return;
+    }
+
+    let target = scaled.0.get_or_insert_with(|| {
+        let image = images.add(scene_image(size));
+        let layer = RenderLayers::layer(PRESENT_LAYER);
+        let presenter = commands
+            .spawn((
+                Camera2dBundle {
+                    camera: Camera { order: 1, ..default() },
+                    // 場面のカメラで色調を整えてあるので二重に掛けない
+                    tonemapping: Tonemapping::None,
+                    ..default()
+                },
+                layer.clone(),
+            ))
+            .id();
+        let sprite = commands.spawn((SpriteBundle { texture: image.clone(), ..default() }, layer)).id();
+        ScaledTarget { image, presenter, sprite, size, window_size: Vec2::ZERO }
+    });
+    if let Some(image) = images.get_mut(&target.image)
+        && image.texture_descriptor.size != size
+    {
+        image.resize(size);
+    }
+    commands.entity(target.sprite).insert(Sprite { custom_size: Some(window_size), ..default() });
+    target.size = size;
+    target.window_size = window_size;
+    for mut camera in cameras.iter_mut() {
+        camera.target = RenderTarget::Image(target.image.clone());
+    }
+    info!(width = size.width, height = size.height, "rendering the scene at {scale}x");
+}
+
+// 手が見えずに休んでいる間も物理を止める。入力は止めないので手が戻ればすぐ動き出す
+fn apply_pause(paused: Res<Paused>, idle: Res<Idle>, mut config: ResMut<RapierConfiguration>) {
+    let active = !paused.0 && !idle.asleep;
+    if config.physics_pipeline_active != active {
+        config.physics_pipeline_active = active;
+    }
+}
+
+#[cfg(feature = "ui")]
+fn pause_overlay(mut contexts: EguiContexts, paused: Res<Paused>) {
+    if !paused.0 {
+        return;
+    }
+    egui::Area::new(egui::Id::new("paused"))
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(egui::RichText::new("PAUSED").size(48.0).color(egui::Color32::WHITE));
+            ui.label("F8 to resume");
+        });
+}
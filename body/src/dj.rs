@@ -0,0 +1,209 @@
+use bevy::core_pipeline::bloom::BloomSettings;
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::core::aesthetics::TapTempo;
+use crate::core::gesture::Gesture;
+use crate::packet::{IncomingPacket, PacketSet};
+use crate::settings::Settings;
+use crate::spawn::SpawnedBox;
+use crate::{HandPoint, HandStates};
+
+// 見た目の値が手の動きに追いつく速さ (1/秒)。手の揺れで明滅しないよう少し鈍らせる
+const FOLLOW_RATE: f32 = 8.0;
+
+// 音の拍。送信側の検出とタップテンポの両方から届く
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Beat;
+
+#[derive(Resource, Default)]
+pub struct DjMode {
+    pub active: bool,
+    tempo: TapTempo,
+    last_tempo_beat: Option<u64>,
+    ambient: f32,
+    bloom: f32,
+    pulse: f32,
+    beats: u32,
+    // DJ モードに入る前の環境光と、光らせた小道具の元の発光色
+    saved_ambient: f32,
+    saved_emissive: HashMap<AssetId<StandardMaterial>, LinearRgba>,
+}
+
+pub struct DjPlugin;
+
+impl Plugin for DjPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DjMode::default())
+            .add_event::<Beat>()
+            .add_systems(
+                Update,
+                (toggle_dj, collect_beats.after(PacketSet::Override), drive_aesthetics, pulse_props).chain(),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, dj_hud);
+    }
+}
+
+fn toggle_dj(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut dj: ResMut<DjMode>,
+    settings: Res<Settings>,
+    mut ambient: ResMut<AmbientLight>,
+    mut cameras: Query<(Entity, &mut Camera), With<Camera3d>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyD) {
+        return;
+    }
+    dj.active = !dj.active;
+    info!("dj mode: {}", dj.active);
+    // ブルームは HDR のカメラでしか効かない。抜けたら描画品質の設定に戻す
+    for (entity, mut camera) in cameras.iter_mut() {
+        if dj.active {
+            camera.hdr = true;
+            commands.entity(entity).insert(BloomSettings { intensity: 0.0, ..BloomSettings::NATURAL });
+        } else {
+            settings.render.apply_to_camera(&mut commands, entity, &mut camera);
+        }
+    }
+    if dj.active {
+        dj.saved_ambient = ambient.brightness;
+        dj.ambient = ambient.brightness;
+        return;
+    }
+    ambient.brightness = dj.saved_ambient;
+    for (id, emissive) in dj.saved_emissive.drain() {
+        if let Some(material) = materials.get_mut(id) {
+            material.emissive = emissive;
+        }
+    }
+    dj.pulse = 0.0;
+}
+
+fn collect_beats(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut dj: ResMut<DjMode>,
+    incoming: Res<IncomingPacket>,
+    mut beats: EventWriter<Beat>,
+    time: Res<Time>,
+) {
+    if !dj.active {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    if keys.just_pressed(KeyCode::KeyY) {
+        dj.tempo.tap(now);
+        dj.last_tempo_beat = dj.tempo.beat_index(now);
+        beats.send(Beat);
+    }
+    if incoming.0.as_ref().is_some_and(|p| p.beat) {
+        beats.send(Beat);
+    }
+    // タップしたテンポで拍を刻み続ける
+    let index = dj.tempo.beat_index(now);
+    if index.is_some() && index != dj.last_tempo_beat {
+        dj.last_tempo_beat = index;
+        beats.send(Beat);
+    }
+}
+
+// 手のひらの高さで環境光、つまみ具合でブルームを決める
+fn drive_aesthetics(
+    mut dj: ResMut<DjMode>,
+    settings: Res<Settings>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    mut ambient: ResMut<AmbientLight>,
+    mut blooms: Query<&mut BloomSettings>,
+    time: Res<Time>,
+) {
+    if !dj.active {
+        return;
+    }
+    let mapping = &settings.aesthetics;
+    let follow = (FOLLOW_RATE * time.delta_seconds()).min(1.0);
+
+    if let Some(height) = hand_states.hands.values().map(|h| h.center.y).reduce(f32::max) {
+        let target = mapping.ambient.apply(height);
+        dj.ambient += (target - dj.ambient) * follow;
+    }
+    ambient.brightness = dj.ambient;
+
+    let tips: HashMap<_, Vec3> = points
+        .iter()
+        .filter(|(p, t)| (p.id == 4 || p.id == 8) && t.translation.y > -50.0)
+        .map(|(p, t)| ((p.client, p.side, p.id), t.translation))
+        .collect();
+    let pinch = hand_states
+        .hands
+        .keys()
+        .filter_map(|(client, side)| Some(tips.get(&(*client, *side, 4))?.distance(*tips.get(&(*client, *side, 8))?)))
+        .reduce(f32::min);
+    let target = pinch.map_or(mapping.bloom.output.0, |d| mapping.bloom.apply(d));
+    dj.bloom += (target - dj.bloom) * follow;
+    for mut bloom in blooms.iter_mut() {
+        bloom.intensity = dj.bloom;
+    }
+}
+
+// 拳を握っている間は、拍ごとに小道具を光らせる
+fn pulse_props(
+    mut dj: ResMut<DjMode>,
+    settings: Res<Settings>,
+    hand_states: Res<HandStates>,
+    mut beats: EventReader<Beat>,
+    props: Query<&Handle<StandardMaterial>, With<SpawnedBox>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let beat = beats.read().count() > 0;
+    if !dj.active {
+        return;
+    }
+    let mapping = &settings.aesthetics;
+    if beat {
+        dj.beats += 1;
+        if hand_states.hands.values().any(|h| h.gesture == Gesture::Fist) {
+            dj.pulse = 1.0;
+        }
+    }
+    dj.pulse = (dj.pulse - mapping.pulse_decay * time.delta_seconds()).max(0.0);
+
+    let dj = &mut *dj;
+    for handle in props.iter() {
+        let Some(material) = materials.get_mut(handle) else {
+            continue;
+        };
+        let original = *dj.saved_emissive.entry(handle.id()).or_insert(material.emissive);
+        let glow = material.base_color.to_linear() * (dj.pulse * mapping.pulse_strength);
+        material.emissive = LinearRgba::rgb(
+            original.red + glow.red,
+            original.green + glow.green,
+            original.blue + glow.blue,
+        );
+    }
+}
+
+#[cfg(feature = "ui")]
+fn dj_hud(mut contexts: EguiContexts, dj: Res<DjMode>) {
+    if !dj.active {
+        return;
+    }
+    egui::Window::new("DJ")
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            match dj.tempo.bpm() {
+                Some(bpm) => ui.label(format!("Tempo: {bpm:.0} BPM  (beats {})", dj.beats)),
+                None => ui.label(format!("Tempo: tap Y  (beats {})", dj.beats)),
+            };
+            ui.label(format!("Ambient {:.0}  Bloom {:.2}", dj.ambient, dj.bloom));
+            ui.add(egui::ProgressBar::new(dj.pulse).text("Pulse"));
+            ui.small("Palm height: light / Pinch: bloom / Fist: pulse on beat / D: quit");
+        });
+}
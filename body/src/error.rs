@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+// 起動時や読み込みで起きた失敗。起動に要るものは main でメッセージを出して終了する
+#[derive(Debug)]
+pub enum AppError {
+    Bind { addr: String, source: io::Error },
+    Read { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, source: serde_json::Error },
+    EmptySecret { path: PathBuf },
+    BadArgument { name: &'static str, message: String },
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Bind { addr, source } if source.kind() == io::ErrorKind::AddrInUse => {
+                write!(f, "cannot listen on {addr}: the port is already in use (is another instance running?)")
+            }
+            AppError::Bind { addr, source } => write!(f, "cannot listen on {addr}: {source}"),
+            AppError::Read { path, source } => write!(f, "cannot read {}: {source}", path.display()),
+            AppError::Parse { path, source } => write!(f, "{} is not valid: {source}", path.display()),
+            AppError::EmptySecret { path } => write!(f, "secret file {} is empty", path.display()),
+            AppError::BadArgument { name, message } => write!(f, "invalid {name}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Bind { source, .. } | AppError::Read { source, .. } => Some(source),
+            AppError::Parse { source, .. } => Some(source),
+            AppError::EmptySecret { .. } | AppError::BadArgument { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthState {
+    Ok,
+    // 動いてはいるが一部が欠けている (既定値で代用した、音が無い、など)
+    Degraded(String),
+    // その機能は使えない
+    Failed(String),
+}
+
+// 各機能の調子。問題のあるものだけ画面の隅に出す
+#[derive(Resource, Default)]
+pub struct Health {
+    subsystems: BTreeMap<&'static str, HealthState>,
+}
+
+impl Health {
+    pub fn set(&mut self, subsystem: &'static str, state: HealthState) {
+        match &state {
+            HealthState::Ok => {}
+            HealthState::Degraded(message) => warn!("{subsystem}: {message}"),
+            HealthState::Failed(message) => error!("{subsystem}: {message}"),
+        }
+        self.subsystems.insert(subsystem, state);
+    }
+
+    // 毎フレーム呼ぶ側のために、状態の種類が変わったときだけログに出す。文言 (件数など) は黙って差し替える
+    pub fn update(&mut self, subsystem: &'static str, state: HealthState) {
+        match self.subsystems.get_mut(subsystem) {
+            Some(current) if std::mem::discriminant(current) == std::mem::discriminant(&state) => *current = state,
+            _ => self.set(subsystem, state),
+        }
+    }
+
+    pub fn ok(&mut self, subsystem: &'static str) {
+        self.update(subsystem, HealthState::Ok);
+    }
+
+    pub fn degraded(&mut self, subsystem: &'static str, message: impl Into<String>) {
+        self.update(subsystem, HealthState::Degraded(message.into()));
+    }
+
+    pub fn failed(&mut self, subsystem: &'static str, message: impl Into<String>) {
+        self.update(subsystem, HealthState::Failed(message.into()));
+    }
+
+    #[cfg(feature = "ui")]
+    pub fn problems(&self) -> impl Iterator<Item = (&'static str, &HealthState)> {
+        self.subsystems.iter().filter(|(_, s)| **s != HealthState::Ok).map(|(name, s)| (*name, s))
+    }
+}
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        // 他のプラグインが build の中で書き込めるよう、先に入っていても上書きしない
+        app.init_resource::<Health>();
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, health_overlay);
+    }
+}
+
+#[cfg(feature = "ui")]
+fn health_overlay(mut contexts: EguiContexts, health: Res<Health>) {
+    if health.problems().next().is_none() {
+        return;
+    }
+    egui::Area::new(egui::Id::new("health"))
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -8.0])
+        .show(contexts.ctx_mut(), |ui| {
+            for (subsystem, state) in health.problems() {
+                let (color, message) = match state {
+                    HealthState::Failed(message) => (egui::Color32::from_rgb(255, 90, 80), message),
+                    HealthState::Degraded(message) => (egui::Color32::from_rgb(255, 200, 60), message),
+                    HealthState::Ok => continue,
+                };
+                ui.label(egui::RichText::new(format!("{subsystem}: {message}")).color(color));
+            }
+        });
+}
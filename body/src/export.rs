@@ -0,0 +1,389 @@
+use bevy::prelude::*;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::topology::{MEDIAPIPE_CONNECTIONS, MEDIAPIPE_LANDMARKS, MEDIAPIPE_NAMES};
+use crate::{HandPoint, HandSide, HandStates};
+
+const SAMPLE_RATE: f32 = 30.0;
+const EXPORT_DIR: &str = "exports";
+// 骨は MediaPipe の 21 点だけで組む。トラッカーが出す追加の点は書き出さない
+const JOINT_COUNT: usize = MEDIAPIPE_LANDMARKS;
+
+type Pose = [Vec3; JOINT_COUNT];
+
+// MEDIAPIPE_CONNECTIONS を手首から幅優先でたどって得る骨の木
+pub struct Skeleton {
+    pub parents: [Option<usize>; JOINT_COUNT],
+    pub children: Vec<Vec<usize>>,
+}
+
+impl Skeleton {
+    pub fn from_connections(connections: &[(usize, usize)], root: usize) -> Self {
+        let mut parents = [None; JOINT_COUNT];
+        let mut children = vec![Vec::new(); JOINT_COUNT];
+        let mut visited = [false; JOINT_COUNT];
+        let mut queue = VecDeque::from([root]);
+        visited[root] = true;
+        while let Some(joint) = queue.pop_front() {
+            for &(a, b) in connections {
+                let next = if a == joint {
+                    b
+                } else if b == joint {
+                    a
+                } else {
+                    continue;
+                };
+                if !visited[next] {
+                    visited[next] = true;
+                    parents[next] = Some(joint);
+                    children[joint].push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Self { parents, children }
+    }
+
+    fn root(&self) -> usize {
+        (0..JOINT_COUNT).find(|&j| self.parents[j].is_none()).unwrap_or(0)
+    }
+
+    // 親が先に来る順序
+    fn order(&self) -> Vec<usize> {
+        let mut order = vec![self.root()];
+        let mut i = 0;
+        while i < order.len() {
+            order.extend(self.children[order[i]].iter().copied());
+            i += 1;
+        }
+        order
+    }
+
+    // 各関節のローカル回転 (レストポーズで単位回転)
+    pub fn solve_local_rotations(&self, rest: &Pose, pose: &Pose) -> [Quat; JOINT_COUNT] {
+        let mut global = [Quat::IDENTITY; JOINT_COUNT];
+        let mut local = [Quat::IDENTITY; JOINT_COUNT];
+
+        for joint in self.order() {
+            let parent_global = self.parents[joint].map(|p| global[p]).unwrap_or(Quat::IDENTITY);
+            let dir = |pose: &Pose, child: usize| (pose[child] - pose[joint]).normalize_or_zero();
+
+            global[joint] = match self.children[joint].as_slice() {
+                [] => parent_global,
+                [a] => {
+                    let from = parent_global * dir(rest, *a);
+                    let to = dir(pose, *a);
+                    if from == Vec3::ZERO || to == Vec3::ZERO {
+                        parent_global
+                    } else {
+                        Quat::from_rotation_arc(from, to) * parent_global
+                    }
+                }
+                [a, b, ..] => match (frame(dir(rest, *a), dir(rest, *b)), frame(dir(pose, *a), dir(pose, *b))) {
+                    (Some(rest_frame), Some(pose_frame)) => {
+                        Quat::from_mat3(&(pose_frame * rest_frame.transpose())).normalize()
+                    }
+                    _ => parent_global,
+                },
+            };
+            local[joint] = parent_global.inverse() * global[joint];
+        }
+        local
+    }
+}
+
+fn frame(a: Vec3, b: Vec3) -> Option<Mat3> {
+    let x = a.try_normalize()?;
+    let z = a.cross(b).try_normalize()?;
+    let y = z.cross(x);
+    Some(Mat3::from_cols(x, y, z))
+}
+
+#[derive(Default)]
+struct CaptureFrame {
+    right: Option<Pose>,
+    left: Option<Pose>,
+}
+
+#[derive(Resource, Default)]
+pub struct MotionCapture {
+    pub recording: bool,
+    frames: Vec<CaptureFrame>,
+    last_sample: f32,
+}
+
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MotionCapture::default())
+            .add_systems(Update, (toggle_capture, sample_capture).chain());
+    }
+}
+
+fn toggle_capture(keys: Res<ButtonInput<KeyCode>>, mut capture: ResMut<MotionCapture>) {
+    if !keys.just_pressed(KeyCode::F6) {
+        return;
+    }
+    if capture.recording {
+        capture.recording = false;
+        let frames = std::mem::take(&mut capture.frames);
+        match export_capture(&frames) {
+            Ok(paths) => {
+                for path in paths {
+                    info!("exported {}", path.display());
+                }
+            }
+            Err(e) => error!("failed to export capture: {e}"),
+        }
+    } else {
+        capture.recording = true;
+        capture.frames.clear();
+        capture.last_sample = f32::NEG_INFINITY;
+        info!("motion capture started");
+    }
+}
+
+// 書き出すのはローカルのクライアントの手だけ
+fn sample_capture(
+    mut capture: ResMut<MotionCapture>,
+    points: Query<(&HandPoint, &Transform)>,
+    hand_states: Res<HandStates>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    if !capture.recording || now - capture.last_sample < 1.0 / SAMPLE_RATE {
+        return;
+    }
+    capture.last_sample = now;
+
+    let pose = |side: HandSide| {
+        hand_states.get(side)?;
+        let mut pose = [Vec3::ZERO; JOINT_COUNT];
+        for (point, transform) in points.iter().filter(|(p, _)| p.client == 0 && p.side == side) {
+            if point.id < JOINT_COUNT {
+                pose[point.id] = transform.translation;
+            }
+        }
+        Some(pose)
+    };
+    let frame = CaptureFrame {
+        right: pose(HandSide::Right),
+        left: pose(HandSide::Left),
+    };
+    if frame.right.is_some() || frame.left.is_some() || !capture.frames.is_empty() {
+        capture.frames.push(frame);
+    }
+}
+
+// 見えていないフレームは直前 (先頭では最初に見えた) 姿勢で埋める
+fn fill_track(frames: &[CaptureFrame], side: HandSide) -> Option<Vec<Pose>> {
+    let pick = |f: &CaptureFrame| match side {
+        HandSide::Right => f.right,
+        HandSide::Left => f.left,
+    };
+    let mut last = frames.iter().find_map(pick)?;
+    Some(
+        frames
+            .iter()
+            .map(|f| {
+                if let Some(pose) = pick(f) {
+                    last = pose;
+                }
+                last
+            })
+            .collect(),
+    )
+}
+
+fn export_capture(frames: &[CaptureFrame]) -> std::io::Result<Vec<PathBuf>> {
+    let skeleton = Skeleton::from_connections(MEDIAPIPE_CONNECTIONS, 0);
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let dir = Path::new(EXPORT_DIR);
+    fs::create_dir_all(dir)?;
+
+    let tracks: Vec<(HandSide, Vec<Pose>)> = [HandSide::Right, HandSide::Left]
+        .into_iter()
+        .filter_map(|side| fill_track(frames, side).map(|t| (side, t)))
+        .collect();
+    if tracks.is_empty() {
+        return Err(std::io::Error::other("no hand motion captured"));
+    }
+
+    let mut written = Vec::new();
+    for (side, track) in &tracks {
+        let path = dir.join(format!("hand-{stamp}-{}.bvh", side.label().to_lowercase()));
+        fs::write(&path, write_bvh(&skeleton, track))?;
+        written.push(path);
+    }
+    let path = dir.join(format!("hand-{stamp}.gltf"));
+    fs::write(&path, write_gltf(&skeleton, &tracks).to_string())?;
+    written.push(path);
+    Ok(written)
+}
+
+pub fn write_bvh(skeleton: &Skeleton, track: &[Pose]) -> String {
+    let rest = &track[0];
+    let mut out = String::from("HIERARCHY\n");
+    let mut channel_order = Vec::new();
+
+    fn write_joint(
+        out: &mut String,
+        skeleton: &Skeleton,
+        rest: &Pose,
+        joint: usize,
+        depth: usize,
+        channel_order: &mut Vec<usize>,
+    ) {
+        let indent = "  ".repeat(depth);
+        channel_order.push(joint);
+        let offset = match skeleton.parents[joint] {
+            Some(parent) => rest[joint] - rest[parent],
+            None => Vec3::ZERO,
+        };
+        let (keyword, channels) = if skeleton.parents[joint].is_none() {
+            ("ROOT", "CHANNELS 6 Xposition Yposition Zposition Zrotation Xrotation Yrotation")
+        } else {
+            ("JOINT", "CHANNELS 3 Zrotation Xrotation Yrotation")
+        };
+        let _ = writeln!(out, "{indent}{keyword} {}", MEDIAPIPE_NAMES[joint]);
+        let _ = writeln!(out, "{indent}{{");
+        let _ = writeln!(out, "{indent}  OFFSET {:.6} {:.6} {:.6}", offset.x, offset.y, offset.z);
+        let _ = writeln!(out, "{indent}  {channels}");
+        if skeleton.children[joint].is_empty() {
+            let _ = writeln!(out, "{indent}  End Site");
+            let _ = writeln!(out, "{indent}  {{");
+            let _ = writeln!(out, "{indent}    OFFSET 0.000000 0.000000 0.000000");
+            let _ = writeln!(out, "{indent}  }}");
+        }
+        for &child in &skeleton.children[joint] {
+            write_joint(out, skeleton, rest, child, depth + 1, channel_order);
+        }
+        let _ = writeln!(out, "{indent}}}");
+    }
+    write_joint(&mut out, skeleton, rest, skeleton.root(), 0, &mut channel_order);
+
+    let _ = writeln!(out, "MOTION");
+    let _ = writeln!(out, "Frames: {}", track.len());
+    let _ = writeln!(out, "Frame Time: {:.6}", 1.0 / SAMPLE_RATE);
+    for pose in track {
+        let rotations = skeleton.solve_local_rotations(rest, pose);
+        let mut values = Vec::new();
+        for &joint in &channel_order {
+            if skeleton.parents[joint].is_none() {
+                values.extend(pose[joint].to_array());
+            }
+            let (z, x, y) = rotations[joint].to_euler(EulerRot::ZXY);
+            values.extend([z.to_degrees(), x.to_degrees(), y.to_degrees()]);
+        }
+        let line: Vec<String> = values.iter().map(|v| format!("{v:.4}")).collect();
+        let _ = writeln!(out, "{}", line.join(" "));
+    }
+    out
+}
+
+pub fn write_gltf(skeleton: &Skeleton, tracks: &[(HandSide, Vec<Pose>)]) -> serde_json::Value {
+    let frame_count = tracks[0].1.len();
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut accessors = Vec::new();
+    let mut push_accessor = |bytes: &mut Vec<u8>, data: &[f32], kind: &str, count: usize, extra: serde_json::Value| {
+        let offset = bytes.len();
+        for v in data {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        let mut accessor = json!({
+            "bufferView": 0,
+            "byteOffset": offset,
+            "componentType": 5126,
+            "count": count,
+            "type": kind,
+        });
+        if let (Some(a), Some(e)) = (accessor.as_object_mut(), extra.as_object()) {
+            a.extend(e.clone());
+        }
+        accessors.push(accessor);
+        accessors.len() - 1
+    };
+
+    let times: Vec<f32> = (0..frame_count).map(|i| i as f32 / SAMPLE_RATE).collect();
+    let time_max = times.last().copied().unwrap_or(0.0);
+    let time_accessor = push_accessor(&mut bytes, &times, "SCALAR", frame_count, json!({ "min": [0.0], "max": [time_max] }));
+
+    let mut nodes = Vec::new();
+    let mut roots = Vec::new();
+    let mut samplers = Vec::new();
+    let mut channels = Vec::new();
+
+    for (hand_index, (side, track)) in tracks.iter().enumerate() {
+        let base = hand_index * JOINT_COUNT;
+        let rest = &track[0];
+        let rotations: Vec<[Quat; JOINT_COUNT]> =
+            track.iter().map(|pose| skeleton.solve_local_rotations(rest, pose)).collect();
+
+        for joint in 0..JOINT_COUNT {
+            let translation = match skeleton.parents[joint] {
+                Some(parent) => rest[joint] - rest[parent],
+                None => rest[joint],
+            };
+            let children: Vec<usize> = skeleton.children[joint].iter().map(|c| base + c).collect();
+            let mut node = json!({
+                "name": format!("{}_{}", side.label(), MEDIAPIPE_NAMES[joint]),
+                "translation": translation.to_array(),
+            });
+            if !children.is_empty() {
+                node["children"] = json!(children);
+            }
+            nodes.push(node);
+
+            let data: Vec<f32> = rotations.iter().flat_map(|r| r[joint].to_array()).collect();
+            let output = push_accessor(&mut bytes, &data, "VEC4", frame_count, json!({}));
+            samplers.push(json!({ "input": time_accessor, "output": output, "interpolation": "LINEAR" }));
+            channels.push(json!({ "sampler": samplers.len() - 1, "target": { "node": base + joint, "path": "rotation" } }));
+        }
+
+        let root = skeleton.root();
+        let data: Vec<f32> = track.iter().flat_map(|pose| pose[root].to_array()).collect();
+        let output = push_accessor(&mut bytes, &data, "VEC3", frame_count, json!({}));
+        samplers.push(json!({ "input": time_accessor, "output": output, "interpolation": "LINEAR" }));
+        channels.push(json!({ "sampler": samplers.len() - 1, "target": { "node": base + root, "path": "translation" } }));
+        roots.push(base + root);
+    }
+
+    json!({
+        "asset": { "version": "2.0", "generator": "MasterHand" },
+        "scene": 0,
+        "scenes": [{ "nodes": roots }],
+        "nodes": nodes,
+        "animations": [{ "name": "HandCapture", "samplers": samplers, "channels": channels }],
+        "accessors": accessors,
+        "bufferViews": [{ "buffer": 0, "byteOffset": 0, "byteLength": bytes.len() }],
+        "buffers": [{
+            "byteLength": bytes.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64(&bytes)),
+        }],
+    })
+}
+
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(TABLE[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
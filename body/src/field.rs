@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+
+use crate::core::mapping::Region;
+
+// 手が作る力場。複数の場は CompositeField で足し合わせる
+pub trait ForceField: Send + Sync {
+    fn force_at(&self, position: Vec3) -> Vec3;
+}
+
+// 逆二乗で引き寄せる (strength が負なら反発する) 点
+#[derive(Debug, Clone, Copy)]
+pub struct Pole {
+    pub center: Vec3,
+    pub strength: f32,
+}
+
+impl ForceField for Pole {
+    fn force_at(&self, position: Vec3) -> Vec3 {
+        let dir = self.center - position;
+        let dist_sq = dir.length_squared().max(1.0);
+        dir.normalize_or_zero() * self.strength / dist_sq
+    }
+}
+
+// 反発する極から引き寄せる極へ向かう双極子
+#[derive(Debug, Clone, Copy)]
+pub struct Dipole {
+    pub source: Vec3,
+    pub sink: Vec3,
+    pub strength: f32,
+}
+
+impl Dipole {
+    fn poles(&self) -> [Pole; 2] {
+        [
+            Pole { center: self.sink, strength: self.strength },
+            Pole { center: self.source, strength: -self.strength },
+        ]
+    }
+
+    // source の周りから力の向きに沿ってたどった力線
+    pub fn field_lines(&self, count: usize, step: f32, max_steps: usize) -> Vec<Vec<Vec3>> {
+        let axis = (self.sink - self.source).normalize_or(Vec3::X);
+        let side = axis.any_orthonormal_vector();
+        let mut lines = Vec::with_capacity(count);
+        for i in 0..count {
+            let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+            let offset = Quat::from_axis_angle(axis, angle) * (side * 0.6 + axis * 0.3);
+            let mut point = self.source + offset;
+            let mut line = vec![point];
+            for _ in 0..max_steps {
+                let dir = self.force_at(point).normalize_or_zero();
+                if dir == Vec3::ZERO {
+                    break;
+                }
+                point += dir * step;
+                line.push(point);
+                if point.distance(self.sink) < step * 1.5 {
+                    break;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl ForceField for Dipole {
+    fn force_at(&self, position: Vec3) -> Vec3 {
+        self.poles().iter().map(|p| p.force_at(position)).sum()
+    }
+}
+
+// 場所によらない一定の力 (風)
+#[derive(Debug, Clone, Copy)]
+pub struct Uniform {
+    pub force: Vec3,
+}
+
+impl ForceField for Uniform {
+    fn force_at(&self, _position: Vec3) -> Vec3 {
+        self.force
+    }
+}
+
+// 手のひらの前の円錐の中だけを、手のひらから離れる向きに押す。近いほど強い
+#[derive(Debug, Clone, Copy)]
+pub struct Push {
+    pub origin: Vec3,
+    pub direction: Vec3,
+    pub strength: f32,
+    pub reach: f32,
+    // 円錐の半角の cos
+    pub spread: f32,
+}
+
+impl ForceField for Push {
+    fn force_at(&self, position: Vec3) -> Vec3 {
+        let offset = position - self.origin;
+        let distance = offset.length();
+        if distance >= self.reach || distance <= f32::EPSILON || offset.dot(self.direction) < distance * self.spread {
+            return Vec3::ZERO;
+        }
+        self.direction * self.strength * (1.0 - distance / self.reach)
+    }
+}
+
+#[derive(Default)]
+pub struct CompositeField {
+    fields: Vec<Box<dyn ForceField>>,
+}
+
+impl CompositeField {
+    pub fn add(&mut self, field: impl ForceField + 'static) {
+        self.fields.push(Box::new(field));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl ForceField for CompositeField {
+    fn force_at(&self, position: Vec3) -> Vec3 {
+        self.fields.iter().map(|f| f.force_at(position)).sum()
+    }
+}
+
+// 直近のパケットで各クライアントが作った場。分離モードではそのクライアントの領域の中だけで効く
+#[derive(Resource, Default)]
+pub struct ActiveFields {
+    fields: Vec<(CompositeField, Option<Region>)>,
+}
+
+impl ActiveFields {
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    pub fn push(&mut self, field: CompositeField, region: Option<Region>) {
+        if !field.is_empty() {
+            self.fields.push((field, region));
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl ForceField for ActiveFields {
+    fn force_at(&self, position: Vec3) -> Vec3 {
+        self.fields
+            .iter()
+            .filter(|(_, region)| region.is_none_or(|r| r.contains_x(position.x)))
+            .map(|(field, _)| field.force_at(position))
+            .sum()
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::field::{ActiveFields, ForceField};
+use crate::workspace::Workspaces;
+use crate::update_hands_and_physics;
+
+// 矢印を置く格子の間隔と、力線の種をまく格子の間隔
+const ARROW_SPACING: f32 = 3.0;
+const SEED_SPACING: f32 = 6.0;
+// これより弱い力は描かない
+const MIN_FORCE: f32 = 0.05;
+const MAX_ARROW: f32 = ARROW_SPACING * 0.8;
+// 矢印の長さと色はこの強さで頭打ちにする
+const FULL_FORCE: f32 = 50.0;
+const LINE_STEP: f32 = 0.5;
+const LINE_STEPS: usize = 40;
+
+// 今かかっている力場の見せ方
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldView {
+    #[default]
+    Off,
+    Arrows,
+    Streamlines,
+}
+
+impl FieldView {
+    pub fn next(self) -> Self {
+        match self {
+            FieldView::Off => FieldView::Arrows,
+            FieldView::Arrows => FieldView::Streamlines,
+            FieldView::Streamlines => FieldView::Off,
+        }
+    }
+}
+
+pub struct FieldViewPlugin;
+
+impl Plugin for FieldViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FieldView::default())
+            .add_systems(Update, (toggle_field_view, draw_field).chain().after(update_hands_and_physics));
+    }
+}
+
+fn toggle_field_view(keys: Res<ButtonInput<KeyCode>>, mut view: ResMut<FieldView>) {
+    if keys.just_pressed(KeyCode::KeyF) {
+        *view = view.next();
+        info!("force field view: {:?}", *view);
+    }
+}
+
+// 弱い力は青、強い力は赤
+fn strength_color(magnitude: f32) -> Color {
+    let t = (magnitude / FULL_FORCE).clamp(0.0, 1.0).sqrt();
+    Color::srgb(t, 0.3 * (1.0 - t), 1.0 - t)
+}
+
+// 作業空間をおおう格子点
+fn grid(workspaces: &Workspaces, spacing: f32) -> impl Iterator<Item = Vec3> {
+    let min = Vec3::from_array(workspaces.bounds.min);
+    let counts = ((Vec3::from_array(workspaces.bounds.max) - min) / spacing).floor().as_uvec3() + UVec3::ONE;
+    (0..counts.x).flat_map(move |x| {
+        (0..counts.y).flat_map(move |y| (0..counts.z).map(move |z| min + UVec3::new(x, y, z).as_vec3() * spacing))
+    })
+}
+
+fn draw_field(view: Res<FieldView>, fields: Res<ActiveFields>, workspaces: Res<Workspaces>, mut gizmos: Gizmos) {
+    if *view == FieldView::Off || fields.is_empty() {
+        return;
+    }
+    match *view {
+        FieldView::Arrows => {
+            for point in grid(&workspaces, ARROW_SPACING) {
+                let force = fields.force_at(point);
+                let magnitude = force.length();
+                if magnitude < MIN_FORCE {
+                    continue;
+                }
+                // 強さの差が大きいので、長さは対数的に伸ばす
+                let length = MAX_ARROW * (1.0 + magnitude).ln() / (1.0 + FULL_FORCE).ln();
+                gizmos.arrow(point, point + force / magnitude * length.min(MAX_ARROW), strength_color(magnitude));
+            }
+        }
+        FieldView::Streamlines => {
+            for seed in grid(&workspaces, SEED_SPACING) {
+                let mut point = seed;
+                let mut line = vec![point];
+                let mut magnitude = 0.0;
+                for _ in 0..LINE_STEPS {
+                    let force = fields.force_at(point);
+                    if force.length() < MIN_FORCE || !workspaces.bounds.contains(point) {
+                        break;
+                    }
+                    magnitude = force.length().max(magnitude);
+                    point += force.normalize() * LINE_STEP;
+                    line.push(point);
+                }
+                if line.len() > 2 {
+                    gizmos.linestrip(line, strength_color(magnitude));
+                }
+            }
+        }
+        FieldView::Off => {}
+    }
+}
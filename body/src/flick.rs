@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use crate::display::InteractionSet;
+use crate::grab::{surface_radius, Grabbable, Held};
+use crate::spawn::SpawnedBox;
+use crate::versus::{can_touch, Team};
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandStates};
+
+const INDEX_TIP: usize = 8;
+const PALM: usize = 9;
+// 指先の速度はこの時間幅の位置の差から求める
+const HISTORY_WINDOW: f32 = 0.08;
+// 手のひらに対する指先の速さがこれを超え、手のひら自体はあまり動いていなければ指で弾いたとみなす
+const FLICK_SPEED: f32 = 25.0;
+const MAX_PALM_SPEED: f32 = 10.0;
+// 弾ける物体の大きさ (中心から表面まで) の上限。大きな物は手で押す
+const MAX_FLICK_RADIUS: f32 = 1.0;
+const CONTACT_DISTANCE: f32 = 0.4;
+// 指先の速度のうち物体に渡す割合と、一度に与える速度変化の上限
+const TRANSFER: f32 = 0.6;
+const MAX_DELTA_V: f32 = 30.0;
+const COOLDOWN: f32 = 0.25;
+
+#[derive(Clone, Copy)]
+struct Sample {
+    time: f32,
+    tip: Vec3,
+    palm: Vec3,
+}
+
+#[derive(Resource, Default)]
+struct FingertipHistory {
+    samples: HashMap<HandKey, VecDeque<Sample>>,
+    last_flick: HashMap<HandKey, f32>,
+}
+
+impl FingertipHistory {
+    // 窓の両端の差から求めた (指先の速度, 手のひらの速度)
+    fn velocities(&self, key: HandKey) -> Option<(Vec3, Vec3)> {
+        let samples = self.samples.get(&key)?;
+        let (first, last) = (samples.front()?, samples.back()?);
+        let dt = last.time - first.time;
+        (dt > HISTORY_WINDOW * 0.5).then(|| ((last.tip - first.tip) / dt, (last.palm - first.palm) / dt))
+    }
+}
+
+pub struct FlickPlugin;
+
+impl Plugin for FlickPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FingertipHistory::default()).add_systems(
+            Update,
+            (read_masses, record_fingertips, flick_objects)
+                .chain()
+                .after(update_hands_and_physics)
+                .in_set(InteractionSet),
+        );
+    }
+}
+
+// 衝撃を質量に合わせるため、小さな物体の質量を物理エンジンから読めるようにする
+#[allow(clippy::type_complexity)]
+fn read_masses(
+    mut commands: Commands,
+    added: Query<Entity, (Or<(Added<SpawnedBox>, Added<Grabbable>)>, Without<ReadMassProperties>)>,
+) {
+    for entity in added.iter() {
+        commands.entity(entity).insert(ReadMassProperties::default());
+    }
+}
+
+fn record_fingertips(
+    mut history: ResMut<FingertipHistory>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let mut tips: HashMap<HandKey, (Option<Vec3>, Option<Vec3>)> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let entry = tips.entry((point.client, point.side)).or_default();
+        match point.id {
+            INDEX_TIP => entry.0 = Some(transform.translation),
+            PALM => entry.1 = Some(transform.translation),
+            _ => {}
+        }
+    }
+    for (key, (tip, palm)) in tips {
+        let (Some(tip), Some(palm)) = (tip, palm) else {
+            continue;
+        };
+        if !hand_states.hands.contains_key(&key) {
+            history.samples.remove(&key);
+            continue;
+        }
+        let samples = history.samples.entry(key).or_default();
+        samples.push_back(Sample { time: now, tip, palm });
+        while samples.front().is_some_and(|s| now - s.time > HISTORY_WINDOW) {
+            samples.pop_front();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn flick_objects(
+    mut commands: Commands,
+    mut history: ResMut<FingertipHistory>,
+    hand_states: Res<HandStates>,
+    objects: Query<
+        (Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, &ReadMassProperties, Option<&Team>),
+        (With<RigidBody>, Without<Held>),
+    >,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let keys: Vec<HandKey> = history.samples.keys().copied().collect();
+    for key in keys {
+        if !hand_states.is_confident(key) || history.last_flick.get(&key).is_some_and(|t| now - t < COOLDOWN) {
+            continue;
+        }
+        let Some((tip_velocity, palm_velocity)) = history.velocities(key) else {
+            continue;
+        };
+        // 手ごと押しているときは指先も手のひらと一緒に動くので、手のひらに対する速さで見る
+        let relative = tip_velocity - palm_velocity;
+        if relative.length() < FLICK_SPEED || palm_velocity.length() > MAX_PALM_SPEED {
+            continue;
+        }
+        let Some(tip) = history.samples.get(&key).and_then(|s| s.back()).map(|s| s.tip) else {
+            continue;
+        };
+
+        let touched = objects
+            .iter()
+            .filter(|(_, _, shape, mass, team)| {
+                surface_radius(*shape) <= MAX_FLICK_RADIUS && mass.mass > 0.0 && can_touch(*team, key.0)
+            })
+            .map(|(entity, transform, shape, mass, _)| {
+                let gap = transform.translation.distance(tip) - surface_radius(shape);
+                (entity, transform, shape, mass, gap)
+            })
+            .filter(|(.., gap)| *gap < CONTACT_DISTANCE)
+            .min_by(|a, b| a.4.total_cmp(&b.4));
+        let Some((entity, transform, shape, mass, _)) = touched else {
+            continue;
+        };
+
+        // 指先に一番近い表面の点を押す。中心からずれた点なら回転もかかる
+        let center_of_mass = transform.transform_point(mass.local_center_of_mass);
+        let contact = transform.translation + (tip - transform.translation).normalize_or_zero() * surface_radius(shape);
+        let delta_v = (relative * TRANSFER).clamp_length_max(MAX_DELTA_V);
+        commands
+            .entity(entity)
+            .insert(ExternalImpulse::at_point(delta_v * mass.mass, contact, center_of_mass));
+        history.last_flick.insert(key, now);
+        gizmos.arrow(contact, contact + delta_v.normalize_or_zero() * 2.0, Color::srgb(1.0, 0.9, 0.2));
+        debug!(client = key.0, side = key.1.label(), speed = relative.length(), "finger flick");
+    }
+    history.last_flick.retain(|_, t| now - *t < COOLDOWN);
+}
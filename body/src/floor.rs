@@ -0,0 +1,298 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::grab::{surface_radius, Grabbable};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::sound::SurfaceMaterial;
+use crate::spawn::SpawnedBox;
+use crate::HandStates;
+
+pub const FLOOR_Y: f32 = -5.0;
+pub const TILE_SIZE: f32 = 10.0;
+// カメラ・手・箱のまわりに敷くタイルの半径 (タイル数)
+const LOAD_RADIUS: i32 = 2;
+// 少し離れてもすぐには消さない。境目を行き来したときに作り直さないため
+const UNLOAD_RADIUS: i32 = LOAD_RADIUS + 1;
+// 床より下に落ちた箱のためにはタイルを敷かない
+const MIN_FOCUS_Y: f32 = FLOOR_Y - 2.0;
+// 開いた手のひらを水平にして床を叩くと床の材質が変わる
+const SLAP_SPEED: f32 = 15.0;
+const SLAP_HEIGHT: f32 = 3.0;
+const SLAP_FLATNESS: f32 = 0.7;
+const SLAP_COOLDOWN: f32 = 1.0;
+// 泥の上でこの高さより下にある物体は減衰を強める
+const MUD_DEPTH: f32 = 0.3;
+const MUD_DAMPING: f32 = 4.0;
+
+// 床の物理的な性質。見た目と Rapier の摩擦・反発をまとめて切り替える
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FloorSurface {
+    #[default]
+    Plain,
+    Ice,
+    Rubber,
+    Mud,
+}
+
+impl FloorSurface {
+    pub const ALL: [FloorSurface; 4] = [FloorSurface::Plain, FloorSurface::Ice, FloorSurface::Rubber, FloorSurface::Mud];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FloorSurface::Plain => "plain",
+            FloorSurface::Ice => "ice",
+            FloorSurface::Rubber => "rubber",
+            FloorSurface::Mud => "mud",
+        }
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(Self::ALL.iter().position(|s| *s == self).unwrap_or(0) + 1) % Self::ALL.len()]
+    }
+
+    // 物体側の値と平均すると性質が薄まるので、氷は小さい方、ゴムと泥は大きい方を使わせる
+    fn friction(self) -> Friction {
+        match self {
+            FloorSurface::Plain => Friction::default(),
+            FloorSurface::Ice => Friction { coefficient: 0.02, combine_rule: CoefficientCombineRule::Min },
+            FloorSurface::Rubber => Friction { coefficient: 1.2, combine_rule: CoefficientCombineRule::Max },
+            FloorSurface::Mud => Friction { coefficient: 1.5, combine_rule: CoefficientCombineRule::Max },
+        }
+    }
+
+    fn restitution(self) -> Restitution {
+        match self {
+            FloorSurface::Plain => Restitution::default(),
+            FloorSurface::Ice => Restitution::coefficient(0.05),
+            FloorSurface::Rubber => Restitution { coefficient: 0.9, combine_rule: CoefficientCombineRule::Max },
+            FloorSurface::Mud => Restitution { coefficient: 0.0, combine_rule: CoefficientCombineRule::Min },
+        }
+    }
+
+    fn sound(self) -> SurfaceMaterial {
+        match self {
+            FloorSurface::Plain | FloorSurface::Mud => SurfaceMaterial::Wood,
+            FloorSurface::Ice => SurfaceMaterial::Metal,
+            FloorSurface::Rubber => SurfaceMaterial::Rubber,
+        }
+    }
+
+    // 市松模様の明るい方の色
+    fn color(self) -> Color {
+        match self {
+            FloorSurface::Plain => Color::srgb(0.2, 0.2, 0.2),
+            FloorSurface::Ice => Color::srgb(0.7, 0.85, 0.95),
+            FloorSurface::Rubber => Color::srgb(0.55, 0.12, 0.12),
+            FloorSurface::Mud => Color::srgb(0.3, 0.2, 0.1),
+        }
+    }
+
+    fn roughness(self) -> f32 {
+        match self {
+            FloorSurface::Ice => 0.05,
+            FloorSurface::Mud => 1.0,
+            _ => 0.8,
+        }
+    }
+}
+
+// 泥に入る前の減衰。出たら戻す
+#[derive(Component)]
+struct InMud(Option<Damping>);
+
+#[derive(Component)]
+pub struct FloorTile;
+
+#[derive(Resource)]
+struct FloorAssets {
+    mesh: Handle<Mesh>,
+    // 材質ごとに市松模様にする 2 色
+    materials: HashMap<FloorSurface, [Handle<StandardMaterial>; 2]>,
+}
+
+#[derive(Resource, Default)]
+struct FloorTiles {
+    tiles: HashMap<IVec2, Entity>,
+    last_slap: f32,
+}
+
+pub struct FloorPlugin;
+
+impl Plugin for FloorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FloorTiles::default())
+            .insert_resource(FloorSurface::default())
+            .add_systems(Startup, setup_floor)
+            .add_systems(
+                Update,
+                (slap_to_switch.after(PalmPoseSet).in_set(InteractionSet), apply_surface, mud_damping).chain(),
+            )
+            .add_systems(PostUpdate, stream_tiles);
+    }
+}
+
+fn setup_floor(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let mut material = |surface: FloorSurface, shade: f32| {
+        let color = surface.color().to_srgba();
+        materials.add(StandardMaterial {
+            base_color: Color::srgb(color.red * shade, color.green * shade, color.blue * shade),
+            perceptual_roughness: surface.roughness(),
+            ..default()
+        })
+    };
+    let materials = FloorSurface::ALL.into_iter().map(|s| (s, [material(s, 1.0), material(s, 0.85)])).collect();
+    commands.insert_resource(FloorAssets {
+        mesh: meshes.add(Plane3d::default().mesh().size(TILE_SIZE, TILE_SIZE)),
+        materials,
+    });
+}
+
+fn tile_of(position: Vec3) -> IVec2 {
+    IVec2::new((position.x / TILE_SIZE).round() as i32, (position.z / TILE_SIZE).round() as i32)
+}
+
+fn stream_tiles(
+    mut commands: Commands,
+    assets: Option<Res<FloorAssets>>,
+    mut floor: ResMut<FloorTiles>,
+    surface: Res<FloorSurface>,
+    hand_states: Res<HandStates>,
+    cameras: Query<&Transform, With<Camera3d>>,
+    boxes: Query<&Transform, With<SpawnedBox>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    let focus: Vec<IVec2> = cameras
+        .iter()
+        .chain(boxes.iter())
+        .map(|t| t.translation)
+        .chain(hand_states.hands.values().map(|h| h.center))
+        .filter(|p| p.y > MIN_FOCUS_Y)
+        .map(tile_of)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let near = |tile: IVec2, radius: i32| {
+        focus.iter().any(|f| (tile.x - f.x).abs() <= radius && (tile.y - f.y).abs() <= radius)
+    };
+
+    floor.tiles.retain(|tile, entity| {
+        let keep = near(*tile, UNLOAD_RADIUS);
+        if !keep {
+            commands.entity(*entity).despawn_recursive();
+        }
+        keep
+    });
+
+    for center in &focus {
+        for dx in -LOAD_RADIUS..=LOAD_RADIUS {
+            for dz in -LOAD_RADIUS..=LOAD_RADIUS {
+                let tile = *center + IVec2::new(dx, dz);
+                if floor.tiles.contains_key(&tile) {
+                    continue;
+                }
+                let material = assets.materials[&*surface][((tile.x + tile.y) & 1) as usize].clone();
+                let entity = commands
+                    .spawn((
+                        PbrBundle {
+                            mesh: assets.mesh.clone(),
+                            material,
+                            transform: Transform::from_xyz(tile.x as f32 * TILE_SIZE, FLOOR_Y, tile.y as f32 * TILE_SIZE),
+                            ..default()
+                        },
+                        RigidBody::Fixed,
+                        Collider::cuboid(TILE_SIZE / 2.0, 0.01, TILE_SIZE / 2.0),
+                        surface.friction(),
+                        surface.restitution(),
+                        surface.sound(),
+                        FloorTile,
+                    ))
+                    .id();
+                floor.tiles.insert(tile, entity);
+            }
+        }
+    }
+}
+
+fn slap_to_switch(
+    mut floor: ResMut<FloorTiles>,
+    mut surface: ResMut<FloorSurface>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    if now - floor.last_slap < SLAP_COOLDOWN {
+        return;
+    }
+    // 手のひらの表裏は問わず、水平に構えて振り下ろしたら叩いたとみなす
+    let slapped = hand_states.hands.iter().filter(|(_, hand)| hand.gesture == Gesture::Open).any(|(key, _)| {
+        palms.poses.get(key).is_some_and(|palm| {
+            let normal = palm.orientation() * Vec3::Z;
+            palm.linear_velocity.y < -SLAP_SPEED
+                && palm.position().y < FLOOR_Y + SLAP_HEIGHT
+                && normal.dot(Vec3::Y).abs() > SLAP_FLATNESS
+        })
+    });
+    if slapped {
+        floor.last_slap = now;
+        *surface = surface.next();
+        info!("floor surface: {}", surface.label());
+    }
+}
+
+fn apply_surface(
+    surface: Res<FloorSurface>,
+    assets: Option<Res<FloorAssets>>,
+    floor: Res<FloorTiles>,
+    mut tiles: Query<(&mut Friction, &mut Restitution, &mut SurfaceMaterial, &mut Handle<StandardMaterial>), With<FloorTile>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    if !surface.is_changed() {
+        return;
+    }
+    for (tile, entity) in &floor.tiles {
+        if let Ok((mut friction, mut restitution, mut sound, mut material)) = tiles.get_mut(*entity) {
+            *friction = surface.friction();
+            *restitution = surface.restitution();
+            *sound = surface.sound();
+            *material = assets.materials[&*surface][((tile.x + tile.y) & 1) as usize].clone();
+        }
+    }
+}
+
+// 泥は床の性質では表せないので、床に接している物体の減衰を一時的に上げる
+#[allow(clippy::type_complexity)]
+fn mud_damping(
+    mut commands: Commands,
+    surface: Res<FloorSurface>,
+    bodies: Query<(Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, Option<&Damping>, Option<&InMud>)>,
+) {
+    for (entity, transform, shape, damping, in_mud) in bodies.iter() {
+        let bottom = transform.translation.y - surface_radius(shape);
+        let touching = *surface == FloorSurface::Mud && bottom < FLOOR_Y + MUD_DEPTH && bottom > FLOOR_Y - MUD_DEPTH;
+        match (touching, in_mud) {
+            (true, None) => {
+                commands.entity(entity).insert((
+                    InMud(damping.copied()),
+                    Damping { linear_damping: MUD_DAMPING, angular_damping: MUD_DAMPING },
+                ));
+            }
+            (false, Some(InMud(previous))) => {
+                let mut entity = commands.entity(entity);
+                entity.remove::<InMud>();
+                match previous {
+                    Some(previous) => entity.insert(*previous),
+                    None => entity.remove::<Damping>(),
+                };
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::core_pipeline::dof::{DepthOfFieldMode, DepthOfFieldSettings};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::focus::{choose_subject, pull_focus, Activity};
+use crate::grab::Held;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::settings::Settings;
+use crate::HandKey;
+
+// 動きの激しさをならす速さと、ピントを寄せる速さ (1/秒)
+const ACTIVITY_RATE: f32 = 3.0;
+const FOCUS_RATE: f32 = 4.0;
+// これ以上激しく動く手が出るまでピントを乗り換えない (ワールド単位/秒)
+const SWITCH_MARGIN: f32 = 4.0;
+// 背景 (空) をこの距離にあるものとしてぼかす
+const MAX_DEPTH: f32 = 80.0;
+
+#[derive(Default)]
+struct FocusState {
+    activity: HashMap<HandKey, Activity>,
+    subject: Option<HandKey>,
+    distance: Option<f32>,
+}
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, focus_on_active_hand.after(PalmPoseSet));
+    }
+}
+
+// 画面に写っている手のうち一番よく動いている手にピントを合わせる。その手が物体を掴んでいれば物体に合わせる
+fn focus_on_active_hand(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    palms: Res<PalmPoses>,
+    held: Query<(&Held, &GlobalTransform)>,
+    mut camera: Query<(Entity, &Camera, &GlobalTransform, Option<&mut DepthOfFieldSettings>), With<Camera3d>>,
+    mut state: Local<FocusState>,
+    time: Res<Time>,
+) {
+    let Ok((entity, camera, camera_transform, dof)) = camera.get_single_mut() else {
+        return;
+    };
+    let render = settings.render;
+    if !render.depth_of_field {
+        if dof.is_some() {
+            commands.entity(entity).remove::<DepthOfFieldSettings>();
+        }
+        *state = FocusState::default();
+        return;
+    }
+    let dt = time.delta_seconds();
+    let state = &mut *state;
+    state.activity.retain(|key, _| palms.poses.contains_key(key));
+    for (key, palm) in &palms.poses {
+        let speed = palm.linear_velocity.length();
+        let spin = palm.angular_velocity.length();
+        state.activity.entry(*key).or_default().update(speed, spin, dt, ACTIVITY_RATE);
+    }
+    let target_of = |key: HandKey| {
+        let object = held.iter().find(|(h, _)| h.by == key).map(|(_, t)| t.translation());
+        object.or(palms.poses.get(&key).map(|p| p.position()))
+    };
+    // 視錐台の外にある手は選ばない
+    let visible = |position: Vec3| {
+        camera
+            .world_to_ndc(camera_transform, position)
+            .is_some_and(|ndc| ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && (0.0..=1.0).contains(&ndc.z))
+    };
+    let candidates: Vec<(HandKey, f32)> = state
+        .activity
+        .iter()
+        .filter(|(key, _)| target_of(**key).is_some_and(visible))
+        .map(|(key, activity)| (*key, activity.level()))
+        .collect();
+    state.subject = choose_subject(state.subject, &candidates, SWITCH_MARGIN);
+    // 誰も写っていなければ最後のピントのまま
+    if let Some(target) = state.subject.and_then(target_of) {
+        let depth = (target - camera_transform.translation()).dot(*camera_transform.forward()).max(0.1);
+        let distance = state.distance.map_or(depth, |d| pull_focus(d, depth, dt, FOCUS_RATE));
+        state.distance = Some(distance);
+    }
+    let focal_distance = state.distance.unwrap_or(DepthOfFieldSettings::default().focal_distance);
+    match dof {
+        Some(mut dof) => {
+            dof.focal_distance = focal_distance;
+            dof.aperture_f_stops = render.aperture_f_stops;
+        }
+        None => {
+            commands.entity(entity).insert(DepthOfFieldSettings {
+                mode: DepthOfFieldMode::Bokeh,
+                focal_distance,
+                aperture_f_stops: render.aperture_f_stops,
+                max_depth: MAX_DEPTH,
+                ..default()
+            });
+        }
+    }
+}
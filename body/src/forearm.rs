@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::forearm::ForearmCalibration;
+use crate::core::mapping::depth_from_size;
+use crate::packet::OneHand;
+use crate::{hand_color, update_hands_and_physics, HandKey, HandMaterials, HandPoint, HandStates};
+
+const WRIST: usize = 0;
+const FOREARM_RADIUS: f32 = 0.6;
+const FOREARM_ALPHA: f32 = 0.35;
+// 肘の推定は手より粗いので、前のフレームの向きとならす
+const ELBOW_BLEND: f32 = 0.3;
+
+#[derive(Default)]
+struct Forearm {
+    calibration: ForearmCalibration,
+    occluded: bool,
+    // 手首から肘へのずれ (ワールド座標)
+    offset: Option<Vec3>,
+}
+
+// 姿勢推定モデルから肘が届いている手の前腕。指が隠れたときの奥行きと向きの手がかりにする
+#[derive(Resource, Default)]
+pub struct Forearms {
+    arms: HashMap<HandKey, Forearm>,
+}
+
+impl Forearms {
+    // 肘が届いていれば前腕で補正した奥行きを返す。届いていなければ None で、ランドマークだけで推定する
+    pub fn hand_depth(&mut self, key: HandKey, hand: &OneHand) -> Option<f32> {
+        let arm = self.arms.entry(key).or_default();
+        let Some(elbow) = &hand.elbow else {
+            arm.occluded = false;
+            arm.offset = None;
+            return None;
+        };
+        let estimate = arm.calibration.hand_size(&hand.landmarks, elbow)?;
+        arm.occluded = estimate.occluded;
+        Some(depth_from_size(estimate.size))
+    }
+
+    pub fn set_elbow(&mut self, key: HandKey, offset: Vec3) {
+        let arm = self.arms.entry(key).or_default();
+        arm.offset = Some(arm.offset.map_or(offset, |previous| previous.lerp(offset, ELBOW_BLEND)));
+    }
+
+    pub fn is_occluded(&self, key: HandKey) -> bool {
+        self.arms.get(&key).is_some_and(|arm| arm.occluded)
+    }
+
+    // 肘から手首への向き
+    pub fn direction(&self, key: HandKey) -> Option<Vec3> {
+        let offset = self.arms.get(&key)?.offset?;
+        Some(-offset.normalize_or_zero()).filter(|d| *d != Vec3::ZERO)
+    }
+}
+
+#[derive(Component)]
+struct ForearmCapsule(HandKey);
+
+pub struct ForearmPlugin;
+
+impl Plugin for ForearmPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Forearms::default())
+            .add_systems(Update, (spawn_capsules, update_capsules).chain().after(update_hands_and_physics));
+    }
+}
+
+fn spawn_capsules(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hand_mats: Res<HandMaterials>,
+    existing: Query<&ForearmCapsule>,
+) {
+    if !hand_mats.is_changed() {
+        return;
+    }
+    // y 方向に伸ばして前腕の長さに合わせる
+    let mesh = meshes.add(Capsule3d::new(FOREARM_RADIUS, 1.0));
+    for &key in hand_mats.materials.keys() {
+        if existing.iter().any(|c| c.0 == key) {
+            continue;
+        }
+        let material = materials.add(StandardMaterial {
+            base_color: hand_color(key.0, key.1).with_alpha(FOREARM_ALPHA),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        });
+        commands.spawn((
+            PbrBundle { mesh: mesh.clone(), material, visibility: Visibility::Hidden, ..default() },
+            ForearmCapsule(key),
+        ));
+    }
+}
+
+fn update_capsules(
+    mut forearms: ResMut<Forearms>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform), Without<ForearmCapsule>>,
+    mut capsules: Query<(&ForearmCapsule, &mut Transform, &mut Visibility)>,
+) {
+    forearms.arms.retain(|key, _| hand_states.hands.contains_key(key));
+    let wrists: HashMap<HandKey, Vec3> = points
+        .iter()
+        .filter(|(point, _)| point.id == WRIST)
+        .map(|(point, transform)| ((point.client, point.side), transform.translation))
+        .collect();
+    for (capsule, mut transform, mut visibility) in capsules.iter_mut() {
+        let arm = forearms.arms.get(&capsule.0).and_then(|arm| arm.offset);
+        let (Some(offset), Some(wrist)) = (arm, wrists.get(&capsule.0)) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        // 端の半球も含めて手首から肘までの長さに合わせる
+        let stretch = offset.length() / (1.0 + FOREARM_RADIUS * 2.0);
+        *transform = Transform::from_translation(*wrist + offset * 0.5)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, offset.normalize_or(Vec3::Y)))
+            .with_scale(Vec3::new(1.0, stretch.max(0.01), 1.0));
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
@@ -0,0 +1,180 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::{HandKey, HandStates};
+
+// 手前の手が押し当てられる位置に壁を立てる
+const WALL_Z: f32 = 9.0;
+const WALL_HALF_EXTENTS: Vec3 = Vec3::new(10.0, 7.5, 0.25);
+const WALL_CENTER_Y: f32 = 2.5;
+const PANEL_HALF_EXTENTS: Vec3 = Vec3::new(1.5, 1.5, 0.2);
+const PANEL_POSITIONS: [Vec2; 2] = [Vec2::new(-4.0, 1.0), Vec2::new(4.0, 4.0)];
+// 手のひらが板の表面からこの距離以内で、板とほぼ平行なら貼りつく
+const CONTACT_DISTANCE: f32 = 1.2;
+const MIN_FACING: f32 = 0.5;
+
+#[derive(Resource, Default)]
+pub struct Gecko {
+    pub active: bool,
+}
+
+// 壁に取りつけた板。壁に沿ってだけ動ける
+#[derive(Component)]
+pub struct WallProp;
+
+#[derive(Component)]
+struct Wall;
+
+// 手のひらに追従する運動学的な剛体。ジョイントの親になる
+#[derive(Component)]
+struct PalmAnchor(HandKey);
+
+// 手のひらに貼りついている板
+#[derive(Component)]
+struct Stuck {
+    by: HandKey,
+}
+
+pub struct GeckoPlugin;
+
+impl Plugin for GeckoPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Gecko::default()).add_systems(
+            Update,
+            (toggle_gecko, (move_anchors, stick_and_release).chain().in_set(InteractionSet)).chain().after(PalmPoseSet),
+        );
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_gecko(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut gecko: ResMut<Gecko>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    existing: Query<Entity, Or<(With<Wall>, With<WallProp>, With<PalmAnchor>)>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyK) {
+        return;
+    }
+    gecko.active = !gecko.active;
+    info!("gecko mode: {}", gecko.active);
+    if !gecko.active {
+        for entity in existing.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::from_size(WALL_HALF_EXTENTS * 2.0)),
+            material: materials.add(Color::srgb(0.5, 0.55, 0.5)),
+            transform: Transform::from_xyz(0.0, WALL_CENTER_Y, WALL_Z),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid(WALL_HALF_EXTENTS.x, WALL_HALF_EXTENTS.y, WALL_HALF_EXTENTS.z),
+        Wall,
+    ));
+
+    let panel_z = WALL_Z + WALL_HALF_EXTENTS.z + PANEL_HALF_EXTENTS.z + 0.05;
+    let mesh = meshes.add(Cuboid::from_size(PANEL_HALF_EXTENTS * 2.0));
+    let material = materials.add(Color::srgb(0.3, 0.8, 0.45));
+    for position in PANEL_POSITIONS {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_xyz(position.x, position.y, panel_z),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            Collider::cuboid(PANEL_HALF_EXTENTS.x, PANEL_HALF_EXTENTS.y, PANEL_HALF_EXTENTS.z),
+            // 壁の面から離れず、傾かない。手を離した位置にとどまる
+            LockedAxes::TRANSLATION_LOCKED_Z | LockedAxes::ROTATION_LOCKED,
+            GravityScale(0.0),
+            Damping { linear_damping: 4.0, angular_damping: 0.0 },
+            WallProp,
+        ));
+    }
+}
+
+fn move_anchors(
+    mut commands: Commands,
+    gecko: Res<Gecko>,
+    palms: Res<PalmPoses>,
+    mut anchors: Query<(&PalmAnchor, &mut Transform)>,
+) {
+    if !gecko.active {
+        return;
+    }
+    for (anchor, mut transform) in anchors.iter_mut() {
+        if let Some(pose) = palms.poses.get(&anchor.0) {
+            transform.translation = pose.position();
+        }
+    }
+    for (key, pose) in &palms.poses {
+        if !anchors.iter().any(|(anchor, _)| anchor.0 == *key) {
+            commands.spawn((
+                TransformBundle::from_transform(Transform::from_translation(pose.position())),
+                RigidBody::KinematicPositionBased,
+                PalmAnchor(*key),
+            ));
+        }
+    }
+}
+
+// 開いた手のひらが板に触れたら、触れた点で手のひらと板を固定ジョイントでつなぐ。
+// 手を閉じるか見失ったらジョイントを外す
+fn stick_and_release(
+    mut commands: Commands,
+    gecko: Res<Gecko>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    anchors: Query<(Entity, &PalmAnchor)>,
+    props: Query<(Entity, &Transform, Option<&Stuck>), With<WallProp>>,
+) {
+    if !gecko.active {
+        return;
+    }
+    let is_open = |key: &HandKey| hand_states.hands.get(key).is_some_and(|h| h.gesture == Gesture::Open);
+
+    for (entity, _, stuck) in props.iter() {
+        if let Some(stuck) = stuck
+            && (!is_open(&stuck.by) || !palms.poses.contains_key(&stuck.by))
+        {
+            commands.entity(entity).remove::<(ImpulseJoint, Stuck)>();
+        }
+    }
+
+    for (anchor_entity, anchor) in anchors.iter() {
+        let key = anchor.0;
+        let Some(pose) = palms.poses.get(&key) else {
+            continue;
+        };
+        if !is_open(&key) || props.iter().any(|(_, _, stuck)| stuck.is_some_and(|s| s.by == key)) {
+            continue;
+        }
+        let palm = pose.position();
+        let facing = (pose.orientation() * Vec3::Z).dot(Vec3::Z).abs();
+        let touched = props.iter().find(|(_, transform, stuck)| {
+            let local = palm - transform.translation;
+            stuck.is_none()
+                && facing > MIN_FACING
+                && local.x.abs() <= PANEL_HALF_EXTENTS.x
+                && local.y.abs() <= PANEL_HALF_EXTENTS.y
+                && (local.z - PANEL_HALF_EXTENTS.z).abs() < CONTACT_DISTANCE
+        });
+        if let Some((prop, transform, _)) = touched {
+            // 触れた点を板側のアンカーにするので、貼りついた瞬間に板は動かない
+            let joint = FixedJointBuilder::new().local_anchor2(palm - transform.translation);
+            commands.entity(prop).insert((ImpulseJoint::new(anchor_entity, joint), Stuck { by: key }));
+            info!(client = key.0, side = key.1.label(), "palm stuck to wall prop");
+        }
+    }
+}
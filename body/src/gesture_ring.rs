@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use crate::classifier::ActiveClassifier;
+#[cfg(feature = "ui")]
+use crate::display::world_to_window;
+use crate::settings::Settings;
+use crate::{hand_color, update_hands_and_physics, HandKey, HandStates};
+
+// 手の中心からの輪の半径と、区切りの隙間 (ラジアン)
+const RING_RADIUS: f32 = 2.2;
+const SEGMENT_GAP: f32 = 0.12;
+const SEGMENT_POINTS: usize = 12;
+// 表示する確率を新しい値に寄せる速さ (1/秒)。ちらつきを抑える
+const FADE_RATE: f32 = 10.0;
+
+// 手とラベルごとの、ならした確率
+#[derive(Resource, Default)]
+struct RingLevels {
+    levels: HashMap<HandKey, Vec<(String, f32)>>,
+}
+
+pub struct GestureRingPlugin;
+
+impl Plugin for GestureRingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RingLevels::default())
+            .add_systems(Update, draw_gesture_rings.after(update_hands_and_physics));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, label_gesture_rings.after(draw_gesture_rings));
+    }
+}
+
+// カメラから見た輪の上の点。角度 0 が真上で、時計回りに進む
+fn ring_point(center: Vec3, camera: &GlobalTransform, angle: f32, radius: f32) -> Vec3 {
+    let (right, up) = (*camera.right(), *camera.up());
+    center + (up * angle.cos() + right * angle.sin()) * radius
+}
+
+fn segment_span(index: usize, count: usize) -> (f32, f32) {
+    let width = TAU / count as f32;
+    (index as f32 * width + SEGMENT_GAP / 2.0, width - SEGMENT_GAP)
+}
+
+// 分類器が出したジェスチャーごとの確率を、手を囲む輪の区切りの明るさで見せる。一番高いものは外側に線を足す
+fn draw_gesture_rings(
+    settings: Res<Settings>,
+    classifier: Res<ActiveClassifier>,
+    hand_states: Res<HandStates>,
+    camera: Query<&GlobalTransform, With<Camera3d>>,
+    mut levels: ResMut<RingLevels>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    if !settings.display.gesture_ring {
+        levels.levels.clear();
+        return;
+    }
+    let Ok(camera) = camera.get_single() else {
+        return;
+    };
+    let blend = 1.0 - (-FADE_RATE * time.delta_seconds()).exp();
+    levels.levels.retain(|key, _| hand_states.hands.contains_key(key));
+    for (&key, hand) in &hand_states.hands {
+        let Some(probabilities) = classifier.probabilities(key) else {
+            continue;
+        };
+        let shown = levels.levels.entry(key).or_default();
+        if shown.len() != probabilities.len() || shown.iter().zip(probabilities).any(|(a, b)| a.0 != b.0) {
+            *shown = probabilities.to_vec();
+        }
+        for ((_, level), (_, p)) in shown.iter_mut().zip(probabilities) {
+            *level += (p - *level) * blend;
+        }
+        let best = shown.iter().enumerate().max_by(|a, b| a.1 .1.total_cmp(&b.1 .1)).map(|(i, _)| i);
+        let lit = hand_color(key.0, key.1);
+        for (index, (_, level)) in shown.iter().enumerate() {
+            let (start, sweep) = segment_span(index, shown.len());
+            let color = Color::srgba(0.6, 0.6, 0.6, 0.15).mix(&lit, level.clamp(0.0, 1.0));
+            let arc = |radius: f32| {
+                (0..=SEGMENT_POINTS).map(move |i| {
+                    let angle = start + sweep * i as f32 / SEGMENT_POINTS as f32;
+                    ring_point(hand.center, camera, angle, radius)
+                })
+            };
+            gizmos.linestrip(arc(RING_RADIUS), color);
+            if Some(index) == best {
+                gizmos.linestrip(arc(RING_RADIUS * 1.06), color);
+            }
+        }
+    }
+}
+
+// 区切りの外側にラベルを出す
+#[cfg(feature = "ui")]
+fn label_gesture_rings(
+    mut contexts: EguiContexts,
+    levels: Res<RingLevels>,
+    hand_states: Res<HandStates>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let (Ok((camera, camera_transform)), Ok(window)) = (camera.get_single(), windows.get_single()) else {
+        return;
+    };
+    let ctx = contexts.ctx_mut();
+    for (key, shown) in &levels.levels {
+        let Some(hand) = hand_states.hands.get(key) else {
+            continue;
+        };
+        for (index, (label, level)) in shown.iter().enumerate() {
+            let (start, sweep) = segment_span(index, shown.len());
+            let position = ring_point(hand.center, camera_transform, start + sweep / 2.0, RING_RADIUS * 1.25);
+            let Some(screen) = world_to_window(camera, camera_transform, window, position) else {
+                continue;
+            };
+            let alpha = (80.0 + level.clamp(0.0, 1.0) * 175.0) as u8;
+            egui::Area::new(egui::Id::new(("gesture_ring", *key, index)))
+                .fixed_pos(egui::pos2(screen.x, screen.y))
+                .pivot(egui::Align2::CENTER_CENTER)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    let color = egui::Color32::from_rgba_unmultiplied(230, 230, 230, alpha);
+                    ui.label(egui::RichText::new(format!("{label} {:.0}%", level * 100.0)).small().color(color));
+                });
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::core::mapping::map_hand;
+use crate::reach::ActiveReach;
+use crate::replay::ReplaySource;
+#[cfg(feature = "ui")]
+use crate::replay::{MAX_SPEED, MIN_SPEED};
+use crate::topology::Topology;
+use crate::workspace::Workspaces;
+use crate::{draw_hand_skeleton, update_hands_and_physics, HandPoint, HandSide, HandStates};
+
+// この時間より長く手が見えなかったあとに手が映ったら、新しい一回とみなす
+const ABSENT_RESET: f32 = 1.0;
+const GHOST_RADIUS: f32 = 0.12;
+
+#[derive(Component)]
+struct GhostPoint {
+    id: usize,
+    side: HandSide,
+}
+
+// 記録した一回分を、ライブの手とは別の半透明の手で重ねて再生する
+#[derive(Resource)]
+pub struct Ghost {
+    source: ReplaySource,
+    pub visible: bool,
+    // 手が映ったときにゴーストも先頭から再生し、時間をそろえる
+    pub align_with_live: bool,
+    last_live: f32,
+    // ライブとゴーストの対応する関節の平均距離。両方の手がそろわなければ None
+    deviation: Option<f32>,
+}
+
+pub struct GhostPlugin {
+    pub path: Option<PathBuf>,
+}
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let source = match ReplaySource::load(path) {
+            Ok(source) => source,
+            Err(e) => {
+                error!("failed to load ghost {}: {e}", path.display());
+                return;
+            }
+        };
+        info!("ghost of {} frames from {}", source.frames.len(), path.display());
+        app.insert_resource(Ghost {
+            source,
+            visible: true,
+            align_with_live: true,
+            last_live: f32::NEG_INFINITY,
+            deviation: None,
+        })
+        .add_systems(Startup, spawn_ghost_rig)
+        .add_systems(Update, (ghost_controls, advance_ghost, pose_ghost).chain().after(update_hands_and_physics));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, ghost_ui);
+    }
+}
+
+fn spawn_ghost_rig(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    topology: Res<Topology>,
+) {
+    let mesh = meshes.add(Sphere::new(GHOST_RADIUS));
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.85, 0.9, 1.0, 0.3),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    });
+    // 当たり判定は付けない。物体にはライブの手だけが触れる
+    for side in [HandSide::Right, HandSide::Left] {
+        for id in 0..topology.0.landmarks {
+            commands.spawn((
+                PbrBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                GhostPoint { id, side },
+            ));
+        }
+    }
+}
+
+fn ghost_controls(keys: Res<ButtonInput<KeyCode>>, mut ghost: ResMut<Ghost>) {
+    if keys.just_pressed(KeyCode::KeyO) {
+        ghost.source.restart();
+        info!("ghost restarted");
+    }
+}
+
+fn advance_ghost(mut ghost: ResMut<Ghost>, hand_states: Res<HandStates>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    // 手本はローカルのクライアントに合わせて流す
+    let live = [HandSide::Right, HandSide::Left].iter().any(|side| hand_states.get(*side).is_some());
+    if live {
+        if ghost.align_with_live && now - ghost.last_live > ABSENT_RESET {
+            ghost.source.restart();
+        }
+        ghost.last_live = now;
+    }
+    ghost.source.advance(time.delta_seconds());
+}
+
+fn pose_ghost(
+    mut ghost: ResMut<Ghost>,
+    topology: Res<Topology>,
+    reach: Res<ActiveReach>,
+    workspaces: Res<Workspaces>,
+    live_points: Query<(&HandPoint, &Transform), Without<GhostPoint>>,
+    mut ghost_points: Query<(&GhostPoint, &mut Transform, &mut Visibility)>,
+    mut gizmos: Gizmos,
+) {
+    let frame = ghost.source.frame_index_at(ghost.source.position).map(|i| &ghost.source.frames[i].packet);
+    // 記録はローカルのクライアントのものなので、その作業領域に写す
+    let region = workspaces.region(0);
+    let mut positions: HashMap<(HandSide, usize), Vec3> = HashMap::new();
+    if ghost.visible
+        && let Some(packet) = frame
+    {
+        for side in [HandSide::Right, HandSide::Left] {
+            if let Some(hand) = packet.hands.iter().find(|h| h.label == side.label()) {
+                for (id, position) in map_hand(&hand.landmarks, &region, &reach.0) {
+                    positions.insert((side, id), position);
+                }
+            }
+        }
+    }
+
+    for (point, mut transform, mut visibility) in ghost_points.iter_mut() {
+        match positions.get(&(point.side, point.id)) {
+            Some(position) => {
+                transform.translation = *position;
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for side in [HandSide::Right, HandSide::Left] {
+        let color = Color::srgba(0.85, 0.9, 1.0, 0.5);
+        draw_hand_skeleton(&mut gizmos, &topology.0.connections, |id| positions.get(&(side, id)).copied(), color);
+    }
+
+    let distances: Vec<f32> = live_points
+        .iter()
+        .filter(|(point, _)| point.client == 0)
+        .filter_map(|(point, transform)| {
+            positions.get(&(point.side, point.id)).map(|ghost| ghost.distance(transform.translation))
+        })
+        .collect();
+    ghost.deviation = (!distances.is_empty()).then(|| distances.iter().sum::<f32>() / distances.len() as f32);
+}
+
+#[cfg(feature = "ui")]
+fn ghost_ui(mut contexts: EguiContexts, mut ghost: ResMut<Ghost>) {
+    let ghost = &mut *ghost;
+    egui::Window::new("Ghost")
+        .anchor(egui::Align2::LEFT_BOTTOM, [8.0, -8.0])
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let source = &mut ghost.source;
+            ui.label(format!("{:.2}s / {:.2}s", source.position - source.start(), source.end() - source.start()));
+            ui.horizontal(|ui| {
+                if ui.button("Restart").clicked() {
+                    source.restart();
+                }
+                let label = if source.playing { "⏸" } else { "▶" };
+                if ui.button(label).clicked() {
+                    source.playing = !source.playing;
+                }
+                ui.checkbox(&mut source.looping, "Loop");
+            });
+            ui.add(egui::Slider::new(&mut source.speed, MIN_SPEED..=MAX_SPEED).logarithmic(true).text("Speed"));
+            ui.checkbox(&mut ghost.visible, "Show");
+            ui.checkbox(&mut ghost.align_with_live, "Start with my hand");
+            match ghost.deviation {
+                Some(distance) => ui.label(format!("Average offset {distance:.2}")),
+                None => ui.label("Average offset n/a"),
+            };
+            ui.small("O: restart");
+        });
+}
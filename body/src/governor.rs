@@ -0,0 +1,312 @@
+#[cfg(feature = "ui")]
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::grab::Held;
+use crate::spawn::{SpawnBudget, SpawnedBox};
+use crate::HandPoint;
+
+// 60fps のフレーム 16.6ms のうち物理に割り当てる時間
+const REDUCED_STEP_MS: f32 = 6.0;
+const CRITICAL_STEP_MS: f32 = 10.0;
+const RECOVER_STEP_MS: f32 = 4.0;
+const RECOVER_DELAY: f32 = 2.0;
+const MAX_BODIES: usize = 200;
+
+const SLEEP_DISTANCE: f32 = 8.0;
+const SLEEP_SPEED: f32 = 0.5;
+const SETTLE_SPEED: f32 = 0.05;
+const SETTLE_TIME: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum LoadLevel {
+    #[default]
+    Normal,
+    Reduced,
+    Critical,
+}
+
+#[derive(Resource, Default)]
+pub struct PhysicsGovernor {
+    pub enabled: bool,
+    pub step_ms: f32,
+    pub level: LoadLevel,
+    pub body_count: usize,
+    pub sleeping_count: usize,
+    pub merged_stacks: usize,
+    pub show_overlay: bool,
+    step_started: Option<Instant>,
+    calm_since: f32,
+}
+
+// 統合されたスタック。メンバーは剛体を外した見た目だけのエンティティとして残す
+#[derive(Component)]
+pub struct MergedStack {
+    pub members: Vec<Entity>,
+}
+
+#[derive(Component)]
+pub struct Merged;
+
+pub struct GovernorPlugin;
+
+impl Plugin for GovernorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.insert_resource(PhysicsGovernor {
+            enabled: true,
+            show_overlay: true,
+            ..default()
+        })
+        .add_systems(PostUpdate, begin_step_timer.before(PhysicsSet::StepSimulation))
+        .add_systems(PostUpdate, end_step_timer.after(PhysicsSet::StepSimulation))
+        .add_systems(
+            Update,
+            (
+                update_load_level,
+                throttle_spawns,
+                sleep_distant_bodies,
+                merge_settled_stacks,
+                unmerge_stacks,
+            )
+                .chain(),
+        );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, governor_overlay);
+    }
+}
+
+fn begin_step_timer(mut governor: ResMut<PhysicsGovernor>) {
+    governor.step_started = Some(Instant::now());
+}
+
+fn end_step_timer(mut governor: ResMut<PhysicsGovernor>) {
+    if let Some(start) = governor.step_started.take() {
+        let ms = start.elapsed().as_secs_f32() * 1000.0;
+        governor.step_ms = governor.step_ms * 0.9 + ms * 0.1;
+    }
+}
+
+fn update_load_level(
+    mut governor: ResMut<PhysicsGovernor>,
+    keys: Res<ButtonInput<KeyCode>>,
+    bodies: Query<(&RigidBody, Option<&Sleeping>)>,
+    stacks: Query<(), With<MergedStack>>,
+    time: Res<Time>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        governor.show_overlay = !governor.show_overlay;
+    }
+
+    governor.body_count = bodies.iter().filter(|(rb, _)| **rb == RigidBody::Dynamic).count();
+    governor.sleeping_count = bodies.iter().filter(|(_, s)| s.is_some_and(|s| s.sleeping)).count();
+    governor.merged_stacks = stacks.iter().count();
+
+    if !governor.enabled {
+        governor.level = LoadLevel::Normal;
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let target = if governor.step_ms > CRITICAL_STEP_MS {
+        LoadLevel::Critical
+    } else if governor.step_ms > REDUCED_STEP_MS || governor.body_count > MAX_BODIES {
+        LoadLevel::Reduced
+    } else {
+        LoadLevel::Normal
+    };
+
+    // 悪化は即時、回復は一定時間落ち着いてから
+    if target > governor.level {
+        governor.level = target;
+        governor.calm_since = now;
+    } else if governor.step_ms > RECOVER_STEP_MS {
+        governor.calm_since = now;
+    } else if target < governor.level && now - governor.calm_since > RECOVER_DELAY {
+        governor.level = target;
+        governor.calm_since = now;
+    }
+}
+
+fn throttle_spawns(governor: Res<PhysicsGovernor>, mut budget: ResMut<SpawnBudget>) {
+    let (min_interval, blocked) = match governor.level {
+        LoadLevel::Normal => (0.0, false),
+        LoadLevel::Reduced => (1.0, false),
+        LoadLevel::Critical => (0.0, true),
+    };
+    budget.min_interval = min_interval;
+    budget.blocked = blocked || governor.body_count >= MAX_BODIES;
+}
+
+#[allow(clippy::type_complexity)]
+fn sleep_distant_bodies(
+    governor: Res<PhysicsGovernor>,
+    hands: Query<&Transform, With<HandPoint>>,
+    mut bodies: Query<(&Transform, &Velocity, &mut Sleeping), (With<SpawnedBox>, Without<HandPoint>, Without<Held>)>,
+) {
+    if governor.level == LoadLevel::Normal {
+        return;
+    }
+    let hand_positions: Vec<Vec3> = hands
+        .iter()
+        .map(|t| t.translation)
+        .filter(|p| p.y > -50.0)
+        .collect();
+
+    for (transform, velocity, mut sleeping) in bodies.iter_mut() {
+        if sleeping.sleeping {
+            continue;
+        }
+        let near_hand = hand_positions
+            .iter()
+            .any(|p| p.distance(transform.translation) < SLEEP_DISTANCE);
+        let speed = velocity.linvel.length() + velocity.angvel.length();
+        if !near_hand && speed < SLEEP_SPEED {
+            sleeping.sleeping = true;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn merge_settled_stacks(
+    mut commands: Commands,
+    governor: Res<PhysicsGovernor>,
+    bodies: Query<(Entity, &Transform, &Velocity, &SpawnedBox), (With<RigidBody>, Without<Merged>, Without<Held>)>,
+    mut settled_since: Local<HashMap<Entity, f32>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    settled_since.retain(|e, _| bodies.contains(*e));
+
+    let mut settled = Vec::new();
+    for (entity, transform, velocity, spawned) in bodies.iter() {
+        let speed = velocity.linvel.length() + velocity.angvel.length();
+        if speed > SETTLE_SPEED {
+            settled_since.remove(&entity);
+            continue;
+        }
+        let since = *settled_since.entry(entity).or_insert(now);
+        if now - since > SETTLE_TIME {
+            settled.push((entity, *transform, spawned.size));
+        }
+    }
+
+    if governor.level != LoadLevel::Critical || settled.len() < 2 {
+        return;
+    }
+
+    // 接している箱同士を union-find でまとめる
+    let mut parent: Vec<usize> = (0..settled.len()).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[i] = root;
+        root
+    }
+    for i in 0..settled.len() {
+        for j in (i + 1)..settled.len() {
+            let (_, a, size_a) = settled[i];
+            let (_, b, size_b) = settled[j];
+            let reach = (size_a + size_b) * 0.5 * 1.5;
+            if a.translation.distance(b.translation) < reach {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..settled.len() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    for members in groups.values().filter(|m| m.len() >= 2) {
+        let shapes = members
+            .iter()
+            .map(|&i| {
+                let (_, transform, size) = settled[i];
+                let half = size / 2.0;
+                (transform.translation, transform.rotation, Collider::cuboid(half, half, half))
+            })
+            .collect();
+        let entities: Vec<Entity> = members.iter().map(|&i| settled[i].0).collect();
+        for &entity in &entities {
+            commands
+                .entity(entity)
+                .remove::<(RigidBody, Collider)>()
+                .insert(Merged);
+        }
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Fixed,
+            Collider::compound(shapes),
+            Friction::coefficient(1.0),
+            MergedStack { members: entities },
+        ));
+    }
+}
+
+fn unmerge_stacks(
+    mut commands: Commands,
+    governor: Res<PhysicsGovernor>,
+    stacks: Query<(Entity, &MergedStack)>,
+    boxes: Query<&SpawnedBox>,
+) {
+    if governor.level == LoadLevel::Critical {
+        return;
+    }
+    for (stack_entity, stack) in stacks.iter() {
+        for &member in &stack.members {
+            let Ok(spawned) = boxes.get(member) else {
+                continue;
+            };
+            let half = spawned.size / 2.0;
+            commands
+                .entity(member)
+                .remove::<Merged>()
+                .insert((RigidBody::Dynamic, Collider::cuboid(half, half, half)));
+        }
+        commands.entity(stack_entity).despawn();
+    }
+}
+
+#[cfg(feature = "ui")]
+fn governor_overlay(
+    mut contexts: EguiContexts,
+    mut governor: ResMut<PhysicsGovernor>,
+    budget: Res<SpawnBudget>,
+    diagnostics: Res<DiagnosticsStore>,
+) {
+    if !governor.show_overlay {
+        return;
+    }
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.smoothed())
+        .unwrap_or(0.0);
+
+    egui::Window::new("Physics")
+        .anchor(egui::Align2::RIGHT_TOP, [-8.0, 8.0])
+        .resizable(false)
+        .collapsible(true)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("FPS: {fps:.0}"));
+            ui.label(format!("Step: {:.2} ms", governor.step_ms));
+            ui.label(format!("Bodies: {} ({} sleeping)", governor.body_count, governor.sleeping_count));
+            ui.label(format!("Merged stacks: {}", governor.merged_stacks));
+            ui.label(format!("Load: {:?}", governor.level));
+            ui.label(format!("Dropped spawns: {}", budget.dropped));
+            ui.checkbox(&mut governor.enabled, "Governor");
+        });
+}
@@ -0,0 +1,350 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::carry::CoopCarried;
+use crate::core::carry::HEAVY_RADIUS;
+use crate::core::gesture::Gesture;
+use crate::core::spring::{low_pass, CriticalSpring};
+use crate::core::tool_belt::Tool;
+use crate::display::InteractionSet;
+use crate::handles::Manipulated;
+use crate::overlap::{hand_group, HandOverlapSet};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::profile::ActiveProfile;
+use crate::scoop::Scooped;
+use crate::settings::{GrabAssist, Settings};
+use crate::spawn::SpawnedBox;
+use crate::tool_belt::ToolBelt;
+use crate::tuning::Tuning;
+use crate::versus::{can_touch, Team};
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandStates};
+
+const ANGULAR_DAMPING: f32 = 0.8;
+const THUMB_TIP: usize = 4;
+const INDEX_TIP: usize = 8;
+// 手の球で包みにくい小さい物体 (中心から表面までがこれ以下) はつまんでも掴める
+pub const SMALL_OBJECT_RADIUS: f32 = 0.6;
+// つまんだ位置から指の向きへ球を飛ばし、この距離までに当たった小さい物体を掴む
+const PINCH_CAST_RADIUS: f32 = 0.3;
+const PINCH_CAST_DISTANCE: f32 = 0.8;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Held {
+    pub by: HandKey,
+    // 掴んだ瞬間の手の中心から物体までのずれ
+    pub offset: Vec3,
+    // つまんで持っている。握りではなく指を離したときに放す
+    pub pinch: bool,
+}
+
+// 箱以外で掴める物体 (ボールなど)。radius は中心から表面までの距離
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Grabbable {
+    pub radius: f32,
+}
+
+pub fn surface_radius((spawned, grabbable): (Option<&SpawnedBox>, Option<&Grabbable>)) -> f32 {
+    grabbable.map_or_else(|| spawned.map_or(0.0, |b| b.size / 2.0), |g| g.radius)
+}
+
+// 補助ありなら指を握りきらなくても (開いた手以外なら) 握ったとみなす
+pub fn is_closed(gesture: Gesture, assist: &GrabAssist) -> bool {
+    gesture == Gesture::Fist || (assist.enabled && gesture != Gesture::Open)
+}
+
+// 片手で持っている物体をもう片方の手が掴んで持ち替えた
+#[derive(Event, Debug, Clone, Copy)]
+pub struct HandOff {
+    pub entity: Entity,
+    pub from: HandKey,
+    pub to: HandKey,
+}
+
+// 掴まれる前の衝突グループ。持っている手の関節とだけ当たらないようにし、離したら戻す
+#[derive(Component)]
+struct GroupsBeforeHold(Option<CollisionGroups>);
+
+// 保持中の物体ごとの追従の状態
+struct HoldFilter {
+    target: Vec3,
+    spring: CriticalSpring,
+    angular_velocity: Vec3,
+}
+
+#[derive(Resource, Default)]
+pub struct GrabState {
+    previous: HashMap<HandKey, Gesture>,
+    pinching: HashSet<HandKey>,
+}
+
+pub struct GrabPlugin;
+
+impl Plugin for GrabPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HandOff>()
+            .insert_resource(GrabState::default())
+            .add_systems(
+                Update,
+                (arbitrate_grabs, pinch_grabs, ignore_holding_hand, follow_holders, announce_hand_offs)
+                    .chain()
+                    .after(update_hands_and_physics)
+                    .after(PalmPoseSet)
+                    .after(HandOverlapSet)
+                    .in_set(InteractionSet),
+            );
+    }
+}
+
+// 手を握った瞬間に届く範囲の物体を要求し、同じ物体への要求は一番近い手が勝つ。
+// 他の手が持っている物体を勝ち取った場合は持ち替えとして扱う
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn arbitrate_grabs(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    settings: Res<Settings>,
+    mut state: ResMut<GrabState>,
+    mut hand_offs: EventWriter<HandOff>,
+    belt: Res<ToolBelt>,
+    boxes: Query<
+        (Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, Option<&Held>),
+        (With<RigidBody>, Without<Scooped>, Without<CoopCarried>, Without<Manipulated>),
+    >,
+    teams: Query<&Team>,
+) {
+    let holding: HashMap<HandKey, Entity> = boxes
+        .iter()
+        .filter_map(|(entity, _, _, held)| held.map(|h| (h.by, entity)))
+        .collect();
+
+    let assist = &settings.grab_assist;
+    let reach = if assist.enabled { profile.grab_distance.max(assist.radius) } else { profile.grab_distance };
+
+    // 手を離した、または見えなくなった手の保持を解く。手が重なっている間はジェスチャーが怪しいので保つ。
+    // 別の道具に持ち替えた手はすぐに放す
+    for (entity, _, _, held) in boxes.iter() {
+        let Some(held) = held else {
+            continue;
+        };
+        let opened = hand_states.is_confident(held.by)
+            && hand_states.hands.get(&held.by).is_none_or(|h| !is_closed(h.gesture, assist));
+        if (!held.pinch && opened) || belt.tool(held.by) != Tool::Grab {
+            commands.entity(entity).remove::<Held>();
+        }
+    }
+
+    let mut requests: HashMap<Entity, Vec<(HandKey, f32)>> = HashMap::new();
+    for (key, hand) in &hand_states.hands {
+        let was_closed = state.previous.get(key).is_some_and(|g| is_closed(*g, assist));
+        let just_closed = is_closed(hand.gesture, assist) && !was_closed;
+        let grabbing = belt.tool(*key) == Tool::Grab;
+        if !just_closed || !grabbing || holding.contains_key(key) || !hand_states.is_confident(*key) {
+            continue;
+        }
+        let nearest = boxes
+            .iter()
+            .filter(|(_, _, _, held)| held.is_none_or(|h| h.by != *key))
+            .filter(|(entity, ..)| can_touch(teams.get(*entity).ok(), key.0))
+            // 重い物体は両手で運ぶ (carry.rs)
+            .filter(|(_, _, b, _)| surface_radius(*b) < HEAVY_RADIUS)
+            .map(|(entity, transform, b, _)| {
+                let surface = (transform.translation.distance(hand.center) - surface_radius(b)).max(0.0);
+                (entity, surface)
+            })
+            .filter(|(_, surface)| *surface < reach)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((entity, surface)) = nearest {
+            requests.entry(entity).or_default().push((*key, surface));
+        }
+    }
+
+    for (entity, mut candidates) in requests {
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        let (winner, _) = candidates[0];
+        let Ok((_, transform, b, held)) = boxes.get(entity) else {
+            continue;
+        };
+        // 新しい手の中心からのずれをそのまま使うので持ち替えで物体は跳ねない。
+        // 補助ありでは表面が手のひらに接する位置まで吸い寄せる
+        let mut offset = transform.translation - hand_states.hands[&winner].center;
+        if assist.enabled && held.is_none() {
+            offset = offset.normalize_or_zero() * surface_radius(b);
+        }
+        if let Some(held) = held
+            && held.by != winner
+        {
+            hand_offs.send(HandOff { entity, from: held.by, to: winner });
+        }
+        commands.entity(entity).insert(Held { by: winner, offset, pinch: false });
+    }
+
+    state.previous = hand_states.hands.iter().map(|(key, hand)| (*key, hand.gesture)).collect();
+}
+
+// 小さい物体や薄い物体は手の球で包めないので、握らずにつまんだときは
+// つまんだ位置から短く球を飛ばし、当たった物体を指先に吸い寄せて掴む
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn pinch_grabs(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    settings: Res<Settings>,
+    profile: Res<ActiveProfile>,
+    mut state: ResMut<GrabState>,
+    belt: Res<ToolBelt>,
+    rapier: Res<RapierContext>,
+    points: Query<(&HandPoint, &Transform)>,
+    boxes: Query<
+        (Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, Option<&Held>),
+        (With<RigidBody>, Without<Scooped>, Without<Manipulated>),
+    >,
+    teams: Query<&Team>,
+    mut gizmos: Gizmos,
+) {
+    let mut tips: HashMap<HandKey, (Option<Vec3>, Option<Vec3>)> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let entry = tips.entry((point.client, point.side)).or_default();
+        match point.id {
+            THUMB_TIP => entry.0 = Some(transform.translation),
+            INDEX_TIP => entry.1 = Some(transform.translation),
+            _ => {}
+        }
+    }
+    let pinch_points: HashMap<HandKey, Vec3> = tips
+        .into_iter()
+        .filter_map(|(key, tips)| match tips {
+            (Some(thumb), Some(index)) if thumb.distance(index) < profile.pinch_distance => {
+                Some((key, (thumb + index) / 2.0))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut holding = HashSet::new();
+    for (entity, _, _, held) in boxes.iter() {
+        let Some(held) = held else {
+            continue;
+        };
+        holding.insert(held.by);
+        if held.pinch && hand_states.is_confident(held.by) && !pinch_points.contains_key(&held.by) {
+            commands.entity(entity).remove::<Held>();
+        }
+    }
+
+    let assist = &settings.grab_assist;
+    for (key, pinch) in &pinch_points {
+        let Some(hand) = hand_states.hands.get(key) else {
+            continue;
+        };
+        if state.pinching.contains(key)
+            || holding.contains(key)
+            || is_closed(hand.gesture, assist)
+            || !hand_states.is_confident(*key)
+            || belt.tool(*key) != Tool::Grab
+        {
+            continue;
+        }
+        let direction = (*pinch - hand.center).normalize_or_zero();
+        let small = |entity| {
+            boxes.get(entity).is_ok_and(|(_, _, b, held)| {
+                held.is_none() && surface_radius(b) <= SMALL_OBJECT_RADIUS && can_touch(teams.get(entity).ok(), key.0)
+            })
+        };
+        let filter = QueryFilter::new().exclude_sensors().predicate(&small);
+        let hit = rapier.cast_shape(
+            *pinch,
+            Quat::IDENTITY,
+            direction,
+            &Collider::ball(PINCH_CAST_RADIUS),
+            ShapeCastOptions::with_max_time_of_impact(PINCH_CAST_DISTANCE),
+            filter,
+        );
+        let Some((entity, _)) = hit else {
+            continue;
+        };
+        debug!(?entity, hand = ?key, "pinch grab");
+        gizmos.sphere(*pinch, Quat::IDENTITY, PINCH_CAST_RADIUS, Color::srgb(0.6, 1.0, 0.6));
+        commands.entity(entity).insert(Held { by: *key, offset: *pinch - hand.center, pinch: true });
+    }
+
+    state.pinching = pinch_points.into_keys().collect();
+}
+
+// 持っている手の関節と物体がぶつかり合って震えないよう、その手のグループだけ衝突相手から外す
+#[allow(clippy::type_complexity)]
+fn ignore_holding_hand(
+    mut commands: Commands,
+    grabbed: Query<(Entity, &Held, Option<&CollisionGroups>, Option<&GroupsBeforeHold>), Changed<Held>>,
+    saved: Query<&GroupsBeforeHold, Without<Held>>,
+    mut released: RemovedComponents<Held>,
+) {
+    for (entity, held, groups, before) in grabbed.iter() {
+        let original = before.map_or(groups.copied(), |b| b.0);
+        let base = original.unwrap_or_default();
+        commands.entity(entity).insert((
+            CollisionGroups::new(base.memberships, base.filters - hand_group(held.by.0, held.by.1)),
+            GroupsBeforeHold(original),
+        ));
+    }
+    for entity in released.read() {
+        let Ok(before) = saved.get(entity) else {
+            continue;
+        };
+        let mut entity = commands.entity(entity);
+        entity.remove::<GroupsBeforeHold>();
+        match before.0 {
+            Some(groups) => entity.insert(groups),
+            None => entity.remove::<CollisionGroups>(),
+        };
+    }
+}
+
+// 位置を直接書き換えず速度で追従させ、衝突や手放したときの勢いを保つ。
+// 手の位置はならしてから臨界減衰ばねで追い、手首をひねると物体もならした角速度で回る
+fn follow_holders(
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    tuning: Res<Tuning>,
+    mut held: Query<(Entity, &Held, &Transform, &mut Velocity, &mut ExternalForce)>,
+    mut filters: Local<HashMap<Entity, HoldFilter>>,
+    time: Res<Time>,
+) {
+    let grab = &tuning.0.grab;
+    let dt = time.delta_seconds();
+    filters.retain(|entity, _| held.contains(*entity));
+    for (entity, held, transform, mut velocity, mut force) in held.iter_mut() {
+        let Some(hand) = hand_states.hands.get(&held.by) else {
+            continue;
+        };
+        let raw = hand.center + held.offset;
+        let filter = filters.entry(entity).or_insert_with(|| HoldFilter {
+            target: raw,
+            spring: CriticalSpring::new(transform.translation, velocity.linvel),
+            angular_velocity: velocity.angvel,
+        });
+        filter.target = low_pass(filter.target, raw, grab.hold_smoothing, dt);
+        filter.spring.step(filter.target, grab.hold_frequency, dt);
+        // ばねの速度に乗せ、ぶつかってずれた分だけ位置の差で引き戻す
+        let correction = (filter.spring.position - transform.translation) * grab.follow_gain;
+        velocity.linvel = (filter.spring.velocity + correction).clamp_length_max(grab.max_follow_speed);
+        filter.angular_velocity = match palms.poses.get(&held.by) {
+            Some(palm) => low_pass(filter.angular_velocity, palm.angular_velocity, grab.hold_smoothing, dt),
+            None => filter.angular_velocity * ANGULAR_DAMPING,
+        };
+        velocity.angvel = filter.angular_velocity;
+        force.force = Vec3::ZERO;
+    }
+}
+
+fn announce_hand_offs(
+    mut hand_offs: EventReader<HandOff>,
+    boxes: Query<&Transform>,
+    mut gizmos: Gizmos,
+) {
+    for hand_off in hand_offs.read() {
+        info!(entity = ?hand_off.entity, from = ?hand_off.from, to = ?hand_off.to, "hand-off");
+        if let Ok(transform) = boxes.get(hand_off.entity) {
+            gizmos.sphere(transform.translation, Quat::IDENTITY, 3.0, Color::srgb(1.0, 1.0, 0.3));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::handedness::HandednessTracker;
+use crate::packet::{ClientId, ClientPackets, IncomingPacket, PacketSet};
+
+// トラッカーが途中で左右を取り違えると手のリグが反対側へ飛ぶので、位置のつながりで直す
+#[derive(Resource, Default)]
+pub struct HandednessCorrection {
+    trackers: HashMap<ClientId, HandednessTracker>,
+    pub corrected: u64,
+}
+
+pub struct HandednessPlugin;
+
+impl Plugin for HandednessPlugin {
+    fn build(&self, app: &mut App) {
+        // 記録の再生で差し替えられる前に直し、記録にも直したラベルが残るようにする
+        app.insert_resource(HandednessCorrection::default())
+            .add_systems(Update, correct_handedness.after(PacketSet::Receive).before(PacketSet::Override));
+    }
+}
+
+fn correct_handedness(
+    mut correction: ResMut<HandednessCorrection>,
+    mut incoming: ResMut<IncomingPacket>,
+    mut clients: ResMut<ClientPackets>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_seconds();
+    let packets = incoming.0.iter_mut().map(|p| (0, p)).chain(clients.packets.iter_mut().map(|(c, p)| (*c, p)));
+    let mut corrected = 0;
+    for (client, packet) in packets {
+        let fixed = correction.trackers.entry(client).or_default().correct(&mut packet.hands, now);
+        if fixed > 0 {
+            debug!(client, fixed, "relabeled hands by motion continuity");
+            corrected += fixed as u64;
+        }
+    }
+    if corrected > 0 && correction.corrected == 0 {
+        warn!("tracker swapped left/right hand labels; correcting from hand motion");
+    }
+    correction.corrected += corrected;
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::build::{BuildMode, Placed};
+use crate::core::handles::{rotation_yaw, RotateDrag, SNAP_STEP};
+use crate::display::InteractionSet;
+use crate::profile::ActiveProfile;
+use crate::spawn::SpawnedBox;
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandStates};
+
+const THUMB_TIP: usize = 4;
+const INDEX_TIP: usize = 8;
+const HANDLE_RADIUS: f32 = 0.2;
+// つまんだ位置が角のつまみからこの距離以内なら掴む
+const HANDLE_REACH: f32 = 0.6;
+const HANDLE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+// つまみで回している途中の積み木。物理の掴み (grab.rs) はこれを掴まない
+#[derive(Component)]
+pub struct Manipulated;
+
+struct Drag {
+    entity: Entity,
+    rotate: RotateDrag,
+}
+
+#[derive(Resource, Default)]
+struct ManipulationHandles {
+    drags: HashMap<HandKey, Drag>,
+    // 前のフレームにつまんでいた手。つまみ始めだけ掴む
+    pinching: HashSet<HandKey>,
+}
+
+pub struct HandlesPlugin;
+
+impl Plugin for HandlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ManipulationHandles::default())
+            .add_systems(Update, rotate_with_handles.after(update_hands_and_physics).in_set(InteractionSet));
+    }
+}
+
+// 上面の四隅
+fn corners(transform: &Transform, size: f32) -> [Vec3; 4] {
+    let h = size / 2.0;
+    [(h, h), (h, -h), (-h, -h), (-h, h)].map(|(x, z)| transform.translation + transform.rotation * Vec3::new(x, h, z))
+}
+
+// 置いた積み木の上面の角につまみを出し、つまんで中心のまわりに回すと 15° 刻みで向きが変わる
+#[allow(clippy::too_many_arguments)]
+fn rotate_with_handles(
+    mut commands: Commands,
+    build: Res<BuildMode>,
+    profile: Res<ActiveProfile>,
+    hand_states: Res<HandStates>,
+    mut handles: ResMut<ManipulationHandles>,
+    points: Query<(&HandPoint, &Transform), Without<Placed>>,
+    mut blocks: Query<(Entity, &mut Transform, &SpawnedBox), With<Placed>>,
+    mut gizmos: Gizmos,
+) {
+    if !build.active {
+        for drag in handles.drags.drain() {
+            commands.entity(drag.1.entity).remove::<Manipulated>();
+        }
+        handles.pinching.clear();
+        return;
+    }
+    let mut tips: HashMap<HandKey, (Option<Vec3>, Option<Vec3>)> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let entry = tips.entry((point.client, point.side)).or_default();
+        match point.id {
+            THUMB_TIP => entry.0 = Some(transform.translation),
+            INDEX_TIP => entry.1 = Some(transform.translation),
+            _ => {}
+        }
+    }
+    let pinches: HashMap<HandKey, Vec3> = tips
+        .into_iter()
+        .filter_map(|(key, tips)| match tips {
+            (Some(thumb), Some(index)) if thumb.distance(index) < profile.pinch_distance => {
+                Some((key, (thumb + index) / 2.0))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // 指を離したか、積み木が無くなったら放す
+    let handles = &mut *handles;
+    handles.drags.retain(|key, drag| {
+        let keep = pinches.contains_key(key) && blocks.contains(drag.entity);
+        if !keep {
+            commands.entity(drag.entity).remove::<Manipulated>();
+        }
+        keep
+    });
+
+    for (key, pinch) in &pinches {
+        if handles.pinching.contains(key) || handles.drags.contains_key(key) || !hand_states.is_confident(*key) {
+            continue;
+        }
+        let dragged: HashSet<Entity> = handles.drags.values().map(|d| d.entity).collect();
+        let nearest = blocks
+            .iter()
+            .filter(|(entity, ..)| !dragged.contains(entity))
+            .flat_map(|(entity, transform, spawned)| {
+                corners(transform, spawned.size).map(|corner| (entity, corner.distance(*pinch), *transform))
+            })
+            .filter(|(_, distance, _)| *distance < HANDLE_REACH)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((entity, _, transform)) = nearest {
+            let rotate = RotateDrag::new(*pinch - transform.translation, rotation_yaw(transform.rotation));
+            commands.entity(entity).insert(Manipulated);
+            handles.drags.insert(*key, Drag { entity, rotate });
+        }
+    }
+    handles.pinching = pinches.keys().copied().collect();
+
+    for (key, drag) in &mut handles.drags {
+        let Ok((_, mut transform, spawned)) = blocks.get_mut(drag.entity) else {
+            continue;
+        };
+        let yaw = drag.rotate.update(pinches[key] - transform.translation);
+        let rotation = Quat::from_rotation_y(yaw);
+        if transform.rotation.angle_between(rotation) > 1e-4 {
+            transform.rotation = rotation;
+        }
+        draw_protractor(&mut gizmos, &transform, spawned.size, drag.rotate.start_yaw, yaw);
+    }
+
+    let dragged: HashSet<Entity> = handles.drags.values().map(|d| d.entity).collect();
+    for (entity, transform, spawned) in blocks.iter() {
+        if dragged.contains(&entity) {
+            continue;
+        }
+        for corner in corners(transform, spawned.size) {
+            let near = pinches.values().any(|p| p.distance(corner) < HANDLE_REACH);
+            let radius = if near { HANDLE_RADIUS * 1.6 } else { HANDLE_RADIUS };
+            gizmos.sphere(corner, Quat::IDENTITY, radius, HANDLE_COLOR);
+        }
+    }
+}
+
+// 上面に分度器を描く。15° ごとの目盛りと、掴んだときの向きから今の向きまでの弧
+fn draw_protractor(gizmos: &mut Gizmos, transform: &Transform, size: f32, from: f32, to: f32) {
+    let center = transform.translation + Vec3::Y * size / 2.0;
+    let radius = size * 0.9;
+    let at = |yaw: f32, r: f32| center + Quat::from_rotation_y(yaw) * Vec3::X * r;
+    gizmos.circle(center, Dir3::Y, radius, Color::srgba(1.0, 1.0, 1.0, 0.4));
+    for step in 0..24 {
+        let yaw = step as f32 * SNAP_STEP;
+        let length = if step % 6 == 0 { 0.2 } else if step % 2 == 0 { 0.12 } else { 0.06 };
+        gizmos.line(at(yaw, radius), at(yaw, radius * (1.0 + length)), Color::WHITE);
+    }
+    let color = HANDLE_COLOR;
+    gizmos.line(center, at(from, radius), Color::srgba(1.0, 1.0, 1.0, 0.5));
+    gizmos.line(center, at(to, radius * 1.15), color);
+    let turn = to - from;
+    let segments = ((turn.abs() / SNAP_STEP).ceil() as usize * 4).max(1);
+    gizmos.linestrip((0..=segments).map(|i| at(from + turn * i as f32 / segments as f32, radius * 0.6)), color);
+    for corner in corners(transform, size) {
+        gizmos.sphere(corner, Quat::IDENTITY, HANDLE_RADIUS, color);
+    }
+}
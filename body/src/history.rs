@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::gesture::is_two_fingers;
+use crate::core::swipe::SwipeDetector;
+use crate::display::InteractionSet;
+use crate::lifecycle::{vanish, Vanishing};
+use crate::packet::Landmark;
+use crate::snapshot::{BoxSnapshot, SceneCommand};
+use crate::sound::SurfaceMaterial;
+use crate::spawn::{spawn_box_bundle, SpawnedBox};
+use crate::tool_belt::{Drawings, Stroke};
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandStates};
+
+// 覚えておく操作の数。古いものから忘れる
+const MAX_HISTORY: usize = 64;
+const INDEX_TIP: usize = 8;
+const MIDDLE_TIP: usize = 12;
+
+// 取り消せる操作。取り消すときは逆の操作をする
+#[derive(Clone)]
+pub enum Action {
+    // 出した箱 (消す)
+    Spawn(Entity),
+    // 消した箱 (同じ場所に出し直す)
+    Delete(BoxSnapshot),
+    // 描いた線の番号 (消す)
+    Draw(u32),
+    // 消した線 (戻す)
+    Erase(Stroke),
+}
+
+// 箱の出し入れはここで見張るので、それ以外の取り消せる操作はこのイベントで知らせる
+#[derive(Event)]
+pub struct Undoable(pub Action);
+
+#[derive(Event, Default)]
+pub struct UndoRequest;
+
+#[derive(Resource, Default)]
+pub struct History {
+    actions: VecDeque<Action>,
+    // 消えたときに出し直せるよう、箱の大きさを覚えておく
+    sizes: HashMap<Entity, f32>,
+    // 取り消しで消している箱。消したこと自体は記録しない
+    undoing: HashSet<Entity>,
+}
+
+impl History {
+    fn push(&mut self, action: Action) {
+        if self.actions.len() >= MAX_HISTORY {
+            self.actions.pop_front();
+        }
+        self.actions.push_back(action);
+    }
+}
+
+// 取り消しで出し直した箱。出したこととしては記録しない
+#[derive(Component)]
+struct Restored;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(History::default())
+            .add_event::<Undoable>()
+            .add_event::<UndoRequest>()
+            .add_console_command("undo", "undo (take back the last spawn, delete or drawing)", undo_command)
+            .add_systems(Update, (undo_keys, undo_swipe.after(update_hands_and_physics).in_set(InteractionSet)))
+            .add_systems(PostUpdate, (record_undoable, record_boxes, forget_on_scene_change, apply_undo).chain());
+    }
+}
+
+fn record_undoable(mut history: ResMut<History>, mut events: EventReader<Undoable>) {
+    for Undoable(action) in events.read() {
+        history.push(action.clone());
+    }
+}
+
+// 出した箱と、縮めて消した箱 (lifecycle::vanish) を記録する。場面の読み込みなどでいきなり消えた箱は記録しない
+fn record_boxes(
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    added: Query<(Entity, &SpawnedBox, Has<Restored>), Added<SpawnedBox>>,
+    mut removed: RemovedComponents<SpawnedBox>,
+    vanishing: Query<(&Transform, &SurfaceMaterial), With<Vanishing>>,
+) {
+    for (entity, spawned, restored) in added.iter() {
+        history.sizes.insert(entity, spawned.size);
+        if restored {
+            commands.entity(entity).remove::<Restored>();
+        } else {
+            history.push(Action::Spawn(entity));
+        }
+    }
+    for entity in removed.read() {
+        let size = history.sizes.remove(&entity);
+        if history.undoing.remove(&entity) {
+            continue;
+        }
+        if let (Some(size), Ok((transform, material))) = (size, vanishing.get(entity)) {
+            history.push(Action::Delete(BoxSnapshot {
+                position: transform.translation.to_array(),
+                rotation: transform.rotation.to_array(),
+                size,
+                material: *material,
+            }));
+        }
+    }
+}
+
+// 場面を入れ替えたら、前の場面の操作は取り消せない
+fn forget_on_scene_change(mut history: ResMut<History>, mut scene: EventReader<SceneCommand>) {
+    if scene.read().count() > 0 {
+        history.actions.clear();
+    }
+}
+
+fn undo_keys(keys: Res<ButtonInput<KeyCode>>, mut undo: EventWriter<UndoRequest>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::KeyZ) {
+        undo.send_default();
+    }
+}
+
+// 人差し指と中指を伸ばしたまま左へ払うと取り消す
+fn undo_swipe(
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    mut swipes: Local<HashMap<HandKey, SwipeDetector>>,
+    mut undo: EventWriter<UndoRequest>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let mut hands: HashMap<HandKey, Vec<Landmark>> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let Vec3 { x, y, z } = transform.translation;
+        hands.entry((point.client, point.side)).or_default().push(Landmark { id: point.id, x, y, z });
+    }
+    swipes.retain(|key, _| hands.contains_key(key));
+    for (key, landmarks) in &hands {
+        let tip = |id| landmarks.iter().find(|l| l.id == id).map(|l| Vec3::new(l.x, l.y, l.z));
+        let (Some(index), Some(middle)) = (tip(INDEX_TIP), tip(MIDDLE_TIP)) else {
+            continue;
+        };
+        let posed = hand_states.is_confident(*key) && is_two_fingers(landmarks);
+        let tips = (index + middle) / 2.0;
+        if swipes.entry(*key).or_default().update(posed, tips.x, tips.y, now) {
+            info!(client = key.0, side = key.1.label(), "swipe left; undo");
+            undo.send_default();
+        }
+    }
+}
+
+fn undo_command(In(_): In<Vec<String>>, mut undo: EventWriter<UndoRequest>) -> ConsoleResult {
+    undo.send_default();
+    Ok(String::new())
+}
+
+// 新しいものから、まだ取り消せる操作が見つかるまでさかのぼる
+fn apply_undo(
+    mut commands: Commands,
+    mut requests: EventReader<UndoRequest>,
+    mut history: ResMut<History>,
+    mut drawings: ResMut<Drawings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    boxes: Query<(), With<SpawnedBox>>,
+) {
+    for _ in requests.read() {
+        let undone = loop {
+            let Some(action) = history.actions.pop_back() else {
+                break None;
+            };
+            match action {
+                Action::Spawn(entity) if boxes.contains(entity) => {
+                    history.undoing.insert(entity);
+                    vanish(&mut commands, entity);
+                    break Some("spawn");
+                }
+                Action::Spawn(_) => {}
+                Action::Delete(b) => {
+                    let position = Vec3::from_array(b.position);
+                    let transform = Transform::from_translation(position).with_rotation(Quat::from_array(b.rotation));
+                    commands
+                        .spawn(spawn_box_bundle(&mut meshes, &mut materials, position, b.size, b.material))
+                        .insert((transform, Restored));
+                    break Some("delete");
+                }
+                Action::Draw(id) if drawings.erase(id) => break Some("drawing"),
+                Action::Draw(_) => {}
+                Action::Erase(stroke) => {
+                    drawings.restore(stroke);
+                    break Some("erase");
+                }
+            }
+        };
+        match undone {
+            Some(kind) => info!(kind, remaining = history.actions.len(), "undid"),
+            None => info!("nothing to undo"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use bevy::winit::{UpdateMode, WinitSettings};
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::settings::Settings;
+use crate::{update_hands_and_physics, HandStates};
+
+// 常設展示向け。手がしばらく映らなければ物理を止めて描画も間引く
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct IdleSettings {
+    pub enabled: bool,
+    // 両手が見えなくなってから休むまでの秒数
+    pub timeout: f32,
+    // 休んでいる間の描画回数 (毎秒)。手が戻ったことに気づくまでの遅れもこれで決まる
+    pub idle_fps: f32,
+    // 休んでいる間、光る玉を漂わせる。動きを滑らかに見せるため描画は間引かない
+    #[serde(default)]
+    pub screensaver: bool,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout: 60.0,
+            idle_fps: 10.0,
+            screensaver: false,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Idle {
+    pub asleep: bool,
+    last_hand: f32,
+    // 休む前の更新のしかた。起きたら戻す
+    saved_winit: Option<WinitSettings>,
+}
+
+pub struct IdlePlugin;
+
+impl Plugin for IdlePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Idle::default())
+            .add_systems(Update, detect_idle.after(update_hands_and_physics));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, idle_overlay);
+    }
+}
+
+pub fn detect_idle(
+    mut idle: ResMut<Idle>,
+    settings: Res<Settings>,
+    hand_states: Res<HandStates>,
+    mut winit: ResMut<WinitSettings>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_seconds();
+    if !hand_states.hands.is_empty() {
+        idle.last_hand = now;
+    }
+    let config = &settings.display.idle;
+    let asleep = config.enabled && now - idle.last_hand > config.timeout;
+    if asleep == idle.asleep {
+        return;
+    }
+    idle.asleep = asleep;
+    if asleep {
+        info!("no hands for {:.0}s, idling", config.timeout);
+        if config.screensaver {
+            return;
+        }
+        idle.saved_winit = Some(winit.clone());
+        let wait = Duration::from_secs_f32(1.0 / config.idle_fps.max(1.0));
+        winit.focused_mode = UpdateMode::reactive_low_power(wait);
+        winit.unfocused_mode = UpdateMode::reactive_low_power(wait);
+    } else {
+        info!("hands back, resuming");
+        if let Some(saved) = idle.saved_winit.take() {
+            *winit = saved;
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+fn idle_overlay(mut contexts: EguiContexts, idle: Res<Idle>) {
+    if !idle.asleep {
+        return;
+    }
+    egui::Area::new(egui::Id::new("idle"))
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(egui::RichText::new("Show your hands to start").size(36.0).color(egui::Color32::WHITE));
+        });
+}
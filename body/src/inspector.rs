@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::lifecycle::vanish;
+use crate::spawn::SpawnedBox;
+use crate::packet::ClientId;
+use crate::HandPoint;
+
+// 動いている物体を種類ごとに並べ、位置と速度を見ながら消したり止めたりするデバッグ用の窓
+#[derive(Resource, Default)]
+struct Inspector {
+    show: bool,
+}
+
+// 窓から止めた物体。止める前は Dynamic だったので、戻すときは Dynamic にする
+#[derive(Component)]
+struct Frozen;
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Inspector::default())
+            .add_systems(Update, (toggle_inspector, inspector_ui).chain());
+    }
+}
+
+// S で開け閉めする
+fn toggle_inspector(keys: Res<ButtonInput<KeyCode>>, mut inspector: ResMut<Inspector>) {
+    if keys.just_pressed(KeyCode::KeyS) {
+        inspector.show = !inspector.show;
+    }
+}
+
+enum RowAction {
+    Delete,
+    Freeze,
+    Thaw,
+}
+
+fn vec3_text(v: Vec3) -> String {
+    format!("({:.2}, {:.2}, {:.2})", v.x, v.y, v.z)
+}
+
+// 1 つの物体の位置・向き・速度と、消す・止めるボタン
+fn body_row(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    title: String,
+    transform: &Transform,
+    velocity: Option<&Velocity>,
+    frozen: Option<bool>,
+) -> Option<RowAction> {
+    let mut action = None;
+    egui::CollapsingHeader::new(title).id_source(id).show(ui, |ui| {
+        let (yaw, pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+        let degrees = Vec3::new(yaw, pitch, roll) * 180.0 / std::f32::consts::PI;
+        ui.label(format!("position {}", vec3_text(transform.translation)));
+        ui.label(format!("rotation (yaw, pitch, roll) {} deg", vec3_text(degrees)));
+        if let Some(velocity) = velocity {
+            ui.label(format!("velocity {} |{:.2}|", vec3_text(velocity.linvel), velocity.linvel.length()));
+            ui.label(format!("spin {} |{:.2}|", vec3_text(velocity.angvel), velocity.angvel.length()));
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Delete").clicked() {
+                action = Some(RowAction::Delete);
+            }
+            match frozen {
+                Some(false) if ui.button("Freeze").clicked() => action = Some(RowAction::Freeze),
+                Some(true) if ui.button("Unfreeze").clicked() => action = Some(RowAction::Thaw),
+                _ => {}
+            }
+        });
+    });
+    action
+}
+
+#[allow(clippy::type_complexity)]
+fn inspector_ui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut inspector: ResMut<Inspector>,
+    hands: Query<(&HandPoint, &Transform)>,
+    boxes: Query<(Entity, &SpawnedBox, &Transform, Option<&Velocity>, Has<Frozen>)>,
+    props: Query<
+        (Entity, Option<&Name>, &Transform, Option<&Velocity>, &RigidBody, Has<Frozen>),
+        (Without<HandPoint>, Without<SpawnedBox>),
+    >,
+) {
+    if !inspector.show {
+        return;
+    }
+    // 床や壁のように初めから止まっているものは並べない
+    let props: Vec<_> = props.iter().filter(|(.., body, frozen)| **body != RigidBody::Fixed || *frozen).collect();
+    let (hand_count, box_count) = (hands.iter().count(), boxes.iter().count());
+    let mut hand_points: BTreeMap<(ClientId, &str), Vec<(usize, Vec3)>> = BTreeMap::new();
+    for (point, transform) in hands.iter() {
+        hand_points.entry((point.client, point.side.label())).or_default().push((point.id, transform.translation));
+    }
+
+    let mut actions: Vec<(Entity, RowAction, bool)> = Vec::new();
+    let mut open = true;
+    egui::Window::new("Scene inspector")
+        .open(&mut open)
+        .default_width(340.0)
+        .show(contexts.ctx_mut(), |ui| {
+            egui::ScrollArea::vertical().max_height(520.0).show(ui, |ui| {
+                egui::CollapsingHeader::new(format!("Hand points ({})", hand_count)).show(ui, |ui| {
+                    for ((client, side), points) in &mut hand_points {
+                        points.sort_by_key(|(id, _)| *id);
+                        egui::CollapsingHeader::new(format!("client {client} {side} ({})", points.len()))
+                            .id_source(("inspector_hand", *client, *side))
+                            .show(ui, |ui| {
+                                for (id, position) in points.iter() {
+                                    ui.monospace(format!("{id:>2} {}", vec3_text(*position)));
+                                }
+                            });
+                    }
+                });
+                egui::CollapsingHeader::new(format!("Props ({})", props.len())).default_open(true).show(ui, |ui| {
+                    for (entity, name, transform, velocity, body, frozen) in &props {
+                        let title = name.map_or_else(|| format!("{entity}"), |n| format!("{n} ({entity})"));
+                        let id = egui::Id::new(("inspector", *entity));
+                        // 止められるのは物理で動く物体だけ
+                        let freezable = (**body == RigidBody::Dynamic || *frozen).then_some(*frozen);
+                        if let Some(action) = body_row(ui, id, title, transform, *velocity, freezable) {
+                            actions.push((*entity, action, false));
+                        }
+                    }
+                });
+                egui::CollapsingHeader::new(format!("Spawned boxes ({})", box_count)).default_open(true).show(
+                    ui,
+                    |ui| {
+                        for (entity, spawned, transform, velocity, frozen) in boxes.iter() {
+                            let title = format!("box {entity} size {:.1}", spawned.size);
+                            let id = egui::Id::new(("inspector", entity));
+                            if let Some(action) = body_row(ui, id, title, transform, velocity, Some(frozen)) {
+                                actions.push((entity, action, true));
+                            }
+                        }
+                    },
+                );
+            });
+        });
+    if !open {
+        inspector.show = false;
+    }
+
+    for (entity, action, spawned_box) in actions {
+        match action {
+            // 箱は縮めて消し、取り消せるようにする (history.rs)
+            RowAction::Delete if spawned_box => vanish(&mut commands, entity),
+            RowAction::Delete => commands.entity(entity).despawn_recursive(),
+            RowAction::Freeze => {
+                commands.entity(entity).insert((RigidBody::Fixed, Velocity::zero(), Frozen));
+            }
+            RowAction::Thaw => {
+                commands.entity(entity).insert(RigidBody::Dynamic).remove::<Frozen>();
+            }
+        }
+        debug!(?entity, "changed from the scene inspector");
+    }
+}
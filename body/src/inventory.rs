@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::grab::Held;
+use crate::lifecycle::vanish;
+use crate::profile::ActiveProfile;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::{spawn_box_bundle, SpawnBudget, SpawnedBox};
+use crate::{update_hands_and_physics, HandKey, HandStates};
+
+// 棚は手の届く左端、ゴミ箱は右端の床に置く
+const SHELF_X: f32 = -9.0;
+const SHELF_Y: f32 = 0.0;
+const SHELF_Z: [f32; 3] = [8.5, 11.0, 13.5];
+const TEMPLATE_SIZE: f32 = 2.0;
+const TRASH_MIN: Vec3 = Vec3::new(6.5, -5.0, 8.0);
+const TRASH_MAX: Vec3 = Vec3::new(11.5, -2.0, 13.0);
+
+// 棚に並んだ見本。物理を持たず、掴むと同じ物体が手の中に複製される
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ShelfTemplate {
+    pub material: SurfaceMaterial,
+    pub size: f32,
+}
+
+#[derive(Resource, Default)]
+struct InventoryState {
+    previous: HashMap<HandKey, Gesture>,
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InventoryState::default())
+            .add_systems(Startup, spawn_shelf)
+            .add_systems(
+                Update,
+                ((take_from_shelf, empty_trash).in_set(InteractionSet), draw_trash).after(update_hands_and_physics),
+            );
+    }
+}
+
+fn spawn_shelf(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    let board_length = SHELF_Z[2] - SHELF_Z[0] + TEMPLATE_SIZE * 1.5;
+    let board_center = Vec3::new(SHELF_X, SHELF_Y - TEMPLATE_SIZE / 2.0 - 0.1, (SHELF_Z[0] + SHELF_Z[2]) / 2.0);
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(TEMPLATE_SIZE * 1.5, 0.2, board_length)),
+            material: materials.add(Color::srgb(0.45, 0.32, 0.2)),
+            transform: Transform::from_translation(board_center),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid(TEMPLATE_SIZE * 0.75, 0.1, board_length / 2.0),
+    ));
+
+    for (z, material) in SHELF_Z.iter().zip(SurfaceMaterial::ALL) {
+        let mut color = material.color();
+        color.set_alpha(0.7);
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Cuboid::new(TEMPLATE_SIZE, TEMPLATE_SIZE, TEMPLATE_SIZE)),
+                material: materials.add(StandardMaterial {
+                    base_color: color,
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                }),
+                transform: Transform::from_xyz(SHELF_X, SHELF_Y, *z),
+                ..default()
+            },
+            ShelfTemplate { material, size: TEMPLATE_SIZE },
+        ));
+    }
+}
+
+// 手を握った瞬間に見本が届く範囲にあれば複製して持たせる。
+// 届く範囲に本物の物体があるときはそちらを掴む (grab に任せる)
+#[allow(clippy::too_many_arguments)]
+fn take_from_shelf(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    budget: Res<SpawnBudget>,
+    mut state: ResMut<InventoryState>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    templates: Query<(&Transform, &ShelfTemplate)>,
+    boxes: Query<(&Transform, &SpawnedBox, Option<&Held>)>,
+) {
+    for (key, hand) in &hand_states.hands {
+        let just_closed = hand.gesture == Gesture::Fist && state.previous.get(key) != Some(&Gesture::Fist);
+        if !just_closed || budget.blocked || boxes.iter().any(|(_, _, held)| held.is_some_and(|h| h.by == *key)) {
+            continue;
+        }
+        let surface = |transform: &Transform, size: f32| (transform.translation.distance(hand.center) - size / 2.0).max(0.0);
+        if boxes.iter().any(|(transform, b, _)| surface(transform, b.size) < profile.grab_distance) {
+            continue;
+        }
+        let Some((template_transform, template)) = templates
+            .iter()
+            .map(|(transform, template)| (transform, template, surface(transform, template.size)))
+            .filter(|(_, _, distance)| *distance < profile.grab_distance)
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(transform, template, _)| (transform, template))
+        else {
+            continue;
+        };
+        let position = template_transform.translation;
+        commands
+            .spawn(spawn_box_bundle(&mut meshes, &mut materials, position, template.size, template.material))
+            .insert(Held { by: *key, offset: position - hand.center, pinch: false });
+        info!(material = template.material.name(), "took a copy from the shelf");
+    }
+    state.previous = hand_states.hands.iter().map(|(key, hand)| (*key, hand.gesture)).collect();
+}
+
+// 手を離れてゴミ箱の中に入った物体を消す
+#[allow(clippy::type_complexity)]
+fn empty_trash(mut commands: Commands, boxes: Query<(Entity, &Transform), (With<SpawnedBox>, Without<Held>)>) {
+    for (entity, transform) in boxes.iter() {
+        let p = transform.translation;
+        if p.cmpge(TRASH_MIN).all() && p.cmple(TRASH_MAX).all() {
+            vanish(&mut commands, entity);
+        }
+    }
+}
+
+fn draw_trash(mut gizmos: Gizmos) {
+    let y = TRASH_MIN.y + 0.02;
+    let color = Color::srgb(1.0, 0.25, 0.2);
+    let corners = [
+        Vec3::new(TRASH_MIN.x, y, TRASH_MIN.z),
+        Vec3::new(TRASH_MAX.x, y, TRASH_MIN.z),
+        Vec3::new(TRASH_MAX.x, y, TRASH_MAX.z),
+        Vec3::new(TRASH_MIN.x, y, TRASH_MAX.z),
+    ];
+    for i in 0..corners.len() {
+        gizmos.line(corners[i], corners[(i + 1) % corners.len()], color);
+    }
+    gizmos.line(corners[0], corners[2], color);
+    gizmos.line(corners[1], corners[3], color);
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::core::jitter::{propose_dead_zone, JitterSamples};
+use crate::core::smoothing::DeadZone;
+use crate::packet::IncomingPacket;
+use crate::smoothing::Smoothing;
+use crate::{update_hands_and_physics, HandKey, HandStates};
+
+// 各段階で手を止めていてもらう時間
+const MEASURE_TIME: f32 = 3.0;
+const MIN_SAMPLES: usize = 20;
+// これより大きく動いたら静止していなかったとみなしてやり直す
+const MAX_EXCURSION: f32 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    // 今の設定のまま測る
+    Before,
+    // 提案した設定を当てて測り直す
+    After,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Metrics {
+    raw: f32,
+    smoothed: f32,
+}
+
+#[derive(Resource, Default)]
+struct JitterAnalyzer {
+    stage: Option<Stage>,
+    started: f32,
+    raw: HashMap<HandKey, JitterSamples>,
+    smoothed: HashMap<HandKey, JitterSamples>,
+    before: Option<Metrics>,
+    after: Option<Metrics>,
+    previous: Option<DeadZone>,
+    message: Option<String>,
+    show: bool,
+}
+
+impl JitterAnalyzer {
+    fn start(&mut self, stage: Stage, now: f32) {
+        self.stage = Some(stage);
+        self.started = now;
+        self.raw.clear();
+        self.smoothed.clear();
+    }
+
+    fn average(samples: &HashMap<HandKey, JitterSamples>) -> Option<f32> {
+        let values: Vec<f32> = samples.values().filter_map(JitterSamples::std_dev).collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+    }
+
+    fn metrics(&self) -> Result<Metrics, String> {
+        if self.raw.values().map(JitterSamples::len).max().unwrap_or(0) < MIN_SAMPLES {
+            return Err("no hand seen; keep one hand in view".to_string());
+        }
+        if self.raw.values().any(|s| s.max_excursion() > MAX_EXCURSION) {
+            return Err("hand moved; hold it still and try again".to_string());
+        }
+        match (Self::average(&self.raw), Self::average(&self.smoothed)) {
+            (Some(raw), Some(smoothed)) => Ok(Metrics { raw, smoothed }),
+            _ => Err("not enough samples".to_string()),
+        }
+    }
+}
+
+pub struct JitterPlugin;
+
+impl Plugin for JitterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(JitterAnalyzer::default())
+            .add_systems(Update, (start_analysis, measure_jitter.after(update_hands_and_physics)));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, jitter_overlay);
+    }
+}
+
+fn start_analysis(keys: Res<ButtonInput<KeyCode>>, mut analyzer: ResMut<JitterAnalyzer>, time: Res<Time>) {
+    if keys.just_pressed(KeyCode::KeyJ) && analyzer.stage.is_none() {
+        analyzer.show = true;
+        analyzer.before = None;
+        analyzer.after = None;
+        analyzer.message = None;
+        analyzer.start(Stage::Before, time.elapsed_seconds());
+    }
+}
+
+// ローカルの手の生の目標位置 (パケットが届いたフレームだけ) と表示位置を集める
+fn measure_jitter(
+    mut analyzer: ResMut<JitterAnalyzer>,
+    mut smoothing: ResMut<Smoothing>,
+    hand_states: Res<HandStates>,
+    incoming: Res<IncomingPacket>,
+    time: Res<Time>,
+) {
+    let Some(stage) = analyzer.stage else {
+        return;
+    };
+    let analyzer = &mut *analyzer;
+    for key in hand_states.hands.keys().filter(|(client, _)| *client == 0) {
+        if incoming.0.is_some()
+            && let Some(raw) = smoothing.raw(*key)
+        {
+            analyzer.raw.entry(*key).or_default().push(raw);
+        }
+        let smoothed: HashMap<usize, Vec3> = (0..21).filter_map(|id| smoothing.sample(*key, id).map(|p| (id, p))).collect();
+        analyzer.smoothed.entry(*key).or_default().push(&smoothed);
+    }
+
+    let now = time.elapsed_seconds();
+    if now - analyzer.started < MEASURE_TIME {
+        return;
+    }
+    let metrics = match analyzer.metrics() {
+        Ok(metrics) => metrics,
+        Err(message) => {
+            analyzer.message = Some(message);
+            analyzer.stage = None;
+            return;
+        }
+    };
+    match stage {
+        Stage::Before => {
+            analyzer.before = Some(metrics);
+            // 測り直しても最初の設定に戻せるようにしておく
+            analyzer.previous.get_or_insert(smoothing.dead_zone);
+            smoothing.dead_zone.enabled = true;
+            smoothing.dead_zone.radius = propose_dead_zone(metrics.raw);
+            info!(noise = metrics.raw, radius = smoothing.dead_zone.radius, "dead zone tuned from measured jitter");
+            analyzer.start(Stage::After, now);
+        }
+        Stage::After => {
+            info!(raw = metrics.raw, shown = metrics.smoothed, "jitter measured with the tuned dead zone");
+            analyzer.after = Some(metrics);
+            analyzer.stage = None;
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+fn jitter_overlay(mut contexts: EguiContexts, mut analyzer: ResMut<JitterAnalyzer>, mut smoothing: ResMut<Smoothing>, time: Res<Time>) {
+    if !analyzer.show {
+        return;
+    }
+    let mut open = true;
+    egui::Window::new("Jitter")
+        .open(&mut open)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            match analyzer.stage {
+                Some(stage) => {
+                    let remaining = (MEASURE_TIME - (time.elapsed_seconds() - analyzer.started)).max(0.0);
+                    let label = if stage == Stage::Before { "current settings" } else { "tuned settings" };
+                    ui.label(format!("Hold your hand still... ({label}, {remaining:.1} s)"));
+                }
+                None => {
+                    if let Some(message) = &analyzer.message {
+                        ui.colored_label(egui::Color32::LIGHT_RED, message);
+                    }
+                    ui.label("Press J to measure again");
+                }
+            }
+            let row = |ui: &mut egui::Ui, name: &str, metrics: Option<Metrics>| {
+                if let Some(m) = metrics {
+                    ui.label(format!("{name}: raw {:.3}  shown {:.3}", m.raw, m.smoothed));
+                }
+            };
+            row(ui, "Before", analyzer.before);
+            row(ui, "After", analyzer.after);
+            if let Some(previous) = analyzer.previous {
+                ui.label(format!("Dead zone: {:.3} -> {:.3}", previous.radius, smoothing.dead_zone.radius));
+                if analyzer.stage.is_none() && ui.button("Revert").clicked() {
+                    smoothing.dead_zone = previous;
+                    analyzer.previous = None;
+                }
+            }
+        });
+    if !open {
+        analyzer.show = false;
+    }
+}
@@ -0,0 +1,340 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::gesture::Gesture;
+use crate::grab::Held;
+use crate::lifecycle::vanish;
+use crate::packet::{ClientId, ClientPackets, IncomingPacket, PacketSet};
+use crate::sound::SurfaceMaterial;
+use crate::spawn::{spawn_box_bundle, SpawnedBox};
+use crate::{HandKey, HandStates};
+
+// 力の変化がこれ以下なら記録しない
+const FORCE_EPSILON: f32 = 1.0;
+// 再適用時に保持中の物体を記録位置へ引き寄せる速さ (grab の追従と同じ)
+const CARRY_GAIN: f32 = 15.0;
+
+// 記録した物体を実行をまたいで指すための通し番号
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectId(pub u32);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Interaction {
+    // 実行の始まり。記録は追記なので、t と物体の番号は実行ごとに 0 から数え直す
+    Session { started: u64 },
+    Gesture { client: ClientId, side: String, from: String, to: String },
+    Spawn { object: u32, position: [f32; 3], size: f32, material: SurfaceMaterial },
+    Despawn { object: u32 },
+    Grab { object: u32, client: ClientId, side: String },
+    // 保持中の物体の位置 (毎フレーム)
+    Carry { object: u32, position: [f32; 3] },
+    Release { object: u32, velocity: [f32; 3] },
+    Force { object: u32, force: [f32; 3] },
+}
+
+impl Interaction {
+    fn object_mut(&mut self) -> Option<&mut u32> {
+        match self {
+            Interaction::Spawn { object, .. }
+            | Interaction::Despawn { object }
+            | Interaction::Grab { object, .. }
+            | Interaction::Carry { object, .. }
+            | Interaction::Release { object, .. }
+            | Interaction::Force { object, .. } => Some(object),
+            Interaction::Session { .. } | Interaction::Gesture { .. } => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoggedInteraction {
+    pub t: f32,
+    #[serde(flatten)]
+    pub event: Interaction,
+}
+
+#[derive(Resource)]
+struct JournalWriter {
+    writer: LineWriter<File>,
+    start_time: Option<f32>,
+    next_id: u32,
+    objects: HashMap<Entity, u32>,
+    gestures: HashMap<HandKey, Gesture>,
+    forces: HashMap<u32, Vec3>,
+    holders: HashMap<u32, HandKey>,
+}
+
+impl JournalWriter {
+    fn begin_session(&mut self) {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let entry = LoggedInteraction { t: 0.0, event: Interaction::Session { started } };
+        if let Ok(line) = serde_json::to_string(&entry)
+            && let Err(e) = writeln!(self.writer, "{line}")
+        {
+            error!("failed to write interaction journal: {e}");
+        }
+    }
+
+    fn append(&mut self, now: f32, event: Interaction) {
+        let start = *self.start_time.get_or_insert(now);
+        let entry = LoggedInteraction { t: now - start, event };
+        if let Ok(line) = serde_json::to_string(&entry)
+            && let Err(e) = writeln!(self.writer, "{line}")
+        {
+            error!("failed to write interaction journal: {e}");
+        }
+    }
+}
+
+#[derive(Resource)]
+struct Reapply {
+    events: Vec<LoggedInteraction>,
+    next: usize,
+    start_time: Option<f32>,
+    objects: HashMap<u32, Entity>,
+    carried: HashMap<u32, Vec3>,
+}
+
+impl Reapply {
+    fn load(path: &PathBuf) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = Vec::new();
+        // 後の実行の出来事は前の実行の最後の時刻から続け、物体の番号も前の実行と重ならないようずらす
+        let (mut t_offset, mut id_offset) = (0.0, 0);
+        let (mut last_t, mut next_id) = (0.0f32, 0);
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut entry = match serde_json::from_str::<LoggedInteraction>(&line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("skipping bad journal line: {e}");
+                    continue;
+                }
+            };
+            if let Interaction::Session { .. } = entry.event {
+                (t_offset, id_offset) = (last_t, next_id);
+            }
+            entry.t += t_offset;
+            last_t = last_t.max(entry.t);
+            if let Some(object) = entry.event.object_mut() {
+                *object += id_offset;
+                next_id = next_id.max(*object + 1);
+            }
+            events.push(entry);
+        }
+        // 同じ時刻の出来事は書かれた順に適用する
+        events.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Ok(Self { events, next: 0, start_time: None, objects: HashMap::new(), carried: HashMap::new() })
+    }
+}
+
+pub struct JournalPlugin {
+    pub record: Option<PathBuf>,
+    pub reapply: Option<PathBuf>,
+}
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.reapply {
+            match Reapply::load(path) {
+                Ok(reapply) => {
+                    info!("re-applying {} interactions from {}", reapply.events.len(), path.display());
+                    app.insert_resource(reapply)
+                        .add_systems(Update, ignore_live_input.in_set(PacketSet::Override))
+                        .add_systems(Update, reapply_interactions);
+                }
+                Err(e) => error!("failed to load journal {}: {e}", path.display()),
+            }
+        } else if let Some(path) = &self.record {
+            // 追記のみ。既存の記録は消さず、実行の区切りを書いてから続ける
+            match OpenOptions::new().create(true).append(true).open(path) {
+                Ok(file) => {
+                    info!("journaling interactions to {}", path.display());
+                    let mut journal = JournalWriter {
+                        writer: LineWriter::new(file),
+                        start_time: None,
+                        next_id: 0,
+                        objects: HashMap::new(),
+                        gestures: HashMap::new(),
+                        forces: HashMap::new(),
+                        holders: HashMap::new(),
+                    };
+                    journal.begin_session();
+                    app.insert_resource(journal).add_systems(
+                        PostUpdate,
+                        (journal_spawns, journal_gestures, journal_grabs, journal_forces).chain(),
+                    );
+                }
+                Err(e) => error!("failed to open journal {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+fn journal_spawns(
+    mut commands: Commands,
+    mut journal: ResMut<JournalWriter>,
+    added: Query<(Entity, &Transform, &SpawnedBox, &SurfaceMaterial), Without<ObjectId>>,
+    mut removed: RemovedComponents<SpawnedBox>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for (entity, transform, spawned, material) in added.iter() {
+        let object = journal.next_id;
+        journal.next_id += 1;
+        journal.objects.insert(entity, object);
+        commands.entity(entity).insert(ObjectId(object));
+        journal.append(
+            now,
+            Interaction::Spawn { object, position: transform.translation.to_array(), size: spawned.size, material: *material },
+        );
+    }
+    for entity in removed.read() {
+        if let Some(object) = journal.objects.remove(&entity) {
+            journal.append(now, Interaction::Despawn { object });
+        }
+    }
+}
+
+fn journal_gestures(mut journal: ResMut<JournalWriter>, hand_states: Res<HandStates>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    for (key, hand) in &hand_states.hands {
+        let from = journal.gestures.insert(*key, hand.gesture).unwrap_or_default();
+        if from != hand.gesture {
+            journal.append(
+                now,
+                Interaction::Gesture {
+                    client: key.0,
+                    side: key.1.label().to_string(),
+                    from: from.label().to_string(),
+                    to: hand.gesture.label().to_string(),
+                },
+            );
+        }
+    }
+    journal.gestures.retain(|key, _| hand_states.hands.contains_key(key));
+}
+
+fn journal_grabs(
+    mut journal: ResMut<JournalWriter>,
+    boxes: Query<(&ObjectId, &Transform, &Velocity, Option<&Held>)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for (id, transform, velocity, held) in boxes.iter() {
+        let object = id.0;
+        let holder = held.map(|h| h.by);
+        let previous = journal.holders.get(&object).copied();
+        if previous.is_some() && previous != holder {
+            journal.holders.remove(&object);
+            journal.append(now, Interaction::Release { object, velocity: velocity.linvel.to_array() });
+        }
+        let Some(by) = holder else {
+            continue;
+        };
+        if previous != holder {
+            journal.holders.insert(object, by);
+            journal.append(now, Interaction::Grab { object, client: by.0, side: by.1.label().to_string() });
+        }
+        journal.append(now, Interaction::Carry { object, position: transform.translation.to_array() });
+    }
+}
+
+fn journal_forces(mut journal: ResMut<JournalWriter>, boxes: Query<(&ObjectId, &ExternalForce)>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    for (id, force) in boxes.iter() {
+        let previous = journal.forces.get(&id.0).copied().unwrap_or(Vec3::ZERO);
+        if previous.distance(force.force) > FORCE_EPSILON {
+            journal.forces.insert(id.0, force.force);
+            journal.append(now, Interaction::Force { object: id.0, force: force.force.to_array() });
+        }
+    }
+}
+
+// 再適用中はライブ入力で場面が変わらないようにする
+fn ignore_live_input(mut incoming: ResMut<IncomingPacket>, mut clients: ResMut<ClientPackets>) {
+    incoming.0 = None;
+    clients.packets.clear();
+}
+
+fn reapply_interactions(
+    mut commands: Commands,
+    mut reapply: ResMut<Reapply>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bodies: Query<(&Transform, &mut Velocity, &mut ExternalForce)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let elapsed = now - *reapply.start_time.get_or_insert(now);
+    let reapply = &mut *reapply;
+
+    while let Some(entry) = reapply.events.get(reapply.next).filter(|e| e.t <= elapsed) {
+        reapply.next += 1;
+        let entity = |object: &u32| reapply.objects.get(object).copied();
+        match &entry.event {
+            // 新しい実行は空の場面から始まっている
+            Interaction::Session { .. } => {
+                for (_, entity) in reapply.objects.drain() {
+                    vanish(&mut commands, entity);
+                }
+                reapply.carried.clear();
+            }
+            Interaction::Spawn { object, position, size, material } => {
+                let bundle = spawn_box_bundle(&mut meshes, &mut materials, Vec3::from_array(*position), *size, *material);
+                let spawned = commands.spawn(bundle).insert(ObjectId(*object)).id();
+                reapply.objects.insert(*object, spawned);
+            }
+            Interaction::Despawn { object } => {
+                if let Some(entity) = reapply.objects.remove(object) {
+                    vanish(&mut commands, entity);
+                }
+                reapply.carried.remove(object);
+            }
+            Interaction::Carry { object, position } => {
+                reapply.carried.insert(*object, Vec3::from_array(*position));
+            }
+            Interaction::Release { object, velocity } => {
+                reapply.carried.remove(object);
+                if let Some(entity) = entity(object)
+                    && let Ok((_, mut body_velocity, _)) = bodies.get_mut(entity)
+                {
+                    body_velocity.linvel = Vec3::from_array(*velocity);
+                }
+            }
+            Interaction::Force { object, force } => {
+                if let Some(entity) = entity(object)
+                    && let Ok((_, _, mut external)) = bodies.get_mut(entity)
+                {
+                    external.force = Vec3::from_array(*force);
+                }
+            }
+            Interaction::Gesture { client, side, from, to } => {
+                debug!(client, side, from, to, "journal gesture");
+            }
+            Interaction::Grab { object, client, side } => {
+                debug!(object, client, side, "journal grab");
+            }
+        }
+    }
+
+    // 保持されていた物体は手の代わりに記録位置へ速度で追従させる
+    for (object, target) in &reapply.carried {
+        if let Some(entity) = reapply.objects.get(object)
+            && let Ok((transform, mut velocity, _)) = bodies.get_mut(*entity)
+        {
+            velocity.linvel = (*target - transform.translation) * CARRY_GAIN;
+            velocity.angvel = Vec3::ZERO;
+        }
+    }
+}
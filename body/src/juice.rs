@@ -0,0 +1,289 @@
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::extract_component::{
+    ComponentUniforms, DynamicUniformIndex, ExtractComponentPlugin, UniformComponentPlugin,
+};
+use bevy::render::render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::ViewTarget;
+use bevy::render::RenderApp;
+use bevy::transform::TransformSystem;
+use bevy_rapier3d::prelude::*;
+
+use crate::settings::Settings;
+
+// 揺れの上限 (intensity = 1 のとき)
+const MAX_OFFSET: f32 = 0.6;
+const MAX_ROLL: f32 = 0.04;
+const MAX_BLUR: f32 = 0.05;
+const TRAUMA_DECAY: f32 = 1.8;
+
+// この衝撃量を超えた接触だけを、相対速度に応じて揺れに変える
+const IMPACT_IMPULSE: f32 = 150.0;
+const MIN_IMPACT_SPEED: f32 = 4.0;
+const FULL_IMPACT_SPEED: f32 = 25.0;
+
+// 揺れを起こしたいシステムが送る (trauma は 0..1 で加算される)
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ShakeRequest {
+    pub trauma: f32,
+}
+
+#[derive(Resource, Default)]
+struct CameraShake {
+    trauma: f32,
+    applied_offset: Vec3,
+    applied_roll: f32,
+}
+
+// ShaderType の derive が生成する検査用関数が未使用扱いになるので、モジュールごと許可する
+#[allow(dead_code)]
+mod uniform {
+    use bevy::prelude::*;
+    use bevy::render::extract_component::ExtractComponent;
+    use bevy::render::render_resource::ShaderType;
+
+    #[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+    pub struct RadialBlur {
+        pub strength: f32,
+    }
+}
+use uniform::RadialBlur;
+
+pub struct JuicePlugin;
+
+impl Plugin for JuicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShakeRequest>()
+            .insert_resource(CameraShake::default())
+            .add_plugins((
+                ExtractComponentPlugin::<RadialBlur>::default(),
+                UniformComponentPlugin::<RadialBlur>::default(),
+            ))
+            .add_systems(First, restore_camera)
+            .add_systems(Update, (attach_blur, impacts_to_shake, collect_trauma).chain())
+            .add_systems(PostUpdate, apply_shake.before(TransformSystem::TransformPropagate));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<RadialBlurNode>>(Core3d, RadialBlurLabel)
+            .add_render_graph_edges(Core3d, (Node3d::Tonemapping, RadialBlurLabel, Node3d::EndMainPassPostProcessing));
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<RadialBlurPipeline>();
+    }
+}
+
+fn attach_blur(mut commands: Commands, cameras: Query<Entity, (With<Camera3d>, Without<RadialBlur>)>) {
+    for entity in cameras.iter() {
+        commands.entity(entity).insert(RadialBlur::default());
+    }
+}
+
+fn impacts_to_shake(
+    mut contacts: EventReader<ContactForceEvent>,
+    mut shakes: EventWriter<ShakeRequest>,
+    velocities: Query<&Velocity>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for contact in contacts.read() {
+        if contact.total_force_magnitude * dt < IMPACT_IMPULSE {
+            continue;
+        }
+        let linvel = |e: Entity| velocities.get(e).map(|v| v.linvel).unwrap_or(Vec3::ZERO);
+        let speed = (linvel(contact.collider1) - linvel(contact.collider2)).length();
+        let trauma = ((speed - MIN_IMPACT_SPEED) / (FULL_IMPACT_SPEED - MIN_IMPACT_SPEED)).clamp(0.0, 1.0);
+        if trauma > 0.0 {
+            shakes.send(ShakeRequest { trauma: trauma * 0.6 });
+        }
+    }
+}
+
+fn collect_trauma(
+    mut requests: EventReader<ShakeRequest>,
+    mut shake: ResMut<CameraShake>,
+    settings: Res<Settings>,
+    mut blurs: Query<&mut RadialBlur>,
+    time: Res<Time>,
+) {
+    let juice = &settings.juice;
+    for request in requests.read() {
+        if juice.enabled {
+            shake.trauma = (shake.trauma + request.trauma).min(1.0);
+        }
+    }
+    if !juice.enabled {
+        shake.trauma = 0.0;
+    }
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+
+    let strength = MAX_BLUR * juice.blur_intensity * shake.trauma.powi(2);
+    for mut blur in blurs.iter_mut() {
+        blur.strength = strength;
+    }
+}
+
+// 揺れはフレームの最後に足し、次のフレームの最初に取り除く。
+// 他のシステムは常に揺れていないカメラを見る
+fn restore_camera(mut shake: ResMut<CameraShake>, mut camera: Query<&mut Transform, With<Camera3d>>) {
+    let Ok(mut transform) = camera.get_single_mut() else {
+        return;
+    };
+    transform.translation -= shake.applied_offset;
+    let roll = Quat::from_axis_angle(*transform.forward(), shake.applied_roll);
+    transform.rotation = roll.inverse() * transform.rotation;
+    shake.applied_offset = Vec3::ZERO;
+    shake.applied_roll = 0.0;
+}
+
+fn apply_shake(
+    mut shake: ResMut<CameraShake>,
+    settings: Res<Settings>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+    time: Res<Time>,
+) {
+    let Ok(mut transform) = camera.get_single_mut() else {
+        return;
+    };
+    let amount = shake.trauma.powi(2) * settings.juice.shake_intensity;
+    if amount <= 0.0 {
+        return;
+    }
+    // 周波数の違う正弦波を重ねた簡易ノイズ
+    let t = time.elapsed_seconds();
+    let noise = Vec3::new((t * 37.0).sin(), (t * 41.0 + 1.3).sin(), (t * 43.0 + 2.1).sin());
+    let offset = noise * MAX_OFFSET * amount;
+    let roll = (t * 29.0 + 0.7).sin() * MAX_ROLL * amount;
+
+    transform.translation += offset;
+    transform.rotation = Quat::from_axis_angle(*transform.forward(), roll) * transform.rotation;
+    shake.applied_offset = offset;
+    shake.applied_roll = roll;
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct RadialBlurLabel;
+
+#[derive(Default)]
+struct RadialBlurNode;
+
+impl ViewNode for RadialBlurNode {
+    type ViewQuery = (&'static ViewTarget, &'static RadialBlur, &'static DynamicUniformIndex<RadialBlur>);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, blur, blur_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        // 揺れていないときはパスごと省く
+        if blur.strength <= 0.0 {
+            return Ok(());
+        }
+        let blur_pipeline = world.resource::<RadialBlurPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let format = view_target.main_texture_format();
+        let Some(pipeline) = blur_pipeline
+            .pipeline_ids
+            .iter()
+            .find(|(f, _)| *f == format)
+            .and_then(|(_, id)| pipeline_cache.get_render_pipeline(*id))
+        else {
+            return Ok(());
+        };
+        let uniforms = world.resource::<ComponentUniforms<RadialBlur>>();
+        let Some(uniform_binding) = uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "radial_blur_bind_group",
+            &blur_pipeline.layout,
+            &BindGroupEntries::sequential((post_process.source, &blur_pipeline.sampler, uniform_binding.clone())),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("radial_blur_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[blur_index.index()]);
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct RadialBlurPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_ids: [(TextureFormat, CachedRenderPipelineId); 2],
+}
+
+impl FromWorld for RadialBlurPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "radial_blur_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<RadialBlur>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+        let shader = world.load_asset("shaders/radial_blur.wgsl");
+
+        // HDR のカメラではトーンマッピング後も HDR の形式で書き込むので、形式ごとに用意する
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline_ids = [TextureFormat::bevy_default(), ViewTarget::TEXTURE_FORMAT_HDR]
+            .map(|format| {
+                let id = pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("radial_blur_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: shader.clone(),
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                });
+                (format, id)
+            });
+
+        Self { layout, sampler, pipeline_ids }
+    }
+}
@@ -0,0 +1,261 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::lab::{track_fraction, LabParam, LabParams};
+use crate::display::InteractionSet;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::spawn_box_bundle;
+use crate::{update_hands_and_physics, HandPoint, HandStates};
+
+const INDEX_TIP: usize = 8;
+// つまみのレールは左奥に縦に並べる
+const PANEL_ORIGIN: Vec3 = Vec3::new(-9.0, 6.0, -2.0);
+const TRACK_LENGTH: f32 = 5.0;
+const TRACK_SPACING: f32 = 1.6;
+// 指先がレールからこの距離以内ならつまみを動かす
+const TOUCH_RADIUS: f32 = 0.6;
+const KNOB_RADIUS: f32 = 0.35;
+const RESET_BUTTON: Vec3 = Vec3::new(-9.0, -0.8, -2.0);
+const RESET_RADIUS: f32 = 0.5;
+const RESET_COOLDOWN: f32 = 1.5;
+const STACK_ORIGIN: Vec3 = Vec3::new(4.0, -4.5, -2.0);
+const STACK_HEIGHT: usize = 6;
+const BOX_SIZE: f32 = 1.0;
+
+#[derive(Component)]
+struct LabProp;
+
+#[derive(Component)]
+struct LabBox;
+
+#[derive(Component)]
+struct LabKnob(LabParam);
+
+#[derive(Resource, Default)]
+pub struct Lab {
+    pub active: bool,
+    pub params: LabParams,
+    // 実験室を開く前の重力。閉じたら戻す
+    saved_gravity: Option<Vec3>,
+    touching: Option<LabParam>,
+    last_reset: f32,
+}
+
+pub struct LabPlugin;
+
+impl Plugin for LabPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Lab::default())
+            .add_systems(
+                Update,
+                (toggle_lab, touch_controls.in_set(InteractionSet), apply_params)
+                    .chain()
+                    .after(update_hands_and_physics),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, lab_ui);
+    }
+}
+
+fn track_center(param: LabParam) -> Vec3 {
+    let row = LabParam::ALL.iter().position(|p| *p == param).unwrap_or(0);
+    PANEL_ORIGIN - Vec3::Y * row as f32 * TRACK_SPACING
+}
+
+fn knob_position(param: LabParam, params: &LabParams) -> Vec3 {
+    let fraction = param.fraction(params.get(param));
+    track_center(param) + Vec3::X * (fraction - 0.5) * TRACK_LENGTH
+}
+
+fn spawn_stack(commands: &mut Commands, meshes: &mut Assets<Mesh>, materials: &mut Assets<StandardMaterial>) {
+    for level in 0..STACK_HEIGHT {
+        let position = STACK_ORIGIN + Vec3::Y * (level as f32 + 0.5) * BOX_SIZE;
+        commands.spawn((
+            spawn_box_bundle(meshes, materials, position, BOX_SIZE, SurfaceMaterial::Wood),
+            Damping::default(),
+            LabBox,
+            LabProp,
+        ));
+    }
+}
+
+fn toggle_lab(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut lab: ResMut<Lab>,
+    mut config: ResMut<RapierConfiguration>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<Entity, With<LabProp>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyA) {
+        return;
+    }
+    lab.active = !lab.active;
+    if !lab.active {
+        for entity in props.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        if let Some(gravity) = lab.saved_gravity.take() {
+            config.gravity = gravity;
+        }
+        info!("physics lab closed");
+        return;
+    }
+
+    lab.saved_gravity = Some(config.gravity);
+    lab.params = LabParams::default();
+    lab.touching = None;
+    let track = meshes.add(Cuboid::new(TRACK_LENGTH, 0.08, 0.08));
+    let track_material = materials.add(Color::srgb(0.5, 0.5, 0.55));
+    let knob = meshes.add(Sphere::new(KNOB_RADIUS));
+    let knob_material = materials.add(Color::srgb(0.95, 0.75, 0.2));
+    for param in LabParam::ALL {
+        commands.spawn((
+            PbrBundle {
+                mesh: track.clone(),
+                material: track_material.clone(),
+                transform: Transform::from_translation(track_center(param)),
+                ..default()
+            },
+            LabProp,
+        ));
+        commands.spawn((
+            PbrBundle {
+                mesh: knob.clone(),
+                material: knob_material.clone(),
+                transform: Transform::from_translation(knob_position(param, &lab.params)),
+                ..default()
+            },
+            LabKnob(param),
+            LabProp,
+        ));
+    }
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Sphere::new(RESET_RADIUS)),
+            material: materials.add(Color::srgb(0.9, 0.25, 0.2)),
+            transform: Transform::from_translation(RESET_BUTTON),
+            ..default()
+        },
+        LabProp,
+    ));
+    // 箱を置く台。床の材質に左右されずに摩擦の違いを見られるようにする
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(6.0, 0.2, 4.0)),
+            material: materials.add(Color::srgb(0.35, 0.35, 0.4)),
+            transform: Transform::from_translation(STACK_ORIGIN - Vec3::Y * 0.1),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid(3.0, 0.1, 2.0),
+        LabProp,
+    ));
+    spawn_stack(&mut commands, &mut meshes, &mut materials);
+    info!("physics lab ready");
+}
+
+// 人差し指の先でレールに触れるとつまみがその位置へ動く。赤い玉に触れると箱を積み直す
+#[allow(clippy::too_many_arguments)]
+fn touch_controls(
+    mut commands: Commands,
+    mut lab: ResMut<Lab>,
+    points: Query<(&HandPoint, &Transform)>,
+    hand_states: Res<HandStates>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    boxes: Query<Entity, With<LabBox>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    if !lab.active {
+        return;
+    }
+    let tips: Vec<Vec3> = points
+        .iter()
+        .filter(|(p, _)| p.id == INDEX_TIP && hand_states.is_confident((p.client, p.side)))
+        .map(|(_, t)| t.translation)
+        .collect();
+
+    let mut touching = None;
+    for param in LabParam::ALL {
+        let center = track_center(param);
+        let touch = tips.iter().find(|tip| {
+            let offset = **tip - center;
+            offset.x.abs() < TRACK_LENGTH / 2.0 + TOUCH_RADIUS && offset.y.abs() < TOUCH_RADIUS && offset.z.abs() < TOUCH_RADIUS
+        });
+        if let Some(tip) = touch {
+            let value = param.value_at(track_fraction(tip.x - center.x, TRACK_LENGTH));
+            if lab.params.get(param) != value {
+                lab.params.set(param, value);
+            }
+            touching = Some(param);
+            gizmos.sphere(knob_position(param, &lab.params), Quat::IDENTITY, KNOB_RADIUS * 1.4, Color::WHITE);
+        }
+    }
+
+    if lab.touching != touching {
+        lab.touching = touching;
+    }
+
+    let now = time.elapsed_seconds();
+    if now - lab.last_reset > RESET_COOLDOWN && tips.iter().any(|tip| tip.distance(RESET_BUTTON) < RESET_RADIUS + 0.2) {
+        lab.last_reset = now;
+        for entity in boxes.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_stack(&mut commands, &mut meshes, &mut materials);
+        info!("lab stack rebuilt");
+    }
+}
+
+fn apply_params(
+    lab: Res<Lab>,
+    mut config: ResMut<RapierConfiguration>,
+    mut knobs: Query<(&LabKnob, &mut Transform)>,
+    mut boxes: Query<(&mut Friction, &mut Restitution, &mut Damping), With<LabBox>>,
+    added: Query<(), Added<LabBox>>,
+) {
+    if !lab.active || (!lab.is_changed() && added.is_empty()) {
+        return;
+    }
+    let params = lab.params;
+    let gravity = Vec3::NEG_Y * params.gravity;
+    if config.gravity != gravity {
+        config.gravity = gravity;
+    }
+    for (knob, mut transform) in knobs.iter_mut() {
+        transform.translation = knob_position(knob.0, &params);
+    }
+    for (mut friction, mut restitution, mut damping) in boxes.iter_mut() {
+        friction.coefficient = params.friction;
+        restitution.coefficient = params.restitution;
+        damping.linear_damping = params.damping;
+        damping.angular_damping = params.damping;
+    }
+}
+
+#[cfg(feature = "ui")]
+fn lab_ui(mut contexts: EguiContexts, lab: Res<Lab>) {
+    if !lab.active {
+        return;
+    }
+    egui::Window::new("Physics lab")
+        .anchor(egui::Align2::LEFT_TOP, [8.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            for param in LabParam::ALL {
+                let text = format!("{}: {:.2}", param.label(), lab.params.get(param));
+                if lab.touching == Some(param) {
+                    ui.label(egui::RichText::new(text).strong());
+                } else {
+                    ui.label(text);
+                }
+            }
+            ui.small("Slide the knobs with a fingertip / red ball: restack / A: quit");
+        });
+}
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::lifecycle::{appear_scale, vanish_scale};
+use crate::grab::Held;
+use crate::spawn::SpawnedBox;
+
+const APPEAR_TIME: f32 = 0.3;
+const VANISH_TIME: f32 = 0.25;
+// 大きさ 0 の当たり判定は作れないので、ここから膨らませる
+const MIN_SCALE: f32 = 0.02;
+const PUFF_COUNT: usize = 10;
+const PUFF_SPEED: f32 = 3.0;
+const PUFF_LIFE: f32 = 0.45;
+
+#[derive(Component, Default)]
+pub struct Appearing {
+    elapsed: f32,
+}
+
+#[derive(Component, Default)]
+pub struct Vanishing {
+    elapsed: f32,
+}
+
+// 出現・消滅のときに散る煙の粒
+#[derive(Component)]
+struct Puff {
+    velocity: Vec3,
+    size: f32,
+    age: f32,
+}
+
+#[derive(Resource)]
+struct PuffAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+// すぐに消さずに縮めてから消す。SpawnedBox を外すので、力・掴み・記録の対象からはその場で外れる
+pub fn vanish(commands: &mut Commands, entity: Entity) {
+    commands
+        .entity(entity)
+        .remove::<(SpawnedBox, Held, RigidBody)>()
+        .insert((ColliderDisabled, Vanishing::default()));
+}
+
+pub struct LifecyclePlugin;
+
+impl Plugin for LifecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_puffs)
+            .add_systems(Update, (start_appearing, grow_in, shrink_out, update_puffs).chain());
+    }
+}
+
+fn setup_puffs(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.insert_resource(PuffAssets {
+        mesh: meshes.add(Sphere::new(1.0).mesh().ico(1).unwrap()),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.95, 0.95, 1.0, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+fn spawn_puff(commands: &mut Commands, assets: &PuffAssets, center: Vec3, size: f32) {
+    for i in 0..PUFF_COUNT {
+        // 水平に輪を描くように散らし、少し上向きにする
+        let angle = i as f32 / PUFF_COUNT as f32 * std::f32::consts::TAU;
+        let direction = Vec3::new(angle.cos(), 0.4 + 0.3 * (i % 3) as f32, angle.sin()).normalize();
+        let size = size * 0.15;
+        commands.spawn((
+            PbrBundle {
+                mesh: assets.mesh.clone(),
+                material: assets.material.clone(),
+                transform: Transform::from_translation(center + direction * size).with_scale(Vec3::splat(size)),
+                ..default()
+            },
+            Puff { velocity: direction * PUFF_SPEED, size, age: 0.0 },
+        ));
+    }
+}
+
+fn start_appearing(
+    mut commands: Commands,
+    assets: Res<PuffAssets>,
+    mut added: Query<(Entity, &mut Transform, &SpawnedBox), Added<SpawnedBox>>,
+) {
+    for (entity, mut transform, spawned) in added.iter_mut() {
+        transform.scale = Vec3::splat(MIN_SCALE);
+        commands.entity(entity).insert(Appearing::default());
+        spawn_puff(&mut commands, &assets, transform.translation, spawned.size);
+    }
+}
+
+fn grow_in(mut commands: Commands, mut appearing: Query<(Entity, &mut Transform, &mut Appearing)>, time: Res<Time>) {
+    for (entity, mut transform, mut appear) in appearing.iter_mut() {
+        appear.elapsed += time.delta_seconds();
+        let t = appear.elapsed / APPEAR_TIME;
+        transform.scale = Vec3::splat(appear_scale(t).max(MIN_SCALE));
+        if t >= 1.0 {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<Appearing>();
+        }
+    }
+}
+
+fn shrink_out(
+    mut commands: Commands,
+    assets: Res<PuffAssets>,
+    mut vanishing: Query<(Entity, &mut Transform, &mut Vanishing, Option<&Handle<Mesh>>)>,
+    meshes: Res<Assets<Mesh>>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut vanish, mesh) in vanishing.iter_mut() {
+        if vanish.elapsed == 0.0 {
+            let size = mesh
+                .and_then(|m| meshes.get(m))
+                .and_then(|m| m.compute_aabb())
+                .map_or(1.0, |aabb| aabb.half_extents.max_element() * 2.0);
+            spawn_puff(&mut commands, &assets, transform.translation, size * transform.scale.max_element());
+            commands.entity(entity).remove::<Appearing>();
+        }
+        vanish.elapsed += time.delta_seconds();
+        let t = vanish.elapsed / VANISH_TIME;
+        if t >= 1.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.scale = Vec3::splat(vanish_scale(t).max(MIN_SCALE));
+    }
+}
+
+fn update_puffs(mut commands: Commands, mut puffs: Query<(Entity, &mut Transform, &mut Puff)>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+    for (entity, mut transform, mut puff) in puffs.iter_mut() {
+        puff.age += dt;
+        if puff.age >= PUFF_LIFE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // 空気に止められて広がりながら消える
+        puff.velocity *= 1.0 - (4.0 * dt).min(1.0);
+        transform.translation += puff.velocity * dt;
+        let t = puff.age / PUFF_LIFE;
+        transform.scale = Vec3::splat(puff.size * (1.0 + t) * (1.0 - t));
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::log::{Level, LogPlugin};
+
+// RUST_LOG が設定されていればそちらが優先される (bevy の LogPlugin の仕様)
+const FILTER_ENV: &str = "MASTERHAND_LOG";
+const DEFAULT_FILTER: &str = "wgpu=error,naga=warn,body=info";
+
+// `--features trace_chrome` でビルドすると、終了時に trace-*.json (Chrome トレース形式) が書き出される。
+// スパン: packet_decode / gesture_recognition / hand_update / force_application
+pub fn log_plugin() -> LogPlugin {
+    let filter = std::env::var(FILTER_ENV).unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    LogPlugin {
+        filter,
+        level: Level::INFO,
+        ..Default::default()
+    }
+}
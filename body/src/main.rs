@@ -1,76 +1,502 @@
+mod admin;
+mod arena;
+mod bowling;
+mod build;
+mod carry;
+#[cfg(feature = "character")]
+mod character;
+mod checkers;
+#[cfg(feature = "recording")]
+mod capture;
+mod classifier;
+mod confirm;
+mod console;
+mod crater;
+mod crush;
+#[cfg(feature = "recording")]
+mod dataset;
+mod dial;
+mod dice;
+mod display;
+mod dj;
+mod error;
+mod core;
+mod export;
+mod field;
+mod field_view;
+mod flick;
+mod floor;
+mod focus;
+mod forearm;
+mod gecko;
+mod handedness;
+mod handles;
+mod history;
+mod idle;
+mod ghost;
+mod governor;
+mod juice;
+mod lifecycle;
+mod gesture_ring;
+mod grab;
+#[cfg(feature = "ui")]
+mod inspector;
+mod inventory;
+mod jitter;
+mod journal;
+mod lab;
+mod logging;
+mod notes;
+mod occlusion;
+mod overlap;
+mod ownership;
+mod packet;
+mod palm;
+mod palm_menu;
+mod particles;
+mod presenter;
+mod profile;
+#[cfg(feature = "props")]
+mod props;
+mod punching_bag;
+mod puppet;
+mod reach;
+mod render_pose;
+mod render_quality;
+mod replay;
+mod sequencer;
+mod rps;
+mod scoop;
+mod search;
+mod screensaver;
+mod settings;
+mod silhouette;
+mod smoothing;
+mod snapshot;
+mod soft_ball;
+mod sound;
+mod spawn;
+mod spectator;
+mod resting;
+mod reveal;
+mod speed_gate;
+mod stamp;
+mod stats;
+mod substep;
+mod teaching;
+mod throw_arc;
+mod time_scale;
+mod tool_belt;
+mod topology;
+mod trackpad;
+mod tuning;
+mod turntable;
+mod twist;
+mod versus;
+mod wind_zone;
+mod workspace;
+
 use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{EguiContexts, EguiPlugin, EguiSet};
 use bevy_rapier3d::prelude::*;
-use std::net::UdpSocket;
-use serde::Deserialize;
-use std::collections::HashMap;
-
-#[derive(Deserialize, Debug)]
-struct Landmark {
-    id: usize,
-    x: f32,
-    y: f32,
-    z: f32,
-}
-
-#[derive(Deserialize, Debug)]
-struct OneHand {
-    label: String,
-    landmarks: Vec<Landmark>,
-    #[serde(default)]
-    gesture: String, 
-}
-
-#[derive(Deserialize, Debug)]
-struct HandPacket {
-    hands: Vec<OneHand>,
-    #[serde(default)] 
-    snap: bool,
-}
-
-#[derive(Component, PartialEq, Eq, Clone, Copy, Debug, Hash)]
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use packet::{
+    ClientId, ClientPackets, ClientRegistry, HandPacket, IncomingPacket, PacketAuth, PacketSet, SplitInputs,
+    UdpConnection, RECEIVER_ADDR,
+};
+use render_pose::RenderPosePlugin;
+use render_quality::RenderQualityPlugin;
+use replay::ReplayPlugin;
+use sequencer::SequencerPlugin;
+use rps::RpsPlugin;
+use scoop::ScoopPlugin;
+use search::SearchPlugin;
+use screensaver::ScreensaverPlugin;
+#[cfg(feature = "ui")]
+use notes::NotesPlugin;
+use arena::ArenaPlugin;
+use snapshot::SnapshotPlugin;
+use soft_ball::SoftBallPlugin;
+use export::ExportPlugin;
+use smoothing::Smoothing;
+use field::{ActiveFields, CompositeField, Dipole, ForceField, Pole, Uniform};
+use crate::core::gesture::Gesture;
+use crate::core::tool_belt::Tool;
+use crate::core::forearm::{elbow_offset, FOREARM_RATIO};
+use crate::core::topology::HandTopology;
+use crate::core::mapping::{landmark, map_hand, map_hand_at_depth, palm_normal, LEGACY_REGION};
+use admin::{AdminPlugin, DEFAULT_ADMIN_BIND};
+use bowling::BowlingPlugin;
+use build::BuildPlugin;
+use carry::CarryPlugin;
+#[cfg(feature = "character")]
+use character::CharacterPlugin;
+use checkers::CheckersPlugin;
+#[cfg(feature = "recording")]
+use capture::CapturePlugin;
+use classifier::{ActiveClassifier, ClassifierPlugin};
+use confirm::ConfirmPlugin;
+use console::ConsolePlugin;
+use crater::CraterPlugin;
+use crush::CrushPlugin;
+#[cfg(feature = "recording")]
+use dataset::DatasetPlugin;
+use dial::DialPlugin;
+use dice::DicePlugin;
+use display::DisplayPlugin;
+use dj::DjPlugin;
+use error::{AppError, Health, HealthPlugin};
+use field_view::FieldViewPlugin;
+use flick::FlickPlugin;
+use floor::FloorPlugin;
+use focus::FocusPlugin;
+use forearm::{ForearmPlugin, Forearms};
+use gecko::GeckoPlugin;
+use handedness::HandednessPlugin;
+use handles::HandlesPlugin;
+use history::HistoryPlugin;
+use idle::IdlePlugin;
+use gesture_ring::GestureRingPlugin;
+use ghost::GhostPlugin;
+use governor::GovernorPlugin;
+use grab::GrabPlugin;
+#[cfg(feature = "ui")]
+use inspector::InspectorPlugin;
+use inventory::InventoryPlugin;
+use jitter::JitterPlugin;
+use journal::JournalPlugin;
+use lab::LabPlugin;
+use juice::JuicePlugin;
+use lifecycle::LifecyclePlugin;
+use occlusion::{FingerOcclusion, OcclusionPlugin};
+use overlap::HandOverlapPlugin;
+use ownership::OwnershipPlugin;
+use palm::PalmPosePlugin;
+use palm_menu::PalmMenuPlugin;
+use particles::ParticlePlugin;
+use presenter::PresenterPlugin;
+#[cfg(feature = "props")]
+use props::PropsPlugin;
+use punching_bag::PunchingBagPlugin;
+use puppet::PuppetPlugin;
+use settings::SettingsPlugin;
+use profile::ProfilePlugin;
+use reach::{ActiveReach, ReachPlugin};
+use teaching::TeachingPlugin;
+use throw_arc::ThrowArcPlugin;
+use time_scale::TimeScalePlugin;
+use tool_belt::{push_field, ToolBelt, ToolBeltPlugin};
+use topology::{Topology, TopologyPlugin};
+use trackpad::TrackpadPlugin;
+use tuning::{Tuning, TuningPlugin};
+use turntable::TurntablePlugin;
+use twist::TwistPlugin;
+use versus::{can_touch, Team, VersusPlugin};
+use wind_zone::WindZonePlugin;
+use workspace::{Workspaces, WorkspacePlugin};
+use sound::{SoundPlugin, SurfaceMaterial};
+use silhouette::SilhouettePlugin;
+use spectator::SpectatorPlugin;
+use resting::RestingPlugin;
+use reveal::RevealPlugin;
+use speed_gate::SpeedGatePlugin;
+use stamp::StampPlugin;
+use stats::StatsPlugin;
+use substep::SubstepPlugin;
+use spawn::{SpawnBudget, SpawnRequest, SpawnedBox};
+
+#[derive(Component, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Hash)]
 enum HandSide {
     Left,
     Right,
 }
 
+impl HandSide {
+    fn label(&self) -> &'static str {
+        match self {
+            HandSide::Left => "Left",
+            HandSide::Right => "Right",
+        }
+    }
+}
+
 #[derive(Component)]
 struct HandPoint {
     id: usize,
     side: HandSide,
+    client: ClientId,
 }
 
-#[derive(Component)]
-struct SpawnedBox; 
-
-#[derive(Resource)]
-struct UdpConnection(UdpSocket);
+type HandKey = (ClientId, HandSide);
 
 #[derive(Resource)]
 struct HandMaterials {
-    right: Handle<StandardMaterial>,
-    left: Handle<StandardMaterial>,
+    sphere_mesh: Handle<Mesh>,
+    materials: HashMap<HandKey, Handle<StandardMaterial>>,
 }
 
 #[derive(Resource, Default)]
 struct HandPresence {
-    last_seen_right: f32,
-    last_seen_left: f32,
+    last_seen: HashMap<HandKey, f32>,
+}
+
+impl HandPresence {
+    fn is_visible(&self, key: HandKey, now: f32) -> bool {
+        self.last_seen.get(&key).is_some_and(|t| now - t < FADE_TIMEOUT)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HandState {
+    center: Vec3,
+    gesture: Gesture,
+}
+
+// 各システムから参照する、現在見えている手の状態
+#[derive(Resource, Default)]
+struct HandStates {
+    hands: HashMap<HandKey, HandState>,
+    // 範囲外に写って位置を押し戻した手。戻ってくるまで物理的な作用を止める
+    out_of_bounds: HashSet<HandKey>,
+    // 左右の手が重なって追跡が怪しいフレームの手。新しい操作を始めない
+    low_confidence: HashSet<HandKey>,
+    // 速すぎて物理的な作用を止めている手 (speed_gate.rs)
+    too_fast: HashSet<HandKey>,
+    // 机に置いて休ませている手。持ち上げるまで何にも触らない (resting.rs)
+    resting: HashSet<HandKey>,
+}
+
+impl HandStates {
+    fn is_confident(&self, key: HandKey) -> bool {
+        !self.low_confidence.contains(&key) && !self.too_fast.contains(&key) && !self.resting.contains(&key)
+    }
+
+    // 当たり判定や力を止めている手
+    fn is_suspended(&self, key: HandKey) -> bool {
+        self.out_of_bounds.contains(&key) || self.too_fast.contains(&key) || self.resting.contains(&key)
+    }
+
+    // ローカル (最初に接続した) クライアントの手。カーソル操作やじゃんけん、ゴースト、モーションの書き出しのように
+    // このマシンの前の人だけを相手にする機能が使う。全員の手に反応させるときは hands を HandKey ごとに回す
+    fn get(&self, side: HandSide) -> Option<&HandState> {
+        self.hands.get(&(0, side))
+    }
+}
+
+// クライアント 0 は従来の色、それ以降は色相をずらす
+fn hand_color(client: ClientId, side: HandSide) -> Color {
+    match (client, side) {
+        (0, HandSide::Right) => Color::srgb(0.0, 0.8, 1.0),
+        (0, HandSide::Left) => Color::srgb(1.0, 0.0, 0.8),
+        (c, side) => {
+            let base = if side == HandSide::Right { 190.0 } else { 310.0 };
+            Color::hsl((base + 70.0 * c as f32) % 360.0, 1.0, 0.55)
+        }
+    }
+}
+
+fn skeleton_color(client: ClientId, side: HandSide) -> Color {
+    match (client, side) {
+        (0, HandSide::Right) => Color::srgba(0.0, 1.0, 1.0, 1.0),
+        (0, HandSide::Left) => Color::srgba(1.0, 0.0, 1.0, 1.0),
+        (c, side) => hand_color(c, side),
+    }
 }
 
 const FADE_TIMEOUT: f32 = 0.5;
 
-fn main() {
-    let socket = UdpSocket::bind("127.0.0.1:5005").expect("Bind failed");
-    socket.set_nonblocking(true).expect("Nonblocking failed");
+fn arg_value(name: &str) -> Option<PathBuf> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
+}
+
+fn main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(logging::log_plugin())).add_plugins(HealthPlugin);
+    // 鍵が読めないまま署名なしのパケットを受け付けることはしない。理由をログに出して終える。
+    // 同じ秘密は管理用の接続の合言葉にもなる (admin.rs)
+    let auth = match PacketAuth::from_secret_file(arg_value("--secret").as_deref()) {
+        Ok(auth) => auth,
+        Err(e) => {
+            error!("{e}");
+            return AppExit::error();
+        }
+    };
+    // 管理用の接続は既定ではこの機械からだけ受け付ける
+    let admin_bind = match arg_value("--admin-bind") {
+        Some(addr) => match addr.to_string_lossy().parse::<IpAddr>() {
+            Ok(addr) => addr,
+            Err(e) => {
+                error!("{}", AppError::BadArgument { name: "--admin-bind", message: e.to_string() });
+                return AppExit::error();
+            }
+        },
+        None => DEFAULT_ADMIN_BIND,
+    };
+    // 受信ポートが開けなくても、リプレイやゴーストは使えるので起動は続ける
+    match UdpConnection::bind(RECEIVER_ADDR) {
+        Ok(connection) => {
+            app.insert_resource(connection);
+        }
+        Err(e) => app.world_mut().resource_mut::<Health>().failed("receiver", e.to_string()),
+    }
+    // 片手ずつ別の追跡器を使うときは、そのポートも開いて 1 つの送信元としてまとめる
+    if let Some(spec) = arg_value("--split-inputs") {
+        match SplitInputs::bind(&spec.to_string_lossy()) {
+            Ok(split) => {
+                app.insert_resource(split).insert_resource(ClientRegistry::with_split_inputs());
+            }
+            Err(e @ AppError::BadArgument { .. }) => {
+                error!("{e}");
+                return AppExit::error();
+            }
+            Err(e) => app.world_mut().resource_mut::<Health>().failed("split inputs", e.to_string()),
+        }
+    }
 
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
-        .insert_resource(UdpConnection(socket))
+    app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        .add_plugins(ReplayPlugin {
+            record: arg_value("--record"),
+            replay: arg_value("--replay"),
+        })
+        .add_plugins(GhostPlugin { path: arg_value("--ghost") })
+        .add_plugins(JournalPlugin {
+            record: arg_value("--journal"),
+            reapply: arg_value("--reapply"),
+        })
+        .add_plugins(GovernorPlugin)
+        .add_plugins(TuningPlugin)
+        .add_plugins(TopologyPlugin)
+        .add_plugins(ProfilePlugin)
+        .add_plugins(TimeScalePlugin)
+        .add_plugins(RpsPlugin)
+        .add_plugins(ArenaPlugin)
+        .add_plugins(SnapshotPlugin)
+        .add_plugins(ExportPlugin)
+        .add_plugins(StatsPlugin)
+        .add_plugins(HandednessPlugin)
+        .add_plugins(OcclusionPlugin)
+        .add_plugins(AdminPlugin {
+            port: arg_value("--admin").and_then(|port| port.to_str()?.parse().ok()),
+            bind: admin_bind,
+            token: auth.key().map(<[u8]>::to_vec),
+            origins: arg_value("--admin-origin")
+                .map(|list| list.to_string_lossy().split(',').map(|o| o.trim().to_string()).collect())
+                .unwrap_or_default(),
+        })
+        .add_plugins(WorkspacePlugin)
+        .add_plugins(SoundPlugin)
+        .add_plugins(GrabPlugin)
+        .add_plugins(ScoopPlugin)
+        .add_plugins(CarryPlugin)
+        .add_plugins(OwnershipPlugin)
+        .add_plugins(StampPlugin)
+        .add_plugins(HandOverlapPlugin)
+        .add_plugins(SpeedGatePlugin)
+        .add_plugins(SubstepPlugin)
+        .add_plugins(RestingPlugin)
+        .add_plugins(RevealPlugin)
+        .add_plugins(RenderPosePlugin)
+        .add_plugins(RenderQualityPlugin)
+        .add_plugins(FlickPlugin)
+        .add_plugins(ThrowArcPlugin)
+        .add_plugins(TeachingPlugin)
+        .add_plugins(GestureRingPlugin)
+        .add_plugins(SilhouettePlugin)
+        .add_plugins(PuppetPlugin)
+        .add_plugins(ForearmPlugin)
+        .add_plugins(FieldViewPlugin)
+        .add_plugins(PresenterPlugin)
+        .add_plugins(LifecyclePlugin)
+        .add_plugins(ConfirmPlugin)
+        .add_plugins(ConsolePlugin)
+        .add_plugins(PalmMenuPlugin)
+        .add_plugins(CrushPlugin)
+        .add_plugins(SequencerPlugin)
+        .add_plugins(CraterPlugin)
+        .add_plugins(TrackpadPlugin)
+        .add_plugins(SpectatorPlugin)
+        .add_plugins(SearchPlugin)
+        .add_plugins(TurntablePlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(DisplayPlugin)
+        .add_plugins(IdlePlugin)
+        .add_plugins(ScreensaverPlugin)
+        .add_plugins(JuicePlugin)
+        .add_plugins(ClassifierPlugin)
+        .add_plugins(ParticlePlugin)
+        .add_plugins(InventoryPlugin)
+        .add_plugins(PalmPosePlugin)
+        .add_plugins(TwistPlugin)
+        .add_plugins(DialPlugin)
+        .add_plugins(GeckoPlugin)
+        .add_plugins(JitterPlugin)
+        .add_plugins(ReachPlugin)
+        .add_plugins(BowlingPlugin)
+        .add_plugins(LabPlugin)
+        .add_plugins(DicePlugin)
+        .add_plugins(SoftBallPlugin)
+        .add_plugins(PunchingBagPlugin)
+        .add_plugins(WindZonePlugin)
+        .add_plugins(VersusPlugin)
+        .add_plugins(DjPlugin)
+        .add_plugins(FloorPlugin)
+        .add_plugins(FocusPlugin)
+        .add_plugins(BuildPlugin)
+        .add_plugins(HandlesPlugin)
+        .add_plugins(ToolBeltPlugin)
+        .add_plugins(HistoryPlugin)
+        .add_plugins(CheckersPlugin)
+        .add_event::<SpawnRequest>()
+        .insert_resource(SpawnBudget::default())
+        .insert_resource(IncomingPacket::default())
+        .insert_resource(ClientPackets::default())
+        .init_resource::<ClientRegistry>()
+        .insert_resource(auth)
         .insert_resource(HandPresence::default())
+        .insert_resource(HandStates::default())
+        .insert_resource(ActiveFields::default())
+        .insert_resource(Smoothing::default())
+        .configure_sets(Update, (PacketSet::Receive, PacketSet::Override).chain())
         .add_systems(Startup, setup)
-        .add_systems(Update, update_hands_and_physics)
-        .run();
+        .add_systems(Update, packet::receive_packets.in_set(PacketSet::Receive))
+        .add_systems(Update, packet::request_thinning.after(PacketSet::Receive))
+        .add_systems(Update, spawn_client_rigs.after(PacketSet::Receive))
+        .add_systems(
+            Update,
+            update_hands_and_physics
+                .after(PacketSet::Override)
+                .after(spawn_client_rigs)
+                .run_if(display::running),
+        )
+        .add_systems(Update, spawn::spawn_requested.after(update_hands_and_physics));
+    #[cfg(feature = "ui")]
+    app.add_plugins(EguiPlugin)
+        .add_plugins(NotesPlugin)
+        .add_plugins(InspectorPlugin)
+        .add_systems(PreUpdate, release_keys_to_egui.after(EguiSet::ProcessInput));
+    #[cfg(feature = "recording")]
+    app.add_plugins(CapturePlugin).add_plugins(DatasetPlugin);
+    #[cfg(feature = "character")]
+    app.add_plugins(CharacterPlugin);
+    #[cfg(feature = "props")]
+    app.add_plugins(PropsPlugin);
+    app.run()
+}
+
+// テキスト入力中のキーをショートカットとして扱わない
+#[cfg(feature = "ui")]
+fn release_keys_to_egui(mut contexts: EguiContexts, mut keys: ResMut<ButtonInput<KeyCode>>) {
+    if contexts.ctx_mut().wants_keyboard_input() {
+        keys.reset_all();
+    }
 }
 
 fn setup(
@@ -78,6 +504,7 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut gizmo_config: ResMut<GizmoConfigStore>,
+    topology: Res<Topology>,
 ) {
     let (config, _) = gizmo_config.config_mut::<DefaultGizmoConfigGroup>();
     config.depth_bias = -1.0;
@@ -99,290 +526,333 @@ fn setup(
         ..default()
     });
 
-    commands.spawn((
-        PbrBundle {
-            mesh: meshes.add(Plane3d::default().mesh().size(30.0, 30.0)),
-            material: materials.add(StandardMaterial {
-                base_color: Color::srgb(0.2, 0.2, 0.2),
-                perceptual_roughness: 0.8,
-                ..default()
-            }),
-            transform: Transform::from_xyz(0.0, -5.0, 0.0),
-            ..default()
-        },
-        RigidBody::Fixed,
-        Collider::cuboid(15.0, 0.01, 15.0), 
-    ));
+    let mut hand_mats = HandMaterials {
+        sphere_mesh: meshes.add(Sphere::new(0.08)),
+        materials: HashMap::new(),
+    };
+    for side in [HandSide::Right, HandSide::Left] {
+        spawn_hand_rig(&mut commands, &mut materials, &mut hand_mats, &topology.0, 0, side);
+    }
+    commands.insert_resource(hand_mats);
+}
 
-    let right_mat = materials.add(StandardMaterial {
-        base_color: Color::srgba(0.0, 0.8, 1.0, 1.0),
-        emissive: LinearRgba::new(0.0, 0.8, 1.0, 1.0),
+fn spawn_hand_rig(
+    commands: &mut Commands,
+    materials: &mut Assets<StandardMaterial>,
+    hand_mats: &mut HandMaterials,
+    topology: &HandTopology,
+    client: ClientId,
+    side: HandSide,
+) {
+    let color = hand_color(client, side);
+    let material = materials.add(StandardMaterial {
+        base_color: color,
+        emissive: color.to_linear(),
         alpha_mode: AlphaMode::Blend,
         ..default()
     });
-    
-    let left_mat = materials.add(StandardMaterial {
-        base_color: Color::srgba(1.0, 0.0, 0.8, 1.0),
-        emissive: LinearRgba::new(1.0, 0.0, 0.8, 1.0),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
+    hand_mats.materials.insert((client, side), material.clone());
+
+    spawn_hand_points(commands, &hand_mats.sphere_mesh, &material, topology, |id| {
+        (
+            HandPoint { id, side, client },
+            RigidBody::KinematicPositionBased,
+            Collider::ball(0.1),
+            Friction::coefficient(2.0),
+            // 速く振った手が薄い物体をすり抜けないように (substep.rs も参照)
+            Ccd::enabled(),
+        )
     });
+}
 
-    commands.insert_resource(HandMaterials {
-        right: right_mat.clone(),
-        left: left_mat.clone(),
-    });
+// 関節の球を topology の数だけ出す。追跡した手と、じゃんけんのボットの手 (rps.rs) で共用する
+fn spawn_hand_points<B: Bundle>(
+    commands: &mut Commands,
+    mesh: &Handle<Mesh>,
+    material: &Handle<StandardMaterial>,
+    topology: &HandTopology,
+    point: impl Fn(usize) -> B,
+) {
+    for id in 0..topology.landmarks {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_xyz(0.0, -100.0, 0.0),
+                ..default()
+            },
+            point(id),
+        ));
+    }
+}
 
-    let sphere_mesh = meshes.add(Sphere::new(0.08));
-
-    let sides = [
-        (HandSide::Right, right_mat),
-        (HandSide::Left, left_mat)
-    ];
-
-    for (side, material) in sides {
-        for i in 0..21 {
-            commands.spawn((
-                PbrBundle {
-                    mesh: sphere_mesh.clone(),
-                    material: material.clone(),
-                    transform: Transform::from_xyz(0.0, -100.0, 0.0),
-                    ..default()
-                },
-                HandPoint { id: i, side: side },
-                RigidBody::KinematicPositionBased,
-                Collider::ball(0.1),
-                Friction::coefficient(2.0),
-            ));
+fn spawn_client_rigs(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut hand_mats: ResMut<HandMaterials>,
+    clients: Res<ClientPackets>,
+    topology: Res<Topology>,
+) {
+    for &client in clients.packets.keys() {
+        for side in [HandSide::Right, HandSide::Left] {
+            if !hand_mats.materials.contains_key(&(client, side)) {
+                info!("spawning hand rig for client {client}");
+                spawn_hand_rig(&mut commands, &mut materials, &mut hand_mats, &topology.0, client, side);
+            }
         }
     }
 }
 
-const HAND_CONNECTIONS: &[(usize, usize)] = &[
-    (0, 1), (1, 2), (2, 3), (3, 4),
-    (0, 5), (5, 6), (6, 7), (7, 8),
-    (9, 10), (10, 11), (11, 12),
-    (13, 14), (14, 15), (15, 16),
-    (0, 17), (17, 18), (18, 19), (19, 20),
-    (5, 9), (9, 13), (13, 17)
-];
-
+#[allow(clippy::collapsible_if, clippy::too_many_arguments, clippy::type_complexity)]
 fn update_hands_and_physics(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
+    mut spawn_events: EventWriter<SpawnRequest>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     hand_mats: Res<HandMaterials>,
     mut hand_presence: ResMut<HandPresence>,
-    socket_res: Res<UdpConnection>,
+    mut hand_states: ResMut<HandStates>,
+    (tuning, mut forearms, mut active_fields, tool_belt): (
+        Res<Tuning>,
+        ResMut<Forearms>,
+        ResMut<ActiveFields>,
+        Res<ToolBelt>,
+    ),
+    reach: Res<ActiveReach>,
+    (mut classifier, occlusion): (ResMut<ActiveClassifier>, Res<FingerOcclusion>),
+    mut smoothing: ResMut<Smoothing>,
+    workspaces: Res<Workspaces>,
+    incoming: Res<IncomingPacket>,
+    clients: Res<ClientPackets>,
     mut hand_query: Query<(&HandPoint, &mut Transform)>,
-    mut box_query: Query<(&mut ExternalForce, &Transform), (With<SpawnedBox>, Without<HandPoint>)>,
+    mut box_query: Query<(Entity, &mut ExternalForce, &Transform, Option<&Team>), (With<SpawnedBox>, Without<HandPoint>)>,
     mut gizmos: Gizmos,
-    time: Res<Time>, 
+    time: Res<Time>,
 ) {
-    let mut buf = [0; 65536];
-    let mut latest_packet: Option<HandPacket> = None;
     let current_time = time.elapsed_seconds();
-
-    while let Ok((amt, _src)) = socket_res.0.recv_from(&mut buf) {
-        let valid_data = &buf[..amt];
-        if let Ok(packet) = serde_json::from_slice::<HandPacket>(valid_data) {
-            latest_packet = Some(packet);
-        }
+    smoothing.advance(time.delta_seconds());
+
+    let packets: Vec<(ClientId, &HandPacket)> = incoming
+        .0
+        .iter()
+        .map(|p| (0, p))
+        .chain(clients.packets.iter().map(|(c, p)| (*c, p)))
+        .collect();
+
+    let mut total_force_field: HashMap<Entity, Vec3> = HashMap::new();
+    if !packets.is_empty() {
+        active_fields.clear();
     }
 
-    if let Some(packet) = &latest_packet {
-        if packet.hands.iter().any(|h| h.label == "Right") {
-            hand_presence.last_seen_right = current_time;
-        }
-        if packet.hands.iter().any(|h| h.label == "Left") {
-            hand_presence.last_seen_left = current_time;
-        }
+    for &(client, packet) in &packets {
+        let _span = info_span!("hand_update", client).entered();
+        let region = workspaces.region(client);
+        let isolated = workspaces.is_isolated();
 
-        if packet.snap {
-            let rand_x = (time.elapsed_seconds() * 10.0).sin() * 5.0;
-            let box_size = 5.0;
-            commands.spawn((
-                PbrBundle {
-                    mesh: meshes.add(Cuboid::new(box_size, box_size, box_size)),
-                    material: materials.add(Color::srgb(1.0, 0.5, 0.0)),
-                    transform: Transform::from_xyz(rand_x, 15.0, 0.0),
-                    ..default()
-                },
-                RigidBody::Dynamic,
-                Collider::cuboid(box_size / 2.0, box_size / 2.0, box_size / 2.0),
-                Restitution::coefficient(0.1),
-                Friction::coefficient(1.0),
-                ColliderMassProperties::Density(5.0),
-                ExternalForce::default(), 
-                SpawnedBox, 
-            ));
+        for side in [HandSide::Right, HandSide::Left] {
+            if packet.hands.iter().any(|h| h.label == side.label()) {
+                hand_presence.last_seen.insert((client, side), current_time);
+            }
         }
 
-        let mut hand_centers: HashMap<String, Vec3> = HashMap::new();
-        let mut hand_normals: HashMap<String, Vec3> = HashMap::new();
-        let mut hand_gestures: HashMap<String, String> = HashMap::new();
-
-        for (point, mut transform) in hand_query.iter_mut() {
-            let target_hand_data = packet.hands.iter().find(|h| {
-                match point.side {
-                    HandSide::Right => h.label == "Right",
-                    HandSide::Left => h.label == "Left",
-                }
+        if packet.snap {
+            let spread = 5.0 * region.width() / LEGACY_REGION.width();
+            let rand_x = region.center() + (time.elapsed_seconds() * 10.0).sin() * spread;
+            spawn_events.send(SpawnRequest {
+                position: Vec3::new(rand_x, 15.0, 0.0),
+                size: 5.0,
+                material: SurfaceMaterial::ALL[(current_time * 7.0) as usize % SurfaceMaterial::ALL.len()],
             });
+        }
 
-            if let Some(hand_data) = target_hand_data {
-                if point.id == 9 { 
-                    hand_gestures.insert(hand_data.label.clone(), hand_data.gesture.clone());
-                }
-
-                let mut depth_offset = 0.0;
-                let wrist = hand_data.landmarks.iter().find(|l| l.id == 0);
-                let middle_mcp = hand_data.landmarks.iter().find(|l| l.id == 9);
-                let pinky_mcp = hand_data.landmarks.iter().find(|l| l.id == 17);
-                let index_mcp = hand_data.landmarks.iter().find(|l| l.id == 5);
-
-                if let (Some(w), Some(m)) = (wrist, middle_mcp) {
-                    let dx = w.x - m.x;
-                    let dy = w.y - m.y;
-                    let hand_size = (dx * dx + dy * dy).sqrt();
-                    depth_offset = 20.0 - (hand_size * 80.0);
+        let mut hand_centers: HashMap<HandSide, Vec3> = HashMap::new();
+        let mut hand_normals: HashMap<HandSide, Vec3> = HashMap::new();
+        let mut hand_gestures: HashMap<HandSide, Gesture> = HashMap::new();
+        let stamp = packet.timestamp.unwrap_or(time.elapsed_seconds_f64());
+        // 送信側の撮影時刻と比べるための壁時計
+        let received_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64());
+
+        for side in [HandSide::Right, HandSide::Left] {
+            let Some(hand_data) = packet.hands.iter().find(|h| h.label == side.label()) else {
+                continue;
+            };
+            let gesture = info_span!("gesture_recognition", side = side.label())
+                .in_scope(|| classifier.gesture((client, side), hand_data, occlusion.fingers((client, side))));
+            hand_gestures.insert(side, gesture);
+
+            // 肘が届いていれば、指が隠れて手が小さく写っても前腕から奥行きを出す
+            let mut hand_targets = match forearms.hand_depth((client, side), hand_data) {
+                Some(depth) => map_hand_at_depth(&hand_data.landmarks, &region, &reach.0, depth),
+                None => map_hand(&hand_data.landmarks, &region, &reach.0),
+            };
+            if let (Some(elbow), Some(wrist)) = (&hand_data.elbow, landmark(&hand_data.landmarks, 0))
+                && let (Some(w), Some(m)) = (hand_targets.get(&0), hand_targets.get(&9))
+            {
+                let length = w.distance(*m) * FOREARM_RATIO;
+                forearms.set_elbow((client, side), elbow_offset(wrist, elbow, &region, reach.0.hand_scale, length));
+            }
+            // 指先がはみ出す程度は押し戻すだけにし、手の中心が出たときだけ範囲外とみなす
+            let out_of_bounds = hand_targets.get(&9).is_some_and(|center| !workspaces.bounds.contains(*center));
+            if out_of_bounds && hand_states.out_of_bounds.insert((client, side)) {
+                info!(client, side = side.label(), "hand out of bounds; interaction suspended");
+            } else if !out_of_bounds && hand_states.out_of_bounds.remove(&(client, side)) {
+                info!(client, side = side.label(), "hand back in bounds");
+            }
+            for target in hand_targets.values_mut() {
+                *target = workspaces.bounds.clamp(*target);
+                if isolated {
+                    target.x = region.clamp_x(target.x);
                 }
+            }
+            smoothing.gate((client, side), &mut hand_targets, current_time);
+            smoothing.push((client, side), stamp, hand_targets);
+            if let Some(sent) = packet.timestamp {
+                smoothing.record_latency((client, side), received_at - sent);
+            }
 
-                if let Some(lm) = hand_data.landmarks.iter().find(|l| l.id == point.id) {
-                    let scale = 20.0;
-                    let x = (lm.x - 0.5) * scale; 
-                    let y = (0.5 - lm.y) * scale + 3.0; 
-                    let z = depth_offset + (lm.z * scale);
-
-                    let target_pos = Vec3::new(x, y, z);
-                    
-                    let smooth_factor = 40.0 * time.delta_seconds(); 
-                    let t = smooth_factor.clamp(0.0, 1.0);
-                    transform.translation = transform.translation.lerp(target_pos, t);
-
-                    if point.id == 9 {
-                        hand_centers.insert(hand_data.label.clone(), transform.translation);
-                    }
-                }
+            if let Some(normal) = palm_normal(&hand_data.landmarks, side == HandSide::Right) {
+                hand_normals.insert(side, normal);
+            }
+        }
 
-                if point.id == 0 {
-                    if let (Some(w), Some(i), Some(p)) = (wrist, index_mcp, pinky_mcp) {
-                         let to_index = Vec3::new(i.x - w.x, w.y - i.y, i.z - w.z);
-                         let to_pinky = Vec3::new(p.x - w.x, w.y - p.y, p.z - w.z);
-                         
-                         let mut normal = if hand_data.label == "Right" {
-                             to_index.cross(to_pinky).normalize_or_zero()
-                         } else {
-                             to_pinky.cross(to_index).normalize_or_zero()
-                         };
-                         
-                         normal.y *= -1.0; 
-                         hand_normals.insert(hand_data.label.clone(), normal);
-                    }
-                }
+        for side in hand_gestures.keys() {
+            if let Some(center) = smoothing.sample((client, *side), 9) {
+                hand_centers.insert(*side, center);
             }
         }
 
-        let mut total_force_field = HashMap::new(); 
-
-        for (label, gesture) in &hand_gestures {
-            if gesture == "Fist" {
-                if let Some(center) = hand_centers.get(label) {
-                    for (_box_force, box_transform) in box_query.iter() {
-                        let dir = *center - box_transform.translation;
-                        let dist_sq = dir.length_squared().max(1.0);
-                        let force_mag = 50000.0 / dist_sq; 
-                        let force = dir.normalize_or_zero() * force_mag;
-                        
-                        let entity_ptr = box_transform as *const _ as usize; 
-                        total_force_field.entry(entity_ptr).and_modify(|f: &mut Vec3| *f += force).or_insert(force);
-                    }
-                    gizmos.sphere(*center, Quat::IDENTITY, 1.0, Color::srgb(1.0, 0.0, 0.0));
-                }
+        let mut field = CompositeField::default();
+
+        // 握った手は引き寄せ、鷲づかみの手は反発する。極性が逆の両手は双極子になる。
+        // 引き寄せと風は「掴む」の道具を持った手だけ
+        let grabbing = |side: HandSide| tool_belt.tool((client, side)) == Tool::Grab;
+        let poles: Vec<(Vec3, f32)> = [HandSide::Right, HandSide::Left]
+            .iter()
+            .filter(|side| !hand_states.is_suspended((client, **side)) && grabbing(**side))
+            .filter_map(|side| {
+                let polarity = hand_gestures.get(side)?.polarity()?;
+                Some((*hand_centers.get(side)?, polarity))
+            })
+            .collect();
+
+        if let [(a, pa), (b, pb)] = poles[..]
+            && pa * pb < 0.0
+        {
+            let (sink, source) = if pa > 0.0 { (a, b) } else { (b, a) };
+            let dipole = Dipole { source, sink, strength: tuning.0.magnet.strength };
+            for line in dipole.field_lines(8, 0.4, 60) {
+                gizmos.linestrip(line, Color::srgb(0.9, 0.4, 1.0));
+            }
+            field.add(dipole);
+        } else {
+            for &(center, polarity) in &poles {
+                field.add(Pole { center, strength: tuning.0.magnet.strength * polarity });
             }
         }
+        for &(center, polarity) in &poles {
+            let color = if polarity > 0.0 { Color::srgb(1.0, 0.0, 0.0) } else { Color::srgb(0.0, 0.4, 1.0) };
+            gizmos.sphere(center, Quat::IDENTITY, 1.0, color);
+        }
 
-        let right_open = hand_gestures.get("Right").map(|g| g == "Open").unwrap_or(false);
-        let left_open = hand_gestures.get("Left").map(|g| g == "Open").unwrap_or(false);
+        let active = |side: HandSide| !hand_states.is_suspended((client, side));
+        let open = |side: HandSide| hand_gestures.get(&side) == Some(&Gesture::Open) && active(side);
+        let right_open = open(HandSide::Right) && grabbing(HandSide::Right);
+        let left_open = open(HandSide::Left) && grabbing(HandSide::Left);
+
+        // 「押す」の道具を持った手は、開いた手のひらの前の物体を押しやる
+        for side in [HandSide::Right, HandSide::Left] {
+            if tool_belt.tool((client, side)) == Tool::Push
+                && open(side)
+                && let (Some(center), Some(normal)) = (hand_centers.get(&side), hand_normals.get(&side))
+            {
+                gizmos.arrow(*center, *center + *normal * 3.0, Color::srgb(1.0, 0.6, 0.1));
+                field.add(push_field(*center, *normal, tuning.0.wind.force));
+            }
+        }
 
         if right_open && left_open {
-             if let (Some(n_r), Some(n_l)) = (hand_normals.get("Right"), hand_normals.get("Left")) {
-                 if n_r.dot(*n_l) > 0.5 { 
+             if let (Some(n_r), Some(n_l)) = (hand_normals.get(&HandSide::Right), hand_normals.get(&HandSide::Left)) {
+                 if n_r.dot(*n_l) > tuning.0.wind.alignment {
                      let avg_dir = (*n_r + *n_l).normalize();
-                     let wind_force = avg_dir * 1500.0; 
+                     field.add(Uniform { force: avg_dir * tuning.0.wind.force });
 
-                     for (_box_force, box_transform) in box_query.iter() {
-                         let entity_ptr = box_transform as *const _ as usize;
-                         total_force_field.entry(entity_ptr).and_modify(|f: &mut Vec3| *f += wind_force).or_insert(wind_force);
-                     }
-                     
-                     if let Some(center) = hand_centers.get("Right") {
+                     if let Some(center) = hand_centers.get(&HandSide::Right) {
                          gizmos.arrow(*center, *center + avg_dir * 5.0, Color::srgb(0.0, 1.0, 0.0));
                      }
                  }
              }
         }
 
-        for (mut box_force, box_transform) in box_query.iter_mut() {
-            let entity_ptr = box_transform as *const _ as usize;
-            if let Some(force) = total_force_field.get(&entity_ptr) {
-                box_force.force = *force;
-            } else {
-                box_force.force = Vec3::ZERO;
+        if !field.is_empty() {
+            let _span = info_span!("force_application").entered();
+            for (entity, _box_force, box_transform, team) in box_query.iter() {
+                // 分離モードでは自分の領域にある箱にだけ力を及ぼす
+                if isolated && !region.contains_x(box_transform.translation.x) {
+                    continue;
+                }
+                // 対戦中は相手の色の箱に力を及ぼさない
+                if !can_touch(team, client) {
+                    continue;
+                }
+                *total_force_field.entry(entity).or_insert(Vec3::ZERO) += field.force_at(box_transform.translation);
             }
         }
-    }
-
-    let show_right = (current_time - hand_presence.last_seen_right) < FADE_TIMEOUT;
-    let show_left = (current_time - hand_presence.last_seen_left) < FADE_TIMEOUT;
+        active_fields.push(field, isolated.then_some(region));
 
-    if let Some(mat) = materials.get_mut(&hand_mats.right) {
-        if show_right {
-            mat.base_color.set_alpha(1.0);
-            mat.emissive = LinearRgba::new(0.0, 0.8, 1.0, 1.0);
-        } else {
-            mat.base_color.set_alpha(0.1);
-            mat.emissive = LinearRgba::new(0.0, 0.1, 0.15, 1.0);
+        hand_states.hands.retain(|(c, _), _| *c != client);
+        for side in [HandSide::Right, HandSide::Left] {
+            if hand_states.out_of_bounds.contains(&(client, side)) {
+                continue;
+            }
+            if let Some(center) = hand_centers.get(&side) {
+                hand_states.hands.insert((client, side), HandState {
+                    center: *center,
+                    gesture: hand_gestures.get(&side).copied().unwrap_or_default(),
+                });
+            }
         }
     }
-    if let Some(mat) = materials.get_mut(&hand_mats.left) {
-        if show_left {
-            mat.base_color.set_alpha(1.0);
-            mat.emissive = LinearRgba::new(1.0, 0.0, 0.8, 1.0);
-        } else {
-            mat.base_color.set_alpha(0.1);
-            mat.emissive = LinearRgba::new(0.15, 0.0, 0.1, 1.0);
+
+    // パケットが届かないフレームも補間を進める
+    for (point, mut transform) in hand_query.iter_mut() {
+        if let Some(position) = smoothing.predict((point.client, point.side), point.id) {
+            transform.translation = position;
         }
     }
 
-    let mut current_positions: HashMap<(HandSide, usize), Vec3> = HashMap::new();
-
-    for (point, transform) in hand_query.iter() {
-        if transform.translation.y > -50.0 {
-            current_positions.insert((point.side, point.id), transform.translation);
+    if !packets.is_empty() {
+        for (entity, mut box_force, _, _) in box_query.iter_mut() {
+            box_force.force = total_force_field.get(&entity).copied().unwrap_or(Vec3::ZERO);
         }
     }
 
-    for side in [HandSide::Right, HandSide::Left] {
-        let (is_visible, base_color) = if side == HandSide::Right { 
-            (show_right, Color::srgba(0.0, 1.0, 1.0, 1.0))
-        } else { 
-            (show_left, Color::srgba(1.0, 0.0, 1.0, 1.0))
-        };
+    hand_states.hands.retain(|key, _| hand_presence.is_visible(*key, current_time));
+    hand_states.out_of_bounds.retain(|key| hand_presence.is_visible(*key, current_time));
 
-        let color = if is_visible {
-            base_color
-        } else {
-            base_color.with_alpha(0.1)
+    for (&(client, side), handle) in &hand_mats.materials {
+        let Some(mat) = materials.get_mut(handle) else {
+            continue;
         };
+        let emissive = hand_color(client, side).to_linear();
+        if hand_presence.is_visible((client, side), current_time) {
+            mat.base_color.set_alpha(1.0);
+            mat.emissive = emissive;
+        } else {
+            mat.base_color.set_alpha(0.1);
+            mat.emissive = LinearRgba::rgb(emissive.red * 0.15, emissive.green * 0.15, emissive.blue * 0.15);
+        }
+    }
+}
 
-        for &(start_idx, end_idx) in HAND_CONNECTIONS {
-            if let (Some(&start), Some(&end)) = (
-                current_positions.get(&(side, start_idx)),
-                current_positions.get(&(side, end_idx))
-            ) {
-                gizmos.line(start, end, color);
-            }
+fn draw_hand_skeleton(
+    gizmos: &mut Gizmos,
+    connections: &[(usize, usize)],
+    position: impl Fn(usize) -> Option<Vec3>,
+    color: Color,
+) {
+    for &(start_idx, end_idx) in connections {
+        if let (Some(start), Some(end)) = (position(start_idx), position(end_idx)) {
+            gizmos.line(start, end, color);
         }
     }
 }
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+#[cfg(feature = "ui")]
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "ui")]
+use crate::display::world_to_window;
+#[cfg(feature = "ui")]
+use crate::packet::ClientId;
+#[cfg(feature = "ui")]
+use crate::profile::ActiveProfile;
+#[cfg(feature = "ui")]
+use crate::{HandPoint, HandSide};
+
+// メモの入力と表示は egui で行うので、UI なしのビルドでは保存済みのメモを読み書きするだけにする
+#[cfg(feature = "ui")]
+const START_GAP: f32 = 2.5;
+#[cfg(feature = "ui")]
+const PULL_DISTANCE: f32 = 4.0;
+#[cfg(feature = "ui")]
+const COOLDOWN: f32 = 1.0;
+
+#[cfg(feature = "ui")]
+pub const PRESET_PHRASES: &[&str] = &["TODO", "Check this", "Looks good", "Bug here", "Question?"];
+
+#[derive(Component, Debug, Clone)]
+pub struct Note {
+    pub text: String,
+}
+
+#[cfg(feature = "ui")]
+#[derive(Resource, Default)]
+struct NoteEditor {
+    editing: Option<Entity>,
+    buffer: String,
+    focus_requested: bool,
+}
+
+#[cfg(feature = "ui")]
+#[derive(Resource, Default)]
+struct PullGesture {
+    // クライアントごとに独立して判定する
+    start: HashMap<ClientId, (Vec3, f32)>,
+    last_spawn: f32,
+}
+
+#[cfg(feature = "ui")]
+pub struct NotesPlugin;
+
+#[cfg(feature = "ui")]
+impl Plugin for NotesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(NoteEditor::default())
+            .insert_resource(PullGesture::default())
+            .add_systems(Update, (detect_pinch_pull, note_editor_ui, draw_notes).chain());
+    }
+}
+
+pub fn note_bundle(position: Vec3, text: String) -> impl Bundle {
+    (SpatialBundle::from_transform(Transform::from_translation(position)), Note { text })
+}
+
+#[cfg(feature = "ui")]
+fn detect_pinch_pull(
+    mut commands: Commands,
+    points: Query<(&HandPoint, &Transform)>,
+    mut pull: ResMut<PullGesture>,
+    mut editor: ResMut<NoteEditor>,
+    profile: Res<ActiveProfile>,
+    time: Res<Time>,
+) {
+    let pinch_distance = profile.pinch_distance;
+    let positions: HashMap<(ClientId, HandSide, usize), Vec3> = points
+        .iter()
+        .filter(|(_, t)| t.translation.y > -50.0)
+        .map(|(p, t)| ((p.client, p.side, p.id), t.translation))
+        .collect();
+    let clients: HashSet<ClientId> = positions.keys().map(|(c, _, _)| *c).collect();
+    let now = time.elapsed_seconds();
+
+    for client in clients {
+        // 親指と人差し指の先の中点 (つまんでいなければ None)
+        let pinch = |side: HandSide| {
+            let thumb = positions.get(&(client, side, 4))?;
+            let index = positions.get(&(client, side, 8))?;
+            (thumb.distance(*index) < pinch_distance).then(|| (*thumb + *index) / 2.0)
+        };
+
+        let (Some(right), Some(left)) = (pinch(HandSide::Right), pinch(HandSide::Left)) else {
+            pull.start.remove(&client);
+            continue;
+        };
+        let gap = right.distance(left);
+
+        match pull.start.get(&client).copied() {
+            None => {
+                if gap < START_GAP {
+                    pull.start.insert(client, ((right + left) / 2.0, gap));
+                }
+            }
+            Some((anchor, start_gap)) => {
+                if gap > start_gap + PULL_DISTANCE && now - pull.last_spawn > COOLDOWN && editor.editing.is_none() {
+                    let entity = commands.spawn(note_bundle(anchor, String::new())).id();
+                    editor.editing = Some(entity);
+                    editor.buffer.clear();
+                    editor.focus_requested = true;
+                    pull.last_spawn = now;
+                    pull.start.remove(&client);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+fn note_editor_ui(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut editor: ResMut<NoteEditor>,
+    mut notes: Query<&mut Note>,
+) {
+    let Some(entity) = editor.editing else {
+        return;
+    };
+    if notes.get(entity).is_err() {
+        editor.editing = None;
+        return;
+    }
+
+    let mut confirm = false;
+    let mut cancel = false;
+    egui::Window::new("New note")
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let response = ui.text_edit_singleline(&mut editor.buffer);
+            if editor.focus_requested {
+                response.request_focus();
+                editor.focus_requested = false;
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                confirm = true;
+            }
+            ui.horizontal_wrapped(|ui| {
+                for phrase in PRESET_PHRASES {
+                    if ui.button(*phrase).clicked() {
+                        editor.buffer = phrase.to_string();
+                        confirm = true;
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    confirm = true;
+                }
+                if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    cancel = true;
+                }
+            });
+        });
+
+    if confirm && !editor.buffer.trim().is_empty() {
+        if let Ok(mut note) = notes.get_mut(entity) {
+            note.text = editor.buffer.trim().to_string();
+        }
+        editor.editing = None;
+    } else if cancel {
+        commands.entity(entity).despawn_recursive();
+        editor.editing = None;
+    }
+}
+
+#[cfg(feature = "ui")]
+fn draw_notes(
+    mut contexts: EguiContexts,
+    notes: Query<(Entity, &Note, &GlobalTransform)>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut gizmos: Gizmos,
+) {
+    let (Ok((camera, camera_transform)), Ok(window)) = (camera.get_single(), windows.get_single()) else {
+        return;
+    };
+    let ctx = contexts.ctx_mut();
+    for (entity, note, transform) in notes.iter() {
+        let position = transform.translation();
+        gizmos.sphere(position, Quat::IDENTITY, 0.15, Color::srgb(1.0, 1.0, 0.6));
+        if note.text.is_empty() {
+            continue;
+        }
+        let Some(screen) = world_to_window(camera, camera_transform, window, position) else {
+            continue;
+        };
+        egui::Area::new(egui::Id::new(("note", entity)))
+            .fixed_pos(egui::pos2(screen.x, screen.y))
+            .pivot(egui::Align2::CENTER_BOTTOM)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(&note.text);
+                });
+            });
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::gesture::Finger;
+use crate::core::occlusion::FingerMemory;
+use crate::packet::{ClientId, ClientPackets, HandPacket, IncomingPacket, PacketSet};
+use crate::{update_hands_and_physics, HandKey, HandSide};
+
+// 指が隠れて一部の点だけが届いた手。抜けた点は少しの間だけ直前の形で補い、
+// その指はジェスチャーの判定から外す (classifier.rs)
+#[derive(Resource, Default)]
+pub struct FingerOcclusion {
+    memory: HashMap<HandKey, FingerMemory>,
+    occluded: HashMap<HandKey, Vec<Finger>>,
+}
+
+impl FingerOcclusion {
+    pub fn fingers(&self, key: HandKey) -> &[Finger] {
+        self.occluded.get(&key).map_or(&[], Vec::as_slice)
+    }
+}
+
+pub struct OcclusionPlugin;
+
+impl Plugin for OcclusionPlugin {
+    fn build(&self, app: &mut App) {
+        // 記録には届いたままのパケットを残し、再生したパケットも同じように補う
+        app.insert_resource(FingerOcclusion::default()).add_systems(
+            Update,
+            fill_occluded_fingers.after(PacketSet::Override).before(update_hands_and_physics),
+        );
+    }
+}
+
+fn fill_occluded_fingers(
+    mut occlusion: ResMut<FingerOcclusion>,
+    mut incoming: ResMut<IncomingPacket>,
+    mut clients: ResMut<ClientPackets>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_seconds();
+    let packets = incoming.0.iter_mut().map(|p| (0, p)).chain(clients.packets.iter_mut().map(|(c, p)| (*c, p)));
+    for (client, packet) in packets {
+        fill_packet(&mut occlusion, client, packet, now);
+    }
+}
+
+fn fill_packet(occlusion: &mut FingerOcclusion, client: ClientId, packet: &mut HandPacket, now: f32) {
+    for side in [HandSide::Right, HandSide::Left] {
+        let key = (client, side);
+        let Some(hand) = packet.hands.iter_mut().find(|h| h.label == side.label()) else {
+            continue;
+        };
+        let fingers = occlusion.memory.entry(key).or_default().fill(&mut hand.landmarks, now);
+        if fingers.is_empty() {
+            occlusion.occluded.remove(&key);
+        } else {
+            if occlusion.fingers(key) != fingers.as_slice() {
+                debug!(client, side = side.label(), ?fingers, "fingers occluded");
+            }
+            occlusion.occluded.insert(key, fingers);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::packet::ClientId;
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandSide, HandStates};
+
+// 左右の手の点がこの距離より近い組がいくつもあれば手が重なっているとみなす
+const CONTACT_DISTANCE: f32 = 0.4;
+const MIN_CONTACTS: usize = 3;
+// 離れてからもしばらくは重なっている扱いにし、判定が点滅しないようにする
+const RELEASE_TIME: f32 = 0.3;
+
+// 手の重なりの判定が済んだあとに低信頼度の印を読むシステム用
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HandOverlapSet;
+
+#[derive(Resource, Default)]
+struct HandOverlap {
+    // クライアントごとに最後に重なっていた時刻
+    last_overlap: HashMap<ClientId, f32>,
+}
+
+pub struct HandOverlapPlugin;
+
+impl Plugin for HandOverlapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HandOverlap::default()).add_systems(
+            Update,
+            (detect_overlap, separate_hands)
+                .chain()
+                .in_set(HandOverlapSet)
+                .after(update_hands_and_physics),
+        );
+    }
+}
+
+// 手ごとに別の衝突グループに入れ、重なっている間だけ相方の手を衝突相手から外す
+pub fn hand_group(client: ClientId, side: HandSide) -> Group {
+    let index = client as u32 * 2 + if side == HandSide::Right { 0 } else { 1 };
+    Group::from_bits_truncate(1 << index.min(31))
+}
+
+fn partner(side: HandSide) -> HandSide {
+    if side == HandSide::Right { HandSide::Left } else { HandSide::Right }
+}
+
+fn detect_overlap(
+    mut overlap: ResMut<HandOverlap>,
+    mut hand_states: ResMut<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let mut by_hand: HashMap<HandKey, Vec<Vec3>> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let key = (point.client, point.side);
+        if hand_states.hands.contains_key(&key) {
+            by_hand.entry(key).or_default().push(transform.translation);
+        }
+    }
+
+    let clients: HashSet<ClientId> = by_hand.keys().map(|(c, _)| *c).collect();
+    for client in clients {
+        let (Some(right), Some(left)) = (by_hand.get(&(client, HandSide::Right)), by_hand.get(&(client, HandSide::Left)))
+        else {
+            continue;
+        };
+        let contacts = right
+            .iter()
+            .flat_map(|r| left.iter().map(move |l| r.distance(*l)))
+            .filter(|d| *d < CONTACT_DISTANCE)
+            .count();
+        if contacts >= MIN_CONTACTS && overlap.last_overlap.insert(client, now).is_none() {
+            warn!(client, "hands intersecting; interactions paused until they separate");
+        }
+    }
+
+    overlap.last_overlap.retain(|client, since| {
+        let active = now - *since < RELEASE_TIME;
+        if !active {
+            info!(client, "hands separated");
+        }
+        active
+    });
+
+    hand_states.low_confidence.clear();
+    for client in overlap.last_overlap.keys() {
+        for side in [HandSide::Right, HandSide::Left] {
+            hand_states.low_confidence.insert((*client, side));
+        }
+        let hand = |side| hand_states.hands.get(&(*client, side));
+        if let (Some(right), Some(left)) = (hand(HandSide::Right), hand(HandSide::Left)) {
+            let middle = (right.center + left.center) / 2.0;
+            gizmos.sphere(middle, Quat::IDENTITY, 1.5, Color::srgb(1.0, 0.2, 0.1));
+            gizmos.line(right.center, left.center, Color::srgb(1.0, 0.2, 0.1));
+        }
+    }
+}
+
+fn separate_hands(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    points: Query<(Entity, &HandPoint, Option<&CollisionGroups>)>,
+) {
+    for (entity, point, groups) in points.iter() {
+        let membership = hand_group(point.client, point.side);
+        let filters = if hand_states.low_confidence.contains(&(point.client, point.side)) {
+            Group::ALL - hand_group(point.client, partner(point.side))
+        } else {
+            Group::ALL
+        };
+        let wanted = CollisionGroups::new(membership, filters);
+        if groups != Some(&wanted) {
+            commands.entity(entity).insert(wanted);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::checkers::CheckerPiece;
+use crate::grab::{Grabbable, Held};
+use crate::spawn::SpawnedBox;
+use crate::versus::Team;
+use crate::{hand_color, update_hands_and_physics, HandKey, HandPoint};
+
+// 持ち主の手の色へ寄せる割合と、色が移り変わる速さ (1/秒)
+const TINT_STRENGTH: f32 = 0.45;
+const TINT_RATE: f32 = 3.0;
+
+// 最後に触れた (ぶつかった・掴んだ) 手。ゲームモードは得点や陣地の判定にこれを読む
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Owner {
+    pub hand: HandKey,
+    pub since: f32,
+}
+
+// 持ち主の色に染める前の色。染めるときに材質を複製して、ほかの物体と色を分ける
+#[derive(Component)]
+struct Tint {
+    base: LinearRgba,
+    shown: LinearRgba,
+}
+
+pub struct OwnershipPlugin;
+
+impl Plugin for OwnershipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (attribute_contacts, tint_owned).chain().after(update_hands_and_physics));
+    }
+}
+
+// 手の関節と物体の接触、掴んだ手から持ち主を決める
+#[allow(clippy::type_complexity)]
+fn attribute_contacts(
+    mut commands: Commands,
+    mut contacts: EventReader<ContactForceEvent>,
+    hand_points: Query<&HandPoint>,
+    objects: Query<Option<&Owner>, (With<RigidBody>, Or<(With<SpawnedBox>, With<Grabbable>)>)>,
+    grabbed: Query<(Entity, &Held), Changed<Held>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let mut claim = |entity: Entity, hand: HandKey| {
+        if let Ok(owner) = objects.get(entity)
+            && owner.is_none_or(|o| o.hand != hand)
+        {
+            commands.entity(entity).insert(Owner { hand, since: now });
+        }
+    };
+    for contact in contacts.read() {
+        for (hand, object) in [(contact.collider1, contact.collider2), (contact.collider2, contact.collider1)] {
+            if let Ok(point) = hand_points.get(hand) {
+                claim(object, (point.client, point.side));
+            }
+        }
+    }
+    for (entity, held) in grabbed.iter() {
+        claim(entity, held.by);
+    }
+}
+
+// 持ち主の手の色へゆっくり寄せる。チームの色で塗られた対戦の物体と、色で陣営を見分けるチェッカーの駒は染めない
+#[allow(clippy::type_complexity)]
+fn tint_owned(
+    mut commands: Commands,
+    mut owned: Query<
+        (Entity, &Owner, &mut Handle<StandardMaterial>, Option<&mut Tint>),
+        (Without<Team>, Without<CheckerPiece>),
+    >,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    let follow = 1.0 - (-TINT_RATE * time.delta_seconds()).exp();
+    for (entity, owner, mut handle, tint) in owned.iter_mut() {
+        let target_of = |base: LinearRgba| base.mix(&hand_color(owner.hand.0, owner.hand.1).to_linear(), TINT_STRENGTH);
+        let Some(mut tint) = tint else {
+            let Some(material) = materials.get(&*handle).cloned() else {
+                continue;
+            };
+            let base = material.base_color.to_linear();
+            *handle = materials.add(material);
+            commands.entity(entity).insert(Tint { base, shown: base });
+            continue;
+        };
+        let target = target_of(tint.base);
+        if tint.shown == target {
+            continue;
+        }
+        let gap = Vec3::new(tint.shown.red - target.red, tint.shown.green - target.green, tint.shown.blue - target.blue);
+        tint.shown = if gap.length() < 1e-3 { target } else { tint.shown.mix(&target, follow) };
+        if let Some(material) = materials.get_mut(&*handle) {
+            material.base_color = tint.shown.with_alpha(material.base_color.alpha()).into();
+        }
+    }
+}
@@ -0,0 +1,371 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::core::auth::{self, ReplayGuard};
+use crate::core::split_input::{parse_ports, relabel, SplitMerger, SplitPort};
+use crate::core::thinning::{DepthCache, ThinningRequest};
+use crate::error::{AppError, Health};
+use crate::settings::Settings;
+
+pub const RECEIVER_ADDR: &str = "127.0.0.1:5005";
+// 間引きの希望を送り直す間隔 (秒)。返信は届かないこともあるので定期的に送る
+const THINNING_INTERVAL: f32 = 1.0;
+// 壊れたパケットが最後に届いてから、受信の警告を消すまでの時間 (秒)
+const MALFORMED_GRACE: f32 = 5.0;
+// 片手用の追跡器からこれだけ届かなければ、その手は写っていないとみなす (秒)
+const SPLIT_PRESENCE_TIMEOUT: f32 = 0.3;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Landmark {
+    pub id: usize,
+    pub x: f32,
+    pub y: f32,
+    // 間引いたパケットでは省かれる。受信時に DepthCache で埋める
+    #[serde(default = "missing_z")]
+    pub z: f32,
+}
+
+fn missing_z() -> f32 {
+    f32::NAN
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OneHand {
+    pub label: String,
+    pub landmarks: Vec<Landmark>,
+    #[serde(default)]
+    pub gesture: String,
+    // 姿勢推定モデルの肘 (手と同じ正規化座標)。送られてくれば前腕で奥行きと向きを補う
+    #[serde(default)]
+    pub elbow: Option<Landmark>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HandPacket {
+    pub hands: Vec<OneHand>,
+    #[serde(default)]
+    pub snap: bool,
+    // 送信側が音の拍を検出したフレームで true
+    #[serde(default)]
+    pub beat: bool,
+    // 送信側の撮影時刻 (秒)。古い送信側では受信時刻で代用する
+    #[serde(default)]
+    pub timestamp: Option<f64>,
+}
+
+// 受信ポートを開けなかったときは入れない。リプレイやゴーストはそれでも動く
+#[derive(Resource)]
+pub struct UdpConnection(pub UdpSocket);
+
+impl UdpConnection {
+    pub fn bind(addr: &str) -> Result<Self, AppError> {
+        let bind_error = |source| AppError::Bind { addr: addr.to_string(), source };
+        let socket = UdpSocket::bind(addr).map_err(bind_error)?;
+        socket.set_nonblocking(true).map_err(bind_error)?;
+        Ok(Self(socket))
+    }
+}
+
+// 片手ずつ別の追跡器から受けるポート (--split-inputs)。届いた手は 1 つの送信元のパケットにまとめる
+#[derive(Resource)]
+pub struct SplitInputs {
+    ports: Vec<(UdpSocket, SplitPort)>,
+    merger: SplitMerger,
+    depth: DepthCache,
+    // ポートごとに最後に送ってきた追跡器のアドレス。間引きの希望はそのポートのソケットから返す
+    senders: Vec<Option<SocketAddr>>,
+}
+
+impl SplitInputs {
+    pub fn bind(spec: &str) -> Result<Self, AppError> {
+        let ports = parse_ports(spec).map_err(|message| AppError::BadArgument { name: "--split-inputs", message })?;
+        let ports = ports
+            .into_iter()
+            .map(|port| {
+                let addr = format!("127.0.0.1:{}", port.port);
+                UdpConnection::bind(&addr).map(|connection| (connection.0, port))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for (_, port) in &ports {
+            let label = port.label.as_deref().unwrap_or("as sent");
+            info!(port = port.port, label, "listening for one-hand tracker");
+        }
+        Ok(Self {
+            merger: SplitMerger::new(ports.len()),
+            senders: vec![None; ports.len()],
+            ports,
+            depth: DepthCache::default(),
+        })
+    }
+
+    // 返信に使うソケットと、その先の追跡器
+    fn senders(&self) -> impl Iterator<Item = (&UdpSocket, SocketAddr)> {
+        self.ports.iter().zip(&self.senders).filter_map(|((socket, _), addr)| Some((socket, (*addr)?)))
+    }
+}
+
+// このフレームで処理するパケット (UDP またはリプレイから)
+#[derive(Resource, Default)]
+pub struct IncomingPacket(pub Option<HandPacket>);
+
+// 送信元ごとに振る番号 (最初に届いた送信元が 0)
+pub type ClientId = u8;
+
+const MAX_CLIENTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientSource {
+    Udp(SocketAddr),
+    // 片手用の追跡器をまとめたもの。追跡器のアドレスは返信にだけ使う
+    Split,
+}
+
+#[derive(Resource, Default)]
+pub struct ClientRegistry {
+    pub sources: Vec<ClientSource>,
+}
+
+impl ClientRegistry {
+    // 片手用の追跡器をまとめた手はクライアント 0 に固定する。
+    // 追跡器が立ち上げ直して送信元のポートが変わっても、起動の順が入れ替わっても番号は動かない
+    pub fn with_split_inputs() -> Self {
+        Self { sources: vec![ClientSource::Split] }
+    }
+
+    fn client_id(&mut self, source: ClientSource) -> Option<ClientId> {
+        if let Some(index) = self.sources.iter().position(|s| *s == source) {
+            return Some(index as ClientId);
+        }
+        if self.sources.len() >= MAX_CLIENTS {
+            return None;
+        }
+        info!(client = self.sources.len(), ?source, "new client");
+        self.sources.push(source);
+        Some((self.sources.len() - 1) as ClientId)
+    }
+
+    // 主ポートに送ってきた送信元のアドレス
+    pub fn addresses(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.sources.iter().filter_map(|s| match s {
+            ClientSource::Udp(addr) => Some(*addr),
+            ClientSource::Split => None,
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.sources.len().max(1)
+    }
+}
+
+// クライアント 1 以降がこのフレームに送ってきたパケット
+#[derive(Resource, Default)]
+pub struct ClientPackets {
+    pub packets: HashMap<ClientId, HandPacket>,
+}
+
+// 共有の秘密鍵が設定されていれば、正しく署名されたパケットだけを受け付ける
+#[derive(Resource, Default)]
+pub struct PacketAuth {
+    key: Option<Vec<u8>>,
+    // 署名がない・合わないために捨てたパケットの数
+    pub rejected: u64,
+    // 署名は正しいが、古いか二度目のために捨てたパケットの数
+    pub replayed: u64,
+    guard: ReplayGuard,
+}
+
+impl PacketAuth {
+    // 鍵はコマンドラインに残らないようファイルから読む。前後の空白と改行は除く
+    // 鍵が読めないまま署名なしで受け付けることはしない。起動をやめる
+    pub fn from_secret_file(path: Option<&Path>) -> Result<Self, AppError> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let secret = std::fs::read_to_string(path).map_err(|source| AppError::Read { path: path.into(), source })?;
+        if secret.trim().is_empty() {
+            return Err(AppError::EmptySecret { path: path.into() });
+        }
+        info!("accepting only packets signed with the secret in {}", path.display());
+        Ok(Self { key: Some(secret.trim().as_bytes().to_vec()), ..default() })
+    }
+
+    // 管理用の接続でも同じ秘密を合言葉に使う (admin.rs)
+    pub fn key(&self) -> Option<&[u8]> {
+        self.key.as_deref()
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PacketSet {
+    Receive,
+    Override,
+}
+
+// 署名を確かめて読む。読めないものは数えて捨てる
+fn decode(
+    auth: &mut PacketAuth,
+    data: &[u8],
+    src: SocketAddr,
+    malformed: &mut (u64, f32, String),
+    now: f32,
+) -> Option<HandPacket> {
+    let _span = info_span!("packet_decode", bytes = data.len()).entered();
+    let valid_data = match &auth.key {
+        Some(key) => match auth::verify(key, data) {
+            Some(signed) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+                if let Err(rejection) = auth.guard.check(&signed, now) {
+                    if auth.replayed == 0 {
+                        warn!(%src, ?rejection, "dropping stale or replayed packet");
+                    }
+                    auth.replayed += 1;
+                    return None;
+                }
+                signed.payload
+            }
+            None => {
+                // 送信元は偽れるので、数えるだけで送信元の登録はしない
+                if auth.rejected == 0 {
+                    warn!(%src, "dropping unauthenticated packet");
+                }
+                auth.rejected += 1;
+                return None;
+            }
+        },
+        None => data,
+    };
+    match serde_json::from_slice::<HandPacket>(valid_data) {
+        Ok(packet) => Some(packet),
+        Err(e) => {
+            debug!(%src, error = %e, "dropping malformed packet");
+            *malformed = (malformed.0 + 1, now, e.to_string());
+            None
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn receive_packets(
+    socket_res: Option<Res<UdpConnection>>,
+    mut split: Option<ResMut<SplitInputs>>,
+    mut registry: ResMut<ClientRegistry>,
+    mut incoming: ResMut<IncomingPacket>,
+    mut clients: ResMut<ClientPackets>,
+    mut auth: ResMut<PacketAuth>,
+    mut health: ResMut<Health>,
+    mut depths: Local<HashMap<ClientId, DepthCache>>,
+    mut malformed: Local<(u64, f32, String)>,
+    time: Res<Time>,
+) {
+    let mut buf = [0; 65536];
+    incoming.0 = None;
+    clients.packets.clear();
+    if socket_res.is_none() && split.is_none() {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    let mut received = Vec::new();
+
+    while let Some((amt, src)) = socket_res.as_ref().and_then(|s| s.0.recv_from(&mut buf).ok()) {
+        let Some(mut packet) = decode(&mut auth, &buf[..amt], src, &mut malformed, now) else {
+            continue;
+        };
+        let Some(client) = registry.client_id(ClientSource::Udp(src)) else {
+            continue;
+        };
+        let depth = depths.entry(client).or_default();
+        for hand in &mut packet.hands {
+            depth.fill(hand);
+        }
+        received.push((client, packet));
+    }
+    if let Some(split) = split.as_deref_mut() {
+        for (index, (socket, port)) in split.ports.iter().enumerate() {
+            while let Ok((amt, src)) = socket.recv_from(&mut buf) {
+                let Some(mut packet) = decode(&mut auth, &buf[..amt], src, &mut malformed, now) else {
+                    continue;
+                };
+                split.senders[index] = Some(src);
+                relabel(&mut packet, port.label.as_deref());
+                for hand in &mut packet.hands {
+                    split.depth.fill(hand);
+                }
+                split.merger.push(index, packet, now);
+            }
+        }
+        let merged = split.merger.take(now, SPLIT_PRESENCE_TIMEOUT);
+        if let Some((packet, client)) = merged.zip(registry.client_id(ClientSource::Split)) {
+            received.push((client, packet));
+        }
+    }
+    for (client, packet) in received {
+        match client {
+            0 => incoming.0 = Some(packet),
+            client => {
+                clients.packets.insert(client, packet);
+            }
+        }
+    }
+    // 壊れたパケットは捨てて続けるが、送信側の不具合に気づけるよう数と最後の理由を出しておく
+    if malformed.0 > 0 && now - malformed.1 < MALFORMED_GRACE {
+        health.degraded("receiver", format!("{} malformed packets dropped (last: {})", malformed.0, malformed.2));
+    } else {
+        health.ok("receiver");
+    }
+    if auth.rejected > 0 {
+        health.degraded("auth", format!("{} unsigned packets dropped", auth.rejected));
+    }
+    // 全部が古いと言われるときは、送る側と時計がずれている
+    if auth.replayed > 0 {
+        health.degraded("replay", format!("{} stale or replayed packets dropped (check the clocks)", auth.replayed));
+    }
+}
+
+// 送信元ごとに送る頻度と z の有無、使うカメラの希望を返す。切ったときは全部送るよう一度だけ伝える。
+// 追跡器は送り先のポート以外からの返信を捨てるので、片手用の追跡器にはそのポートのソケットから返す
+pub fn request_thinning(
+    socket_res: Option<Res<UdpConnection>>,
+    split: Option<Res<SplitInputs>>,
+    registry: Res<ClientRegistry>,
+    settings: Res<Settings>,
+    mut last_sent: Local<Option<(f32, ThinningRequest, usize)>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let thinning = &settings.thinning;
+    let request = if thinning.enabled {
+        ThinningRequest { rate: thinning.rate, include_z: thinning.include_z, camera: thinning.camera }
+    } else {
+        ThinningRequest { camera: thinning.camera, ..ThinningRequest::FULL }
+    };
+    let mut targets: Vec<(&UdpSocket, SocketAddr)> = Vec::new();
+    if let Some(socket_res) = &socket_res {
+        targets.extend(registry.addresses().map(|addr| (&socket_res.0, addr)));
+    }
+    if let Some(split) = &split {
+        targets.extend(split.senders());
+    }
+    let due = match *last_sent {
+        None => thinning.enabled || thinning.camera.is_some(),
+        Some((at, sent, count)) => {
+            let stale = (thinning.enabled || thinning.camera.is_some()) && now - at >= THINNING_INTERVAL;
+            sent != request || count != targets.len() || stale
+        }
+    };
+    if !due || targets.is_empty() {
+        return;
+    }
+    let Ok(payload) = serde_json::to_vec(&request) else {
+        return;
+    };
+    for (socket, addr) in &targets {
+        if let Err(e) = socket.send_to(&payload, addr) {
+            debug!(%addr, error = %e, "failed to send thinning request");
+        }
+    }
+    *last_sent = Some((now, request, targets.len()));
+}
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::pose::{palm_rotation, DualQuat};
+use crate::forearm::Forearms;
+use crate::smoothing::Smoothing;
+use crate::{update_hands_and_physics, HandKey, HandSide, HandStates};
+
+const WRIST: usize = 0;
+const INDEX_MCP: usize = 5;
+const MIDDLE_MCP: usize = 9;
+const PINKY_MCP: usize = 17;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PalmPose {
+    // 中指の付け根を原点にした手のひらの姿勢
+    pub pose: DualQuat,
+    pub linear_velocity: Vec3,
+    // 回転軸の向きで、長さが角速度 (rad/秒)
+    pub angular_velocity: Vec3,
+}
+
+impl PalmPose {
+    pub fn position(&self) -> Vec3 {
+        self.pose.translation()
+    }
+
+    pub fn orientation(&self) -> Quat {
+        self.pose.rotation()
+    }
+}
+
+// 見えている手の手のひらの姿勢。手を扱うプラグインはランドマークから計算し直さずにこれを読む
+#[derive(Resource, Default)]
+pub struct PalmPoses {
+    pub poses: HashMap<HandKey, PalmPose>,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PalmPoseSet;
+
+pub struct PalmPosePlugin;
+
+impl Plugin for PalmPosePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PalmPoses::default())
+            .add_systems(Update, solve_palm_poses.in_set(PalmPoseSet).after(update_hands_and_physics));
+    }
+}
+
+fn solve_palm_poses(
+    hand_states: Res<HandStates>,
+    smoothing: Res<Smoothing>,
+    forearms: Res<Forearms>,
+    mut palms: ResMut<PalmPoses>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut poses = HashMap::new();
+    for key in hand_states.hands.keys() {
+        let point = |id| smoothing.sample(*key, id);
+        let (Some(wrist), Some(index), Some(middle), Some(pinky)) =
+            (point(WRIST), point(INDEX_MCP), point(MIDDLE_MCP), point(PINKY_MCP))
+        else {
+            continue;
+        };
+        // 指が隠れて付け根の点が崩れているときは、前腕の延長を手の向きにする
+        let forward = match forearms.direction(*key) {
+            Some(dir) if forearms.is_occluded(*key) => wrist + dir * wrist.distance(middle),
+            _ => middle,
+        };
+        let Some(rotation) = palm_rotation(wrist, index, forward, pinky, key.1 == HandSide::Right) else {
+            continue;
+        };
+
+        let (linear_velocity, angular_velocity) = match palms.poses.get(key) {
+            Some(previous) if dt > 0.0 => {
+                let (axis, angle) = (rotation * previous.orientation().inverse()).to_axis_angle();
+                // 180 度を超える回転は逆回りの小さい回転とみなす
+                let angle = if angle > std::f32::consts::PI { angle - std::f32::consts::TAU } else { angle };
+                ((middle - previous.position()) / dt, axis * angle / dt)
+            }
+            _ => (Vec3::ZERO, Vec3::ZERO),
+        };
+        poses.insert(*key, PalmPose {
+            pose: DualQuat::from_rotation_translation(rotation, middle),
+            linear_velocity,
+            angular_velocity,
+        });
+    }
+    palms.poses = poses;
+}
@@ -0,0 +1,235 @@
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::confirm::Confirmations;
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+#[cfg(feature = "ui")]
+use crate::display::world_to_window;
+use crate::field_view::FieldView;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::snapshot::SceneCommand;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::SpawnRequest;
+use crate::{HandKey, HandPoint, HandSide, HandStates};
+
+const INDEX_TIP: usize = 8;
+// palm_rotation の z は手の甲の向きなので、手のひらはその逆を向く
+const PALM_UP: f32 = 0.75;
+const SUMMON_TIME: f32 = 0.3;
+// 手のひらが下がってからもしばらくは開いたままにする
+const CLOSE_GRACE: f32 = 0.25;
+const MENU_HEIGHT: f32 = 2.5;
+const OPTION_SPACING: f32 = 1.6;
+const OPTION_RADIUS: f32 = 0.5;
+const SELECT_COOLDOWN: f32 = 0.6;
+
+struct MenuAction {
+    label: String,
+    system: SystemId,
+    // 取り消せない操作。触れただけでは動かさず、指を置いたまま保たせる
+    confirm: bool,
+}
+
+// メニューに並べる項目。ほかのプラグインは add_menu_action で項目を足す
+#[derive(Resource, Default)]
+pub struct MenuActions {
+    actions: Vec<MenuAction>,
+}
+
+pub trait PalmMenuExt {
+    fn add_menu_action<M>(&mut self, label: &str, system: impl IntoSystem<(), (), M> + 'static) -> &mut Self;
+    fn add_confirmed_menu_action<M>(&mut self, label: &str, system: impl IntoSystem<(), (), M> + 'static)
+    -> &mut Self;
+}
+
+fn push_action<M>(app: &mut App, label: &str, system: impl IntoSystem<(), (), M> + 'static, confirm: bool) {
+    let system = app.world_mut().register_system(system);
+    app.world_mut()
+        .get_resource_or_insert_with(MenuActions::default)
+        .actions
+        .push(MenuAction { label: label.to_string(), system, confirm });
+}
+
+impl PalmMenuExt for App {
+    fn add_menu_action<M>(&mut self, label: &str, system: impl IntoSystem<(), (), M> + 'static) -> &mut Self {
+        push_action(self, label, system, false);
+        self
+    }
+
+    fn add_confirmed_menu_action<M>(&mut self, label: &str, system: impl IntoSystem<(), (), M> + 'static)
+    -> &mut Self {
+        push_action(self, label, system, true);
+        self
+    }
+}
+
+struct OpenMenu {
+    anchor: Vec3,
+    hovered: Option<usize>,
+    closing_since: Option<f32>,
+}
+
+#[derive(Resource, Default)]
+struct PalmMenu {
+    up_since: HashMap<HandKey, f32>,
+    open: HashMap<HandKey, OpenMenu>,
+    last_select: f32,
+}
+
+pub struct PalmMenuPlugin;
+
+impl Plugin for PalmMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuActions>()
+            .insert_resource(PalmMenu::default())
+            .add_menu_action("Spawn box", spawn_box)
+            .add_menu_action("Force view", cycle_field_view)
+            .add_confirmed_menu_action("Clear scene", clear_scene)
+            .add_systems(Update, (summon_menus, select_options).chain().after(PalmPoseSet).in_set(InteractionSet))
+            .add_systems(Update, draw_menus);
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, label_menus);
+    }
+}
+
+fn spawn_box(mut spawns: EventWriter<SpawnRequest>) {
+    spawns.send(SpawnRequest { position: Vec3::new(0.0, 10.0, 0.0), size: 2.0, material: SurfaceMaterial::Wood });
+}
+
+fn cycle_field_view(mut view: ResMut<FieldView>) {
+    *view = view.next();
+}
+
+fn clear_scene(mut scene: EventWriter<SceneCommand>) {
+    scene.send(SceneCommand::Clear);
+}
+
+fn option_position(anchor: Vec3, index: usize, count: usize) -> Vec3 {
+    anchor + Vec3::X * (index as f32 - (count as f32 - 1.0) / 2.0) * OPTION_SPACING
+}
+
+// 開いた手のひらを上に向けてしばらく保つとその上にメニューを出す
+fn summon_menus(mut menu: ResMut<PalmMenu>, palms: Res<PalmPoses>, hand_states: Res<HandStates>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let menu = &mut *menu;
+    for (key, hand) in &hand_states.hands {
+        let palm_up = palms
+            .poses
+            .get(key)
+            .is_some_and(|palm| -(palm.orientation() * Vec3::Z).dot(Vec3::Y) > PALM_UP);
+        let up = palm_up && hand.gesture == Gesture::Open && hand_states.is_confident(*key);
+        if !up {
+            menu.up_since.remove(key);
+            if let Some(open) = menu.open.get_mut(key) {
+                open.closing_since.get_or_insert(now);
+            }
+            continue;
+        }
+        let since = *menu.up_since.entry(*key).or_insert(now);
+        let anchor = palms.poses[key].position() + Vec3::Y * MENU_HEIGHT;
+        match menu.open.get_mut(key) {
+            Some(open) => {
+                open.anchor = anchor;
+                open.closing_since = None;
+            }
+            None if now - since >= SUMMON_TIME => {
+                menu.open.insert(*key, OpenMenu { anchor, hovered: None, closing_since: None });
+            }
+            None => {}
+        }
+    }
+    menu.up_since.retain(|key, _| hand_states.hands.contains_key(key));
+    menu.open.retain(|key, open| {
+        hand_states.hands.contains_key(key) && open.closing_since.is_none_or(|t| now - t < CLOSE_GRACE)
+    });
+}
+
+// もう一方の手の人差し指で項目に触れると、その項目の処理を動かす
+fn select_options(
+    mut commands: Commands,
+    mut menu: ResMut<PalmMenu>,
+    actions: Res<MenuActions>,
+    mut confirmations: ResMut<Confirmations>,
+    points: Query<(&HandPoint, &Transform)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let tips: HashMap<HandKey, Vec3> = points
+        .iter()
+        .filter(|(point, _)| point.id == INDEX_TIP)
+        .map(|(point, transform)| ((point.client, point.side), transform.translation))
+        .collect();
+    let count = actions.actions.len();
+    let menu = &mut *menu;
+    for (&(client, side), open) in menu.open.iter_mut() {
+        let other = if side == HandSide::Right { HandSide::Left } else { HandSide::Right };
+        let hovered = tips.get(&(client, other)).and_then(|tip| {
+            (0..count).find(|&i| option_position(open.anchor, i, count).distance(*tip) < OPTION_RADIUS)
+        });
+        // 触れた瞬間だけ選ぶ。確認が要る項目は指を置いたまま保ちきったときに選ぶ
+        if let Some(index) = hovered {
+            let action = &actions.actions[index];
+            let selected = if action.confirm {
+                let key = (u64::from(client) << 16) | ((side as u64) << 8) | index as u64;
+                confirmations.hold(("palm_menu", key), option_position(open.anchor, index, count), now)
+            } else {
+                open.hovered != Some(index) && now - menu.last_select > SELECT_COOLDOWN
+            };
+            if selected {
+                info!(client, option = action.label, "palm menu");
+                commands.run_system(action.system);
+                menu.last_select = now;
+            }
+        }
+        open.hovered = hovered;
+    }
+}
+
+fn draw_menus(menu: Res<PalmMenu>, actions: Res<MenuActions>, mut gizmos: Gizmos) {
+    let count = actions.actions.len();
+    for open in menu.open.values() {
+        for i in 0..count {
+            let position = option_position(open.anchor, i, count);
+            let color = if open.hovered == Some(i) { Color::srgb(1.0, 0.9, 0.2) } else { Color::srgb(0.6, 0.9, 1.0) };
+            gizmos.sphere(position, Quat::IDENTITY, OPTION_RADIUS, color);
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+fn label_menus(
+    mut contexts: EguiContexts,
+    menu: Res<PalmMenu>,
+    actions: Res<MenuActions>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+) {
+    let (Ok((camera, camera_transform)), Ok(window)) = (camera.get_single(), windows.get_single()) else {
+        return;
+    };
+    let ctx = contexts.ctx_mut();
+    let count = actions.actions.len();
+    for (key, open) in &menu.open {
+        for (i, action) in actions.actions.iter().enumerate() {
+            let position = option_position(open.anchor, i, count);
+            let Some(screen) = world_to_window(camera, camera_transform, window, position + Vec3::Y * OPTION_RADIUS) else {
+                continue;
+            };
+            egui::Area::new(egui::Id::new(("palm_menu", *key, i)))
+                .fixed_pos(egui::pos2(screen.x, screen.y))
+                .pivot(egui::Align2::CENTER_BOTTOM)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.label(&action.label);
+                    });
+                });
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::display::InteractionSet;
+use crate::{update_hands_and_physics, HandPoint};
+
+const PARTICLE_COUNT: usize = 2000;
+const PARTICLE_RADIUS: f32 = 0.18;
+// 粒子を入れておく水槽 (手が届く手前側に置く)
+const BASIN_MIN: Vec3 = Vec3::new(-10.0, -4.9, 2.0);
+const BASIN_MAX: Vec3 = Vec3::new(10.0, 20.0, 15.0);
+// 関節の点どうしの隙間から漏れないよう、見た目より大きな球で押す
+const HAND_RADIUS: f32 = 0.6;
+const SUBSTEPS: usize = 2;
+const MAX_DT: f32 = 1.0 / 30.0;
+
+const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+// 近傍とみなす距離 (SPH のカーネル半径)
+const SMOOTHING_RADIUS: f32 = 1.0;
+const REST_DENSITY: f32 = 6.0;
+const STIFFNESS: f32 = 800.0;
+const NEAR_STIFFNESS: f32 = 1600.0;
+const MAX_SPEED: f32 = 30.0;
+// 壁や床にぶつかったときに残す速さの割合
+const RESTITUTION: f32 = 0.3;
+// 手に触れた粒子を手の速度に寄せる割合 (かき混ぜたりすくったりできる)
+const HAND_DRAG: f32 = 0.4;
+
+// 位置ベースの簡易 SPH (Clavet らの double density relaxation)
+struct ParticleSim {
+    positions: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    grid: HashMap<IVec3, Vec<usize>>,
+    min: Vec3,
+    max: Vec3,
+}
+
+impl ParticleSim {
+    fn new(positions: Vec<Vec3>, min: Vec3, max: Vec3) -> Self {
+        let count = positions.len();
+        Self {
+            previous: positions.clone(),
+            positions,
+            velocities: vec![Vec3::ZERO; count],
+            grid: HashMap::new(),
+            min,
+            max,
+        }
+    }
+
+    // colliders は (中心, 速度) で、半径 radius の球として扱う
+    fn step(&mut self, dt: f32, colliders: &[(Vec3, Vec3)], radius: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        for i in 0..self.positions.len() {
+            self.velocities[i] += GRAVITY * dt;
+            self.previous[i] = self.positions[i];
+            self.positions[i] += self.velocities[i] * dt;
+        }
+        self.rebuild_grid();
+        self.relax_density(dt);
+
+        let mut touched = vec![None; self.positions.len()];
+        for (i, p) in self.positions.iter_mut().enumerate() {
+            for &(center, velocity) in colliders {
+                let offset = *p - center;
+                let distance = offset.length();
+                if distance < radius && distance > f32::EPSILON {
+                    *p = center + offset / distance * radius;
+                    touched[i] = Some(velocity);
+                }
+            }
+        }
+
+        for (i, hand) in touched.into_iter().enumerate() {
+            let mut velocity = (self.positions[i] - self.previous[i]) / dt;
+            if let Some(hand) = hand {
+                velocity = velocity.lerp(hand, HAND_DRAG);
+            }
+            self.velocities[i] = velocity.clamp_length_max(MAX_SPEED);
+            self.keep_inside(i);
+        }
+    }
+
+    fn cell(position: Vec3) -> IVec3 {
+        (position / SMOOTHING_RADIUS).floor().as_ivec3()
+    }
+
+    fn rebuild_grid(&mut self) {
+        for cell in self.grid.values_mut() {
+            cell.clear();
+        }
+        for (i, p) in self.positions.iter().enumerate() {
+            self.grid.entry(Self::cell(*p)).or_default().push(i);
+        }
+    }
+
+    fn relax_density(&mut self, dt: f32) {
+        let mut neighbors: Vec<(usize, Vec3, f32)> = Vec::new();
+        for i in 0..self.positions.len() {
+            let p = self.positions[i];
+            let cell = Self::cell(p);
+            neighbors.clear();
+            let mut density = 0.0;
+            let mut near_density = 0.0;
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(indices) = self.grid.get(&(cell + IVec3::new(dx, dy, dz))) else {
+                            continue;
+                        };
+                        for &j in indices {
+                            if j == i {
+                                continue;
+                            }
+                            let offset = self.positions[j] - p;
+                            let q = offset.length() / SMOOTHING_RADIUS;
+                            if q < 1.0 {
+                                let w = 1.0 - q;
+                                density += w * w;
+                                near_density += w * w * w;
+                                neighbors.push((j, offset, w));
+                            }
+                        }
+                    }
+                }
+            }
+            let pressure = STIFFNESS * (density - REST_DENSITY);
+            let near_pressure = NEAR_STIFFNESS * near_density;
+            let mut displacement = Vec3::ZERO;
+            for &(j, offset, w) in &neighbors {
+                let direction = offset.try_normalize().unwrap_or(Vec3::Y);
+                let d = direction * (dt * dt * (pressure * w + near_pressure * w * w) * 0.5);
+                self.positions[j] += d;
+                displacement -= d;
+            }
+            self.positions[i] += displacement;
+        }
+    }
+
+    fn keep_inside(&mut self, i: usize) {
+        let p = &mut self.positions[i];
+        let v = &mut self.velocities[i];
+        for axis in 0..3 {
+            if p[axis] < self.min[axis] {
+                p[axis] = self.min[axis];
+                v[axis] = -v[axis] * RESTITUTION;
+            } else if p[axis] > self.max[axis] {
+                p[axis] = self.max[axis];
+                v[axis] = -v[axis] * RESTITUTION;
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct Particle(usize);
+
+#[derive(Resource, Default)]
+struct ParticleSandbox {
+    sim: Option<ParticleSim>,
+    // 手の点の前フレームの位置 (速度を求める)
+    previous_hand: HashMap<Entity, Vec3>,
+}
+
+pub struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticleSandbox::default()).add_systems(
+            Update,
+            (toggle_sandbox, step_particles.in_set(InteractionSet), sync_particles, draw_basin)
+                .chain()
+                .after(update_hands_and_physics),
+        );
+    }
+}
+
+fn toggle_sandbox(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut sandbox: ResMut<ParticleSandbox>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    particles: Query<Entity, With<Particle>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    if sandbox.sim.take().is_some() {
+        for entity in particles.iter() {
+            commands.entity(entity).despawn();
+        }
+        info!("particle sandbox off");
+        return;
+    }
+
+    // 水槽の奥の方に格子状に積んでおき、流れ落ちさせる
+    let spacing = PARTICLE_RADIUS * 2.5;
+    let columns = ((BASIN_MAX.x - BASIN_MIN.x) / spacing * 0.5) as usize;
+    let rows = ((BASIN_MAX.z - BASIN_MIN.z) / spacing * 0.5) as usize;
+    let origin = Vec3::new(BASIN_MIN.x * 0.5, BASIN_MIN.y + 2.0, BASIN_MIN.z + 1.0);
+    let positions: Vec<Vec3> = (0..PARTICLE_COUNT)
+        .map(|i| {
+            let (x, z, y) = (i % columns, (i / columns) % rows, i / (columns * rows));
+            origin + Vec3::new(x as f32, y as f32, z as f32) * spacing
+        })
+        .collect();
+
+    // 同じメッシュとマテリアルを共有してまとめて描画させる
+    let mesh = meshes.add(Sphere::new(PARTICLE_RADIUS).mesh().ico(1).unwrap());
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.2, 0.55, 1.0),
+        perceptual_roughness: 0.2,
+        ..default()
+    });
+    for (i, position) in positions.iter().enumerate() {
+        commands.spawn((
+            PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                transform: Transform::from_translation(*position),
+                ..default()
+            },
+            Particle(i),
+        ));
+    }
+    sandbox.sim = Some(ParticleSim::new(positions, BASIN_MIN, BASIN_MAX));
+    info!("particle sandbox on ({PARTICLE_COUNT} particles)");
+}
+
+fn step_particles(
+    mut sandbox: ResMut<ParticleSandbox>,
+    hands: Query<(Entity, &Transform), With<HandPoint>>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds().min(MAX_DT);
+    let sandbox = &mut *sandbox;
+    let Some(sim) = sandbox.sim.as_mut() else {
+        return;
+    };
+    let colliders: Vec<(Vec3, Vec3)> = hands
+        .iter()
+        .map(|(entity, transform)| {
+            let position = transform.translation;
+            let velocity = sandbox
+                .previous_hand
+                .get(&entity)
+                .filter(|_| dt > 0.0)
+                .map_or(Vec3::ZERO, |previous| (position - *previous) / dt);
+            (position, velocity)
+        })
+        .collect();
+    sandbox.previous_hand = hands.iter().map(|(entity, transform)| (entity, transform.translation)).collect();
+
+    let _span = info_span!("particle_step", count = PARTICLE_COUNT).entered();
+    for _ in 0..SUBSTEPS {
+        sim.step(dt / SUBSTEPS as f32, &colliders, HAND_RADIUS);
+    }
+}
+
+fn sync_particles(sandbox: Res<ParticleSandbox>, mut particles: Query<(&Particle, &mut Transform)>) {
+    let Some(sim) = &sandbox.sim else {
+        return;
+    };
+    for (particle, mut transform) in particles.iter_mut() {
+        if let Some(position) = sim.positions.get(particle.0) {
+            transform.translation = *position;
+        }
+    }
+}
+
+fn draw_basin(sandbox: Res<ParticleSandbox>, mut gizmos: Gizmos) {
+    if sandbox.sim.is_none() {
+        return;
+    }
+    let top = 2.0;
+    let corners = [
+        Vec3::new(BASIN_MIN.x, BASIN_MIN.y, BASIN_MIN.z),
+        Vec3::new(BASIN_MAX.x, BASIN_MIN.y, BASIN_MIN.z),
+        Vec3::new(BASIN_MAX.x, BASIN_MIN.y, BASIN_MAX.z),
+        Vec3::new(BASIN_MIN.x, BASIN_MIN.y, BASIN_MAX.z),
+    ];
+    let color = Color::srgba(0.4, 0.7, 1.0, 0.5);
+    for (i, corner) in corners.iter().enumerate() {
+        let next = corners[(i + 1) % corners.len()];
+        gizmos.line(*corner, next, color);
+        gizmos.line(*corner + Vec3::Y * top, next + Vec3::Y * top, color);
+        gizmos.line(*corner, *corner + Vec3::Y * top, color);
+    }
+}
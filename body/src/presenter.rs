@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::process::Command;
+
+use crate::core::gesture::is_pointing;
+use crate::display::InteractionSet;
+use crate::packet::{ClientPackets, IncomingPacket};
+use crate::profile::ActiveProfile;
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandSide, HandStates};
+
+const PRESENTER_PATH: &str = "presenter.json";
+const THUMB_TIP: usize = 4;
+const INDEX_MCP: usize = 5;
+const INDEX_TIP: usize = 8;
+const MAX_DISTANCE: f32 = 80.0;
+const DOT_RADIUS: f32 = 0.25;
+// つまみを離してから次のつまみを受け付けるまで
+const COOLDOWN: f32 = 0.6;
+
+// つまんだときに実行するコマンド (先頭がプログラム、残りが引数)。空なら SlideCommand を送るだけ
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PresenterConfig {
+    #[serde(default)]
+    next: Vec<String>,
+    #[serde(default)]
+    previous: Vec<String>,
+}
+
+impl Default for PresenterConfig {
+    fn default() -> Self {
+        Self {
+            next: vec!["xdotool".into(), "key".into(), "Right".into()],
+            previous: vec!["xdotool".into(), "key".into(), "Left".into()],
+        }
+    }
+}
+
+impl PresenterConfig {
+    fn load() -> Self {
+        match fs::read_to_string(PRESENTER_PATH) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {PRESENTER_PATH}: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+// 指さした光線が当たった物体。当たる物体が変わったときに送る
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PointerHit {
+    pub key: HandKey,
+    pub entity: Entity,
+    pub point: Vec3,
+}
+
+// 右手でつまむと次へ、左手でつまむと前へ
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlideCommand {
+    Next,
+    Previous,
+}
+
+#[derive(Resource, Default)]
+pub struct Presenter {
+    pub active: bool,
+    config: PresenterConfig,
+    pointing: HashSet<HandKey>,
+    targets: HashMap<HandKey, Entity>,
+    pinched: HashSet<HandKey>,
+    last_slide: f32,
+}
+
+pub struct PresenterPlugin;
+
+impl Plugin for PresenterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PointerHit>()
+            .add_event::<SlideCommand>()
+            .insert_resource(Presenter { config: PresenterConfig::load(), ..default() })
+            .add_systems(
+                Update,
+                (toggle_presenter, read_pointing, cast_pointers, detect_slide_pinch, inject_keys)
+                    .chain()
+                    .after(update_hands_and_physics)
+                    .in_set(InteractionSet),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, presenter_ui);
+    }
+}
+
+fn toggle_presenter(keys: Res<ButtonInput<KeyCode>>, mut presenter: ResMut<Presenter>) {
+    if keys.just_pressed(KeyCode::KeyN) {
+        presenter.active = !presenter.active;
+        presenter.pointing.clear();
+        presenter.targets.clear();
+        presenter.pinched.clear();
+        info!("presenter mode {}", if presenter.active { "on" } else { "off" });
+    }
+}
+
+// パケットが届いたフレームだけ指さしかどうかを判定し直す
+fn read_pointing(
+    mut presenter: ResMut<Presenter>,
+    incoming: Res<IncomingPacket>,
+    clients: Res<ClientPackets>,
+    hand_states: Res<HandStates>,
+) {
+    if !presenter.active {
+        return;
+    }
+    let packets = incoming.0.iter().map(|p| (0, p)).chain(clients.packets.iter().map(|(c, p)| (*c, p)));
+    for (client, packet) in packets {
+        for side in [HandSide::Right, HandSide::Left] {
+            let pointing = packet
+                .hands
+                .iter()
+                .find(|h| h.label == side.label())
+                .is_some_and(|h| is_pointing(&h.landmarks));
+            if pointing {
+                presenter.pointing.insert((client, side));
+            } else {
+                presenter.pointing.remove(&(client, side));
+            }
+        }
+    }
+    presenter.pointing.retain(|key| hand_states.is_confident(*key));
+}
+
+fn cast_pointers(
+    mut presenter: ResMut<Presenter>,
+    rapier: Res<RapierContext>,
+    points: Query<(&HandPoint, &Transform)>,
+    hand_entities: Query<(), With<HandPoint>>,
+    mut hits: EventWriter<PointerHit>,
+    mut gizmos: Gizmos,
+) {
+    if !presenter.active {
+        return;
+    }
+    let mut fingers: HashMap<HandKey, (Option<Vec3>, Option<Vec3>)> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let entry = fingers.entry((point.client, point.side)).or_default();
+        match point.id {
+            INDEX_MCP => entry.0 = Some(transform.translation),
+            INDEX_TIP => entry.1 = Some(transform.translation),
+            _ => {}
+        }
+    }
+    // 手の球には当てない
+    let not_hand = |entity| !hand_entities.contains(entity);
+    let filter = QueryFilter::new().exclude_sensors().predicate(&not_hand);
+    let mut targets = HashMap::new();
+    for &key in &presenter.pointing {
+        let Some((Some(mcp), Some(tip))) = fingers.get(&key).copied() else {
+            continue;
+        };
+        let direction = (tip - mcp).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            continue;
+        }
+        let hit = rapier.cast_ray(tip, direction, MAX_DISTANCE, true, filter);
+        let end = tip + direction * hit.map_or(MAX_DISTANCE, |(_, toi)| toi);
+        gizmos.line(tip, end, Color::srgb(1.0, 0.15, 0.1));
+        let Some((entity, _)) = hit else {
+            continue;
+        };
+        gizmos.sphere(end, Quat::IDENTITY, DOT_RADIUS, Color::srgb(1.0, 0.3, 0.2));
+        gizmos.sphere(end, Quat::IDENTITY, DOT_RADIUS * 0.5, Color::WHITE);
+        if presenter.targets.get(&key) != Some(&entity) {
+            hits.send(PointerHit { key, entity, point: end });
+        }
+        targets.insert(key, entity);
+    }
+    presenter.targets = targets;
+}
+
+fn detect_slide_pinch(
+    mut presenter: ResMut<Presenter>,
+    points: Query<(&HandPoint, &Transform)>,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    mut slides: EventWriter<SlideCommand>,
+    time: Res<Time>,
+) {
+    if !presenter.active {
+        return;
+    }
+    let mut tips: HashMap<HandKey, (Option<Vec3>, Option<Vec3>)> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let entry = tips.entry((point.client, point.side)).or_default();
+        match point.id {
+            THUMB_TIP => entry.0 = Some(transform.translation),
+            INDEX_TIP => entry.1 = Some(transform.translation),
+            _ => {}
+        }
+    }
+    let now = time.elapsed_seconds();
+    for (key, (thumb, index)) in tips {
+        let pinching = hand_states.is_confident(key)
+            && matches!((thumb, index), (Some(t), Some(i)) if t.distance(i) < profile.pinch_distance);
+        if !pinching {
+            presenter.pinched.remove(&key);
+            continue;
+        }
+        // つまんだ瞬間だけ送る
+        if !presenter.pinched.insert(key) || now - presenter.last_slide < COOLDOWN {
+            continue;
+        }
+        presenter.last_slide = now;
+        slides.send(if key.1 == HandSide::Right { SlideCommand::Next } else { SlideCommand::Previous });
+    }
+}
+
+fn inject_keys(presenter: Res<Presenter>, mut slides: EventReader<SlideCommand>, mut hits: EventReader<PointerHit>) {
+    for hit in hits.read() {
+        debug!(client = hit.key.0, side = hit.key.1.label(), entity = ?hit.entity, point = ?hit.point, "pointer hit");
+    }
+    for command in slides.read() {
+        let argv = match command {
+            SlideCommand::Next => &presenter.config.next,
+            SlideCommand::Previous => &presenter.config.previous,
+        };
+        info!(?command, "slide");
+        let Some((program, args)) = argv.split_first() else {
+            continue;
+        };
+        let mut process = Command::new(program);
+        process.args(args);
+        // 終了を待つ間に描画を止めない
+        std::thread::spawn(move || {
+            if let Err(e) = process.status() {
+                error!("failed to run slide command: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(feature = "ui")]
+fn presenter_ui(mut contexts: EguiContexts, presenter: Res<Presenter>) {
+    if !presenter.active {
+        return;
+    }
+    egui::Window::new("Presenter")
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("{} pointing", presenter.pointing.len()));
+            ui.small("Point: laser / Pinch right: next / Pinch left: previous / N: quit");
+        });
+}
@@ -0,0 +1,222 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::profile::{palm_length, HandProportions};
+use crate::core::tuning::GestureTuning;
+use crate::packet::{IncomingPacket, PacketSet};
+use crate::reach::ActiveReach;
+use crate::tuning::Tuning;
+use crate::HandPoint;
+
+const PROFILES_PATH: &str = "profiles.json";
+const MEASURE_TIME: f32 = 3.0;
+const FORGET_TIME: f32 = 10.0;
+const MATCH_TOLERANCE: f32 = 0.12;
+
+// 基準とする手の見かけの大きさ (正規化座標での手首〜中指MCP)
+const REFERENCE_PALM_LENGTH: f32 = 0.12;
+// 基準とする人差し指の長さ (手首〜中指MCP との比)
+const REFERENCE_INDEX_LENGTH: f32 = 0.95;
+pub const BASE_COLLIDER_RADIUS: f32 = 0.1;
+
+// 手の形の比率で見分ける。手の大きさは写る距離で変わるので、プロファイルには残さず測るたびに求める
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HandProfile {
+    pub name: String,
+    pub proportions: HandProportions,
+    // つまみ判定の距離に掛ける倍率。指の長い人ほど親指と人差し指の先が離れたままつまめる
+    pub pinch_scale: f32,
+}
+
+impl HandProfile {
+    pub fn from_proportions(name: String, proportions: HandProportions) -> Self {
+        let pinch_scale = (proportions.finger_lengths[0] / REFERENCE_INDEX_LENGTH).clamp(0.8, 1.25);
+        Self { name, proportions, pinch_scale }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ProfileStore {
+    pub path: PathBuf,
+    pub profiles: Vec<HandProfile>,
+}
+
+impl ProfileStore {
+    fn load(path: PathBuf) -> Self {
+        let profiles = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { path, profiles }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(&self.profiles) {
+            Ok(text) => {
+                if let Err(e) = fs::write(&self.path, text) {
+                    error!("failed to save {}: {e}", self.path.display());
+                }
+            }
+            Err(e) => error!("failed to serialize profiles: {e}"),
+        }
+    }
+
+    fn select_or_create(&mut self, proportions: HandProportions) -> HandProfile {
+        let best = self
+            .profiles
+            .iter()
+            .map(|p| (p.proportions.difference(&proportions), p))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((diff, profile)) = best
+            && diff < MATCH_TOLERANCE
+        {
+            return profile.clone();
+        }
+
+        let profile = HandProfile::from_proportions(format!("user-{}", self.profiles.len() + 1), proportions);
+        self.profiles.push(profile.clone());
+        self.save();
+        profile
+    }
+}
+
+#[derive(Resource)]
+pub struct ActiveProfile {
+    pub profile: Option<String>,
+    // 測ったときの手の見かけの大きさ (基準との比) と、その人のつまみ判定の倍率
+    scale: f32,
+    pinch_scale: f32,
+    pub collider_radius: f32,
+    pub grab_distance: f32,
+    pub pinch_distance: f32,
+    pub max_fist_gap: f32,
+}
+
+impl ActiveProfile {
+    // プロファイルが決まるまでは gestures.toml の値をそのまま使う
+    fn unprofiled(tuning: &GestureTuning) -> Self {
+        let mut active = Self {
+            profile: None,
+            scale: 1.0,
+            pinch_scale: 1.0,
+            collider_radius: BASE_COLLIDER_RADIUS,
+            grab_distance: 0.0,
+            pinch_distance: 0.0,
+            max_fist_gap: 0.0,
+        };
+        active.retune(tuning);
+        active
+    }
+
+    // gestures.toml の距離を手の大きさに合わせて縮尺する
+    fn retune(&mut self, tuning: &GestureTuning) {
+        self.collider_radius = BASE_COLLIDER_RADIUS * self.scale;
+        self.grab_distance = tuning.grab.distance * self.scale;
+        self.pinch_distance = tuning.pinch.distance * self.scale * self.pinch_scale;
+        self.max_fist_gap = tuning.bullet_time.max_fist_gap * self.scale;
+    }
+
+    fn apply(&mut self, profile: &HandProfile, palm_length: f32, tuning: &GestureTuning) {
+        self.profile = Some(profile.name.clone());
+        self.scale = (palm_length / REFERENCE_PALM_LENGTH).clamp(0.7, 1.4);
+        self.pinch_scale = profile.pinch_scale;
+        self.retune(tuning);
+    }
+}
+
+#[derive(Resource, Default)]
+struct Calibration {
+    samples: Vec<HandProportions>,
+    palm_lengths: Vec<f32>,
+    tracked_time: f32,
+    last_seen: f32,
+    done: bool,
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ProfileStore::load(PathBuf::from(PROFILES_PATH)))
+            .insert_resource(ActiveProfile::unprofiled(&GestureTuning::default()))
+            .insert_resource(Calibration::default())
+            .add_systems(
+                Update,
+                (follow_tuning, measure_hands, apply_collider_scale).chain().after(PacketSet::Override),
+            );
+    }
+}
+
+fn follow_tuning(tuning: Res<Tuning>, mut active: ResMut<ActiveProfile>) {
+    if tuning.is_changed() {
+        active.retune(&tuning.0);
+    }
+}
+
+fn measure_hands(
+    incoming: Res<IncomingPacket>,
+    tuning: Res<Tuning>,
+    mut calibration: ResMut<Calibration>,
+    mut store: ResMut<ProfileStore>,
+    mut active: ResMut<ActiveProfile>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+
+    let Some(packet) = incoming.0.as_ref().filter(|p| !p.hands.is_empty()) else {
+        // 手がしばらく見えなければ次のユーザーとして測り直す
+        if calibration.done && now - calibration.last_seen > FORGET_TIME {
+            *calibration = Calibration::default();
+            *active = ActiveProfile::unprofiled(&tuning.0);
+        }
+        return;
+    };
+
+    // 途切れずに追跡できている時間だけを数える
+    if now - calibration.last_seen < 0.5 {
+        calibration.tracked_time += now - calibration.last_seen;
+    }
+    calibration.last_seen = now;
+
+    if calibration.done {
+        return;
+    }
+
+    calibration.samples.extend(packet.hands.iter().filter_map(HandProportions::measure));
+    calibration.palm_lengths.extend(packet.hands.iter().filter_map(palm_length));
+
+    if calibration.tracked_time < MEASURE_TIME {
+        return;
+    }
+    let Some(proportions) = HandProportions::median(&calibration.samples) else {
+        return;
+    };
+    calibration.palm_lengths.sort_by(f32::total_cmp);
+    let palm_length = calibration.palm_lengths[calibration.palm_lengths.len() / 2];
+
+    let profile = store.select_or_create(proportions);
+    info!("hand profile selected: {} ({:?}, palm length {palm_length:.3})", profile.name, proportions);
+    active.apply(&profile, palm_length, &tuning.0);
+    calibration.done = true;
+    calibration.samples.clear();
+    calibration.palm_lengths.clear();
+}
+
+fn apply_collider_scale(
+    active: Res<ActiveProfile>,
+    reach: Res<ActiveReach>,
+    mut points: Query<(Ref<HandPoint>, &mut Transform)>,
+) {
+    // Transform のスケールはメッシュとコライダーの両方に効く。巨大な手では関節の球も大きくする
+    let scale = Vec3::splat(active.collider_radius / BASE_COLLIDER_RADIUS * reach.0.hand_scale);
+    for (point, mut transform) in points.iter_mut() {
+        if active.is_changed() || reach.is_changed() || point.is_added() {
+            transform.scale = scale;
+        }
+    }
+}
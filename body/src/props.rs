@@ -0,0 +1,208 @@
+use bevy::gltf::{Gltf, GltfMesh};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::core::hull::{ConvexHull, HullCache};
+use crate::error::Health;
+use crate::grab::Grabbable;
+use crate::sound::SurfaceMaterial;
+
+const ASSET_DIR: &str = "assets";
+// assets からの相対パス
+const PROPS_DIR: &str = "props";
+// 計算した凸包の置き場所 (assets/props/.hulls/<名前>.json)
+const CACHE_DIR: &str = ".hulls";
+// 模型の大きさによらず、中心から一番遠い点までがこの長さになるよう縮尺をそろえる
+const PROP_RADIUS: f32 = 1.5;
+const SPAWN_POINT: Vec3 = Vec3::new(0.0, 10.0, 0.0);
+
+enum HullState {
+    Loading,
+    Ready(ConvexHull),
+    Failed,
+}
+
+struct CatalogEntry {
+    name: String,
+    path: String,
+    gltf: Handle<Gltf>,
+    // 元のファイルの大きさと更新時刻。凸包の保存と比べる
+    source: (u64, u64),
+    hull: HullState,
+}
+
+// assets/props に置いた glTF の一覧。spawn <名前> で出せる
+#[derive(Resource, Default)]
+pub struct PropCatalog {
+    entries: Vec<CatalogEntry>,
+    requests: Vec<String>,
+}
+
+impl PropCatalog {
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    // 名前の合う物体を出す。凸包がまだ出来ていなければ出来た時点で出す
+    pub fn request(&mut self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        if !self.entries.iter().any(|e| e.name == name) {
+            return false;
+        }
+        self.requests.push(name);
+        true
+    }
+}
+
+pub struct PropsPlugin;
+
+impl Plugin for PropsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PropCatalog::default())
+            .add_systems(Startup, scan_props)
+            .add_systems(Update, (build_hulls, spawn_requested_props).chain());
+    }
+}
+
+fn props_dir() -> PathBuf {
+    Path::new(ASSET_DIR).join(PROPS_DIR)
+}
+
+fn cache_path(name: &str) -> PathBuf {
+    props_dir().join(CACHE_DIR).join(format!("{name}.json"))
+}
+
+fn source_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some((metadata.len(), modified))
+}
+
+// 元のファイルが変わっていなければ、保存した凸包を使う
+fn cached_hull(name: &str, source: (u64, u64)) -> Option<ConvexHull> {
+    let text = fs::read_to_string(cache_path(name)).ok()?;
+    let cache: HullCache = serde_json::from_str(&text).ok()?;
+    cache.matches(source.0, source.1).then_some(cache.hull)
+}
+
+fn save_hull(name: &str, source: (u64, u64), hull: &ConvexHull) -> std::io::Result<()> {
+    let cache = HullCache { source_len: source.0, source_modified: source.1, hull: hull.clone() };
+    fs::create_dir_all(props_dir().join(CACHE_DIR))?;
+    fs::write(cache_path(name), serde_json::to_string(&cache)?)
+}
+
+fn scan_props(asset_server: Res<AssetServer>, mut catalog: ResMut<PropCatalog>, mut health: ResMut<Health>) {
+    let Ok(files) = fs::read_dir(props_dir()) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = files
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "gltf" || e == "glb"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let (Some(stem), Some(file)) = (path.file_stem().and_then(|s| s.to_str()), path.file_name()) else {
+            continue;
+        };
+        let name = stem.to_lowercase();
+        let Some(source) = source_stamp(&path) else {
+            continue;
+        };
+        let asset_path = format!("{PROPS_DIR}/{}", file.to_string_lossy());
+        let hull = match cached_hull(&name, source) {
+            Some(hull) => HullState::Ready(hull),
+            None => HullState::Loading,
+        };
+        catalog.entries.push(CatalogEntry {
+            name,
+            gltf: asset_server.load(asset_path.clone()),
+            path: asset_path,
+            source,
+            hull,
+        });
+    }
+    info!(props = ?catalog.names(), "prop catalog");
+    health.ok("props");
+}
+
+// 読み込めた模型の全メッシュの頂点から凸包を作る。ノードの変換は見ないので、原点に置いて作った模型を前提にする
+fn build_hulls(
+    mut catalog: ResMut<PropCatalog>,
+    gltfs: Res<Assets<Gltf>>,
+    gltf_meshes: Res<Assets<GltfMesh>>,
+    meshes: Res<Assets<Mesh>>,
+    mut health: ResMut<Health>,
+) {
+    for entry in catalog.entries.iter_mut().filter(|e| matches!(e.hull, HullState::Loading)) {
+        let Some(gltf) = gltfs.get(&entry.gltf) else {
+            continue;
+        };
+        let mut points = Vec::new();
+        for primitive in gltf.meshes.iter().filter_map(|h| gltf_meshes.get(h)).flat_map(|m| &m.primitives) {
+            if let Some(positions) = meshes.get(&primitive.mesh).and_then(|m| m.attribute(Mesh::ATTRIBUTE_POSITION))
+                && let Some(positions) = positions.as_float3()
+            {
+                points.extend(positions.iter().map(|p| Vec3::from_array(*p)));
+            }
+        }
+        let Some(hull) = ConvexHull::from_points(&points) else {
+            health.degraded("props", format!("{} has no solid shape to collide with", entry.path));
+            entry.hull = HullState::Failed;
+            continue;
+        };
+        info!(prop = entry.name, points = points.len(), hull = hull.vertices.len(), "built prop collider");
+        if let Err(e) = save_hull(&entry.name, entry.source, &hull) {
+            warn!(prop = entry.name, "failed to cache the prop collider: {e}");
+        }
+        entry.hull = HullState::Ready(hull);
+    }
+}
+
+fn spawn_requested_props(
+    mut commands: Commands,
+    mut catalog: ResMut<PropCatalog>,
+    gltfs: Res<Assets<Gltf>>,
+    mut health: ResMut<Health>,
+) {
+    let catalog = &mut *catalog;
+    catalog.requests.retain(|name| {
+        let Some(entry) = catalog.entries.iter().find(|e| e.name == *name) else {
+            return false;
+        };
+        let (HullState::Ready(hull), Some(gltf)) = (&entry.hull, gltfs.get(&entry.gltf)) else {
+            return matches!(entry.hull, HullState::Loading);
+        };
+        let Some(scene) = gltf.default_scene.clone().or_else(|| gltf.scenes.first().cloned()) else {
+            health.degraded("props", format!("{} has no scene to show", entry.path));
+            return false;
+        };
+        let scale = PROP_RADIUS / hull.radius().max(f32::EPSILON);
+        let hull = hull.scaled(scale);
+        let Some(collider) = Collider::convex_mesh(hull.points(), &hull.faces) else {
+            health.degraded("props", format!("{} collider could not be built", entry.path));
+            return false;
+        };
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(SPAWN_POINT)),
+                RigidBody::Dynamic,
+                collider,
+                ColliderMassProperties::Density(2.0),
+                ActiveEvents::CONTACT_FORCE_EVENTS,
+                SurfaceMaterial::default(),
+                ExternalForce::default(),
+                Velocity::default(),
+                Grabbable { radius: PROP_RADIUS },
+                Name::new(entry.name.clone()),
+            ))
+            .with_children(|prop| {
+                prop.spawn(SceneBundle { scene, transform: Transform::from_scale(Vec3::splat(scale)), ..default() });
+            });
+        info!(prop = entry.name, "spawned prop");
+        false
+    });
+}
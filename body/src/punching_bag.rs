@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy::window::PrimaryWindow;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::punching_bag::ImpactReadouts;
+#[cfg(feature = "ui")]
+use crate::display::world_to_window;
+
+// 吊るす点と、そこから袋の上端までの紐の長さ
+const ANCHOR: Vec3 = Vec3::new(6.0, 10.0, 0.0);
+const ROPE_LENGTH: f32 = 2.0;
+const BAG_HALF_HEIGHT: f32 = 3.5;
+const BAG_RADIUS: f32 = 1.4;
+// これより弱い接触は数えない (手を添えているだけ)
+const MIN_FORCE: f32 = 200.0;
+
+// 叩くと吊るした点を中心に揺れるサンドバッグ
+#[derive(Component)]
+struct PunchingBag {
+    anchor: Entity,
+    readouts: ImpactReadouts,
+}
+
+pub struct PunchingBagPlugin;
+
+impl Plugin for PunchingBagPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (toggle_punching_bag, record_impacts, draw_punching_bag).chain());
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, label_impacts.after(record_impacts));
+    }
+}
+
+// W で出し入れする
+fn toggle_punching_bag(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    bags: Query<(Entity, &PunchingBag)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyW) {
+        return;
+    }
+    if !bags.is_empty() {
+        for (entity, bag) in bags.iter() {
+            info!(best = bag.readouts.best, "punching bag removed");
+            commands.entity(bag.anchor).despawn_recursive();
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let anchor = commands
+        .spawn((TransformBundle::from_transform(Transform::from_translation(ANCHOR)), RigidBody::Fixed))
+        .id();
+    // 袋の上端から紐の長さだけ上が吊るす点
+    let joint = SphericalJointBuilder::new().local_anchor2(Vec3::Y * (BAG_HALF_HEIGHT + ROPE_LENGTH));
+    let center = ANCHOR - Vec3::Y * (BAG_HALF_HEIGHT + ROPE_LENGTH);
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cylinder::new(BAG_RADIUS, BAG_HALF_HEIGHT * 2.0)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.55, 0.12, 0.1),
+                perceptual_roughness: 0.8,
+                ..default()
+            }),
+            transform: Transform::from_translation(center),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::cylinder(BAG_HALF_HEIGHT, BAG_RADIUS),
+        ColliderMassProperties::Density(2.0),
+        Damping { linear_damping: 0.3, angular_damping: 0.8 },
+        ActiveEvents::CONTACT_FORCE_EVENTS,
+        ContactForceEventThreshold(MIN_FORCE),
+        ImpulseJoint::new(anchor, joint),
+        Velocity::default(),
+        PunchingBag { anchor, readouts: ImpactReadouts::default() },
+    ));
+    info!("punching bag ready");
+}
+
+// 接触の力を一発ごとにまとめ、当たった点に最大値を残す。接触点が取れなければ相手の位置を使う
+fn record_impacts(
+    mut contacts: EventReader<ContactForceEvent>,
+    mut bags: Query<(Entity, &mut PunchingBag)>,
+    transforms: Query<&GlobalTransform>,
+    rapier: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for contact in contacts.read() {
+        let (bag_entity, other) = if bags.contains(contact.collider1) {
+            (contact.collider1, contact.collider2)
+        } else if bags.contains(contact.collider2) {
+            (contact.collider2, contact.collider1)
+        } else {
+            continue;
+        };
+        let point = rapier
+            .contact_pair(contact.collider1, contact.collider2)
+            .and_then(|pair| pair.manifolds().find_map(|m| m.solver_contacts().next().map(|c| c.point())))
+            .or_else(|| transforms.get(other).ok().map(GlobalTransform::translation));
+        let (Some(point), Ok((_, mut bag))) = (point, bags.get_mut(bag_entity)) else {
+            continue;
+        };
+        let force = contact.max_force_magnitude;
+        if bag.readouts.hit(point, force, now) {
+            debug!(force, ?point, "punching bag hit");
+        }
+    }
+    for (_, mut bag) in bags.iter_mut() {
+        bag.readouts.expire(now);
+    }
+}
+
+// 紐と、数字の出ている当たった点
+fn draw_punching_bag(bags: Query<(&PunchingBag, &Transform)>, mut gizmos: Gizmos, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    for (bag, transform) in bags.iter() {
+        let top = transform.transform_point(Vec3::Y * BAG_HALF_HEIGHT);
+        gizmos.line(ANCHOR, top, Color::srgb(0.8, 0.75, 0.6));
+        for readout in bag.readouts.readouts() {
+            let (_, alpha) = readout.shown(now);
+            gizmos.sphere(readout.position, Quat::IDENTITY, 0.2, Color::srgba(1.0, 0.8, 0.2, alpha));
+        }
+    }
+}
+
+// 当たった点から浮き上がって消える力の数字。右上に一番強かった一発を出す
+#[cfg(feature = "ui")]
+fn label_impacts(
+    mut contexts: EguiContexts,
+    bags: Query<&PunchingBag>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    time: Res<Time>,
+) {
+    let (Ok((camera, camera_transform)), Ok(window)) = (camera.get_single(), windows.get_single()) else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    let ctx = contexts.ctx_mut();
+    for (index, bag) in bags.iter().enumerate() {
+        for (i, readout) in bag.readouts.readouts().iter().enumerate() {
+            let (position, alpha) = readout.shown(now);
+            let Some(screen) = world_to_window(camera, camera_transform, window, position) else {
+                continue;
+            };
+            egui::Area::new(egui::Id::new(("punching_bag_hit", index, i)))
+                .fixed_pos(egui::pos2(screen.x, screen.y))
+                .pivot(egui::Align2::CENTER_BOTTOM)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    let color = egui::Color32::from_rgba_unmultiplied(255, 210, 60, (alpha * 255.0) as u8);
+                    ui.label(egui::RichText::new(format!("{:.0} N", readout.peak)).size(22.0).strong().color(color));
+                });
+        }
+        egui::Area::new(egui::Id::new(("punching_bag_best", index)))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-12.0, 12.0 + index as f32 * 24.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(format!("best {:.0} N", bag.readouts.best)).strong());
+            });
+    }
+}
@@ -0,0 +1,228 @@
+#[cfg(feature = "audio")]
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use std::collections::HashMap;
+#[cfg(feature = "audio")]
+use std::path::Path;
+
+#[cfg(feature = "audio")]
+use crate::core::puppet::FlapDetector;
+use crate::core::puppet::{face, PuppetFace};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::settings::Settings;
+use crate::silhouette::HandDisplayMode;
+use crate::{update_hands_and_physics, HandKey, HandPoint, HandStates};
+
+// 口を閉じるたびに鳴らす音 (assets/ からの相対パス)。無ければ鳴らさない
+#[cfg(feature = "audio")]
+const FLAP_SOUND: &str = "sounds/puppet_flap.ogg";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Part {
+    Eye(usize),
+    Pupil(usize),
+    Mouth,
+}
+
+#[derive(Component)]
+struct PuppetPart {
+    key: HandKey,
+    part: Part,
+}
+
+// 手の形に目と口を付けて、親指と指先の開きで口をぱくぱくさせる遊び
+#[derive(Resource, Default)]
+pub struct Puppet {
+    pub active: bool,
+    // 人形にする前の手の見せ方。戻すときに使う
+    saved_display: Option<HandDisplayMode>,
+}
+
+#[derive(Resource)]
+struct PuppetAssets {
+    sphere: Handle<Mesh>,
+    eye: Handle<StandardMaterial>,
+    pupil: Handle<StandardMaterial>,
+    mouth: Handle<StandardMaterial>,
+}
+
+pub struct PuppetPlugin;
+
+impl Plugin for PuppetPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Puppet::default())
+            .add_systems(Startup, load_puppet_assets)
+            .add_systems(
+                Update,
+                (toggle_puppet, spawn_faces, pose_faces).chain().after(update_hands_and_physics).after(PalmPoseSet),
+            );
+        #[cfg(feature = "audio")]
+        app.add_systems(Update, flap_sounds.after(pose_faces));
+    }
+}
+
+fn load_puppet_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(PuppetAssets {
+        sphere: meshes.add(Sphere::new(1.0)),
+        eye: materials.add(StandardMaterial { base_color: Color::WHITE, emissive: LinearRgba::gray(0.3), ..default() }),
+        pupil: materials.add(Color::srgb(0.05, 0.05, 0.08)),
+        mouth: materials.add(StandardMaterial { base_color: Color::srgb(0.45, 0.02, 0.08), unlit: true, ..default() }),
+    });
+}
+
+// U で切り替える。人形の間は手を半透明の手の形で見せる
+fn toggle_puppet(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut puppet: ResMut<Puppet>,
+    mut settings: ResMut<Settings>,
+    parts: Query<Entity, With<PuppetPart>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyU) {
+        return;
+    }
+    puppet.active = !puppet.active;
+    info!("puppet mode: {}", puppet.active);
+    if puppet.active {
+        puppet.saved_display = Some(settings.display.hands);
+        settings.display.hands = HandDisplayMode::Silhouette;
+        return;
+    }
+    for entity in parts.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if let Some(display) = puppet.saved_display.take() {
+        settings.display.hands = display;
+    }
+}
+
+fn spawn_faces(
+    mut commands: Commands,
+    puppet: Res<Puppet>,
+    assets: Res<PuppetAssets>,
+    hand_states: Res<HandStates>,
+    parts: Query<&PuppetPart>,
+) {
+    if !puppet.active {
+        return;
+    }
+    for key in hand_states.hands.keys() {
+        if parts.iter().any(|p| p.key == *key) {
+            continue;
+        }
+        for part in [Part::Eye(0), Part::Eye(1), Part::Pupil(0), Part::Pupil(1), Part::Mouth] {
+            let material = match part {
+                Part::Eye(_) => assets.eye.clone(),
+                Part::Pupil(_) => assets.pupil.clone(),
+                Part::Mouth => assets.mouth.clone(),
+            };
+            commands.spawn((
+                PbrBundle { mesh: assets.sphere.clone(), material, visibility: Visibility::Hidden, ..default() },
+                PuppetPart { key: *key, part },
+            ));
+        }
+    }
+}
+
+fn hand_faces<'a>(
+    points: impl Iterator<Item = (&'a HandPoint, &'a Transform)>,
+    hand_states: &HandStates,
+    palms: &PalmPoses,
+) -> HashMap<HandKey, PuppetFace> {
+    let mut hands: HashMap<HandKey, [Option<Vec3>; 21]> = HashMap::new();
+    for (point, transform) in points {
+        let key = (point.client, point.side);
+        if point.id < 21 && hand_states.hands.contains_key(&key) {
+            hands.entry(key).or_insert([None; 21])[point.id] = Some(transform.translation);
+        }
+    }
+    hands
+        .into_iter()
+        .filter_map(|(key, landmarks)| {
+            let landmarks = landmarks.map(|p| p.unwrap_or(Vec3::NAN));
+            if landmarks.iter().any(|p| p.is_nan()) {
+                return None;
+            }
+            // palm_rotation の z は手の甲の向き
+            let back = palms.poses.get(&key)?.orientation() * Vec3::Z;
+            Some((key, face(&landmarks, back)?))
+        })
+        .collect()
+}
+
+// 目は甲の上に浮かせて黒目を外へ向け、口は上あごと下あごの間で開きに合わせて縦に伸ばす
+fn pose_faces(
+    puppet: Res<Puppet>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    points: Query<(&HandPoint, &Transform), Without<PuppetPart>>,
+    mut parts: Query<(&PuppetPart, &mut Transform, &mut Visibility)>,
+) {
+    if !puppet.active {
+        return;
+    }
+    let faces = hand_faces(points.iter(), &hand_states, &palms);
+    for (part, mut transform, mut visibility) in parts.iter_mut() {
+        let Some(face) = faces.get(&part.key) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        let back = palms.poses.get(&part.key).map_or(Vec3::Y, |p| p.orientation() * Vec3::Z);
+        *transform = match part.part {
+            Part::Eye(i) => Transform::from_translation(face.eyes[i]).with_scale(Vec3::splat(face.eye_radius)),
+            Part::Pupil(i) => Transform::from_translation(face.eyes[i] + back * face.eye_radius * 0.6)
+                .with_scale(Vec3::splat(face.eye_radius * 0.5)),
+            Part::Mouth => {
+                let height = face.mouth_width * (0.08 + face.openness * 0.9);
+                Transform::from_translation(face.mouth)
+                    .with_rotation(Quat::from_rotation_arc(Vec3::Y, face.jaw))
+                    .with_scale(Vec3::new(face.mouth_width, height, face.mouth_width * 0.5) / 2.0)
+            }
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+// 口を開いてから閉じるたびに短い音を鳴らす。大きく開いたほど低く大きく
+#[cfg(feature = "audio")]
+#[allow(clippy::too_many_arguments)]
+fn flap_sounds(
+    mut commands: Commands,
+    puppet: Res<Puppet>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    points: Query<(&HandPoint, &Transform)>,
+    asset_server: Res<AssetServer>,
+    mut detectors: Local<HashMap<HandKey, FlapDetector>>,
+    mut sound: Local<Option<Option<Handle<AudioSource>>>>,
+) {
+    if !puppet.active {
+        detectors.clear();
+        return;
+    }
+    let sound = sound.get_or_insert_with(|| {
+        if !Path::new("assets").join(FLAP_SOUND).exists() {
+            warn!("sound not found: assets/{FLAP_SOUND}");
+            return None;
+        }
+        Some(asset_server.load(FLAP_SOUND))
+    });
+    detectors.retain(|key, _| hand_states.hands.contains_key(key));
+    for (key, face) in hand_faces(points.iter(), &hand_states, &palms) {
+        let Some(widest) = detectors.entry(key).or_default().update(face.openness) else {
+            continue;
+        };
+        if let Some(handle) = sound {
+            commands.spawn(AudioBundle {
+                source: handle.clone(),
+                settings: PlaybackSettings::DESPAWN
+                    .with_speed(1.4 - widest * 0.6)
+                    .with_volume(Volume::new(0.3 + widest * 0.7)),
+            });
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::core::mapping::Reach;
+use crate::settings::Settings;
+
+// 設定値に近づく速さ (1/秒)。約 0.5 秒でほぼ追いつく
+const BLEND_RATE: f32 = 6.0;
+
+// マッピングに実際に使う値。設定が変わっても物理が急に跳ばないよう徐々に追従する
+#[derive(Resource, Default)]
+pub struct ActiveReach(pub Reach);
+
+pub struct ReachPlugin;
+
+impl Plugin for ReachPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActiveReach::default())
+            .add_systems(Startup, start_from_settings)
+            .add_systems(PreUpdate, blend_reach);
+    }
+}
+
+fn start_from_settings(settings: Res<Settings>, mut active: ResMut<ActiveReach>) {
+    active.0 = settings.reach;
+}
+
+fn blend_reach(settings: Res<Settings>, mut active: ResMut<ActiveReach>, time: Res<Time>) {
+    if active.0 == settings.reach {
+        return;
+    }
+    let mut reach = active.0;
+    reach.blend_toward(&settings.reach, BLEND_RATE * time.delta_seconds());
+    if (reach.hand_scale - settings.reach.hand_scale).abs() < 1e-3
+        && (reach.amplification - settings.reach.amplification).abs() < 1e-3
+    {
+        reach = settings.reach;
+    }
+    active.0 = reach;
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::trail::LandmarkTrail;
+use crate::settings::Settings;
+use crate::topology::Topology;
+use crate::{
+    draw_hand_skeleton, skeleton_color, update_hands_and_physics, HandKey, HandMaterials, HandPoint, HandPresence,
+};
+
+// 骨格の線を引くための、描画専用にならした関節の位置。物理の関節の位置とは別に持つ
+#[derive(Resource, Default)]
+pub struct RenderPose {
+    trails: HashMap<(HandKey, usize), LandmarkTrail>,
+    pub positions: HashMap<(HandKey, usize), Vec3>,
+}
+
+pub struct RenderPosePlugin;
+
+impl Plugin for RenderPosePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RenderPose::default())
+            .add_systems(Update, (follow_physics_pose, draw_skeletons).chain().after(update_hands_and_physics));
+    }
+}
+
+fn follow_physics_pose(mut pose: ResMut<RenderPose>, points: Query<(&HandPoint, &Transform)>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let pose = &mut *pose;
+    pose.positions.clear();
+    for (point, transform) in points.iter() {
+        let key = ((point.client, point.side), point.id);
+        // 見えない手の関節は画面の下へ退避しているので描かない
+        if transform.translation.y <= -50.0 {
+            pose.trails.remove(&key);
+            continue;
+        }
+        let position = match pose.trails.get_mut(&key) {
+            Some(trail) => trail.push(transform.translation, now),
+            None => {
+                pose.trails.insert(key, LandmarkTrail::new(transform.translation, now));
+                transform.translation
+            }
+        };
+        pose.positions.insert(key, position);
+    }
+}
+
+fn draw_skeletons(
+    pose: Res<RenderPose>,
+    hand_mats: Res<HandMaterials>,
+    hand_presence: Res<HandPresence>,
+    settings: Res<Settings>,
+    topology: Res<Topology>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    if !settings.display.hands.draws_skeleton() {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    for &(client, side) in hand_mats.materials.keys() {
+        let base_color = skeleton_color(client, side);
+        let color = if hand_presence.is_visible((client, side), now) {
+            base_color
+        } else {
+            base_color.with_alpha(0.1)
+        };
+        draw_hand_skeleton(&mut gizmos, &topology.0.connections, |id| pose.positions.get(&((client, side), id)).copied(), color);
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::core_pipeline::bloom::BloomSettings;
+use bevy::core_pipeline::tonemapping::Tonemapping;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::dj::DjMode;
+use crate::settings::Settings;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    None,
+    Reinhard,
+    AcesFitted,
+    AgX,
+    #[default]
+    TonyMcMapface,
+    BlenderFilmic,
+}
+
+impl ToneMapping {
+    #[cfg(feature = "ui")]
+    pub const ALL: [ToneMapping; 6] = [
+        ToneMapping::None,
+        ToneMapping::Reinhard,
+        ToneMapping::AcesFitted,
+        ToneMapping::AgX,
+        ToneMapping::TonyMcMapface,
+        ToneMapping::BlenderFilmic,
+    ];
+
+    #[cfg(feature = "ui")]
+    pub fn label(self) -> &'static str {
+        match self {
+            ToneMapping::None => "None",
+            ToneMapping::Reinhard => "Reinhard",
+            ToneMapping::AcesFitted => "ACES",
+            ToneMapping::AgX => "AgX",
+            ToneMapping::TonyMcMapface => "TonyMcMapface",
+            ToneMapping::BlenderFilmic => "Blender Filmic",
+        }
+    }
+
+    fn tonemapping(self) -> Tonemapping {
+        match self {
+            ToneMapping::None => Tonemapping::None,
+            ToneMapping::Reinhard => Tonemapping::Reinhard,
+            ToneMapping::AcesFitted => Tonemapping::AcesFitted,
+            ToneMapping::AgX => Tonemapping::AgX,
+            ToneMapping::TonyMcMapface => Tonemapping::TonyMcMapface,
+            ToneMapping::BlenderFilmic => Tonemapping::BlenderFilmic,
+        }
+    }
+}
+
+// 手の発光を光らせるブルームとトーンマッピング、アンチエイリアスの設定。
+// 発光色はブルーム (HDR のカメラが要る) がないと単に明るい色で塗られるだけになる
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RenderQuality {
+    pub bloom: bool,
+    pub bloom_intensity: f32,
+    pub tonemapping: ToneMapping,
+    // MSAA のサンプル数 (1, 2, 4, 8)。1 で切る
+    pub msaa_samples: u32,
+    // 一番よく動いている手 (か掴んでいる物体) にピントを合わせ、ほかをぼかす (focus.rs)
+    pub depth_of_field: bool,
+    // 小さいほど浅く、強くぼける
+    pub aperture_f_stops: f32,
+}
+
+impl Default for RenderQuality {
+    fn default() -> Self {
+        Self {
+            bloom: true,
+            bloom_intensity: 0.2,
+            tonemapping: ToneMapping::default(),
+            msaa_samples: 4,
+            depth_of_field: false,
+            aperture_f_stops: 0.5,
+        }
+    }
+}
+
+impl RenderQuality {
+    #[cfg(feature = "ui")]
+    pub const MSAA_SAMPLES: [u32; 4] = [1, 2, 4, 8];
+
+    fn msaa(&self) -> Msaa {
+        match self.msaa_samples {
+            0 | 1 => Msaa::Off,
+            2 => Msaa::Sample2,
+            3..=4 => Msaa::Sample4,
+            _ => Msaa::Sample8,
+        }
+    }
+
+    // カメラをこの設定に合わせる。DJ モードを抜けたときにも使う
+    pub fn apply_to_camera(&self, commands: &mut Commands, entity: Entity, camera: &mut Camera) {
+        camera.hdr = self.bloom;
+        let mut entity = commands.entity(entity);
+        entity.insert(self.tonemapping.tonemapping());
+        if self.bloom {
+            entity.insert(BloomSettings { intensity: self.bloom_intensity, ..BloomSettings::NATURAL });
+        } else {
+            entity.remove::<BloomSettings>();
+        }
+    }
+}
+
+pub struct RenderQualityPlugin;
+
+impl Plugin for RenderQualityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_render_quality);
+    }
+}
+
+// 設定が変わったとき (と起動直後のカメラ) に反映する。DJ モードの間はブルームを DJ モードに任せる
+fn apply_render_quality(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    dj: Res<DjMode>,
+    mut msaa: ResMut<Msaa>,
+    mut cameras: Query<(Entity, &mut Camera), With<Camera3d>>,
+    added: Query<(), Added<Camera3d>>,
+    mut applied: Local<Option<RenderQuality>>,
+) {
+    let quality = settings.render;
+    if *applied == Some(quality) && added.is_empty() {
+        return;
+    }
+    if *msaa != quality.msaa() {
+        *msaa = quality.msaa();
+    }
+    if !dj.active {
+        for (entity, mut camera) in cameras.iter_mut() {
+            quality.apply_to_camera(&mut commands, entity, &mut camera);
+        }
+    }
+    *applied = Some(quality);
+}
@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::PathBuf;
+
+use crate::packet::{HandPacket, IncomingPacket, PacketSet};
+use crate::smoothing::Smoothing;
+
+#[cfg(feature = "ui")]
+pub const MIN_SPEED: f32 = 0.25;
+#[cfg(feature = "ui")]
+pub const MAX_SPEED: f32 = 4.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedFrame {
+    pub t: f32,
+    pub packet: HandPacket,
+}
+
+#[derive(Resource)]
+struct Recorder {
+    writer: LineWriter<File>,
+    start_time: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    #[cfg(feature = "ui")]
+    pub label: String,
+    pub t: f32,
+}
+
+#[derive(Resource)]
+pub struct ReplaySource {
+    pub frames: Vec<RecordedFrame>,
+    pub position: f32,
+    pub playing: bool,
+    pub speed: f32,
+    pub looping: bool,
+    pub bookmarks: Vec<Bookmark>,
+    current: Option<usize>,
+}
+
+impl ReplaySource {
+    pub fn load(path: &PathBuf) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut frames = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<RecordedFrame>(&line) {
+                Ok(frame) => frames.push(frame),
+                Err(e) => warn!("skipping bad replay line: {e}"),
+            }
+        }
+        frames.sort_by(|a, b| a.t.total_cmp(&b.t));
+        Ok(Self::from_frames(frames))
+    }
+
+    pub fn from_frames(frames: Vec<RecordedFrame>) -> Self {
+        let position = frames.first().map(|f| f.t).unwrap_or(0.0);
+        Self {
+            frames,
+            position,
+            playing: true,
+            speed: 1.0,
+            looping: false,
+            bookmarks: Vec::new(),
+            current: None,
+        }
+    }
+
+    pub fn start(&self) -> f32 {
+        self.frames.first().map(|f| f.t).unwrap_or(0.0)
+    }
+
+    pub fn end(&self) -> f32 {
+        self.frames.last().map(|f| f.t).unwrap_or(0.0)
+    }
+
+    // position 以前で最後のフレーム
+    pub fn frame_index_at(&self, t: f32) -> Option<usize> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let idx = self.frames.partition_point(|f| f.t <= t);
+        Some(idx.saturating_sub(1))
+    }
+
+    #[cfg(feature = "ui")]
+    pub fn seek(&mut self, t: f32) {
+        self.position = t.clamp(self.start(), self.end());
+    }
+
+    pub fn step(&mut self, delta: isize) {
+        self.playing = false;
+        let Some(idx) = self.frame_index_at(self.position) else {
+            return;
+        };
+        let next = (idx as isize + delta).clamp(0, self.frames.len() as isize - 1) as usize;
+        self.position = self.frames[next].t;
+    }
+
+    // 再生中なら dt 秒ぶん進める。末尾で止まるか先頭に戻る
+    pub fn advance(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        let next = self.position + dt * self.speed;
+        if next >= self.end() {
+            if self.looping {
+                self.position = self.start();
+                self.current = None;
+            } else {
+                self.position = self.end();
+                self.playing = false;
+            }
+        } else {
+            self.position = next;
+        }
+    }
+
+    // 先頭から再生し直す
+    pub fn restart(&mut self) {
+        self.position = self.start();
+        self.current = None;
+        self.playing = true;
+    }
+
+    pub fn add_bookmark(&mut self) {
+        self.bookmarks.push(Bookmark {
+            #[cfg(feature = "ui")]
+            label: format!("#{} {:.2}s", self.bookmarks.len() + 1, self.position - self.start()),
+            t: self.position,
+        });
+        self.bookmarks.sort_by(|a, b| a.t.total_cmp(&b.t));
+    }
+}
+
+pub struct ReplayPlugin {
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = &self.replay {
+            match ReplaySource::load(path) {
+                Ok(source) => {
+                    info!("replaying {} frames from {}", source.frames.len(), path.display());
+                    app.insert_resource(source);
+                }
+                Err(e) => error!("failed to load replay {}: {e}", path.display()),
+            }
+        } else if let Some(path) = &self.record {
+            match File::create(path) {
+                Ok(file) => {
+                    info!("recording to {}", path.display());
+                    app.insert_resource(Recorder {
+                        writer: LineWriter::new(file),
+                        start_time: None,
+                    });
+                }
+                Err(e) => error!("failed to create recording {}: {e}", path.display()),
+            }
+        }
+
+        app.add_systems(
+            Update,
+            (
+                record_packets.run_if(resource_exists::<Recorder>),
+                (replay_controls, advance_replay)
+                    .chain()
+                    .run_if(resource_exists::<ReplaySource>),
+            )
+                .in_set(PacketSet::Override),
+        );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, timeline_ui.run_if(resource_exists::<ReplaySource>));
+    }
+}
+
+fn record_packets(mut recorder: ResMut<Recorder>, incoming: Res<IncomingPacket>, time: Res<Time>) {
+    let Some(packet) = &incoming.0 else {
+        return;
+    };
+    let now = time.elapsed_seconds();
+    let start = *recorder.start_time.get_or_insert(now);
+    let frame = RecordedFrame {
+        t: now - start,
+        packet: packet.clone(),
+    };
+    if let Ok(line) = serde_json::to_string(&frame)
+        && let Err(e) = writeln!(recorder.writer, "{line}")
+    {
+        error!("failed to write recording: {e}");
+    }
+}
+
+fn replay_controls(keys: Res<ButtonInput<KeyCode>>, mut source: ResMut<ReplaySource>) {
+    if keys.just_pressed(KeyCode::Space) {
+        source.playing = !source.playing;
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        source.step(1);
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        source.step(-1);
+    }
+    if keys.just_pressed(KeyCode::KeyB) {
+        source.add_bookmark();
+    }
+}
+
+fn advance_replay(
+    mut source: ResMut<ReplaySource>,
+    mut incoming: ResMut<IncomingPacket>,
+    mut smoothing: ResMut<Smoothing>,
+    time: Res<Time>,
+) {
+    source.advance(time.delta_seconds());
+    // 記録の時刻軸で補間させ、追いつく速さも再生速度に合わせる
+    smoothing.playback_rate = if source.playing { source.speed } else { 1.0 };
+
+    // リプレイ中はライブ入力を無視する
+    let idx = source.frame_index_at(source.position);
+    let reached_new_frame = idx != source.current;
+    source.current = idx;
+
+    incoming.0 = idx.map(|i| {
+        let mut packet = source.frames[i].packet.clone();
+        // 一時停止中にスナップを繰り返さない
+        packet.snap &= reached_new_frame;
+        // 送信側の撮影時刻ではなく記録上の時刻を使う。巻き戻しやループでは時刻が戻るので補間をやり直す
+        packet.timestamp = Some(source.frames[i].t as f64);
+        packet
+    });
+}
+
+#[cfg(feature = "ui")]
+fn timeline_ui(mut contexts: EguiContexts, mut source: ResMut<ReplaySource>) {
+    let ctx = contexts.ctx_mut();
+    egui::TopBottomPanel::bottom("replay_timeline").show(ctx, |ui| {
+        let start = source.start();
+        let end = source.end();
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮").clicked() {
+                source.step(-1);
+            }
+            let label = if source.playing { "⏸" } else { "▶" };
+            if ui.button(label).clicked() {
+                source.playing = !source.playing;
+            }
+            if ui.button("⏭").clicked() {
+                source.step(1);
+            }
+
+            ui.separator();
+            ui.label("Speed");
+            ui.add(
+                egui::Slider::new(&mut source.speed, MIN_SPEED..=MAX_SPEED)
+                    .logarithmic(true)
+                    .suffix("×"),
+            );
+            for preset in [0.25, 0.5, 1.0, 2.0, 4.0] {
+                if ui.small_button(format!("{preset}×")).clicked() {
+                    source.speed = preset;
+                }
+            }
+
+            ui.separator();
+            ui.checkbox(&mut source.looping, "Loop");
+
+            ui.separator();
+            let frame = source.frame_index_at(source.position).map(|i| i + 1).unwrap_or(0);
+            ui.label(format!(
+                "{:.2}s / {:.2}s  frame {}/{}",
+                source.position - start,
+                end - start,
+                frame,
+                source.frames.len()
+            ));
+        });
+
+        let mut position = source.position;
+        let slider = ui.add_sized(
+            [ui.available_width(), 18.0],
+            egui::Slider::new(&mut position, start..=end.max(start))
+                .show_value(false)
+                .trailing_fill(true),
+        );
+        if slider.changed() {
+            source.seek(position);
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            if ui.button("+ Bookmark").clicked() {
+                source.add_bookmark();
+            }
+            let mut jump_to = None;
+            let mut remove = None;
+            for (i, bookmark) in source.bookmarks.iter().enumerate() {
+                let response = ui.button(&bookmark.label);
+                if response.clicked() {
+                    jump_to = Some(bookmark.t);
+                }
+                if response.secondary_clicked() {
+                    remove = Some(i);
+                }
+            }
+            if let Some(t) = jump_to {
+                source.playing = false;
+                source.seek(t);
+            }
+            if let Some(i) = remove {
+                source.bookmarks.remove(i);
+            }
+        });
+    });
+}
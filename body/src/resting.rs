@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::resting::{RestDetector, RestThresholds};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::settings::Settings;
+use crate::{HandKey, HandStates};
+
+// 手のひらが上か下を向いているとみなす内積 (約 35° まで)
+const MIN_FLATNESS: f32 = 0.8;
+// 机の面に出す手の影の大きさ
+const SHADOW_SIZE: f32 = 1.6;
+
+pub struct RestingPlugin;
+
+impl Plugin for RestingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, detect_resting_hands.after(PalmPoseSet));
+    }
+}
+
+// 仮想の机に低く・平らに・じっと置いた手を休ませる。休ませた手は当たり判定を外され (workspace.rs)、
+// 新しく掴めなくなる。持ち上げたら戻す
+fn detect_resting_hands(
+    settings: Res<Settings>,
+    palms: Res<PalmPoses>,
+    mut hand_states: ResMut<HandStates>,
+    mut detectors: Local<HashMap<HandKey, RestDetector>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let config = settings.resting;
+    if !config.enabled {
+        detectors.clear();
+        hand_states.resting.clear();
+        return;
+    }
+    let limits = RestThresholds {
+        rest_height: config.rest_height,
+        lift_height: config.lift_height,
+        min_flatness: MIN_FLATNESS,
+        max_speed: config.max_speed,
+        dwell: config.dwell,
+    };
+    let now = time.elapsed_seconds();
+    detectors.retain(|key, _| palms.poses.contains_key(key));
+    for (key, palm) in &palms.poses {
+        let position = palm.position();
+        let height = position.y - config.table_height;
+        let flatness = (palm.orientation() * Vec3::Z).dot(Vec3::Y).abs();
+        let speed = palm.linear_velocity.length();
+        let resting = detectors.entry(*key).or_default().update(height, flatness, speed, now, &limits);
+        if resting && hand_states.resting.insert(*key) {
+            info!(client = key.0, side = key.1.label(), "hand resting; interaction suspended");
+        } else if !resting && hand_states.resting.remove(key) {
+            info!(client = key.0, side = key.1.label(), "hand lifted");
+        }
+        // 机の近くにある手は机の面に影を落とし、休ませている間は色を変える
+        if height < config.lift_height {
+            let shadow = Vec3::new(position.x, config.table_height, position.z);
+            let color = if resting { Color::srgb(0.4, 0.6, 1.0) } else { Color::srgba(0.8, 0.8, 0.8, 0.4) };
+            let rotation = Quat::from_rotation_arc(Vec3::Z, Vec3::Y);
+            gizmos.rect(shadow, rotation, Vec2::splat(SHADOW_SIZE), color);
+        }
+    }
+    hand_states.resting.retain(|key| palms.poses.contains_key(key));
+}
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::gesture::Gesture;
+use crate::core::reveal::Cone;
+use crate::grab::{surface_radius, Grabbable};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::spawn::SpawnedBox;
+use crate::{hand_color, HandStates};
+
+// 光の円すいの開き (半分の角度) と届く距離
+const BEAM_HALF_ANGLE: f32 = 0.35;
+const BEAM_RANGE: f32 = 30.0;
+// 見え方を照らされ具合に寄せる速さ (1/秒)
+const FADE_RATE: f32 = 8.0;
+
+// 隠し物モードで、懐中電灯の光に入ったときだけ見える物体
+#[derive(Component)]
+pub struct Concealed {
+    // 今見せている濃さ (0 で見えない)
+    shown: f32,
+}
+
+// 隠し物モード。開いた手のひらから光の円すいを伸ばし、その中の隠した物体だけを見せる (かくれんぼのデモ用)
+#[derive(Resource, Default)]
+pub struct Reveal {
+    pub enabled: bool,
+}
+
+pub struct RevealPlugin;
+
+impl Plugin for RevealPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Reveal::default())
+            .add_console_command("reveal", "reveal on | reveal off | reveal hide | reveal show", reveal_command)
+            .add_systems(Update, reveal_concealed.after(PalmPoseSet));
+    }
+}
+
+// 物体の見た目を濃さ shown にする。半透明の間だけ混ぜて描く。持ち主の色 (ownership.rs) は色だけを変えるので透明度は残る
+fn set_shown(material: &mut StandardMaterial, shown: f32) {
+    material.base_color.set_alpha(shown);
+    material.alpha_mode = if shown < 1.0 { AlphaMode::Blend } else { AlphaMode::Opaque };
+}
+
+#[allow(clippy::type_complexity)]
+fn reveal_command(
+    In(args): In<Vec<String>>,
+    mut commands: Commands,
+    mut reveal: ResMut<Reveal>,
+    candidates: Query<Entity, (Or<(With<SpawnedBox>, With<Grabbable>)>, With<RigidBody>, Without<Concealed>)>,
+    mut concealed: Query<(Entity, Option<&Handle<StandardMaterial>>, &mut Visibility), With<Concealed>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) -> ConsoleResult {
+    match args.first().map(String::as_str) {
+        Some("on") => {
+            reveal.enabled = true;
+            match concealed.iter().count() {
+                0 => Ok("reveal mode on; nothing is hidden yet (reveal hide)".to_string()),
+                count => Ok(format!("reveal mode on; {count} objects hidden")),
+            }
+        }
+        Some("off") => {
+            reveal.enabled = false;
+            Ok("reveal mode off".to_string())
+        }
+        // 今ある箱と掴める物体をすべて隠す
+        Some("hide") => {
+            let mut count = 0;
+            for entity in candidates.iter() {
+                commands.entity(entity).insert(Concealed { shown: 1.0 });
+                count += 1;
+            }
+            Ok(format!("hid {count} objects"))
+        }
+        Some("show") => {
+            let mut count = 0;
+            for (entity, material, mut visibility) in concealed.iter_mut() {
+                if let Some(material) = material.and_then(|m| materials.get_mut(m)) {
+                    set_shown(material, 1.0);
+                }
+                *visibility = Visibility::Inherited;
+                commands.entity(entity).remove::<Concealed>();
+                count += 1;
+            }
+            Ok(format!("showed {count} objects"))
+        }
+        _ => Err("reveal on | reveal off | reveal hide | reveal show".to_string()),
+    }
+}
+
+// 物体ごとに、どれかの手の光に入っているかを調べて濃さを寄せる。モードを切っている間はすべて見せる
+#[allow(clippy::type_complexity)]
+fn reveal_concealed(
+    reveal: Res<Reveal>,
+    palms: Res<PalmPoses>,
+    hand_states: Res<HandStates>,
+    mut objects: Query<(
+        &GlobalTransform,
+        &mut Concealed,
+        &mut Visibility,
+        Option<&Handle<StandardMaterial>>,
+        (Option<&SpawnedBox>, Option<&Grabbable>),
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let mut cones = Vec::new();
+    if reveal.enabled {
+        for (key, palm) in &palms.poses {
+            let open = hand_states.hands.get(key).is_some_and(|h| h.gesture == Gesture::Open);
+            if !open || !hand_states.is_confident(*key) {
+                continue;
+            }
+            // palm_rotation の z は手の甲の向きなので、その逆の手のひらの側を照らす
+            let direction = palm.orientation() * Vec3::NEG_Z;
+            let cone = Cone { apex: palm.position(), direction, half_angle: BEAM_HALF_ANGLE, range: BEAM_RANGE };
+            draw_beam(&mut gizmos, &cone, hand_color(key.0, key.1).with_alpha(0.35));
+            cones.push(cone);
+        }
+    }
+
+    let blend = 1.0 - (-FADE_RATE * time.delta_seconds()).exp();
+    for (transform, mut concealed, mut visibility, material, size) in objects.iter_mut() {
+        let target = if reveal.enabled {
+            let (center, radius) = (transform.translation(), surface_radius(size));
+            cones.iter().map(|cone| cone.reveal(center, radius)).fold(0.0, f32::max)
+        } else {
+            1.0
+        };
+        let shown = if (target - concealed.shown).abs() < 0.01 {
+            target
+        } else {
+            concealed.shown + (target - concealed.shown) * blend
+        };
+        if shown == concealed.shown {
+            continue;
+        }
+        concealed.shown = shown;
+        *visibility = if shown > 0.0 { Visibility::Inherited } else { Visibility::Hidden };
+        // 色を持たない物体 (glTF の模型など) は出すか消すかだけ
+        if let Some(material) = material.and_then(|m| materials.get_mut(m)) {
+            set_shown(material, shown);
+        }
+    }
+}
+
+// 光の先の輪と、手のひらから輪へ伸びる線
+fn draw_beam(gizmos: &mut Gizmos, cone: &Cone, color: Color) {
+    let Ok(axis) = Dir3::new(cone.direction) else {
+        return;
+    };
+    let end = cone.apex + cone.direction * cone.range;
+    let radius = cone.range * cone.half_angle.tan();
+    gizmos.circle(end, axis, radius, color);
+    let side = cone.direction.any_orthonormal_vector();
+    for i in 0..4 {
+        let spoke = Quat::from_axis_angle(cone.direction, i as f32 * std::f32::consts::FRAC_PI_2) * side;
+        gizmos.line(cone.apex, end + spoke * radius, color);
+    }
+}
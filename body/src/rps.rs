@@ -0,0 +1,356 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::core::gesture::{classify_rps, RpsShape};
+use crate::packet::{IncomingPacket, PacketSet};
+use crate::topology::Topology;
+use crate::{draw_hand_skeleton, setup, spawn_hand_points, update_hands_and_physics, HandMaterials, HandSide, HandStates};
+
+const BEAT: f32 = 0.6;
+const SHOOT_WINDOW: f32 = 0.4;
+const RESULT_TIME: f32 = 2.0;
+const HAND_TIMEOUT: f32 = 1.0;
+
+const BOT_ANCHOR: Vec3 = Vec3::new(7.0, 0.0, 4.0);
+const BOT_SCALE: f32 = 2.0;
+const PUMP_HEIGHT: f32 = 0.8;
+
+#[derive(Component)]
+struct BotHandPoint {
+    id: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Waiting,
+    CountIn(u8),
+    Shoot,
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Win,
+    Lose,
+    Draw,
+    NoThrow,
+}
+
+#[derive(Resource)]
+pub struct RpsGame {
+    pub active: bool,
+    pub player_score: u32,
+    pub bot_score: u32,
+    pub draws: u32,
+    phase: Phase,
+    phase_time: f32,
+    player_shape: Option<RpsShape>,
+    player_seen: f32,
+    bot_shape: RpsShape,
+    last_round: Option<(Option<RpsShape>, RpsShape, Outcome)>,
+    history: HashMap<RpsShape, u32>,
+    rng: u32,
+}
+
+impl Default for RpsGame {
+    fn default() -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(1);
+        Self {
+            active: false,
+            player_score: 0,
+            bot_score: 0,
+            draws: 0,
+            phase: Phase::Waiting,
+            phase_time: 0.0,
+            player_shape: None,
+            player_seen: f32::NEG_INFINITY,
+            bot_shape: RpsShape::Rock,
+            last_round: None,
+            history: HashMap::new(),
+            rng: seed.max(1),
+        }
+    }
+}
+
+impl RpsGame {
+    fn next_random(&mut self) -> u32 {
+        // xorshift32
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+        x
+    }
+
+    // 相手が一番よく出す手に勝つ手を基本にしつつ、ランダムも混ぜる
+    fn choose_bot_shape(&mut self) -> RpsShape {
+        let favourite = self
+            .history
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(shape, _)| *shape);
+        let roll = self.next_random() % 100;
+        match favourite {
+            Some(shape) if roll < 60 => shape.counter(),
+            _ => RpsShape::ALL[(self.next_random() % 3) as usize],
+        }
+    }
+
+    fn enter(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.phase_time = 0.0;
+    }
+}
+
+pub struct RpsPlugin;
+
+impl Plugin for RpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RpsGame::default())
+            .add_systems(Startup, spawn_bot_rig.after(setup))
+            .add_systems(
+                Update,
+                (toggle_game, read_player_shape, advance_game, pose_bot_rig)
+                    .chain()
+                    .after(PacketSet::Override)
+                    .after(update_hands_and_physics),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, scoreboard_ui);
+    }
+}
+
+// 追跡した手と同じ関節の球と骨組みで出し、色だけ変える
+fn spawn_bot_rig(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hand_mats: Res<HandMaterials>,
+    topology: Res<Topology>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.85, 0.1),
+        emissive: LinearRgba::new(1.0, 0.8, 0.1, 1.0),
+        ..default()
+    });
+    spawn_hand_points(&mut commands, &hand_mats.sphere_mesh, &material, &topology.0, |id| BotHandPoint { id });
+}
+
+fn toggle_game(keys: Res<ButtonInput<KeyCode>>, mut game: ResMut<RpsGame>) {
+    if keys.just_pressed(KeyCode::KeyG) {
+        game.active = !game.active;
+        game.enter(Phase::Waiting);
+    }
+}
+
+fn read_player_shape(
+    incoming: Res<IncomingPacket>,
+    hand_states: Res<HandStates>,
+    mut game: ResMut<RpsGame>,
+    time: Res<Time>,
+) {
+    // じゃんけんはローカルのクライアントとだけ遊ぶ。
+    // 手が見えなくなったら、見えていたときの形で勝負しない
+    if hand_states.get(HandSide::Right).is_none() && hand_states.get(HandSide::Left).is_none() {
+        game.player_shape = None;
+        return;
+    }
+    let Some(packet) = &incoming.0 else {
+        return;
+    };
+    let hand = packet
+        .hands
+        .iter()
+        .find(|h| h.label == "Right")
+        .or_else(|| packet.hands.first());
+    if let Some(hand) = hand {
+        game.player_seen = time.elapsed_seconds();
+        game.player_shape = classify_rps(hand);
+    }
+}
+
+fn advance_game(mut game: ResMut<RpsGame>, time: Res<Time>) {
+    if !game.active {
+        return;
+    }
+    game.phase_time += time.delta_seconds();
+    let hand_present = time.elapsed_seconds() - game.player_seen < HAND_TIMEOUT;
+
+    match game.phase {
+        Phase::Waiting => {
+            if hand_present {
+                game.enter(Phase::CountIn(0));
+            }
+        }
+        Phase::CountIn(beat) => {
+            if !hand_present {
+                game.enter(Phase::Waiting);
+            } else if game.phase_time >= BEAT {
+                if beat < 2 {
+                    game.enter(Phase::CountIn(beat + 1));
+                } else {
+                    // ボットの手はプレイヤーの手を見る前に決める
+                    game.bot_shape = game.choose_bot_shape();
+                    game.enter(Phase::Shoot);
+                }
+            }
+        }
+        Phase::Shoot => {
+            if game.phase_time >= SHOOT_WINDOW {
+                let bot = game.bot_shape;
+                let player = game.player_shape;
+                let outcome = match player {
+                    None => Outcome::NoThrow,
+                    Some(p) if p == bot => Outcome::Draw,
+                    Some(p) if p.beats(bot) => Outcome::Win,
+                    Some(_) => Outcome::Lose,
+                };
+                match outcome {
+                    Outcome::Win => game.player_score += 1,
+                    Outcome::Lose => game.bot_score += 1,
+                    Outcome::Draw => game.draws += 1,
+                    Outcome::NoThrow => {}
+                }
+                if let Some(p) = player {
+                    *game.history.entry(p).or_default() += 1;
+                }
+                game.last_round = Some((player, bot, outcome));
+                game.enter(Phase::Result);
+            }
+        }
+        Phase::Result => {
+            if game.phase_time >= RESULT_TIME {
+                let next = if hand_present { Phase::CountIn(0) } else { Phase::Waiting };
+                game.enter(next);
+            }
+        }
+    }
+}
+
+// 手のひらを正面に向けた開いた手 (手首原点、y が指先方向)。MediaPipe の 21 点で、
+// topology で増やした点は表示しない
+const OPEN_POSE: [Vec3; 21] = [
+    Vec3::new(0.0, 0.0, 0.0),
+    Vec3::new(-0.35, 0.25, 0.0),
+    Vec3::new(-0.6, 0.45, 0.0),
+    Vec3::new(-0.8, 0.65, 0.0),
+    Vec3::new(-0.95, 0.85, 0.0),
+    Vec3::new(-0.3, 1.0, 0.0),
+    Vec3::new(-0.35, 1.45, 0.0),
+    Vec3::new(-0.38, 1.75, 0.0),
+    Vec3::new(-0.4, 2.0, 0.0),
+    Vec3::new(0.0, 1.05, 0.0),
+    Vec3::new(0.0, 1.55, 0.0),
+    Vec3::new(0.0, 1.9, 0.0),
+    Vec3::new(0.0, 2.15, 0.0),
+    Vec3::new(0.27, 1.0, 0.0),
+    Vec3::new(0.3, 1.45, 0.0),
+    Vec3::new(0.32, 1.75, 0.0),
+    Vec3::new(0.34, 1.95, 0.0),
+    Vec3::new(0.5, 0.9, 0.0),
+    Vec3::new(0.58, 1.25, 0.0),
+    Vec3::new(0.63, 1.45, 0.0),
+    Vec3::new(0.67, 1.65, 0.0),
+];
+
+fn canned_pose(shape: RpsShape) -> [Vec3; 21] {
+    let mut pose = OPEN_POSE;
+    let folded: &[usize] = match shape {
+        RpsShape::Rock => &[5, 9, 13, 17],
+        RpsShape::Paper => &[],
+        RpsShape::Scissors => &[13, 17],
+    };
+    for &mcp in folded {
+        let base = pose[mcp];
+        pose[mcp + 1] = base + Vec3::new(0.0, 0.35, -0.25);
+        pose[mcp + 2] = base + Vec3::new(0.0, 0.15, -0.45);
+        pose[mcp + 3] = base + Vec3::new(0.0, -0.05, -0.3);
+    }
+    if shape != RpsShape::Paper {
+        pose[2] = Vec3::new(-0.35, 0.5, -0.2);
+        pose[3] = Vec3::new(-0.15, 0.65, -0.35);
+        pose[4] = Vec3::new(0.05, 0.7, -0.35);
+    }
+    pose
+}
+
+fn pose_bot_rig(
+    game: Res<RpsGame>,
+    topology: Res<Topology>,
+    mut points: Query<(&BotHandPoint, &mut Transform, &mut Visibility)>,
+    mut gizmos: Gizmos,
+) {
+    let (shape, pump) = match game.phase {
+        Phase::CountIn(_) => {
+            let t = game.phase_time / BEAT;
+            (RpsShape::Rock, (t * std::f32::consts::PI).sin() * PUMP_HEIGHT)
+        }
+        Phase::Shoot | Phase::Result => (game.bot_shape, 0.0),
+        Phase::Waiting => (RpsShape::Paper, 0.0),
+    };
+    let pose = canned_pose(shape);
+    let world = |id: usize| pose.get(id).map(|p| BOT_ANCHOR + Vec3::Y * pump + *p * BOT_SCALE);
+
+    for (point, mut transform, mut visibility) in points.iter_mut() {
+        let position = world(point.id).filter(|_| game.active);
+        *visibility = if position.is_some() { Visibility::Visible } else { Visibility::Hidden };
+        if let Some(position) = position {
+            transform.translation = position;
+        }
+    }
+
+    if game.active {
+        draw_hand_skeleton(&mut gizmos, &topology.0.connections, world, Color::srgb(1.0, 0.85, 0.1));
+    }
+}
+
+#[cfg(feature = "ui")]
+fn shape_name(shape: Option<RpsShape>) -> &'static str {
+    match shape {
+        Some(RpsShape::Rock) => "Rock",
+        Some(RpsShape::Paper) => "Paper",
+        Some(RpsShape::Scissors) => "Scissors",
+        None => "?",
+    }
+}
+
+#[cfg(feature = "ui")]
+fn scoreboard_ui(mut contexts: EguiContexts, game: Res<RpsGame>) {
+    if !game.active {
+        return;
+    }
+    egui::Window::new("Rock Paper Scissors")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(format!(
+                "You {}  -  {} Bot   (draws {})",
+                game.player_score, game.bot_score, game.draws
+            ));
+            let prompt = match game.phase {
+                Phase::Waiting => "Show your hand to start".to_string(),
+                Phase::CountIn(0) => "Rock...".to_string(),
+                Phase::CountIn(1) => "Paper...".to_string(),
+                Phase::CountIn(_) => "Scissors...".to_string(),
+                Phase::Shoot => "Shoot!".to_string(),
+                Phase::Result => match game.last_round {
+                    Some((_, _, Outcome::Win)) => "You win!".to_string(),
+                    Some((_, _, Outcome::Lose)) => "Bot wins!".to_string(),
+                    Some((_, _, Outcome::Draw)) => "Draw".to_string(),
+                    _ => "No throw detected".to_string(),
+                },
+            };
+            ui.label(egui::RichText::new(prompt).size(28.0));
+            if let Some((player, bot, _)) = game.last_round {
+                ui.label(format!("Last round: {} vs {}", shape_name(player), shape_name(Some(bot))));
+            }
+            ui.label(format!("Live: {}", shape_name(game.player_shape)));
+            ui.small("G: quit game");
+        });
+}
@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::gesture::Gesture;
+use crate::core::scoop::{Scoop, SCOOP_RADIUS};
+use crate::display::InteractionSet;
+use crate::grab::{surface_radius, Grabbable, Held, SMALL_OBJECT_RADIUS};
+use crate::packet::ClientId;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::spawn::SpawnedBox;
+use crate::versus::{can_touch, Team};
+use crate::{update_hands_and_physics, HandSide, HandStates};
+
+// 手のひら同士がこの速さ (単位/秒) 以上で近づいているときにすくい始める
+const CLOSING_SPEED: f32 = 1.5;
+// すくったときの間隔よりこれだけ広がったら放す
+const RELEASE_MARGIN: f32 = 0.8;
+
+// 両手ですくって運んでいる物体。手のひらの間の座標系での位置と向きを持つ
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Scooped {
+    pub client: ClientId,
+    offset: Vec3,
+    rotation: Quat,
+    // すくう前の物体の種類。放したら戻す
+    body: RigidBody,
+}
+
+struct Carry {
+    rotation: Quat,
+    release_gap: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct Scoops {
+    carries: HashMap<ClientId, Carry>,
+    previous_gap: HashMap<ClientId, f32>,
+}
+
+pub struct ScoopPlugin;
+
+impl Plugin for ScoopPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Scoops::default())
+            .add_systems(
+                Update,
+                scoop_objects.after(update_hands_and_physics).after(PalmPoseSet).in_set(InteractionSet),
+            );
+    }
+}
+
+// 向かい合わせた両手のひらを近づけると、間にある小さい物体をまとめて運動学的な物体に切り替えて手と一緒に動かす。
+// 手のひらを離すか向かい合わなくなったら、手の速さを与えて物理に戻す
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn scoop_objects(
+    mut commands: Commands,
+    mut scoops: ResMut<Scoops>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    mut objects: Query<
+        (Entity, &mut Transform, AnyOf<(&SpawnedBox, &Grabbable)>, &RigidBody, Option<&Scooped>),
+        Without<Held>,
+    >,
+    teams: Query<&Team>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let mut clients: Vec<ClientId> = hand_states.hands.keys().map(|(client, _)| *client).collect();
+    clients.extend(scoops.carries.keys());
+    clients.sort_unstable();
+    clients.dedup();
+
+    let scoops = &mut *scoops;
+    for client in clients {
+        let (left, right) = ((client, HandSide::Left), (client, HandSide::Right));
+        let pair = palms.poses.get(&left).zip(palms.poses.get(&right));
+        let scoop = pair.and_then(|(l, r)| {
+            Scoop::between(l.position(), l.orientation() * Vec3::Z, r.position(), r.orientation() * Vec3::Z)
+        });
+        let previous_gap = match scoop {
+            Some(scoop) => scoops.previous_gap.insert(client, scoop.gap),
+            None => scoops.previous_gap.remove(&client),
+        };
+
+        if let Some(carry) = scoops.carries.get_mut(&client) {
+            // 手が重なって見失いかけている間は向きが怪しいので、手が消えない限り運び続ける
+            let overlapping = !hand_states.is_confident(left) || !hand_states.is_confident(right);
+            match scoop {
+                Some(scoop) if scoop.gap <= carry.release_gap => {
+                    carry.rotation = scoop.rotation(carry.rotation);
+                    for (_, mut transform, _, _, scooped) in objects.iter_mut() {
+                        if let Some(scooped) = scooped.filter(|s| s.client == client) {
+                            transform.translation = scoop.center + carry.rotation * scooped.offset;
+                            transform.rotation = carry.rotation * scooped.rotation;
+                        }
+                    }
+                    let axis = Dir3::new(scoop.axis).unwrap_or(Dir3::X);
+                    gizmos.circle(scoop.center, axis, SCOOP_RADIUS, Color::srgb(0.5, 0.9, 1.0));
+                }
+                _ if overlapping && pair.is_some() => {}
+                _ => {
+                    let throw = pair.map_or(Vec3::ZERO, |(l, r)| (l.linear_velocity + r.linear_velocity) / 2.0);
+                    let mut released = 0;
+                    for (entity, _, _, _, scooped) in objects.iter() {
+                        if let Some(scooped) = scooped.filter(|s| s.client == client) {
+                            commands
+                                .entity(entity)
+                                .remove::<Scooped>()
+                                .insert((scooped.body, Velocity::linear(throw)));
+                            released += 1;
+                        }
+                    }
+                    scoops.carries.remove(&client);
+                    info!(client, released, "scoop released");
+                }
+            }
+            continue;
+        }
+
+        let Some(scoop) = scoop else {
+            continue;
+        };
+        // 握った手は片手の掴みに任せる
+        let open = |key| hand_states.hands.get(&key).is_some_and(|h| h.gesture != Gesture::Fist);
+        let closing = previous_gap.is_some_and(|gap| dt > 0.0 && (gap - scoop.gap) / dt > CLOSING_SPEED);
+        if !closing || !open(left) || !open(right) || !hand_states.is_confident(left) || !hand_states.is_confident(right) {
+            continue;
+        }
+        let rotation = scoop.rotation(Quat::IDENTITY);
+        let mut captured = 0;
+        for (entity, transform, b, body, scooped) in objects.iter() {
+            let radius = surface_radius(b);
+            if scooped.is_some()
+                || *body != RigidBody::Dynamic
+                || radius > SMALL_OBJECT_RADIUS
+                || !can_touch(teams.get(entity).ok(), client)
+                || !scoop.contains(transform.translation, radius)
+            {
+                continue;
+            }
+            commands.entity(entity).insert((
+                Scooped {
+                    client,
+                    offset: rotation.inverse() * (transform.translation - scoop.center),
+                    rotation: rotation.inverse() * transform.rotation,
+                    body: *body,
+                },
+                RigidBody::KinematicPositionBased,
+            ));
+            captured += 1;
+        }
+        if captured > 0 {
+            scoops.carries.insert(client, Carry { rotation, release_gap: scoop.gap + RELEASE_MARGIN });
+            info!(client, captured, "scooped");
+        }
+    }
+}
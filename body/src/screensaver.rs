@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::core::ambient::{ring_of_orbs, step_orbs, Orb};
+use crate::idle::{detect_idle, Idle};
+use crate::settings::Settings;
+
+const ORB_COUNT: usize = 8;
+const ORB_RADIUS: f32 = 0.45;
+const CENTER: Vec3 = Vec3::new(0.0, 4.0, -2.0);
+// 現れる・消えるまでの秒数
+const FADE_IN: f32 = 2.0;
+const FADE_OUT: f32 = 0.6;
+
+#[derive(Component)]
+struct AmbientOrb(usize);
+
+// 休止中に漂わせる玉。level は 0 (見えない) から 1 (見えている) まで
+#[derive(Resource, Default)]
+pub struct Screensaver {
+    orbs: Vec<Orb>,
+    pub level: f32,
+}
+
+pub struct ScreensaverPlugin;
+
+impl Plugin for ScreensaverPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Screensaver::default())
+            .add_systems(Update, (fade_screensaver, animate_orbs).chain().after(detect_idle));
+    }
+}
+
+fn orb_color(index: usize) -> Color {
+    Color::hsl(200.0 + index as f32 / ORB_COUNT as f32 * 120.0, 0.8, 0.65)
+}
+
+// 手が見えずに休んでいる間は玉を出してゆっくり明るくし、手が見えたらすぐ薄くして消す
+#[allow(clippy::too_many_arguments)]
+fn fade_screensaver(
+    mut commands: Commands,
+    mut screensaver: ResMut<Screensaver>,
+    idle: Res<Idle>,
+    settings: Res<Settings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    orbs: Query<Entity, With<AmbientOrb>>,
+    time: Res<Time<Real>>,
+) {
+    let active = idle.asleep && settings.display.idle.screensaver;
+    let dt = time.delta_seconds();
+    if active {
+        if screensaver.orbs.is_empty() {
+            screensaver.orbs = ring_of_orbs(ORB_COUNT, CENTER);
+            let mesh = meshes.add(Sphere::new(ORB_RADIUS));
+            for (index, orb) in screensaver.orbs.iter().enumerate() {
+                let color = orb_color(index);
+                commands.spawn((
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        material: materials.add(StandardMaterial {
+                            base_color: color.with_alpha(0.0),
+                            emissive: LinearRgba::BLACK,
+                            alpha_mode: AlphaMode::Blend,
+                            ..default()
+                        }),
+                        transform: Transform::from_translation(orb.position).with_scale(Vec3::ZERO),
+                        ..default()
+                    },
+                    AmbientOrb(index),
+                ));
+            }
+            info!("screensaver started");
+        }
+        screensaver.level = (screensaver.level + dt / FADE_IN).min(1.0);
+    } else if !screensaver.orbs.is_empty() {
+        screensaver.level = (screensaver.level - dt / FADE_OUT).max(0.0);
+        if screensaver.level <= 0.0 {
+            for entity in orbs.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            screensaver.orbs.clear();
+            info!("screensaver cleared");
+        }
+    }
+}
+
+// 物理は休止中も止まっているので、玉は物理エンジンを通さず弱い力で動かす
+fn animate_orbs(
+    mut screensaver: ResMut<Screensaver>,
+    mut orbs: Query<(&AmbientOrb, &mut Transform, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time<Real>>,
+) {
+    if screensaver.orbs.is_empty() {
+        return;
+    }
+    let screensaver = &mut *screensaver;
+    step_orbs(&mut screensaver.orbs, CENTER, time.elapsed_seconds(), time.delta_seconds());
+    // 消えるときは縮むより先に薄くなるよう、大きさは明るさの平方根で変える
+    let level = screensaver.level;
+    for (orb, mut transform, material) in orbs.iter_mut() {
+        let Some(state) = screensaver.orbs.get(orb.0) else {
+            continue;
+        };
+        transform.translation = state.position;
+        transform.scale = Vec3::splat(level.sqrt());
+        if let Some(material) = materials.get_mut(material) {
+            let color = orb_color(orb.0);
+            material.base_color = color.with_alpha(level * 0.8);
+            material.emissive = color.to_linear() * level * 4.0;
+        }
+    }
+}
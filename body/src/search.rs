@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::search::{ObjectFacts, ObjectQuery};
+use crate::grab::{surface_radius, Grabbable, Held};
+use crate::spawn::SpawnedBox;
+use crate::spectator::Spectator;
+
+// 注視点へ向き直る速さ
+const FOCUS_RATE: f32 = 3.0;
+
+// 物体が出てきた時刻。「最近出てきた物体」を探すのに使う
+#[derive(Component)]
+pub struct SpawnedAt(pub f32);
+
+// 条件に合う物体を枠で囲んで見せる。管理用 API から設定する
+#[derive(Resource, Default)]
+pub struct Highlight {
+    pub query: Option<ObjectQuery>,
+    // 合った物体の重心へカメラを向ける
+    pub focus: bool,
+    // 向ける前のカメラ。解除したら戻す
+    home: Option<Transform>,
+}
+
+impl Highlight {
+    pub fn set(&mut self, query: ObjectQuery, focus: bool) {
+        self.query = Some(query);
+        self.focus = focus;
+    }
+
+    pub fn clear(&mut self) {
+        self.query = None;
+        self.focus = false;
+    }
+}
+
+pub type Searchable = (
+    Entity,
+    &'static Transform,
+    AnyOf<(&'static SpawnedBox, &'static Grabbable)>,
+    Option<&'static ReadMassProperties>,
+    Option<&'static Sleeping>,
+    Option<&'static SpawnedAt>,
+    Has<Held>,
+);
+
+// 条件に合う物体と、その位置・大きさ
+pub fn search(
+    query: &ObjectQuery,
+    objects: &Query<Searchable, Without<Camera3d>>,
+    now: f32,
+) -> Vec<(Entity, Vec3, f32)> {
+    objects
+        .iter()
+        .filter_map(|(entity, transform, shape, mass, sleeping, spawned_at, held)| {
+            let facts = ObjectFacts {
+                mass: mass.map_or(0.0, |m| m.mass),
+                radius: surface_radius(shape),
+                age: spawned_at.map(|s| now - s.0),
+                sleeping: sleeping.is_some_and(|s| s.sleeping),
+                held,
+            };
+            query.matches(&facts).then_some((entity, transform.translation, facts.radius))
+        })
+        .collect()
+}
+
+pub struct SearchPlugin;
+
+impl Plugin for SearchPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Highlight::default())
+            .add_console_command(
+                "highlight",
+                "highlight [heavier_than=5 sleeping=true ...] [focus] | highlight clear",
+                highlight_command,
+            )
+            .add_systems(Update, (stamp_spawn_times, draw_highlights).chain());
+    }
+}
+
+// 条件は ObjectQuery の項目名=値 で並べる
+fn highlight_command(
+    In(args): In<Vec<String>>,
+    mut highlight: ResMut<Highlight>,
+    objects: Query<Searchable, Without<Camera3d>>,
+    time: Res<Time>,
+) -> ConsoleResult {
+    if args.first().is_some_and(|a| a == "clear") {
+        highlight.clear();
+        return Ok(String::new());
+    }
+    let mut fields = serde_json::Map::new();
+    let mut focus = false;
+    for word in &args {
+        if word == "focus" {
+            focus = true;
+            continue;
+        }
+        let (key, value) = word.split_once('=').ok_or_else(|| format!("expected name=value, got {word:?}"))?;
+        let value = match value.parse::<f64>() {
+            Ok(number) => serde_json::json!(number),
+            Err(_) => serde_json::json!(value.parse::<bool>().map_err(|_| format!("{key}: cannot read {value:?}"))?),
+        };
+        fields.insert(key.to_string(), value);
+    }
+    let query: ObjectQuery = serde_json::from_value(fields.into()).map_err(|e| e.to_string())?;
+    let count = search(&query, &objects, time.elapsed_seconds()).len();
+    highlight.set(query, focus);
+    Ok(format!("{count} objects match"))
+}
+
+#[allow(clippy::type_complexity)]
+fn stamp_spawn_times(
+    mut commands: Commands,
+    added: Query<Entity, (Or<(Added<SpawnedBox>, Added<Grabbable>)>, Without<SpawnedAt>)>,
+    time: Res<Time>,
+) {
+    for entity in added.iter() {
+        commands.entity(entity).insert(SpawnedAt(time.elapsed_seconds()));
+    }
+}
+
+// 毎フレーム探し直すので、眠った・掴まれたといった変化がそのまま枠に出る
+fn draw_highlights(
+    mut highlight: ResMut<Highlight>,
+    spectator: Res<Spectator>,
+    objects: Query<Searchable, Without<Camera3d>>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    let Some(query) = &highlight.query else {
+        if let Some(home) = highlight.home.take() {
+            *camera = home;
+        }
+        return;
+    };
+    let matches = search(query, &objects, time.elapsed_seconds());
+    for (entity, position, radius) in &matches {
+        let rotation = objects.get(*entity).map_or(Quat::IDENTITY, |o| o.1.rotation);
+        let outline =
+            Transform::from_translation(*position).with_rotation(rotation).with_scale(Vec3::splat(radius * 2.3));
+        gizmos.cuboid(outline, Color::srgb(1.0, 0.85, 0.1));
+    }
+    if !highlight.focus || matches.is_empty() || spectator.active {
+        return;
+    }
+    if highlight.home.is_none() {
+        highlight.home = Some(*camera);
+    }
+    let center = matches.iter().map(|(_, p, _)| *p).sum::<Vec3>() / matches.len() as f32;
+    let target = camera.looking_at(center, Vec3::Y).rotation;
+    camera.rotation = camera.rotation.slerp(target, (FOCUS_RATE * time.delta_seconds()).min(1.0));
+}
@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use crate::core::gesture::Gesture;
+use crate::core::sequence::{wave_positions, SceneEvent, SequenceFile, Trigger};
+use crate::display::InteractionSet;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::spawn_box_bundle;
+use crate::{update_hands_and_physics, HandKey, HandStates};
+
+const SEQUENCES_PATH: &str = "sequences.json";
+
+// シーケンスが出した名前付きの箱
+#[derive(Component, Debug, Clone)]
+pub struct SequenceProp {
+    pub name: String,
+}
+
+// move_prop で運ばれている途中
+#[derive(Component)]
+struct PropMove {
+    to: Vec3,
+    remaining: f32,
+}
+
+struct Running {
+    index: usize,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+pub struct Sequencer {
+    file: SequenceFile,
+    running: Vec<Running>,
+    // ジェスチャーの起動条件ごとの、手がその形になった時刻
+    held_since: HashMap<(usize, HandKey), f32>,
+    // 一度起動したジェスチャーは形を崩すまで再び起動しない
+    fired: HashSet<(usize, HandKey)>,
+}
+
+impl Sequencer {
+    fn load() -> Self {
+        let file = match fs::read_to_string(SEQUENCES_PATH) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {SEQUENCES_PATH}: {e}");
+                SequenceFile::default()
+            }),
+            Err(_) => SequenceFile::default(),
+        };
+        if !file.sequences.is_empty() {
+            info!("loaded {} sequences from {SEQUENCES_PATH}", file.sequences.len());
+        }
+        Self { file, ..default() }
+    }
+
+    fn start(&mut self, index: usize) {
+        if self.running.iter().any(|r| r.index == index) {
+            return;
+        }
+        info!(sequence = self.file.sequences[index].name, "sequence started");
+        self.running.push(Running { index, elapsed: 0.0 });
+    }
+}
+
+pub struct SequencerPlugin;
+
+impl Plugin for SequencerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Sequencer::load()).add_systems(
+            Update,
+            (trigger_sequences, run_sequences, move_props)
+                .chain()
+                .after(update_hands_and_physics)
+                .in_set(InteractionSet),
+        );
+    }
+}
+
+fn trigger_sequences(mut sequencer: ResMut<Sequencer>, hand_states: Res<HandStates>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    let previous = now - time.delta_seconds();
+    let sequencer = &mut *sequencer;
+    let mut started = Vec::new();
+    for (index, sequence) in sequencer.file.sequences.iter().enumerate() {
+        match &sequence.trigger {
+            Trigger::Time { at } => {
+                if *at >= previous && *at < now {
+                    started.push(index);
+                }
+            }
+            Trigger::Gesture { gesture, side, hold } => {
+                let gesture = Gesture::from_label(gesture);
+                let hold = *hold;
+                for (key, hand) in &hand_states.hands {
+                    let matches = hand.gesture == gesture
+                        && side.as_ref().is_none_or(|s| s.eq_ignore_ascii_case(key.1.label()))
+                        && hand_states.is_confident(*key);
+                    if !matches {
+                        sequencer.held_since.remove(&(index, *key));
+                        sequencer.fired.remove(&(index, *key));
+                        continue;
+                    }
+                    let since = *sequencer.held_since.entry((index, *key)).or_insert(now);
+                    if now - since >= hold && sequencer.fired.insert((index, *key)) {
+                        started.push(index);
+                    }
+                }
+            }
+        }
+    }
+    for index in started {
+        sequencer.start(index);
+    }
+    // 見えなくなった手は形を崩したものとみなす
+    sequencer.held_since.retain(|(_, key), _| hand_states.hands.contains_key(key));
+    sequencer.fired.retain(|(_, key)| hand_states.hands.contains_key(key));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_sequences(
+    mut commands: Commands,
+    mut sequencer: ResMut<Sequencer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut ambient: ResMut<AmbientLight>,
+    mut lights: Query<&mut PointLight>,
+    props: Query<(Entity, &SequenceProp)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let sequencer = &mut *sequencer;
+    for running in sequencer.running.iter_mut() {
+        let sequence = &sequencer.file.sequences[running.index];
+        for keyframe in sequence.due(running.elapsed, running.elapsed + dt) {
+            match &keyframe.event {
+                SceneEvent::SpawnWave { count, center, spacing, size, material, name } => {
+                    let surface = material
+                        .as_ref()
+                        .and_then(|m| SurfaceMaterial::ALL.into_iter().find(|s| s.name().eq_ignore_ascii_case(m)))
+                        .unwrap_or_default();
+                    for position in wave_positions(*center, *count, *spacing) {
+                        let bundle =
+                            spawn_box_bundle(&mut meshes, &mut materials, Vec3::from_array(position), *size, surface);
+                        let mut entity = commands.spawn(bundle);
+                        if let Some(name) = name {
+                            entity.insert(SequenceProp { name: name.clone() });
+                        }
+                    }
+                }
+                SceneEvent::Light { ambient: brightness, intensity, color } => {
+                    if let Some(brightness) = brightness {
+                        ambient.brightness = *brightness;
+                    }
+                    for mut light in lights.iter_mut() {
+                        if let Some(intensity) = intensity {
+                            light.intensity = *intensity;
+                        }
+                        if let Some([r, g, b]) = color {
+                            light.color = Color::srgb(*r, *g, *b);
+                        }
+                    }
+                }
+                SceneEvent::MoveProp { name, to, duration } => {
+                    for (entity, prop) in props.iter() {
+                        if prop.name == *name {
+                            commands
+                                .entity(entity)
+                                .insert(PropMove { to: Vec3::from_array(*to), remaining: duration.max(dt) });
+                        }
+                    }
+                }
+            }
+        }
+        running.elapsed += dt;
+    }
+    let sequences = &sequencer.file.sequences;
+    sequencer.running.retain(|r| {
+        let sequence = &sequences[r.index];
+        let done = r.elapsed > sequence.duration();
+        if done {
+            info!(sequence = sequence.name, "sequence finished");
+        }
+        !done
+    });
+}
+
+// 残り時間で着くように速度を決める。位置を直接書かないので途中でぶつかれば押し返される
+fn move_props(
+    mut commands: Commands,
+    mut moving: Query<(Entity, &Transform, &mut Velocity, &mut PropMove)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, transform, mut velocity, mut prop_move) in moving.iter_mut() {
+        prop_move.remaining -= dt;
+        if prop_move.remaining <= 0.0 {
+            velocity.linvel = Vec3::ZERO;
+            commands.entity(entity).remove::<PropMove>();
+            continue;
+        }
+        velocity.linvel = (prop_move.to - transform.translation) / prop_move.remaining;
+    }
+}
@@ -0,0 +1,434 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::build::BuildSettings;
+use crate::core::aesthetics::AestheticsMapping;
+use crate::core::gesture::Gesture;
+use crate::display::DisplaySettings;
+use crate::error::{AppError, Health};
+#[cfg(feature = "ui")]
+use crate::display::Paused;
+#[cfg(feature = "ui")]
+use crate::silhouette::HandDisplayMode;
+use crate::core::mapping::Reach;
+use crate::render_quality::RenderQuality;
+#[cfg(feature = "ui")]
+use crate::render_quality::ToneMapping;
+use crate::smoothing::{LatencyCompensation, Smoothing};
+#[cfg(feature = "ui")]
+use crate::HandStates;
+
+const SETTINGS_PATH: &str = "settings.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JuiceSettings {
+    pub enabled: bool,
+    // 0..1 で上限に対する割合
+    pub shake_intensity: f32,
+    pub blur_intensity: f32,
+}
+
+impl Default for JuiceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            shake_intensity: 0.6,
+            blur_intensity: 0.5,
+        }
+    }
+}
+
+// 細かい指の動きが難しい人向けに、軽く握るだけで近くの物体を手のひらへ吸い寄せる
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GrabAssist {
+    pub enabled: bool,
+    // 手の中心から物体の表面までこの距離以内なら掴める
+    pub radius: f32,
+}
+
+impl Default for GrabAssist {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 4.0,
+        }
+    }
+}
+
+// 実際の手を勢いよく振ると物理で非現実的な力になるので、速すぎる手は当たり判定と掴みをしばらく止める
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SpeedGateSettings {
+    pub enabled: bool,
+    // 手のひらの速さ (ワールド単位/秒) がこれを超えたら止める
+    pub limit: f32,
+    // 下回ってから作用を戻すまでの秒数
+    pub hold: f32,
+}
+
+impl Default for SpeedGateSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            limit: 80.0,
+            hold: 0.4,
+        }
+    }
+}
+
+// 速い手が薄い物体をすり抜けないよう、手の速さに合わせて物理のステップを細かく刻む (substep.rs)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SubstepSettings {
+    pub enabled: bool,
+    // 一回の刻みで手が進んでよい距離 (ワールド単位)
+    pub max_travel: f32,
+    pub max_substeps: usize,
+    // 手が遅くなってから刻みを戻すまでの秒数
+    pub hold: f32,
+}
+
+impl Default for SubstepSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_travel: 0.5,
+            max_substeps: 8,
+            hold: 0.5,
+        }
+    }
+}
+
+// 机に手を置いて休ませている間は、その手で何も触らないようにする (resting.rs)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RestingSettings {
+    pub enabled: bool,
+    // 仮想の机の面の高さ (ワールド座標)
+    pub table_height: f32,
+    // 机の面からこの高さより下で、平らで、ゆっくりなら休ませ始める
+    pub rest_height: f32,
+    // 休ませた手は机の面からこの高さまで持ち上げたら戻す
+    pub lift_height: f32,
+    pub max_speed: f32,
+    // 置いてから休ませるまでの秒数
+    pub dwell: f32,
+}
+
+impl Default for RestingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            table_height: -3.0,
+            rest_height: 1.5,
+            lift_height: 3.0,
+            max_speed: 3.0,
+            dwell: 0.6,
+        }
+    }
+}
+
+// Wi-Fi 越しの送信側に、送る頻度と z の有無を落としてもらう (packet.rs)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PacketThinning {
+    pub enabled: bool,
+    // 1 秒あたりのパケット数
+    pub rate: f32,
+    pub include_z: bool,
+    // 同じ返信で送信側に使わせるカメラ番号。None なら送信側に任せる
+    #[serde(default)]
+    pub camera: Option<u32>,
+}
+
+impl Default for PacketThinning {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rate: 30.0,
+            include_z: true,
+            camera: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClassifierKind {
+    #[default]
+    Heuristic,
+    Onnx,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GestureSettings {
+    pub classifier: ClassifierKind,
+    pub model_path: String,
+    // モデルの出力の並び順に対応するラベル
+    pub labels: Vec<String>,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            classifier: ClassifierKind::Heuristic,
+            model_path: "models/gesture.onnx".to_string(),
+            labels: Gesture::ALL.iter().map(|g| g.label().to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Settings {
+    #[serde(default)]
+    pub juice: JuiceSettings,
+    #[serde(default)]
+    pub gesture: GestureSettings,
+    #[serde(default)]
+    pub reach: Reach,
+    #[serde(default)]
+    pub latency: LatencyCompensation,
+    #[serde(default)]
+    pub aesthetics: AestheticsMapping,
+    #[serde(default)]
+    pub grab_assist: GrabAssist,
+    #[serde(default)]
+    pub display: DisplaySettings,
+    #[serde(default)]
+    pub build: BuildSettings,
+    #[serde(default)]
+    pub render: RenderQuality,
+    #[serde(default)]
+    pub speed_gate: SpeedGateSettings,
+    #[serde(default)]
+    pub substeps: SubstepSettings,
+    #[serde(default)]
+    pub resting: RestingSettings,
+    #[serde(default)]
+    pub thinning: PacketThinning,
+}
+
+impl Settings {
+    // ファイルが無いのは初回起動なので既定値。あるのに読めないときは呼び出し側に知らせる
+    fn load() -> Result<Self, AppError> {
+        match fs::read_to_string(SETTINGS_PATH) {
+            Ok(text) => {
+                serde_json::from_str(&text).map_err(|source| AppError::Parse { path: SETTINGS_PATH.into(), source })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(source) => Err(AppError::Read { path: SETTINGS_PATH.into(), source }),
+        }
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(text) => match fs::write(SETTINGS_PATH, text) {
+                Ok(()) => info!("settings saved to {SETTINGS_PATH}"),
+                Err(e) => error!("failed to save {SETTINGS_PATH}: {e}"),
+            },
+            Err(e) => error!("failed to serialize settings: {e}"),
+        }
+    }
+}
+
+#[cfg(feature = "ui")]
+#[derive(Resource, Default)]
+struct SettingsWindow {
+    open: bool,
+    // 入力途中のパスで毎回読み込まないよう、Load を押すまでここに置く
+    model_path: Option<String>,
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = Settings::load().unwrap_or_else(|e| {
+            app.init_resource::<Health>();
+            app.world_mut().resource_mut::<Health>().degraded("settings", format!("{e}; using defaults"));
+            Settings::default()
+        });
+        app.insert_resource(settings).add_systems(PreUpdate, apply_latency_settings);
+        #[cfg(feature = "ui")]
+        app.insert_resource(SettingsWindow::default()).add_systems(Update, settings_ui);
+    }
+}
+
+fn apply_latency_settings(settings: Res<Settings>, mut smoothing: ResMut<Smoothing>) {
+    if smoothing.latency != settings.latency {
+        smoothing.latency = settings.latency;
+    }
+}
+
+#[cfg(feature = "ui")]
+fn settings_ui(
+    mut contexts: EguiContexts,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut window: ResMut<SettingsWindow>,
+    mut settings: ResMut<Settings>,
+    smoothing: Res<Smoothing>,
+    hand_states: Res<HandStates>,
+    mut paused: ResMut<Paused>,
+) {
+    if keys.just_pressed(KeyCode::F10) {
+        window.open = !window.open;
+    }
+    if !window.open {
+        return;
+    }
+    let mut open = window.open;
+    egui::Window::new("Settings")
+        .open(&mut open)
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading("Display");
+            let display = &mut settings.display;
+            ui.checkbox(&mut display.fullscreen, "Fullscreen (F11)");
+            ui.checkbox(&mut display.vsync, "VSync");
+            ui.add(egui::Slider::new(&mut display.resolution_scale, 0.5..=2.0).text("Render scale"));
+            egui::ComboBox::from_label("Hand display (H)")
+                .selected_text(display.hands.label())
+                .show_ui(ui, |ui| {
+                    for mode in HandDisplayMode::ALL {
+                        ui.selectable_value(&mut display.hands, mode, mode.label());
+                    }
+                });
+            ui.checkbox(&mut display.throw_preview, "Show where a thrown object will land");
+            ui.checkbox(&mut display.gesture_ring, "Show gesture confidence around hands");
+            ui.checkbox(&mut paused.0, "Pause physics and input (F8)");
+            let idle = &mut display.idle;
+            ui.checkbox(&mut idle.enabled, "Idle when no hands are seen");
+            ui.add_enabled_ui(idle.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut idle.timeout, 5.0..=600.0).logarithmic(true).text("Idle after (s)"));
+                ui.checkbox(&mut idle.screensaver, "Floating orbs while idle");
+                ui.add_enabled_ui(!idle.screensaver, |ui| {
+                    ui.add(egui::Slider::new(&mut idle.idle_fps, 1.0..=30.0).text("Idle frame rate"));
+                });
+            });
+            ui.separator();
+            ui.heading("Rendering");
+            let render = &mut settings.render;
+            ui.checkbox(&mut render.bloom, "Bloom (glowing hands)");
+            ui.add_enabled_ui(render.bloom, |ui| {
+                ui.add(egui::Slider::new(&mut render.bloom_intensity, 0.0..=1.0).text("Bloom intensity"));
+            });
+            egui::ComboBox::from_label("Tonemapping")
+                .selected_text(render.tonemapping.label())
+                .show_ui(ui, |ui| {
+                    for mode in ToneMapping::ALL {
+                        ui.selectable_value(&mut render.tonemapping, mode, mode.label());
+                    }
+                });
+            egui::ComboBox::from_label("MSAA")
+                .selected_text(format!("{}x", render.msaa_samples))
+                .show_ui(ui, |ui| {
+                    for samples in RenderQuality::MSAA_SAMPLES {
+                        ui.selectable_value(&mut render.msaa_samples, samples, format!("{samples}x"));
+                    }
+                });
+            ui.checkbox(&mut render.depth_of_field, "Depth of field (focus on the active hand)");
+            ui.add_enabled_ui(render.depth_of_field, |ui| {
+                let aperture = egui::Slider::new(&mut render.aperture_f_stops, 0.1..=8.0).logarithmic(true);
+                ui.add(aperture.text("Aperture (f)"));
+            });
+            ui.separator();
+            ui.heading("Effects");
+            let juice = &mut settings.juice;
+            ui.checkbox(&mut juice.enabled, "Camera shake and impact blur");
+            ui.add_enabled_ui(juice.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut juice.shake_intensity, 0.0..=1.0).text("Shake"));
+                ui.add(egui::Slider::new(&mut juice.blur_intensity, 0.0..=1.0).text("Blur"));
+            });
+            ui.separator();
+            ui.heading("Hands");
+            let reach = &mut settings.reach;
+            ui.add(egui::Slider::new(&mut reach.hand_scale, 0.5..=4.0).text("Hand scale"));
+            ui.add(egui::Slider::new(&mut reach.amplification, 0.0..=2.0).text("Reach amplification"));
+            let assist = &mut settings.grab_assist;
+            ui.checkbox(&mut assist.enabled, "Grab assist (close hand loosely to grab)");
+            ui.add_enabled_ui(assist.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut assist.radius, 0.5..=10.0).text("Assist radius"));
+            });
+            let gate = &mut settings.speed_gate;
+            ui.checkbox(&mut gate.enabled, "Ignore hands moving too fast");
+            ui.add_enabled_ui(gate.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut gate.limit, 20.0..=300.0).logarithmic(true).text("Speed limit"));
+                ui.add(egui::Slider::new(&mut gate.hold, 0.0..=2.0).text("Resume after (s)"));
+            });
+            let substeps = &mut settings.substeps;
+            ui.checkbox(&mut substeps.enabled, "Sub-step physics for fast hands");
+            ui.add_enabled_ui(substeps.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut substeps.max_travel, 0.1..=2.0).text("Max travel per step"));
+                ui.add(egui::Slider::new(&mut substeps.max_substeps, 1..=16).text("Max substeps"));
+            });
+            let resting = &mut settings.resting;
+            ui.checkbox(&mut resting.enabled, "Ignore hands resting on the table");
+            ui.add_enabled_ui(resting.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut resting.table_height, -5.0..=5.0).text("Table height"));
+                ui.add(egui::Slider::new(&mut resting.rest_height, 0.2..=4.0).text("Rest below"));
+                ui.add(egui::Slider::new(&mut resting.lift_height, 0.5..=6.0).text("Lift above"));
+                ui.add(egui::Slider::new(&mut resting.dwell, 0.0..=2.0).text("Rest after (s)"));
+            });
+            let latency = &mut settings.latency;
+            ui.checkbox(&mut latency.enabled, "Latency compensation");
+            ui.add_enabled_ui(latency.enabled, |ui| {
+                let mut max_lead_ms = latency.max_lead * 1000.0;
+                if ui.add(egui::Slider::new(&mut max_lead_ms, 0.0..=250.0).text("Max lead (ms)")).changed() {
+                    latency.max_lead = max_lead_ms / 1000.0;
+                }
+            });
+            let mut keys: Vec<_> = hand_states.hands.keys().copied().collect();
+            keys.sort();
+            for key in keys {
+                if let Some(delay) = smoothing.delay(key) {
+                    ui.label(format!(
+                        "Client {} {}: delay {:.0} ms, lead {:.0} ms",
+                        key.0,
+                        key.1.label(),
+                        delay * 1000.0,
+                        smoothing.lead(key) * 1000.0
+                    ));
+                }
+            }
+            ui.separator();
+            ui.heading("Network");
+            let thinning = &mut settings.thinning;
+            ui.checkbox(&mut thinning.enabled, "Ask senders to send less (Wi-Fi)");
+            ui.add_enabled_ui(thinning.enabled, |ui| {
+                ui.add(egui::Slider::new(&mut thinning.rate, 5.0..=60.0).text("Packets per second"));
+                ui.checkbox(&mut thinning.include_z, "Include depth (z)");
+            });
+            let mut choose_camera = thinning.camera.is_some();
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut choose_camera, "Sender camera");
+                let mut index = thinning.camera.unwrap_or(0);
+                ui.add_enabled(choose_camera, egui::DragValue::new(&mut index).range(0..=9));
+                thinning.camera = choose_camera.then_some(index);
+            });
+            ui.separator();
+            ui.heading("Build mode");
+            ui.add(egui::Slider::new(&mut settings.build.cell, 0.5..=5.0).text("Grid cell"));
+            ui.separator();
+            ui.heading("Gesture classifier");
+            let gesture = &mut settings.gesture;
+            egui::ComboBox::from_label("Classifier")
+                .selected_text(format!("{:?}", gesture.classifier))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut gesture.classifier, ClassifierKind::Heuristic, "Heuristic");
+                    ui.selectable_value(&mut gesture.classifier, ClassifierKind::Onnx, "Onnx");
+                });
+            if gesture.classifier == ClassifierKind::Onnx {
+                let path = window.model_path.get_or_insert_with(|| gesture.model_path.clone());
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(path);
+                    if ui.button("Load").clicked() {
+                        gesture.model_path = path.clone();
+                    }
+                });
+                ui.label(format!("Labels: {}", gesture.labels.join(", ")));
+            }
+            ui.separator();
+            if ui.button("Save").clicked() {
+                settings.save();
+            }
+        });
+    window.open = open;
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::view::NoFrustumCulling;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::silhouette::SilhouetteMesh;
+use crate::settings::Settings;
+use crate::{hand_color, update_hands_and_physics, HandKey, HandMaterials, HandPoint, HandStates};
+
+const SILHOUETTE_ALPHA: f32 = 0.45;
+
+// 手の見せ方。関節の球 (と骨格線)、半透明の手の形、骨格線だけ
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HandDisplayMode {
+    #[default]
+    Spheres,
+    Silhouette,
+    Skeleton,
+}
+
+impl HandDisplayMode {
+    pub const ALL: [HandDisplayMode; 3] = [HandDisplayMode::Spheres, HandDisplayMode::Silhouette, HandDisplayMode::Skeleton];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HandDisplayMode::Spheres => "Spheres",
+            HandDisplayMode::Silhouette => "Silhouette",
+            HandDisplayMode::Skeleton => "Skeleton",
+        }
+    }
+
+    fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn draws_skeleton(self) -> bool {
+        self != HandDisplayMode::Silhouette
+    }
+}
+
+#[derive(Component)]
+struct HandSilhouette {
+    key: HandKey,
+    mesh: Handle<Mesh>,
+}
+
+pub struct SilhouettePlugin;
+
+impl Plugin for SilhouettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (cycle_display_mode, show_hand_spheres, spawn_silhouettes, update_silhouettes)
+                .chain()
+                .after(update_hands_and_physics),
+        );
+    }
+}
+
+fn cycle_display_mode(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keys.just_pressed(KeyCode::KeyH) {
+        settings.display.hands = settings.display.hands.next();
+        info!("hand display: {}", settings.display.hands.label());
+    }
+}
+
+// 球を隠しても当たり判定は残す
+fn show_hand_spheres(settings: Res<Settings>, mut points: Query<&mut Visibility, With<HandPoint>>) {
+    let visibility = if settings.display.hands == HandDisplayMode::Spheres { Visibility::Inherited } else { Visibility::Hidden };
+    for mut v in points.iter_mut() {
+        v.set_if_neq(visibility);
+    }
+}
+
+fn spawn_silhouettes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hand_mats: Res<HandMaterials>,
+    existing: Query<&HandSilhouette>,
+) {
+    if !hand_mats.is_changed() {
+        return;
+    }
+    for &(client, side) in hand_mats.materials.keys() {
+        if existing.iter().any(|s| s.key == (client, side)) {
+            continue;
+        }
+        let color = hand_color(client, side);
+        let material = materials.add(StandardMaterial {
+            base_color: color.with_alpha(SILHOUETTE_ALPHA),
+            emissive: color.to_linear() * 0.3,
+            alpha_mode: AlphaMode::Blend,
+            double_sided: true,
+            cull_mode: None,
+            ..default()
+        });
+        let mesh = meshes.add(to_mesh(SilhouetteMesh::default()));
+        commands.spawn((
+            PbrBundle { mesh: mesh.clone(), material, visibility: Visibility::Hidden, ..default() },
+            // 毎フレーム形が変わるので、作ったときの境界箱で切り捨てない
+            NoFrustumCulling,
+            HandSilhouette { key: (client, side), mesh },
+        ));
+    }
+}
+
+fn update_silhouettes(
+    settings: Res<Settings>,
+    hand_states: Res<HandStates>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    points: Query<(&HandPoint, &Transform)>,
+    mut silhouettes: Query<(&HandSilhouette, &mut Visibility)>,
+) {
+    let enabled = settings.display.hands == HandDisplayMode::Silhouette;
+    let mut hands: HashMap<HandKey, [Vec3; 21]> = HashMap::new();
+    if enabled {
+        for (point, transform) in points.iter() {
+            let key = (point.client, point.side);
+            if point.id < 21 && hand_states.hands.contains_key(&key) {
+                hands.entry(key).or_insert([Vec3::ZERO; 21])[point.id] = transform.translation;
+            }
+        }
+    }
+    for (silhouette, mut visibility) in silhouettes.iter_mut() {
+        let Some(landmarks) = hands.get(&silhouette.key) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        if let Some(mesh) = meshes.get_mut(&silhouette.mesh) {
+            *mesh = to_mesh(SilhouetteMesh::from_landmarks(landmarks));
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
+
+fn to_mesh(silhouette: SilhouetteMesh) -> Mesh {
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, silhouette.positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, silhouette.normals)
+        .with_inserted_indices(Indices::U32(silhouette.indices))
+}
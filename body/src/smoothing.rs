@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::core::smoothing::{DeadZone, Gate, Track, DEFAULT_MAX_CATCH_UP};
+use crate::HandKey;
+
+// 遅れている分だけ手を速度方向へ先回りさせて表示する
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LatencyCompensation {
+    pub enabled: bool,
+    // 先回りさせる時間の上限 (秒)
+    pub max_lead: f32,
+}
+
+impl Default for LatencyCompensation {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_lead: 0.1,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Smoothing {
+    // 遅れを取り戻すときの最大再生速度
+    pub max_catch_up: f32,
+    // パケットの時刻が実時間に対して進む速さ。リプレイの再生速度に合わせる
+    pub playback_rate: f32,
+    pub dead_zone: DeadZone,
+    pub latency: LatencyCompensation,
+    gates: HashMap<HandKey, Gate>,
+    tracks: HashMap<HandKey, Track>,
+    // 最後に受け取った、不感帯を通す前の目標位置 (揺れの測定用)
+    raw: HashMap<HandKey, HashMap<usize, Vec3>>,
+}
+
+impl Default for Smoothing {
+    fn default() -> Self {
+        Self {
+            max_catch_up: DEFAULT_MAX_CATCH_UP,
+            playback_rate: 1.0,
+            dead_zone: DeadZone::default(),
+            latency: LatencyCompensation::default(),
+            gates: HashMap::new(),
+            tracks: HashMap::new(),
+            raw: HashMap::new(),
+        }
+    }
+}
+
+impl Smoothing {
+    pub fn push(&mut self, key: HandKey, stamp: f64, targets: HashMap<usize, Vec3>) {
+        self.tracks.entry(key).or_default().push(stamp, targets);
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        for track in self.tracks.values_mut() {
+            track.advance(dt, self.playback_rate, self.max_catch_up);
+        }
+    }
+
+    pub fn sample(&self, key: HandKey, id: usize) -> Option<Vec3> {
+        self.tracks.get(&key)?.sample(id)
+    }
+
+    // 遅延の分だけ速度方向へ進めた位置。描画する手はこちらを使う
+    pub fn predict(&self, key: HandKey, id: usize) -> Option<Vec3> {
+        let track = self.tracks.get(&key)?;
+        let position = track.sample(id)?;
+        if !self.latency.enabled {
+            return Some(position);
+        }
+        Some(position + track.velocity(id) * self.lead(key))
+    }
+
+    // 実際に先回りさせる時間 (上限で切った遅延)
+    pub fn lead(&self, key: HandKey) -> f32 {
+        self.tracks.get(&key).map_or(0.0, |t| t.delay().min(self.latency.max_lead as f64) as f32)
+    }
+
+    pub fn delay(&self, key: HandKey) -> Option<f32> {
+        self.tracks.get(&key).map(|t| t.delay() as f32)
+    }
+
+    // 撮影時刻付きのパケットを受け取ったときの通信遅延を記録する
+    pub fn record_latency(&mut self, key: HandKey, latency: f64) {
+        if let Some(track) = self.tracks.get_mut(&key) {
+            track.record_latency(latency);
+        }
+    }
+
+    pub fn raw(&self, key: HandKey) -> Option<&HashMap<usize, Vec3>> {
+        self.raw.get(&key)
+    }
+
+    // 静止中は目標位置を固定し、radius を超えて動いた瞬間に解除する
+    pub fn gate(&mut self, key: HandKey, targets: &mut HashMap<usize, Vec3>, now: f32) {
+        self.raw.insert(key, targets.clone());
+        self.gates.entry(key).or_default().apply(&self.dead_zone, targets, now);
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::arena::Arena;
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::arena::ArenaConfig;
+use crate::core::wind_zone::WindZone;
+use crate::governor::MergedStack;
+use crate::notes::{note_bundle, Note};
+use crate::sound::{SoundBank, SoundBankConfig, SurfaceMaterial};
+use crate::spawn::{spawn_box_bundle, SpawnedBox};
+use crate::wind_zone::{wind_volume_bundle, WindVolume};
+
+pub const SNAPSHOT_PATH: &str = "scene.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BoxSnapshot {
+    pub position: [f32; 3],
+    pub rotation: [f32; 4],
+    pub size: f32,
+    #[serde(default)]
+    pub material: SurfaceMaterial,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NoteSnapshot {
+    pub position: [f32; 3],
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SceneSnapshot {
+    #[serde(default)]
+    pub boxes: Vec<BoxSnapshot>,
+    #[serde(default)]
+    pub notes: Vec<NoteSnapshot>,
+    #[serde(default)]
+    pub sound_bank: Option<SoundBankConfig>,
+    // 無ければ壁も天井も置かない
+    #[serde(default)]
+    pub arena: Option<ArenaConfig>,
+    #[serde(default)]
+    pub wind_zones: Vec<WindZone>,
+}
+
+// 物体・メモ・風の領域をすべて消して、空の場面か保存した場面に置き換える
+#[derive(Event, Debug, Clone)]
+pub enum SceneCommand {
+    Clear,
+    Load(PathBuf),
+}
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SceneCommand>()
+            .add_console_command("reset", "reset (clear the scene)", reset_command)
+            .add_systems(Update, (save_snapshot, load_snapshot, apply_scene_commands).chain());
+    }
+}
+
+fn reset_command(In(_): In<Vec<String>>, mut scene: EventWriter<SceneCommand>) -> ConsoleResult {
+    scene.send(SceneCommand::Clear);
+    Ok(String::new())
+}
+
+fn save_snapshot(
+    keys: Res<ButtonInput<KeyCode>>,
+    boxes: Query<(&Transform, &SpawnedBox, &SurfaceMaterial)>,
+    notes: Query<(&Transform, &Note)>,
+    bank: Res<SoundBank>,
+    arena: Res<Arena>,
+    wind_zones: Query<&WindVolume>,
+) {
+    if !keys.just_pressed(KeyCode::F5) {
+        return;
+    }
+    let snapshot = SceneSnapshot {
+        boxes: boxes
+            .iter()
+            .map(|(t, b, m)| BoxSnapshot {
+                position: t.translation.to_array(),
+                rotation: t.rotation.to_array(),
+                size: b.size,
+                material: *m,
+            })
+            .collect(),
+        notes: notes
+            .iter()
+            .filter(|(_, n)| !n.text.is_empty())
+            .map(|(t, n)| NoteSnapshot {
+                position: t.translation.to_array(),
+                text: n.text.clone(),
+            })
+            .collect(),
+        sound_bank: Some(bank.config.clone()),
+        arena: (!arena.config.is_empty()).then(|| arena.config.clone()),
+        wind_zones: wind_zones.iter().map(|v| v.0).collect(),
+    };
+    match serde_json::to_string_pretty(&snapshot) {
+        Ok(text) => match fs::write(SNAPSHOT_PATH, text) {
+            Ok(()) => info!("scene saved to {SNAPSHOT_PATH}"),
+            Err(e) => error!("failed to save {SNAPSHOT_PATH}: {e}"),
+        },
+        Err(e) => error!("failed to serialize scene: {e}"),
+    }
+}
+
+fn load_snapshot(keys: Res<ButtonInput<KeyCode>>, mut scene: EventWriter<SceneCommand>) {
+    if keys.just_pressed(KeyCode::F9) {
+        scene.send(SceneCommand::Load(PathBuf::from(SNAPSHOT_PATH)));
+    }
+}
+
+fn read_snapshot(path: &Path) -> Option<SceneSnapshot> {
+    match fs::read_to_string(path).map(|t| serde_json::from_str(&t)) {
+        Ok(Ok(snapshot)) => Some(snapshot),
+        Ok(Err(e)) => {
+            error!("failed to parse {}: {e}", path.display());
+            None
+        }
+        Err(e) => {
+            error!("failed to read {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_scene_commands(
+    mut commands: Commands,
+    mut events: EventReader<SceneCommand>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bank: ResMut<SoundBank>,
+    mut arena: ResMut<Arena>,
+    existing: Query<Entity, Or<(With<SpawnedBox>, With<Note>, With<MergedStack>, With<WindVolume>)>>,
+) {
+    for event in events.read() {
+        let snapshot = match event {
+            SceneCommand::Clear => SceneSnapshot::default(),
+            SceneCommand::Load(path) => match read_snapshot(path) {
+                Some(snapshot) => snapshot,
+                None => continue,
+            },
+        };
+        for entity in existing.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        for b in &snapshot.boxes {
+            commands
+                .spawn(spawn_box_bundle(&mut meshes, &mut materials, Vec3::from_array(b.position), b.size, b.material))
+                .insert(Transform::from_translation(Vec3::from_array(b.position)).with_rotation(Quat::from_array(b.rotation)));
+        }
+        for n in &snapshot.notes {
+            commands.spawn(note_bundle(Vec3::from_array(n.position), n.text.clone()));
+        }
+        for zone in &snapshot.wind_zones {
+            commands.spawn(wind_volume_bundle(*zone));
+        }
+        if let Some(config) = snapshot.sound_bank {
+            bank.config = config;
+        }
+        // 片付けでは物体だけを消し、壁は残す
+        if let SceneCommand::Load(_) = event {
+            arena.config = snapshot.arena.unwrap_or_default();
+        }
+        match event {
+            SceneCommand::Clear => info!("scene cleared"),
+            SceneCommand::Load(path) => info!("scene loaded from {}", path.display()),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::render::view::NoFrustumCulling;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::soft_body::{neighbor_pairs, sphere_directions, Skin};
+use crate::display::InteractionSet;
+use crate::grab::Grabbable;
+
+// 表面の節点の数と、節点の中心が並ぶ半径
+const NODE_COUNT: usize = 14;
+const NODE_ORBIT: f32 = 1.1;
+const NODE_RADIUS: f32 = 0.3;
+// 芯の当たり判定の半径と、節点へのばねを付ける芯の上の位置
+const CORE_RADIUS: f32 = 0.45;
+const CORE_ANCHOR: f32 = 0.4;
+// 芯と節点、隣り合う節点どうしのばね (硬さ・減衰)
+const RADIAL_STIFFNESS: f32 = 60.0;
+const SURFACE_STIFFNESS: f32 = 30.0;
+const SPRING_DAMPING: f32 = 1.5;
+const NEIGHBORS: usize = 3;
+const BALL_RADIUS: f32 = NODE_ORBIT + NODE_RADIUS;
+const SPAWN_POINT: Vec3 = Vec3::new(-3.0, 3.0, 4.0);
+
+// 芯のまわりを節点がばねで囲む柔らかい玉。見た目の球は節点の動きに合わせて凹ませる
+#[derive(Component)]
+struct SoftBall {
+    nodes: Vec<Entity>,
+    // 芯から見た節点と頂点の静止時の位置
+    nodes_rest: Vec<Vec3>,
+    vertices_rest: Vec<Vec3>,
+    skin: Skin,
+    mesh: Handle<Mesh>,
+}
+
+#[derive(Component)]
+struct SoftBallNode;
+
+pub struct SoftBallPlugin;
+
+impl Plugin for SoftBallPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (toggle_soft_ball, deform_soft_balls.in_set(InteractionSet)).chain());
+    }
+}
+
+// Q で出し入れする
+fn toggle_soft_ball(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    balls: Query<(Entity, &SoftBall)>,
+) {
+    if !keys.just_pressed(KeyCode::KeyQ) {
+        return;
+    }
+    if !balls.is_empty() {
+        for (entity, ball) in balls.iter() {
+            for node in &ball.nodes {
+                commands.entity(*node).despawn_recursive();
+            }
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+
+    let Ok(mesh) = Sphere::new(BALL_RADIUS).mesh().ico(3) else {
+        return;
+    };
+    let vertices_rest: Vec<Vec3> = match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.iter().map(|p| Vec3::from_array(*p)).collect(),
+        _ => return,
+    };
+    let nodes_rest: Vec<Vec3> = sphere_directions(NODE_COUNT).into_iter().map(|d| d * NODE_ORBIT).collect();
+    let skin = Skin::bind(&vertices_rest, &nodes_rest);
+    let mesh = meshes.add(mesh);
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.35, 0.55),
+        perceptual_roughness: 0.6,
+        ..default()
+    });
+
+    let core = commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(SPAWN_POINT)),
+            RigidBody::Dynamic,
+            Collider::ball(CORE_RADIUS),
+            ColliderMassProperties::Density(1.0),
+            ExternalForce::default(),
+            Velocity::default(),
+            Grabbable { radius: BALL_RADIUS },
+        ))
+        .with_children(|core| {
+            core.spawn((PbrBundle { mesh: mesh.clone(), material, ..default() }, NoFrustumCulling));
+        })
+        .id();
+
+    let nodes: Vec<Entity> = nodes_rest
+        .iter()
+        .map(|offset| {
+            let direction = offset.normalize();
+            let radial = SpringJointBuilder::new(NODE_ORBIT - CORE_ANCHOR, RADIAL_STIFFNESS, SPRING_DAMPING)
+                .local_anchor1(direction * CORE_ANCHOR)
+                .local_anchor2(Vec3::ZERO);
+            commands
+                .spawn((
+                    TransformBundle::from_transform(Transform::from_translation(SPAWN_POINT + *offset)),
+                    RigidBody::Dynamic,
+                    Collider::ball(NODE_RADIUS),
+                    ColliderMassProperties::Density(0.5),
+                    Friction::coefficient(1.2),
+                    Velocity::default(),
+                    ImpulseJoint::new(core, radial),
+                    SoftBallNode,
+                ))
+                .id()
+        })
+        .collect();
+    // 1 つの剛体に関節は 1 つしか付けられないので、表面のばねは子に付ける (子の関節は親の剛体につながる)
+    for (a, b) in neighbor_pairs(&nodes_rest, NEIGHBORS) {
+        let rest = nodes_rest[a].distance(nodes_rest[b]);
+        let spring = SpringJointBuilder::new(rest, SURFACE_STIFFNESS, SPRING_DAMPING);
+        commands.entity(nodes[a]).with_children(|node| {
+            node.spawn((TransformBundle::default(), ImpulseJoint::new(nodes[b], spring)));
+        });
+    }
+
+    commands.entity(core).insert(SoftBall { nodes, nodes_rest, vertices_rest, skin, mesh });
+    info!("soft ball ready");
+}
+
+fn deform_soft_balls(
+    balls: Query<(&SoftBall, &Transform)>,
+    nodes: Query<&Transform, With<SoftBallNode>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (ball, core) in balls.iter() {
+        let to_local = core.rotation.inverse();
+        let nodes_now: Option<Vec<Vec3>> = ball
+            .nodes
+            .iter()
+            .map(|node| nodes.get(*node).ok().map(|t| to_local * (t.translation - core.translation)))
+            .collect();
+        let Some(nodes_now) = nodes_now else {
+            continue;
+        };
+        let Some(mesh) = meshes.get_mut(&ball.mesh) else {
+            continue;
+        };
+        let positions: Vec<[f32; 3]> = ball
+            .skin
+            .deform(&ball.vertices_rest, &ball.nodes_rest, &nodes_now)
+            .into_iter()
+            .map(|p| p.to_array())
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.compute_smooth_normals();
+    }
+}
@@ -0,0 +1,268 @@
+#[cfg(feature = "audio")]
+use bevy::audio::Volume;
+use bevy::prelude::*;
+#[cfg(feature = "audio")]
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "audio")]
+use std::collections::HashMap;
+use std::fs;
+#[cfg(feature = "audio")]
+use std::path::Path;
+
+#[cfg(feature = "audio")]
+use crate::error::HealthState;
+use crate::error::{AppError, Health};
+use crate::snapshot::{SceneSnapshot, SNAPSHOT_PATH};
+
+#[cfg(feature = "audio")]
+const ASSET_DIR: &str = "assets";
+// これより間が空いた接触は新しい衝突として扱う
+#[cfg(feature = "audio")]
+const CONTACT_GAP: f32 = 0.1;
+#[cfg(feature = "audio")]
+const SCRAPE_INTERVAL: f32 = 0.25;
+#[cfg(feature = "audio")]
+const MAX_VOICES: usize = 16;
+
+#[derive(Component, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum SurfaceMaterial {
+    #[default]
+    Wood,
+    Metal,
+    Rubber,
+}
+
+impl SurfaceMaterial {
+    pub const ALL: [SurfaceMaterial; 3] = [SurfaceMaterial::Wood, SurfaceMaterial::Metal, SurfaceMaterial::Rubber];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SurfaceMaterial::Wood => "wood",
+            SurfaceMaterial::Metal => "metal",
+            SurfaceMaterial::Rubber => "rubber",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            SurfaceMaterial::Wood => Color::srgb(1.0, 0.5, 0.0),
+            SurfaceMaterial::Metal => Color::srgb(0.7, 0.72, 0.75),
+            SurfaceMaterial::Rubber => Color::srgb(0.15, 0.15, 0.18),
+        }
+    }
+
+    pub fn restitution(&self) -> f32 {
+        match self {
+            SurfaceMaterial::Wood => 0.1,
+            SurfaceMaterial::Metal => 0.05,
+            SurfaceMaterial::Rubber => 0.6,
+        }
+    }
+
+    pub fn friction(&self) -> f32 {
+        match self {
+            SurfaceMaterial::Wood => 1.0,
+            SurfaceMaterial::Metal => 0.6,
+            SurfaceMaterial::Rubber => 1.5,
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+fn pair_key(a: SurfaceMaterial, b: SurfaceMaterial) -> (SurfaceMaterial, SurfaceMaterial) {
+    (a.min(b), a.max(b))
+}
+
+// 素材の組み合わせごとの音 (パスは assets/ からの相対パス)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PairSound {
+    pub materials: [SurfaceMaterial; 2],
+    #[serde(default)]
+    pub impact: Option<String>,
+    #[serde(default)]
+    pub scrape: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SoundBankConfig {
+    pub pairs: Vec<PairSound>,
+    // この衝撃量で最大音量になる
+    pub full_volume_impulse: f32,
+    pub min_impulse: f32,
+    pub scrape_speed: f32,
+}
+
+impl Default for SoundBankConfig {
+    fn default() -> Self {
+        let mut pairs = Vec::new();
+        for (i, a) in SurfaceMaterial::ALL.iter().enumerate() {
+            for b in &SurfaceMaterial::ALL[i..] {
+                pairs.push(PairSound {
+                    materials: [*a, *b],
+                    impact: Some(format!("sounds/{}_{}_impact.ogg", a.name(), b.name())),
+                    scrape: Some(format!("sounds/{}_{}_scrape.ogg", a.name(), b.name())),
+                });
+            }
+        }
+        Self {
+            pairs,
+            full_volume_impulse: 400.0,
+            min_impulse: 5.0,
+            scrape_speed: 1.5,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SoundBank {
+    pub config: SoundBankConfig,
+    #[cfg(feature = "audio")]
+    impacts: HashMap<(SurfaceMaterial, SurfaceMaterial), Handle<AudioSource>>,
+    #[cfg(feature = "audio")]
+    scrapes: HashMap<(SurfaceMaterial, SurfaceMaterial), Handle<AudioSource>>,
+}
+
+impl SoundBank {
+    fn load_config() -> Result<SoundBankConfig, AppError> {
+        let Ok(text) = fs::read_to_string(SNAPSHOT_PATH) else {
+            return Ok(SoundBankConfig::default());
+        };
+        match serde_json::from_str::<SceneSnapshot>(&text) {
+            Ok(scene) => Ok(scene.sound_bank.unwrap_or_default()),
+            Err(source) => Err(AppError::Parse { path: SNAPSHOT_PATH.into(), source }),
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+#[derive(Resource, Default)]
+struct ContactSounds {
+    last_contact: HashMap<(Entity, Entity), f32>,
+    last_scrape: HashMap<(Entity, Entity), f32>,
+}
+
+#[cfg(feature = "audio")]
+#[derive(Component)]
+struct ContactVoice;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        let config = SoundBank::load_config().unwrap_or_else(|e| {
+            app.init_resource::<Health>();
+            app.world_mut().resource_mut::<Health>().degraded("sound", format!("{e}; using the default sounds"));
+            SoundBankConfig::default()
+        });
+        app.insert_resource(SoundBank {
+            config,
+            #[cfg(feature = "audio")]
+            impacts: HashMap::new(),
+            #[cfg(feature = "audio")]
+            scrapes: HashMap::new(),
+        });
+        // 音なしのビルドでも、鳴らす音の設定はシーンと一緒に保存できるよう残す
+        #[cfg(feature = "audio")]
+        app.insert_resource(ContactSounds::default())
+            .add_systems(Update, (reload_sound_bank, play_contact_sounds).chain());
+    }
+}
+
+#[cfg(feature = "audio")]
+fn reload_sound_bank(mut bank: ResMut<SoundBank>, asset_server: Res<AssetServer>, mut health: ResMut<Health>) {
+    if !bank.is_changed() {
+        return;
+    }
+    let bank = bank.bypass_change_detection();
+    bank.impacts.clear();
+    bank.scrapes.clear();
+
+    // ファイルが無い音は読み込まず、その組み合わせは無音にする。無音になった組み合わせは画面にも出す
+    let mut missing = Vec::new();
+    let mut load = |path: &Option<String>| {
+        let path = path.as_ref()?;
+        if !Path::new(ASSET_DIR).join(path).exists() {
+            missing.push(format!("{ASSET_DIR}/{path}"));
+            return None;
+        }
+        Some(asset_server.load(path.clone()))
+    };
+    for pair in &bank.config.pairs {
+        let key = pair_key(pair.materials[0], pair.materials[1]);
+        if let Some(handle) = load(&pair.impact) {
+            bank.impacts.insert(key, handle);
+        }
+        if let Some(handle) = load(&pair.scrape) {
+            bank.scrapes.insert(key, handle);
+        }
+    }
+    if missing.is_empty() {
+        health.ok("sound");
+    } else {
+        health.set("sound", HealthState::Degraded(format!("sounds not found: {}", missing.join(", "))));
+    }
+}
+
+#[cfg(feature = "audio")]
+fn play_contact_sounds(
+    mut commands: Commands,
+    mut contacts: EventReader<ContactForceEvent>,
+    bank: Res<SoundBank>,
+    mut state: ResMut<ContactSounds>,
+    bodies: Query<(&SurfaceMaterial, Option<&Velocity>)>,
+    voices: Query<(), With<ContactVoice>>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let dt = time.delta_seconds().max(f32::EPSILON);
+    let mut voice_count = voices.iter().count();
+
+    for contact in contacts.read() {
+        let (Ok((mat_a, vel_a)), Ok((mat_b, vel_b))) = (bodies.get(contact.collider1), bodies.get(contact.collider2)) else {
+            continue;
+        };
+        let pair = (contact.collider1.min(contact.collider2), contact.collider1.max(contact.collider2));
+        let key = pair_key(*mat_a, *mat_b);
+        let is_new = state.last_contact.get(&pair).is_none_or(|t| now - t > CONTACT_GAP);
+        state.last_contact.insert(pair, now);
+
+        if voice_count >= MAX_VOICES {
+            continue;
+        }
+
+        let (handle, volume) = if is_new {
+            let impulse = contact.total_force_magnitude * dt;
+            if impulse < bank.config.min_impulse {
+                continue;
+            }
+            let Some(handle) = bank.impacts.get(&key) else {
+                continue;
+            };
+            (handle, impulse / bank.config.full_volume_impulse)
+        } else {
+            let linvel = |v: Option<&Velocity>| v.map(|v| v.linvel).unwrap_or(Vec3::ZERO);
+            let speed = (linvel(vel_a) - linvel(vel_b)).length();
+            if speed < bank.config.scrape_speed || state.last_scrape.get(&pair).is_some_and(|t| now - t < SCRAPE_INTERVAL) {
+                continue;
+            }
+            let Some(handle) = bank.scrapes.get(&key) else {
+                continue;
+            };
+            state.last_scrape.insert(pair, now);
+            (handle, speed / (bank.config.scrape_speed * 4.0))
+        };
+
+        commands.spawn((
+            AudioBundle {
+                source: handle.clone(),
+                settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(volume.clamp(0.05, 1.0))),
+            },
+            ContactVoice,
+        ));
+        voice_count += 1;
+    }
+
+    state.last_contact.retain(|_, t| now - *t < 1.0);
+    state.last_scrape.retain(|_, t| now - *t < 1.0);
+}
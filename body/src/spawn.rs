@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::sound::SurfaceMaterial;
+
+#[derive(Component)]
+pub struct SpawnedBox {
+    pub size: f32,
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpawnRequest {
+    pub position: Vec3,
+    pub size: f32,
+    pub material: SurfaceMaterial,
+}
+
+// スポーン要求の間引き (PhysicsGovernor が書き換える)
+#[derive(Resource)]
+pub struct SpawnBudget {
+    pub min_interval: f32,
+    pub blocked: bool,
+    pub dropped: u32,
+    last_spawn: f32,
+}
+
+impl Default for SpawnBudget {
+    fn default() -> Self {
+        Self {
+            min_interval: 0.0,
+            blocked: false,
+            dropped: 0,
+            last_spawn: f32::NEG_INFINITY,
+        }
+    }
+}
+
+pub fn spawn_box_bundle(
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    position: Vec3,
+    size: f32,
+    surface: SurfaceMaterial,
+) -> impl Bundle {
+    (
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(size, size, size)),
+            material: materials.add(surface.color()),
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::cuboid(size / 2.0, size / 2.0, size / 2.0),
+        Restitution::coefficient(surface.restitution()),
+        Friction::coefficient(surface.friction()),
+        ColliderMassProperties::Density(5.0),
+        ActiveEvents::CONTACT_FORCE_EVENTS,
+        surface,
+        ExternalForce::default(),
+        Velocity::default(),
+        Sleeping::default(),
+        TransformInterpolation::default(),
+        SpawnedBox { size },
+    )
+}
+
+pub fn spawn_requested(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut requests: EventReader<SpawnRequest>,
+    mut budget: ResMut<SpawnBudget>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    for request in requests.read() {
+        if budget.blocked || now - budget.last_spawn < budget.min_interval {
+            budget.dropped += 1;
+            continue;
+        }
+        budget.last_spawn = now;
+        commands.spawn(spawn_box_bundle(&mut meshes, &mut materials, request.position, request.size, request.material));
+    }
+}
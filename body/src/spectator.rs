@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use crate::spawn::SpawnedBox;
+use crate::HandStates;
+
+const ORBIT_RADIUS: f32 = 26.0;
+const ORBIT_HEIGHT: f32 = 9.0;
+const ORBIT_SPEED: f32 = 0.15;
+// 注視点がぎこちなく跳ねないようにゆっくり追う
+const FOCUS_RATE: f32 = 1.5;
+
+#[derive(Resource, Default)]
+pub struct Spectator {
+    pub active: bool,
+    angle: f32,
+    focus: Vec3,
+    home: Option<Transform>,
+}
+
+pub struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Spectator::default())
+            .add_systems(Update, (spectator_keys, orbit_camera).chain());
+    }
+}
+
+fn spectator_keys(keys: Res<ButtonInput<KeyCode>>, mut spectator: ResMut<Spectator>) {
+    if keys.just_pressed(KeyCode::KeyC) {
+        spectator.active = !spectator.active;
+    }
+}
+
+fn orbit_camera(
+    mut spectator: ResMut<Spectator>,
+    hand_states: Res<HandStates>,
+    boxes: Query<&Transform, (With<SpawnedBox>, Without<Camera3d>)>,
+    mut camera: Query<&mut Transform, With<Camera3d>>,
+    time: Res<Time>,
+) {
+    let Ok(mut camera) = camera.get_single_mut() else {
+        return;
+    };
+    if !spectator.active {
+        if let Some(home) = spectator.home.take() {
+            *camera = home;
+        }
+        return;
+    }
+    if spectator.home.is_none() {
+        spectator.home = Some(*camera);
+        spectator.angle = camera.translation.x.atan2(camera.translation.z);
+    }
+
+    // 手と箱の重心を見る
+    let points: Vec<Vec3> = hand_states
+        .hands
+        .values()
+        .map(|h| h.center)
+        .chain(boxes.iter().map(|t| t.translation))
+        .collect();
+    let target = if points.is_empty() {
+        Vec3::ZERO
+    } else {
+        points.iter().sum::<Vec3>() / points.len() as f32
+    };
+    let dt = time.delta_seconds();
+    spectator.focus = spectator.focus.lerp(target, (FOCUS_RATE * dt).min(1.0));
+    spectator.angle += ORBIT_SPEED * dt;
+
+    let offset = Vec3::new(spectator.angle.sin(), 0.0, spectator.angle.cos()) * ORBIT_RADIUS;
+    camera.translation = spectator.focus + offset + Vec3::Y * ORBIT_HEIGHT;
+    camera.look_at(spectator.focus, Vec3::Y);
+}
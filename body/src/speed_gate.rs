@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::core::speed_gate::SpeedGate;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::settings::Settings;
+use crate::{HandKey, HandStates};
+
+// 止めたことを知らせる表示を出しておく秒数
+#[cfg(feature = "ui")]
+const INDICATOR_TIME: f32 = 1.0;
+
+// 最後に手を止めた時刻
+#[derive(Resource)]
+struct TooFastIndicator {
+    since: f32,
+}
+
+pub struct SpeedGatePlugin;
+
+impl Plugin for SpeedGatePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TooFastIndicator { since: f32::NEG_INFINITY })
+            .add_systems(Update, gate_fast_hands.after(PalmPoseSet));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, too_fast_overlay);
+    }
+}
+
+// 手のひらの速さで止める手を決める。止めた手は当たり判定を外され (workspace.rs)、新しく掴めなくなる
+fn gate_fast_hands(
+    settings: Res<Settings>,
+    palms: Res<PalmPoses>,
+    mut hand_states: ResMut<HandStates>,
+    mut indicator: ResMut<TooFastIndicator>,
+    mut gates: Local<HashMap<HandKey, SpeedGate>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let config = settings.speed_gate;
+    if !config.enabled {
+        gates.clear();
+        hand_states.too_fast.clear();
+        return;
+    }
+    let now = time.elapsed_seconds();
+    gates.retain(|key, _| palms.poses.contains_key(key));
+    for (key, palm) in &palms.poses {
+        let speed = palm.linear_velocity.length();
+        let suspended = gates.entry(*key).or_default().update(speed, now, config.limit, config.hold);
+        if suspended && hand_states.too_fast.insert(*key) {
+            info!(client = key.0, side = key.1.label(), speed, "hand too fast; interaction suspended");
+            indicator.since = now;
+        } else if !suspended && hand_states.too_fast.remove(key) {
+            info!(client = key.0, side = key.1.label(), "hand slowed down");
+        }
+        if suspended {
+            let normal = Dir3::new(palm.orientation() * Vec3::Z).unwrap_or(Dir3::Y);
+            gizmos.circle(palm.position(), normal, 2.0, Color::srgb(1.0, 0.2, 0.1));
+        }
+    }
+    hand_states.too_fast.retain(|key| palms.poses.contains_key(key));
+}
+
+#[cfg(feature = "ui")]
+fn too_fast_overlay(mut contexts: EguiContexts, indicator: Res<TooFastIndicator>, time: Res<Time>) {
+    let age = time.elapsed_seconds() - indicator.since;
+    if age > INDICATOR_TIME {
+        return;
+    }
+    let alpha = ((1.0 - age / INDICATOR_TIME) * 255.0) as u8;
+    egui::Area::new(egui::Id::new("too_fast"))
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+        .show(contexts.ctx_mut(), |ui| {
+            let color = egui::Color32::from_rgba_unmultiplied(255, 80, 40, alpha);
+            ui.label(egui::RichText::new("Too fast!").size(32.0).color(color));
+        });
+}
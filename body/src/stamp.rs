@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::stamp::StampDetector;
+use crate::display::InteractionSet;
+use crate::floor::FLOOR_Y;
+use crate::grab::Held;
+use crate::sound::SurfaceMaterial;
+use crate::spawn::{SpawnRequest, SpawnedBox};
+use crate::update_hands_and_physics;
+
+// 場にある箱がこの数に達したら、はんこを押しても写さない
+const BOX_LIMIT: usize = 60;
+
+pub struct StampPlugin;
+
+impl Plugin for StampPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, stamp_held_boxes.after(update_hands_and_physics).in_set(InteractionSet));
+    }
+}
+
+// 持った箱で床を 2 回たたくと、同じ大きさと材質の箱をたたいた場所に置く
+fn stamp_held_boxes(
+    held: Query<(Entity, &Transform, &SpawnedBox, &SurfaceMaterial), With<Held>>,
+    boxes: Query<(), With<SpawnedBox>>,
+    mut spawns: EventWriter<SpawnRequest>,
+    mut detectors: Local<HashMap<Entity, StampDetector>>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    detectors.retain(|entity, _| held.contains(*entity));
+    let mut count = boxes.iter().count();
+    for (entity, transform, spawned, material) in held.iter() {
+        let half = spawned.size / 2.0;
+        let clearance = transform.translation.y - half - FLOOR_Y;
+        if !detectors.entry(entity).or_default().update(clearance, now) {
+            continue;
+        }
+        let position = Vec3::new(transform.translation.x, FLOOR_Y + half, transform.translation.z);
+        let outline = Transform::from_translation(position).with_scale(Vec3::splat(spawned.size));
+        if count >= BOX_LIMIT {
+            info!(limit = BOX_LIMIT, "stamp skipped: too many boxes");
+            gizmos.cuboid(outline, Color::srgb(1.0, 0.3, 0.3));
+            continue;
+        }
+        count += 1;
+        debug!(?entity, ?position, "stamp");
+        gizmos.cuboid(outline, Color::WHITE);
+        spawns.send(SpawnRequest { position, size: spawned.size, material: *material });
+    }
+}
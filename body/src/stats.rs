@@ -0,0 +1,121 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::core::gesture::Gesture;
+use crate::core::session::{RateMeter, RunningMean, SessionSummary};
+use crate::packet::{ClientPackets, ClientRegistry, IncomingPacket, PacketAuth, PacketSet};
+use crate::smoothing::Smoothing;
+use crate::spawn::SpawnedBox;
+use crate::{HandKey, HandStates};
+
+const STATS_DIR: &str = "sessions";
+
+#[derive(Resource, Default)]
+pub struct SessionStats {
+    started_at: u64,
+    gestures: BTreeMap<String, u32>,
+    last_gestures: HashMap<HandKey, Gesture>,
+    objects_spawned: u32,
+    packets_received: u64,
+    rejected_packets: u64,
+    packet_rate: RateMeter,
+    latency: RunningMean,
+}
+
+impl SessionStats {
+    pub fn summary(&self, duration_secs: f32, clients: usize) -> SessionSummary {
+        SessionSummary {
+            started_at: self.started_at,
+            duration_secs,
+            clients,
+            gestures: self.gestures.clone(),
+            objects_spawned: self.objects_spawned,
+            packets_received: self.packets_received,
+            rejected_packets: self.rejected_packets,
+            peak_packet_rate: self.packet_rate.peak,
+            average_latency_ms: self.latency.mean().map(|s| (s * 1000.0) as f32),
+        }
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        let started_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        app.insert_resource(SessionStats { started_at, ..default() })
+            // 記録の再生で入力が差し替えられる前に、実際に届いた数を数える
+            .add_systems(Update, count_packets.after(PacketSet::Receive).before(PacketSet::Override))
+            .add_systems(PostUpdate, (count_gestures, count_spawns, sample_latency))
+            .add_systems(Last, write_summary_on_exit);
+    }
+}
+
+fn count_packets(
+    mut stats: ResMut<SessionStats>,
+    incoming: Res<IncomingPacket>,
+    clients: Res<ClientPackets>,
+    auth: Res<PacketAuth>,
+    time: Res<Time<Real>>,
+) {
+    let count = incoming.0.is_some() as u32 + clients.packets.len() as u32;
+    stats.packets_received += count as u64;
+    stats.rejected_packets = auth.rejected;
+    stats.packet_rate.record(time.elapsed_seconds_f64(), count);
+}
+
+fn count_gestures(mut stats: ResMut<SessionStats>, hand_states: Res<HandStates>) {
+    for (key, hand) in &hand_states.hands {
+        let previous = stats.last_gestures.insert(*key, hand.gesture).unwrap_or_default();
+        if previous != hand.gesture && hand.gesture != Gesture::Neutral {
+            *stats.gestures.entry(hand.gesture.label().to_string()).or_default() += 1;
+        }
+    }
+    stats.last_gestures.retain(|key, _| hand_states.hands.contains_key(key));
+}
+
+fn count_spawns(mut stats: ResMut<SessionStats>, added: Query<(), Added<SpawnedBox>>) {
+    stats.objects_spawned += added.iter().count() as u32;
+}
+
+fn sample_latency(mut stats: ResMut<SessionStats>, hand_states: Res<HandStates>, smoothing: Res<Smoothing>) {
+    for key in hand_states.hands.keys() {
+        if let Some(delay) = smoothing.delay(*key) {
+            stats.latency.add(delay as f64);
+        }
+    }
+}
+
+// ウィンドウを閉じたときも AppExit が送られる
+fn write_summary_on_exit(
+    mut exits: EventReader<AppExit>,
+    stats: Res<SessionStats>,
+    registry: Res<ClientRegistry>,
+    time: Res<Time<Real>>,
+) {
+    if exits.read().last().is_none() {
+        return;
+    }
+    let summary = stats.summary(time.elapsed_seconds(), registry.sources.len());
+    let dir = PathBuf::from(STATS_DIR);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        error!("failed to create {}: {e}", dir.display());
+        return;
+    }
+    let stem = dir.join(format!("session-{}", stats.started_at));
+    let json = stem.with_extension("json");
+    let result = serde_json::to_string_pretty(&summary)
+        .map_err(std::io::Error::other)
+        .and_then(|text| fs::write(&json, text))
+        .and_then(|()| fs::write(stem.with_extension("md"), summary.to_markdown()));
+    match result {
+        Ok(()) => info!("session summary written to {}", json.display()),
+        Err(e) => error!("failed to write session summary: {e}"),
+    }
+}
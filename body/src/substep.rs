@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::substep::{substeps_for, SubstepGovernor};
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::settings::Settings;
+use crate::HandStates;
+
+// 中指の付け根から指先までのおおよその長さ。手首を返す動きでは指先が手のひらより速く動く
+const FINGER_REACH: f32 = 2.0;
+
+pub struct SubstepPlugin;
+
+impl Plugin for SubstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, substep_for_fast_hands.after(PalmPoseSet));
+    }
+}
+
+// 平手打ちや手刀のような速い手で、1 ステップの間に手の関節が薄い物体を飛び越えないよう刻みを増やす。
+// 手の関節には CCD も付けてある (main.rs の spawn_hand_rig)
+fn substep_for_fast_hands(
+    settings: Res<Settings>,
+    palms: Res<PalmPoses>,
+    hand_states: Res<HandStates>,
+    mut config: ResMut<RapierConfiguration>,
+    mut governor: Local<SubstepGovernor>,
+    time: Res<Time>,
+) {
+    let TimestepMode::Interpolated { dt, .. } = config.timestep_mode else {
+        return;
+    };
+    let substep = settings.substeps;
+    let wanted = if substep.enabled {
+        // 当たり判定を外している手は数えない
+        let speed = palms
+            .poses
+            .iter()
+            .filter(|(key, _)| !hand_states.is_suspended(**key))
+            .map(|(_, palm)| palm.linear_velocity.length() + palm.angular_velocity.length() * FINGER_REACH)
+            .fold(0.0, f32::max);
+        // 1 ステップは実時間の dt ごとに進むので、スローモーション中でも 1 ステップで手が進むのは speed * dt
+        substeps_for(speed, dt, substep.max_travel, substep.max_substeps)
+    } else {
+        1
+    };
+    let before = governor.current();
+    let count = governor.update(wanted, time.elapsed_seconds(), substep.hold);
+    if count != before {
+        debug!(substeps = count, "physics substeps changed");
+    }
+    if let TimestepMode::Interpolated { substeps, .. } = &mut config.timestep_mode
+        && *substeps != count
+    {
+        *substeps = count;
+    }
+}
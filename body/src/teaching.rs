@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use std::fs;
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::console::arg_or;
+use crate::core::teaching::{joint_errors, match_score, palm_local, Lesson, LessonFile, LessonRun, TargetPose};
+#[cfg(feature = "ui")]
+use crate::core::teaching::PASS_SCORE;
+use crate::core::topology::MEDIAPIPE_LANDMARKS;
+use crate::smoothing::Smoothing;
+use crate::topology::Topology;
+use crate::{draw_hand_skeleton, update_hands_and_physics, HandKey, HandSide, HandStates};
+
+const LESSONS_PATH: &str = "lessons.json";
+const TARGET_COLOR: Color = Color::srgba(0.85, 0.9, 1.0, 0.45);
+const JOINT_RADIUS: f32 = 0.15;
+const WRIST: usize = 0;
+const MIDDLE_MCP: usize = 9;
+
+struct Running {
+    lesson: usize,
+    run: LessonRun,
+}
+
+// まねる手の形を順に見せて、ライブの手がどれだけ近いかを関節ごとに採点する (リハビリ・練習用)
+#[derive(Resource, Default)]
+pub struct Teaching {
+    file: LessonFile,
+    running: Option<Running>,
+    // まねている手。見えている間は替えない
+    learner: Option<HandKey>,
+    // 今の形に対する関節ごとのずれ (ラジアン) と点数
+    errors: Vec<(usize, f32)>,
+    score: Option<f32>,
+}
+
+impl Teaching {
+    fn load() -> Self {
+        let file = match fs::read_to_string(LESSONS_PATH) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {LESSONS_PATH}: {e}");
+                LessonFile::default()
+            }),
+            Err(_) => LessonFile::default(),
+        };
+        if !file.lessons.is_empty() {
+            info!("loaded {} lessons from {LESSONS_PATH}", file.lessons.len());
+        }
+        Self { file, ..default() }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(&self.file).map_err(|e| e.to_string())?;
+        fs::write(LESSONS_PATH, text).map_err(|e| format!("failed to write {LESSONS_PATH}: {e}"))
+    }
+
+    fn lesson_index(&self, name: &str) -> Option<usize> {
+        self.file.lessons.iter().position(|l| l.name.eq_ignore_ascii_case(name))
+    }
+
+    // 進めている形。最後の形を終えていれば None
+    fn target(&self) -> Option<&TargetPose> {
+        let running = self.running.as_ref()?;
+        self.file.lessons[running.lesson].poses.get(running.run.pose)
+    }
+}
+
+pub struct TeachingPlugin;
+
+impl Plugin for TeachingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Teaching::load())
+            .add_console_command(
+                "teach",
+                "teach start <lesson> | teach stop | teach skip | teach capture <lesson> <pose> [hold]",
+                teach_command,
+            )
+            .add_systems(Update, (score_learner, draw_target_pose).chain().after(update_hands_and_physics));
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, lesson_ui.after(score_learner));
+    }
+}
+
+// 手の点を番号順に並べる。一つでも欠けていれば None
+fn live_points(smoothing: &Smoothing, key: HandKey) -> Option<Vec<Vec3>> {
+    (0..MEDIAPIPE_LANDMARKS).map(|id| smoothing.sample(key, id)).collect()
+}
+
+// 見えている手から、迷いなく追えている手を一つ選ぶ
+fn pick_learner(hand_states: &HandStates, current: Option<HandKey>) -> Option<HandKey> {
+    if let Some(key) = current.filter(|key| hand_states.hands.contains_key(key)) {
+        return Some(key);
+    }
+    let mut keys: Vec<HandKey> = hand_states.hands.keys().copied().filter(|k| hand_states.is_confident(*k)).collect();
+    keys.sort();
+    keys.first().copied()
+}
+
+fn teach_command(
+    In(args): In<Vec<String>>,
+    mut teaching: ResMut<Teaching>,
+    hand_states: Res<HandStates>,
+    smoothing: Res<Smoothing>,
+) -> ConsoleResult {
+    let teaching = &mut *teaching;
+    match args.first().map(String::as_str) {
+        Some("start") => {
+            let name = args.get(1).ok_or("teach start <lesson>")?;
+            let Some(lesson) = teaching.lesson_index(name) else {
+                let names: Vec<&str> = teaching.file.lessons.iter().map(|l| l.name.as_str()).collect();
+                return Err(format!("no lesson {name:?}; known lessons: {}", names.join(", ")));
+            };
+            if teaching.file.lessons[lesson].poses.is_empty() {
+                return Err(format!("lesson {name:?} has no poses; add some with teach capture"));
+            }
+            teaching.running = Some(Running { lesson, run: LessonRun::default() });
+            Ok(format!("started {}", teaching.file.lessons[lesson].name))
+        }
+        Some("stop") if teaching.running.take().is_some() => Ok("stopped the lesson".to_string()),
+        Some("stop") => Err("no lesson is running".to_string()),
+        Some("skip") => match &mut teaching.running {
+            Some(running) => {
+                running.run.skip();
+                Ok(format!("skipped to pose {}", running.run.pose + 1))
+            }
+            None => Err("no lesson is running".to_string()),
+        },
+        Some("capture") => {
+            let (Some(lesson_name), Some(pose_name)) = (args.get(1), args.get(2)) else {
+                return Err("teach capture <lesson> <pose> [hold]".to_string());
+            };
+            let hold = arg_or(&args, 3, "hold", 1.5)?;
+            let key = pick_learner(&hand_states, teaching.learner).ok_or("no hand to capture")?;
+            let (_, local) = live_points(&smoothing, key)
+                .and_then(|points| palm_local(&points, key.1 == HandSide::Right))
+                .ok_or("the hand is not fully visible")?;
+            let pose = TargetPose {
+                name: pose_name.clone(),
+                side: key.1.label().to_string(),
+                points: local.iter().map(|p| p.to_array()).collect(),
+                hold,
+            };
+            let lesson = match teaching.lesson_index(lesson_name) {
+                Some(index) => &mut teaching.file.lessons[index],
+                None => {
+                    teaching.file.lessons.push(Lesson { name: lesson_name.clone(), poses: vec![] });
+                    teaching.file.lessons.last_mut().unwrap()
+                }
+            };
+            lesson.poses.push(pose);
+            let count = lesson.poses.len();
+            teaching.save()?;
+            Ok(format!("captured {pose_name} as pose {count} of {lesson_name}"))
+        }
+        _ => Err("teach start <lesson> | teach stop | teach skip | teach capture <lesson> <pose> [hold]".to_string()),
+    }
+}
+
+fn score_learner(
+    mut teaching: ResMut<Teaching>,
+    hand_states: Res<HandStates>,
+    smoothing: Res<Smoothing>,
+    time: Res<Time>,
+) {
+    let teaching = &mut *teaching;
+    teaching.learner = pick_learner(&hand_states, teaching.learner);
+    let Some(target) = teaching.target().cloned() else {
+        teaching.errors.clear();
+        teaching.score = None;
+        return;
+    };
+    teaching.errors = teaching
+        .learner
+        .and_then(|key| {
+            let points = live_points(&smoothing, key)?;
+            let (_, live) = palm_local(&points, key.1 == HandSide::Right)?;
+            Some(joint_errors(&live, &target.points_for(key.1 == HandSide::Right)))
+        })
+        .unwrap_or_default();
+    teaching.score = (!teaching.errors.is_empty()).then(|| match_score(&teaching.errors));
+
+    let Some(running) = &mut teaching.running else {
+        return;
+    };
+    let lesson = &teaching.file.lessons[running.lesson];
+    if running.run.update(teaching.score, target.hold, time.delta_seconds()) {
+        let score = running.run.scores.last().copied().flatten().unwrap_or_default();
+        info!(lesson = lesson.name, pose = target.name, score = format!("{:.0}%", score * 100.0), "pose matched");
+        if running.run.pose >= lesson.poses.len() {
+            let average = running.run.average().unwrap_or_default();
+            info!(lesson = lesson.name, average = format!("{:.0}%", average * 100.0), "lesson finished");
+        }
+    }
+}
+
+// まねる形を、まねている手の手首に合わせて手のひらの向きと大きさをそろえて重ねる。関節はずれの大きさで色を変える
+fn draw_target_pose(teaching: Res<Teaching>, smoothing: Res<Smoothing>, topology: Res<Topology>, mut gizmos: Gizmos) {
+    let (Some(target), Some(key)) = (teaching.target(), teaching.learner) else {
+        return;
+    };
+    let right_hand = key.1 == HandSide::Right;
+    let Some(points) = live_points(&smoothing, key) else {
+        return;
+    };
+    let Some((rotation, live)) = palm_local(&points, right_hand) else {
+        return;
+    };
+    let local = target.points_for(right_hand);
+    // 手の大きさの違いは手首から中指の付け根までの長さでそろえる
+    let scale = live[MIDDLE_MCP].length() / local[MIDDLE_MCP].length().max(f32::EPSILON);
+    let world: Vec<Vec3> = local.iter().map(|p| points[WRIST] + rotation * (*p * scale)).collect();
+    draw_hand_skeleton(&mut gizmos, &topology.0.connections, |id| world.get(id).copied(), TARGET_COLOR);
+    for (joint, error) in &teaching.errors {
+        let off = (error / std::f32::consts::FRAC_PI_4).clamp(0.0, 1.0);
+        let color = Color::srgba(0.3, 1.0, 0.4, 0.7).mix(&Color::srgba(1.0, 0.3, 0.2, 0.7), off);
+        gizmos.sphere(world[*joint], Quat::IDENTITY, JOINT_RADIUS, color);
+    }
+}
+
+#[cfg(feature = "ui")]
+fn lesson_ui(mut contexts: EguiContexts, mut teaching: ResMut<Teaching>, topology: Res<Topology>) {
+    let Some(running) = &teaching.running else {
+        return;
+    };
+    let lesson = &teaching.file.lessons[running.lesson];
+    let mut stop = false;
+    let mut skip = false;
+    egui::Window::new("Lesson")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.heading(&lesson.name);
+            match lesson.poses.get(running.run.pose) {
+                Some(pose) => {
+                    ui.label(format!("Pose {}/{}: {}", running.run.pose + 1, lesson.poses.len(), pose.name));
+                    match teaching.score {
+                        Some(score) if score >= PASS_SCORE => {
+                            ui.colored_label(egui::Color32::LIGHT_GREEN, format!("Match {:.0}%", score * 100.0));
+                        }
+                        Some(score) => {
+                            ui.label(format!("Match {:.0}%", score * 100.0));
+                        }
+                        None => {
+                            ui.label("Show your hand");
+                        }
+                    }
+                    ui.add(egui::ProgressBar::new(running.run.hold_fraction(pose.hold)).text("Hold"));
+                    if let Some((joint, error)) = teaching.errors.iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+                        ui.small(format!("Furthest off: {} ({:.0}°)", topology.0.name(*joint), error.to_degrees()));
+                    }
+                    skip = ui.button("Skip").clicked();
+                }
+                None => {
+                    ui.label("Finished");
+                    for (pose, score) in lesson.poses.iter().zip(&running.run.scores) {
+                        match score {
+                            Some(score) => ui.label(format!("{}: {:.0}%", pose.name, score * 100.0)),
+                            None => ui.label(format!("{}: skipped", pose.name)),
+                        };
+                    }
+                    if let Some(average) = running.run.average() {
+                        ui.strong(format!("Average {:.0}%", average * 100.0));
+                    }
+                }
+            }
+            stop = ui.button("Close").clicked();
+        });
+    if stop {
+        teaching.running = None;
+    } else if skip && let Some(running) = &mut teaching.running {
+        running.run.skip();
+    }
+}
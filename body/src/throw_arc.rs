@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::trajectory::Ballistic;
+use crate::floor::FLOOR_Y;
+use crate::grab::{surface_radius, Grabbable, Held};
+use crate::settings::Settings;
+use crate::spawn::SpawnedBox;
+use crate::{update_hands_and_physics, HandPoint};
+
+// 持った物体がこれより速く動いていたら振りかぶっているとみなして軌跡を出す
+const WINDUP_SPEED: f32 = 6.0;
+const PREDICT_STEP: f32 = 1.0 / 30.0;
+const PREDICT_TIME: f32 = 2.5;
+const ARC_ALPHA: f32 = 0.35;
+
+pub struct ThrowArcPlugin;
+
+impl Plugin for ThrowArcPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, draw_throw_arcs.after(update_hands_and_physics));
+    }
+}
+
+// 今の速度で手を離したら物体がどこへ飛ぶかを薄い弧で見せる。途中で何かに当たればそこで切る
+#[allow(clippy::type_complexity)]
+fn draw_throw_arcs(
+    settings: Res<Settings>,
+    config: Res<RapierConfiguration>,
+    rapier: Res<RapierContext>,
+    held: Query<(Entity, &Transform, &Velocity, Option<&Damping>, AnyOf<(&SpawnedBox, &Grabbable)>), With<Held>>,
+    hand_points: Query<(), With<HandPoint>>,
+    mut gizmos: Gizmos,
+) {
+    if !settings.display.throw_preview {
+        return;
+    }
+    for (entity, transform, velocity, damping, shape) in held.iter() {
+        let speed = velocity.linvel.length();
+        if speed < WINDUP_SPEED {
+            continue;
+        }
+        let ballistic = Ballistic {
+            gravity: config.gravity,
+            damping: damping.map_or(0.0, |d| d.linear_damping),
+            step: PREDICT_STEP,
+            max_time: PREDICT_TIME,
+        };
+        let radius = surface_radius(shape);
+        let mut points = ballistic.predict(transform.translation, velocity.linvel, FLOOR_Y + radius);
+        let not_hand = |e: Entity| !hand_points.contains(e);
+        let filter = QueryFilter::new().exclude_sensors().exclude_rigid_body(entity).predicate(&not_hand);
+        let hit = points.windows(2).enumerate().find_map(|(i, pair)| {
+            let offset = pair[1] - pair[0];
+            let (direction, length) = (offset.normalize_or_zero(), offset.length());
+            let (_, toi) = rapier.cast_ray(pair[0], direction, length, true, filter)?;
+            Some((i, pair[0] + direction * toi))
+        });
+        if let Some((i, point)) = hit {
+            points.truncate(i + 1);
+            points.push(point);
+        }
+        // 速く振るほど濃く、先へ行くほど薄く
+        let strength = ((speed - WINDUP_SPEED) / WINDUP_SPEED).clamp(0.3, 1.0) * ARC_ALPHA;
+        let count = points.len().max(2) as f32;
+        let Some(&landing) = points.last() else {
+            continue;
+        };
+        gizmos.linestrip_gradient(points.into_iter().enumerate().map(|(i, p)| {
+            (p, Color::srgba(1.0, 1.0, 1.0, strength * (1.0 - i as f32 / count)))
+        }));
+        gizmos.circle(landing + Vec3::Y * 0.02, Dir3::Y, radius.max(0.3), Color::srgba(1.0, 1.0, 1.0, strength));
+    }
+}
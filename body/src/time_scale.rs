@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::core::gesture::Gesture;
+use crate::display::InteractionSet;
+use crate::profile::ActiveProfile;
+use crate::tuning::Tuning;
+use crate::workspace::Workspaces;
+use crate::{HandSide, HandStates};
+
+const RAMP_RATE: f32 = 4.0;
+const PHYSICS_DT: f32 = 1.0 / 60.0;
+
+// 胸の前とみなす領域 (ワールド座標)
+pub const CHEST_POINT: Vec3 = Vec3::new(0.0, 0.0, 12.0);
+
+#[derive(Resource)]
+pub struct TimeScale {
+    pub current: f32,
+    pub target: f32,
+    // 最後にバレットタイムを始めたクライアントの胸の位置
+    chest: Vec3,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            current: 1.0,
+            target: 1.0,
+            chest: CHEST_POINT,
+        }
+    }
+}
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeScale::default())
+            .add_systems(Startup, use_interpolated_timestep)
+            .add_systems(Update, (detect_bullet_time.in_set(InteractionSet), apply_time_scale).chain());
+    }
+}
+
+// 固定 dt で刻み、ステップ間は TransformInterpolation で補間して描画する
+fn use_interpolated_timestep(mut config: ResMut<RapierConfiguration>) {
+    config.timestep_mode = TimestepMode::Interpolated {
+        dt: PHYSICS_DT,
+        time_scale: 1.0,
+        substeps: 1,
+    };
+}
+
+fn detect_bullet_time(
+    hand_states: Res<HandStates>,
+    mut time_scale: ResMut<TimeScale>,
+    tuning: Res<Tuning>,
+    profile: Res<ActiveProfile>,
+    workspaces: Res<Workspaces>,
+    mut gizmos: Gizmos,
+) {
+    let bullet_time = &tuning.0.bullet_time;
+    // 物理の時間は全員で共有なので、どのクライアントが両手の拳を胸に寄せても遅くする。
+    // 胸の位置はそれぞれの作業領域の中央に置く
+    let mut clients: Vec<_> = hand_states.hands.keys().map(|(client, _)| *client).collect();
+    clients.sort_unstable();
+    clients.dedup();
+    let fists_at_chest = clients.into_iter().find_map(|client| {
+        let chest = Vec3::new(workspaces.region(client).center(), CHEST_POINT.y, CHEST_POINT.z);
+        let right = hand_states.hands.get(&(client, HandSide::Right))?;
+        let left = hand_states.hands.get(&(client, HandSide::Left))?;
+        (right.gesture == Gesture::Fist
+            && left.gesture == Gesture::Fist
+            && right.center.distance(chest) < bullet_time.chest_radius
+            && left.center.distance(chest) < bullet_time.chest_radius
+            && right.center.distance(left.center) < profile.max_fist_gap)
+            .then_some(chest)
+    });
+
+    time_scale.target = if fists_at_chest.is_some() { bullet_time.slow_motion_scale } else { 1.0 };
+    if let Some(chest) = fists_at_chest {
+        time_scale.chest = chest;
+    }
+
+    if time_scale.current < 0.99 {
+        let strength = ((1.0 - time_scale.current) / (1.0 - bullet_time.slow_motion_scale).max(f32::EPSILON)).min(1.0);
+        gizmos.circle(
+            time_scale.chest,
+            Dir3::Z,
+            bullet_time.chest_radius,
+            Color::srgba(0.4, 0.6, 1.0, 0.6 * strength),
+        );
+    }
+}
+
+fn apply_time_scale(
+    mut time_scale: ResMut<TimeScale>,
+    mut config: ResMut<RapierConfiguration>,
+    time: Res<Time>,
+) {
+    let t = 1.0 - (-RAMP_RATE * time.delta_seconds()).exp();
+    time_scale.current += (time_scale.target - time_scale.current) * t;
+    if (time_scale.current - time_scale.target).abs() < 0.001 {
+        time_scale.current = time_scale.target;
+    }
+
+    if let TimestepMode::Interpolated { time_scale: scale, .. }
+    | TimestepMode::Variable { time_scale: scale, .. } = &mut config.timestep_mode
+        && *scale != time_scale.current
+    {
+        *scale = time_scale.current;
+    }
+}
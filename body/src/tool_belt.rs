@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::core::tool_belt::{Tool, WristTap};
+use crate::display::InteractionSet;
+use crate::field::Push;
+use crate::grab::{surface_radius, Grabbable, Held};
+use crate::history::{Action, Undoable};
+use crate::lifecycle::vanish;
+use crate::profile::ActiveProfile;
+use crate::spawn::SpawnedBox;
+use crate::versus::{can_touch, Team};
+use crate::{hand_color, update_hands_and_physics, HandKey, HandPoint, HandSide, HandStates};
+
+const WRIST: usize = 0;
+const THUMB_TIP: usize = 4;
+const INDEX_TIP: usize = 8;
+// 「押す」の届く距離と円錐の半角の cos (約 45°)、風の力に対する強さ
+const PUSH_REACH: f32 = 6.0;
+const PUSH_SPREAD: f32 = 0.7;
+const PUSH_GAIN: f32 = 2.0;
+// 線の点の間隔と、残しておく線の数
+const STROKE_SPACING: f32 = 0.1;
+const MAX_STROKES: usize = 64;
+// つまんだ位置からこの距離までにある物体や線を消す
+const DELETE_REACH: f32 = 0.8;
+// 道具の印を出す手の中心からの高さと大きさ
+const ICON_LIFT: f32 = 1.8;
+const ICON_SIZE: f32 = 0.35;
+
+// 手ごとの今の道具。見えなくなった手も持ち替えた道具を覚えておく
+#[derive(Resource, Default)]
+pub struct ToolBelt {
+    tools: HashMap<HandKey, Tool>,
+    taps: HashMap<HandKey, WristTap>,
+}
+
+impl ToolBelt {
+    pub fn tool(&self, key: HandKey) -> Tool {
+        self.tools.get(&key).copied().unwrap_or_default()
+    }
+}
+
+// 「押す」の開いた手が作る場。normal は手のひらの向き
+pub fn push_field(center: Vec3, normal: Vec3, wind_force: f32) -> Push {
+    Push { origin: center, direction: normal, strength: wind_force * PUSH_GAIN, reach: PUSH_REACH, spread: PUSH_SPREAD }
+}
+
+#[derive(Clone)]
+pub struct Stroke {
+    id: u32,
+    points: Vec<Vec3>,
+    color: Color,
+}
+
+// 「描く」で宙に描いた線
+#[derive(Resource, Default)]
+pub struct Drawings {
+    strokes: Vec<Stroke>,
+    // 描いている途中の手と、その線の番号
+    drawing: HashMap<HandKey, usize>,
+    next_id: u32,
+}
+
+impl Drawings {
+    // 取り消しで線を消す。もう残っていなければ false
+    pub fn erase(&mut self, id: u32) -> bool {
+        let Some(index) = self.strokes.iter().position(|s| s.id == id) else {
+            return false;
+        };
+        self.strokes.remove(index);
+        self.drawing.clear();
+        true
+    }
+
+    // 消した線を取り消しで戻す
+    pub fn restore(&mut self, stroke: Stroke) {
+        self.strokes.push(stroke);
+    }
+}
+
+pub struct ToolBeltPlugin;
+
+impl Plugin for ToolBeltPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ToolBelt::default()).insert_resource(Drawings::default()).add_systems(
+            Update,
+            ((switch_tools, draw_strokes, delete_pinched).chain().in_set(InteractionSet), show_tools)
+                .chain()
+                .after(update_hands_and_physics),
+        );
+    }
+}
+
+fn fingertips(points: &Query<(&HandPoint, &Transform)>) -> HashMap<(HandKey, usize), Vec3> {
+    points
+        .iter()
+        .filter(|(p, _)| matches!(p.id, WRIST | THUMB_TIP | INDEX_TIP))
+        .map(|(p, t)| (((p.client, p.side), p.id), t.translation))
+        .collect()
+}
+
+fn pinch_point(tips: &HashMap<(HandKey, usize), Vec3>, key: HandKey, distance: f32) -> Option<Vec3> {
+    let (thumb, index) = (tips.get(&(key, THUMB_TIP))?, tips.get(&(key, INDEX_TIP))?);
+    (thumb.distance(*index) < distance).then_some((*thumb + *index) / 2.0)
+}
+
+// もう片方の人差し指で手首を軽く叩くと、叩かれた手の道具が次へ移る
+fn switch_tools(
+    mut belt: ResMut<ToolBelt>,
+    hand_states: Res<HandStates>,
+    points: Query<(&HandPoint, &Transform)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let tips = fingertips(&points);
+    let belt = &mut *belt;
+    belt.taps.retain(|key, _| hand_states.hands.contains_key(key));
+    for &(client, side) in hand_states.hands.keys() {
+        let other = if side == HandSide::Right { HandSide::Left } else { HandSide::Right };
+        let (Some(wrist), Some(finger)) = (tips.get(&((client, side), WRIST)), tips.get(&((client, other), INDEX_TIP)))
+        else {
+            continue;
+        };
+        if belt.taps.entry((client, side)).or_default().update(wrist.distance(*finger), now) {
+            let tool = belt.tools.entry((client, side)).or_default();
+            *tool = tool.next();
+            info!(client, side = side.label(), tool = tool.label(), "switched tool");
+        }
+    }
+}
+
+// 「描く」の手はつまんでいる間、指先の軌跡を線として残す
+fn draw_strokes(
+    mut drawings: ResMut<Drawings>,
+    belt: Res<ToolBelt>,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    points: Query<(&HandPoint, &Transform)>,
+    mut undoable: EventWriter<Undoable>,
+    mut gizmos: Gizmos,
+) {
+    let tips = fingertips(&points);
+    let drawings = &mut *drawings;
+    let mut still_drawing = HashSet::new();
+    for &key in hand_states.hands.keys() {
+        if belt.tool(key) != Tool::Draw || !hand_states.is_confident(key) {
+            continue;
+        }
+        let Some(pinch) = pinch_point(&tips, key, profile.pinch_distance) else {
+            continue;
+        };
+        still_drawing.insert(key);
+        match drawings.drawing.get(&key).and_then(|i| drawings.strokes.get_mut(*i)) {
+            Some(stroke) => {
+                if stroke.points.last().is_none_or(|p| p.distance(pinch) >= STROKE_SPACING) {
+                    stroke.points.push(pinch);
+                }
+            }
+            None => {
+                // 古い線から消す。描いている途中の線の番号がずれるので、消したら描き直しを始める
+                if drawings.strokes.len() >= MAX_STROKES {
+                    drawings.strokes.remove(0);
+                    drawings.drawing.clear();
+                }
+                let id = drawings.next_id;
+                drawings.next_id += 1;
+                drawings.strokes.push(Stroke { id, points: vec![pinch], color: hand_color(key.0, key.1) });
+                drawings.drawing.insert(key, drawings.strokes.len() - 1);
+                undoable.send(Undoable(Action::Draw(id)));
+            }
+        }
+    }
+    drawings.drawing.retain(|key, _| still_drawing.contains(key));
+    for stroke in &drawings.strokes {
+        gizmos.linestrip(stroke.points.iter().copied(), stroke.color);
+    }
+}
+
+// 「消す」の手でつまんだ瞬間、近くの物体を消す。物体が無ければ近くの線を消す
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn delete_pinched(
+    mut commands: Commands,
+    mut drawings: ResMut<Drawings>,
+    belt: Res<ToolBelt>,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    points: Query<(&HandPoint, &Transform)>,
+    objects: Query<
+        (Entity, &Transform, AnyOf<(&SpawnedBox, &Grabbable)>, Option<&Team>),
+        (With<RigidBody>, Without<Held>),
+    >,
+    mut undoable: EventWriter<Undoable>,
+    mut was_pinching: Local<HashSet<HandKey>>,
+) {
+    let tips = fingertips(&points);
+    let mut pinching = HashSet::new();
+    for &key in hand_states.hands.keys() {
+        if belt.tool(key) != Tool::Delete || !hand_states.is_confident(key) {
+            continue;
+        }
+        let Some(pinch) = pinch_point(&tips, key, profile.pinch_distance) else {
+            continue;
+        };
+        pinching.insert(key);
+        if was_pinching.contains(&key) {
+            continue;
+        }
+        let nearest = objects
+            .iter()
+            .filter(|(.., team)| can_touch(*team, key.0))
+            .map(|(entity, transform, shape, _)| {
+                (entity, (transform.translation.distance(pinch) - surface_radius(shape)).max(0.0))
+            })
+            .filter(|(_, gap)| *gap < DELETE_REACH)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((entity, _)) = nearest {
+            vanish(&mut commands, entity);
+            continue;
+        }
+        let touched = drawings.strokes.iter().position(|s| s.points.iter().any(|p| p.distance(pinch) < DELETE_REACH));
+        if let Some(index) = touched {
+            undoable.send(Undoable(Action::Erase(drawings.strokes.remove(index))));
+            drawings.drawing.clear();
+        }
+    }
+    *was_pinching = pinching;
+}
+
+// 手の上に今の道具の印を出す。掴むは輪、押すは矢印、描くはジグザグ、消すはバツ
+fn show_tools(belt: Res<ToolBelt>, hand_states: Res<HandStates>, mut gizmos: Gizmos) {
+    for (&key, hand) in &hand_states.hands {
+        let center = hand.center + Vec3::Y * ICON_LIFT;
+        let color = hand_color(key.0, key.1);
+        let (x, y) = (Vec3::X * ICON_SIZE, Vec3::Y * ICON_SIZE);
+        match belt.tool(key) {
+            Tool::Grab => {
+                gizmos.circle(center, Dir3::Z, ICON_SIZE, color);
+            }
+            Tool::Push => {
+                gizmos.arrow(center - x, center + x, color);
+            }
+            Tool::Draw => {
+                gizmos.linestrip([center - x - y, center - x * 0.33 + y, center + x * 0.33 - y, center + x + y], color);
+            }
+            Tool::Delete => {
+                gizmos.line(center - x - y, center + x + y, color);
+                gizmos.line(center - x + y, center + x - y, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use std::fs;
+
+use crate::core::topology::HandTopology;
+
+const TOPOLOGY_PATH: &str = "topology.json";
+
+// 手の点の数とつなぎ方。起動時に topology.json から読む (なければ MediaPipe の 21 点)
+#[derive(Resource, Default)]
+pub struct Topology(pub HandTopology);
+
+impl Topology {
+    fn load() -> Self {
+        let Ok(text) = fs::read_to_string(TOPOLOGY_PATH) else {
+            return Self::default();
+        };
+        let topology: HandTopology = match serde_json::from_str(&text) {
+            Ok(topology) => topology,
+            Err(e) => {
+                warn!("failed to parse {TOPOLOGY_PATH}: {e}");
+                return Self::default();
+            }
+        };
+        if let Err(errors) = topology.validate() {
+            error!("{TOPOLOGY_PATH} was not applied:");
+            for e in errors {
+                error!("  {e}");
+            }
+            return Self::default();
+        }
+        info!(landmarks = topology.landmarks, connections = topology.connections.len(), "hand topology loaded");
+        Self(topology)
+    }
+}
+
+pub struct TopologyPlugin;
+
+impl Plugin for TopologyPlugin {
+    fn build(&self, app: &mut App) {
+        // 手の点を作る Startup より前に読んでおく
+        app.insert_resource(Topology::load());
+    }
+}
@@ -0,0 +1,346 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy::winit::WinitWindows;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::core::spring::low_pass;
+use crate::core::trackpad::{to_screen, ScreenArea, TrackpadPlane};
+use crate::display::InteractionSet;
+use crate::error::Health;
+use crate::profile::ActiveProfile;
+use crate::{update_hands_and_physics, HandPoint, HandSide, HandStates};
+
+const TRACKPAD_PATH: &str = "trackpad.json";
+const THUMB_TIP: usize = 4;
+const INDEX_TIP: usize = 8;
+// 指先の震えでカーソルが揺れないようにならす時定数
+const CURSOR_SMOOTHING: f32 = 0.05;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TrackpadConfig {
+    #[serde(default)]
+    plane: TrackpadPlane,
+    // カーソルを動かす範囲。書かなければ主モニターの範囲を使う
+    #[serde(default)]
+    screen: Option<ScreenArea>,
+    #[serde(default = "default_hand")]
+    hand: String,
+    #[serde(default)]
+    commands: CursorCommands,
+}
+
+// カーソル操作に使うプログラム。一度だけ起動して、標準入力へ 1 行に 1 つずつ命令を書き込む。
+// move の {x} と {y} は画面のピクセル位置に置き換える
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CursorCommands {
+    program: Vec<String>,
+    #[serde(rename = "move")]
+    move_to: String,
+    press: String,
+    release: String,
+}
+
+impl Default for CursorCommands {
+    fn default() -> Self {
+        Self {
+            program: vec!["xdotool".to_string(), "-".to_string()],
+            move_to: "mousemove {x} {y}".to_string(),
+            press: "mousedown 1".to_string(),
+            release: "mouseup 1".to_string(),
+        }
+    }
+}
+
+fn default_hand() -> String {
+    HandSide::Right.label().to_string()
+}
+
+impl Default for TrackpadConfig {
+    fn default() -> Self {
+        Self {
+            plane: TrackpadPlane::default(),
+            screen: None,
+            hand: default_hand(),
+            commands: CursorCommands::default(),
+        }
+    }
+}
+
+impl CursorCommands {
+    // 設定したプログラムが見つからなければその名前
+    fn missing_program(&self) -> Option<&str> {
+        match self.program.first() {
+            Some(program) => (!program_exists(program)).then_some(program.as_str()),
+            None => Some("(empty program)"),
+        }
+    }
+}
+
+fn program_exists(program: &str) -> bool {
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(program).is_file();
+    }
+    std::env::var_os("PATH").is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+impl TrackpadConfig {
+    fn load() -> Self {
+        match fs::read_to_string(TRACKPAD_PATH) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {TRACKPAD_PATH}: {e}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CursorEvent {
+    Move([i32; 2]),
+    Press,
+    Release,
+}
+
+// 命令は別スレッドで順に書き込む。移動は溜まっていれば最後の位置だけを使う。
+// プログラムが終わるか書き込めなくなればスレッドも終わり、次の操作で起動し直す
+fn run_cursor_commands(commands: CursorCommands, events: Receiver<CursorEvent>) {
+    let Some((program, args)) = commands.program.split_first() else {
+        return;
+    };
+    let mut child = match Command::new(program).args(args).stdin(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            error!("failed to start cursor program {program}: {e}");
+            return;
+        }
+    };
+    let Some(mut stdin) = child.stdin.take() else {
+        return;
+    };
+    let mut write = |line: &str, position: Option<[i32; 2]>| {
+        let line = match position {
+            Some([x, y]) => line.replace("{x}", &x.to_string()).replace("{y}", &y.to_string()),
+            None => line.to_string(),
+        };
+        let written = stdin.write_all(format!("{line}\n").as_bytes());
+        written.map_err(|e| error!("failed to send cursor command: {e}")).is_ok()
+    };
+    'events: while let Ok(event) = events.recv() {
+        let mut pending = Some(event);
+        while let Some(event) = pending.take() {
+            let written = match event {
+                CursorEvent::Move(mut position) => {
+                    loop {
+                        match events.try_recv() {
+                            Ok(CursorEvent::Move(next)) => position = next,
+                            Ok(other) => {
+                                pending = Some(other);
+                                break;
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                    write(&commands.move_to, Some(position))
+                }
+                CursorEvent::Press => write(&commands.press, None),
+                CursorEvent::Release => write(&commands.release, None),
+            };
+            if !written {
+                break 'events;
+            }
+        }
+    }
+    drop(stdin);
+    let _ = child.wait();
+}
+
+#[derive(Resource, Default)]
+pub struct Trackpad {
+    pub active: bool,
+    config: TrackpadConfig,
+    // カーソル操作のコマンドが見つかったか。最初に使うときに一度だけ調べる
+    available: Option<bool>,
+    screen: ScreenArea,
+    sender: Option<Sender<CursorEvent>>,
+    cursor: Option<Vec2>,
+    last_sent: Option<[i32; 2]>,
+    pressed: bool,
+}
+
+impl Trackpad {
+    fn send(&mut self, event: CursorEvent) {
+        if self.sender.is_none() {
+            let (sender, receiver) = channel();
+            let commands = self.config.commands.clone();
+            std::thread::spawn(move || run_cursor_commands(commands, receiver));
+            self.sender = Some(sender);
+        }
+        if let Some(sender) = &self.sender
+            && sender.send(event).is_err()
+        {
+            self.sender = None;
+        }
+    }
+
+    fn release(&mut self) {
+        if self.pressed {
+            self.pressed = false;
+            self.send(CursorEvent::Release);
+        }
+    }
+}
+
+pub struct TrackpadPlugin;
+
+impl Plugin for TrackpadPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Trackpad { config: TrackpadConfig::load(), ..default() })
+            .add_systems(
+                Update,
+                (toggle_trackpad, drive_cursor.in_set(InteractionSet)).chain().after(update_hands_and_physics),
+            );
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, trackpad_ui);
+    }
+}
+
+// 主モニターの範囲。xdotool の座標はすべてのモニターをまとめた画面のものなので、モニターの位置も含める。
+// モニターが分からなければ主ウィンドウの大きさ
+fn primary_screen(
+    windows: &Query<(Entity, &Window), With<PrimaryWindow>>,
+    winit: Option<&WinitWindows>,
+) -> Option<ScreenArea> {
+    let (entity, window) = windows.get_single().ok()?;
+    let monitor = winit.and_then(|w| w.get_window(entity)).and_then(|w| w.primary_monitor());
+    Some(match monitor {
+        Some(monitor) => ScreenArea {
+            origin: [monitor.position().x, monitor.position().y],
+            size: [monitor.size().width, monitor.size().height],
+        },
+        None => ScreenArea { origin: [0, 0], size: [window.physical_width(), window.physical_height()] },
+    })
+}
+
+fn toggle_trackpad(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut trackpad: ResMut<Trackpad>,
+    mut health: ResMut<Health>,
+    windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+    winit: Option<NonSend<WinitWindows>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyX) {
+        return;
+    }
+    let trackpad = &mut *trackpad;
+    if !trackpad.active {
+        let config = &trackpad.config;
+        let available = *trackpad.available.get_or_insert_with(|| {
+            let missing = config.commands.missing_program();
+            if let Some(program) = missing {
+                let message = format!("{program} not found; install it or set commands in {TRACKPAD_PATH}");
+                health.failed("trackpad", message);
+            }
+            missing.is_none()
+        });
+        if !available {
+            warn!("trackpad mode is unavailable");
+            return;
+        }
+        trackpad.screen = trackpad.config.screen.or_else(|| primary_screen(&windows, winit.as_deref())).unwrap_or(ScreenArea { origin: [0, 0], size: [1, 1] });
+        info!(screen = ?trackpad.screen, "trackpad mode on");
+    } else {
+        info!("trackpad mode off");
+    }
+    trackpad.active = !trackpad.active;
+    trackpad.release();
+    trackpad.cursor = None;
+}
+
+fn drive_cursor(
+    mut trackpad: ResMut<Trackpad>,
+    points: Query<(&HandPoint, &Transform)>,
+    hand_states: Res<HandStates>,
+    profile: Res<ActiveProfile>,
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+) {
+    if !trackpad.active {
+        return;
+    }
+    let plane = trackpad.config.plane;
+    gizmos.rect(
+        plane.center(),
+        Quat::IDENTITY,
+        Vec2::new(plane.width, plane.height),
+        Color::srgba(0.6, 0.8, 1.0, 0.6),
+    );
+
+    // ローカルのクライアントの、設定した側の手だけで操作する
+    let side = if trackpad.config.hand.eq_ignore_ascii_case(HandSide::Left.label()) {
+        HandSide::Left
+    } else {
+        HandSide::Right
+    };
+    let tip = |id| {
+        points
+            .iter()
+            .find(|(p, _)| p.client == 0 && p.side == side && p.id == id)
+            .map(|(_, t)| t.translation)
+    };
+    let index = tip(INDEX_TIP).filter(|_| hand_states.get(side).is_some() && hand_states.is_confident((0, side)));
+    let Some((index, uv)) = index.and_then(|i| Some((i, plane.project(i)?))) else {
+        trackpad.cursor = None;
+        trackpad.release();
+        return;
+    };
+
+    let smoothed = match trackpad.cursor {
+        Some(previous) => low_pass(previous.extend(0.0), uv.extend(0.0), CURSOR_SMOOTHING, time.delta_seconds()).truncate(),
+        None => uv,
+    };
+    trackpad.cursor = Some(smoothed);
+    let screen = to_screen(smoothed, trackpad.screen);
+    if trackpad.last_sent != Some(screen) {
+        trackpad.last_sent = Some(screen);
+        trackpad.send(CursorEvent::Move(screen));
+    }
+
+    let pinching = tip(THUMB_TIP).is_some_and(|thumb| thumb.distance(index) < profile.pinch_distance);
+    if pinching && !trackpad.pressed {
+        trackpad.pressed = true;
+        trackpad.send(CursorEvent::Press);
+    } else if !pinching {
+        trackpad.release();
+    }
+
+    let on_plane = Vec3::new(index.x, index.y, plane.center[2]);
+    let color = if trackpad.pressed { Color::srgb(1.0, 0.4, 0.2) } else { Color::srgb(0.6, 0.9, 1.0) };
+    gizmos.line(index, on_plane, color);
+    gizmos.circle(on_plane, Dir3::Z, 0.3, color);
+}
+
+#[cfg(feature = "ui")]
+fn trackpad_ui(mut contexts: EguiContexts, trackpad: Res<Trackpad>) {
+    if !trackpad.active {
+        return;
+    }
+    egui::Window::new("Trackpad")
+        .anchor(egui::Align2::RIGHT_TOP, [-8.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            match trackpad.last_sent.filter(|_| trackpad.cursor.is_some()) {
+                Some([x, y]) => ui.label(format!("Cursor {x}, {y}{}", if trackpad.pressed { " (down)" } else { "" })),
+                None => ui.label("Move your index finger over the panel"),
+            };
+            ui.small("Pinch: click / X: quit");
+        });
+}
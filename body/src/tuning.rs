@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::core::tuning::GestureTuning;
+
+const TUNING_PATH: &str = "gestures.toml";
+// ファイルの更新を確かめる間隔
+const POLL_INTERVAL: f32 = 1.0;
+
+#[derive(Resource, Default)]
+pub struct Tuning(pub GestureTuning);
+
+#[derive(Resource)]
+struct TuningFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: f32,
+}
+
+impl TuningFile {
+    fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    // 読めなかったり値がおかしかったりしたら、理由を挙げて今の値のまま続ける
+    fn load(&self) -> Option<GestureTuning> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) => {
+                error!("failed to read {}: {e}", self.path.display());
+                return None;
+            }
+        };
+        match GestureTuning::parse(&text) {
+            Ok(tuning) => Some(tuning),
+            Err(errors) => {
+                error!("{} was not applied:", self.path.display());
+                for e in errors {
+                    error!("  {e}");
+                }
+                None
+            }
+        }
+    }
+}
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        let mut file = TuningFile { path: PathBuf::from(TUNING_PATH), modified: None, last_poll: 0.0 };
+        let tuning = if file.path.exists() {
+            file.modified = file.modified();
+            file.load().unwrap_or_default()
+        } else {
+            // 調整できる項目が分かるよう既定値を書き出しておく
+            let tuning = GestureTuning::default();
+            match toml::to_string_pretty(&tuning) {
+                Ok(text) => match fs::write(&file.path, text) {
+                    Ok(()) => info!("wrote default gesture tuning to {}", file.path.display()),
+                    Err(e) => warn!("failed to write {}: {e}", file.path.display()),
+                },
+                Err(e) => warn!("failed to serialize gesture tuning: {e}"),
+            }
+            file.modified = file.modified();
+            tuning
+        };
+        app.insert_resource(Tuning(tuning))
+            .insert_resource(file)
+            .add_systems(PreUpdate, reload_tuning);
+    }
+}
+
+fn reload_tuning(mut file: ResMut<TuningFile>, mut tuning: ResMut<Tuning>, time: Res<Time>) {
+    let now = time.elapsed_seconds();
+    if now - file.last_poll < POLL_INTERVAL {
+        return;
+    }
+    file.last_poll = now;
+    let modified = file.modified();
+    if modified.is_none() || modified == file.modified {
+        return;
+    }
+    file.modified = modified;
+    if let Some(loaded) = file.load()
+        && loaded != tuning.0
+    {
+        info!("reloaded {}", file.path.display());
+        tuning.0 = loaded;
+    }
+}
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::display::InteractionSet;
+use crate::palm::{PalmPoseSet, PalmPoses};
+
+const POSITION: Vec3 = Vec3::new(-8.0, -4.6, 5.0);
+const RADIUS: f32 = 4.0;
+const HALF_HEIGHT: f32 = 0.3;
+const DENSITY: f32 = 2.0;
+// 手のひらが天板のこの高さまでにあれば触れているとみなす
+const CONTACT_HEIGHT: f32 = 1.5;
+// 手のひらの速さに回転を合わせる強さ (1/秒)
+const COUPLING: f32 = 8.0;
+const MAX_ANGULAR_SPEED: f32 = 12.0;
+
+#[derive(Component)]
+pub struct Turntable {
+    pub radius: f32,
+    pub half_height: f32,
+}
+
+impl Turntable {
+    fn moment_of_inertia(&self) -> f32 {
+        let mass = DENSITY * std::f32::consts::PI * self.radius.powi(2) * self.half_height * 2.0;
+        0.5 * mass * self.radius.powi(2)
+    }
+}
+
+pub struct TurntablePlugin;
+
+impl Plugin for TurntablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (toggle_turntable, swipe_turntable.after(PalmPoseSet).in_set(InteractionSet), draw_turntable),
+        );
+    }
+}
+
+fn toggle_turntable(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    existing: Query<Entity, With<Turntable>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+    if let Ok(entity) = existing.get_single() {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cylinder::new(RADIUS, HALF_HEIGHT * 2.0)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.35, 0.3, 0.4),
+                perceptual_roughness: 0.6,
+                ..default()
+            }),
+            transform: Transform::from_translation(POSITION),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Collider::cylinder(HALF_HEIGHT, RADIUS),
+        ColliderMassProperties::Density(DENSITY),
+        // 縦軸まわりの回転だけを許す
+        LockedAxes::TRANSLATION_LOCKED | LockedAxes::ROTATION_LOCKED_X | LockedAxes::ROTATION_LOCKED_Z,
+        Damping { linear_damping: 0.0, angular_damping: 0.3 },
+        Friction::coefficient(1.5),
+        ExternalForce::default(),
+        Velocity::default(),
+        Turntable { radius: RADIUS, half_height: HALF_HEIGHT },
+    ));
+}
+
+// 天板をなでる手のひらの接線方向の速さに回転速度を近づける (摩擦のような結合)
+fn swipe_turntable(palms: Res<PalmPoses>, mut turntables: Query<(&Turntable, &Transform, &Velocity, &mut ExternalForce)>) {
+    let palms: Vec<(Vec3, Vec3)> = palms.poses.values().map(|p| (p.position(), p.linear_velocity)).collect();
+
+    for (turntable, transform, velocity, mut force) in turntables.iter_mut() {
+        let top = transform.translation.y + turntable.half_height;
+        let mut target_speeds = Vec::new();
+        for (palm, palm_velocity) in &palms {
+            let radial = Vec3::new(palm.x - transform.translation.x, 0.0, palm.z - transform.translation.z);
+            let r = radial.length();
+            if r > turntable.radius || r < 0.3 || !(top..top + CONTACT_HEIGHT).contains(&palm.y) {
+                continue;
+            }
+            let tangent = Vec3::Y.cross(radial / r);
+            target_speeds.push(palm_velocity.dot(tangent) / r);
+        }
+
+        force.torque = if target_speeds.is_empty() {
+            Vec3::ZERO
+        } else {
+            let target = (target_speeds.iter().sum::<f32>() / target_speeds.len() as f32)
+                .clamp(-MAX_ANGULAR_SPEED, MAX_ANGULAR_SPEED);
+            Vec3::Y * turntable.moment_of_inertia() * COUPLING * (target - velocity.angvel.y)
+        };
+    }
+}
+
+fn draw_turntable(turntables: Query<(&Turntable, &Transform)>, mut gizmos: Gizmos) {
+    // 回っていることが分かるように天板に目印を描く
+    for (turntable, transform) in turntables.iter() {
+        let top = transform.translation + Vec3::Y * (turntable.half_height + 0.02);
+        for i in 0..4 {
+            let angle = i as f32 * std::f32::consts::FRAC_PI_2;
+            let spoke = transform.rotation * Quat::from_rotation_y(angle) * Vec3::X * turntable.radius * 0.9;
+            gizmos.line(top, top + spoke, Color::srgb(1.0, 0.9, 0.4));
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::core::twist::TwistTracker;
+use crate::forearm::Forearms;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::HandKey;
+
+// 止めた手の揺れとみなすひねり (rad)
+const DEAD_BAND: f32 = 0.04;
+const RATE_SMOOTHING: f32 = 12.0;
+
+// 手ごとの前腕まわりのひねり。ドアノブや音量のつまみのような回す操作はこれを読む
+#[derive(Resource, Default)]
+pub struct PalmTwist {
+    trackers: HashMap<HandKey, TwistTracker>,
+}
+
+impl PalmTwist {
+    // 手が見え始めてから積み重ねた角度 (rad)。前腕の先から見て反時計回りが正
+    pub fn angle(&self, key: HandKey) -> Option<f32> {
+        self.trackers.get(&key).map(TwistTracker::angle)
+    }
+
+    pub fn rate(&self, key: HandKey) -> Option<f32> {
+        self.trackers.get(&key).map(TwistTracker::rate)
+    }
+}
+
+pub struct TwistPlugin;
+
+impl Plugin for TwistPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PalmTwist::default()).add_systems(Update, track_palm_twist.after(PalmPoseSet));
+    }
+}
+
+// 肘が届いていれば前腕の向き、届いていなければ手首から中指の付け根への向きを軸にする
+pub fn track_palm_twist(palms: Res<PalmPoses>, forearms: Res<Forearms>, mut twist: ResMut<PalmTwist>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+    twist.trackers.retain(|key, _| palms.poses.contains_key(key));
+    for (key, palm) in &palms.poses {
+        let axis = forearms.direction(*key).unwrap_or(palm.orientation() * Vec3::Y);
+        twist.trackers.entry(*key).or_default().update(palm.orientation(), axis, dt, DEAD_BAND, RATE_SMOOTHING);
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+#[cfg(feature = "ui")]
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier3d::prelude::*;
+
+use crate::core::versus::MatchState;
+use crate::floor::FLOOR_Y;
+use crate::overlap::hand_group;
+use crate::packet::{ClientId, ClientRegistry};
+use crate::sound::SurfaceMaterial;
+use crate::spawn::spawn_box_bundle;
+use crate::workspace::Workspaces;
+use crate::{hand_color, HandSide};
+
+const MATCH_TIME: f32 = 120.0;
+const BOXES_PER_PLAYER: usize = 4;
+const BOX_SIZE: f32 = 2.0;
+// 0 番は左 (x < 0) から右のゴールへ、1 番は右から左のゴールへ押し込む
+const START_X: f32 = 8.0;
+const GOAL_X: f32 = 14.0;
+const GOAL_HALF_WIDTH: f32 = 2.5;
+const GOAL_HALF_DEPTH: f32 = 6.0;
+const GOAL_HEIGHT: f32 = 6.0;
+const PLAYERS: [ClientId; 2] = [0, 1];
+
+// 対戦中の箱の持ち主。ほかのプレイヤーの力・掴み・手の当たり判定は受けない
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Team(pub ClientId);
+
+pub fn can_touch(team: Option<&Team>, client: ClientId) -> bool {
+    team.is_none_or(|t| t.0 == client)
+}
+
+#[derive(Component)]
+struct VersusProp;
+
+#[derive(Resource, Default)]
+pub struct Versus {
+    pub state: Option<MatchState>,
+}
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Versus::default())
+            .add_systems(Update, (toggle_versus, score_goals, tick_match).chain());
+        #[cfg(feature = "ui")]
+        app.add_systems(Update, versus_ui);
+    }
+}
+
+fn player_sign(player: usize) -> f32 {
+    if player == 0 { -1.0 } else { 1.0 }
+}
+
+// 自分の陣地 (スタート位置) と、押し込む先の相手のゴール
+fn start_position(player: usize, index: usize) -> Vec3 {
+    let z = (index as f32 - (BOXES_PER_PLAYER - 1) as f32 / 2.0) * BOX_SIZE * 1.8;
+    Vec3::new(player_sign(player) * START_X, FLOOR_Y + BOX_SIZE, z)
+}
+
+fn goal_center(player: usize) -> Vec3 {
+    Vec3::new(-player_sign(player) * GOAL_X, FLOOR_Y, 0.0)
+}
+
+fn in_goal(position: Vec3, goal: Vec3) -> bool {
+    (position.x - goal.x).abs() < GOAL_HALF_WIDTH
+        && (position.z - goal.z).abs() < GOAL_HALF_DEPTH
+        && position.y < goal.y + GOAL_HEIGHT
+}
+
+fn player_color(player: usize) -> Color {
+    hand_color(PLAYERS[player], HandSide::Right)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn toggle_versus(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut versus: ResMut<Versus>,
+    mut workspaces: ResMut<Workspaces>,
+    registry: Res<ClientRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    props: Query<Entity, With<VersusProp>>,
+) {
+    if !keys.just_pressed(KeyCode::KeyV) {
+        return;
+    }
+    for entity in props.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if versus.state.take().is_some() {
+        info!("versus mode ended");
+        return;
+    }
+    if registry.sources.len() < 2 {
+        warn!("versus mode needs two clients; only {} connected", registry.sources.len());
+    }
+    // 相手のゴールまで手が届くよう、陣地の分離は切る
+    if workspaces.isolate {
+        workspaces.isolate = false;
+        info!("workspace isolation turned off for versus mode");
+    }
+
+    for player in 0..PLAYERS.len() {
+        let color = player_color(player);
+        let goal = goal_center(player);
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Cuboid::new(GOAL_HALF_WIDTH * 2.0, 0.05, GOAL_HALF_DEPTH * 2.0)),
+                material: materials.add(StandardMaterial {
+                    base_color: color.with_alpha(0.35),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                }),
+                transform: Transform::from_translation(goal + Vec3::Y * 0.03),
+                ..default()
+            },
+            VersusProp,
+        ));
+        let material = materials.add(color);
+        // 相手の手の球とはぶつからない
+        let opponent = PLAYERS[1 - player];
+        let filters = Group::ALL - hand_group(opponent, HandSide::Right) - hand_group(opponent, HandSide::Left);
+        for index in 0..BOXES_PER_PLAYER {
+            commands
+                .spawn(spawn_box_bundle(&mut meshes, &mut materials, start_position(player, index), BOX_SIZE, SurfaceMaterial::Wood))
+                .insert((material.clone(), CollisionGroups::new(Group::ALL, filters), Team(PLAYERS[player]), VersusProp));
+        }
+    }
+    versus.state = Some(MatchState::new(MATCH_TIME));
+    info!("versus match started");
+}
+
+// 相手のゴールに入った箱は得点にして、自分の陣地に戻す
+fn score_goals(
+    mut versus: ResMut<Versus>,
+    mut boxes: Query<(&Team, &mut Transform, &mut Velocity)>,
+    mut index: Local<usize>,
+) {
+    let Some(state) = versus.state.as_mut() else {
+        return;
+    };
+    if state.finished {
+        return;
+    }
+    for (team, mut transform, mut velocity) in boxes.iter_mut() {
+        let Some(player) = PLAYERS.iter().position(|p| *p == team.0) else {
+            continue;
+        };
+        if !in_goal(transform.translation, goal_center(player)) {
+            continue;
+        }
+        state.score(player);
+        info!(player, scores = ?state.scores, "goal");
+        *index = (*index + 1) % BOXES_PER_PLAYER;
+        *transform = Transform::from_translation(start_position(player, *index) + Vec3::Y * BOX_SIZE);
+        velocity.linvel = Vec3::ZERO;
+        velocity.angvel = Vec3::ZERO;
+    }
+}
+
+fn tick_match(mut versus: ResMut<Versus>, time: Res<Time>) {
+    if let Some(state) = versus.state.as_mut()
+        && state.tick(time.delta_seconds())
+    {
+        info!(scores = ?state.scores, winner = ?state.winner(), "versus match over");
+    }
+}
+
+#[cfg(feature = "ui")]
+fn versus_ui(mut contexts: EguiContexts, mut versus: ResMut<Versus>) {
+    let Some(state) = versus.state.as_mut() else {
+        return;
+    };
+    let label = |player: usize| {
+        let [r, g, b, _] = player_color(player).to_srgba().to_u8_array();
+        egui::RichText::new(format!("P{} {}", player + 1, state.scores[player]))
+            .size(28.0)
+            .color(egui::Color32::from_rgb(r, g, b))
+    };
+    egui::Window::new("Versus")
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 8.0])
+        .resizable(false)
+        .collapsible(false)
+        .title_bar(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(label(0));
+                ui.label(egui::RichText::new(format!("{:.0}", state.remaining.ceil())).size(28.0));
+                ui.label(label(1));
+            });
+            if !state.finished {
+                ui.small("Push your boxes into the other player's goal / V: quit");
+            }
+        });
+    if !state.finished {
+        return;
+    }
+    let mut rematch = false;
+    egui::Window::new("Match over")
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .resizable(false)
+        .collapsible(false)
+        .show(contexts.ctx_mut(), |ui| {
+            let text = match state.winner() {
+                Some(player) => format!("Player {} wins!", player + 1),
+                None => "Draw!".to_string(),
+            };
+            ui.label(egui::RichText::new(text).size(48.0));
+            ui.label(format!("{} - {}", state.scores[0], state.scores[1]));
+            rematch = ui.button("Rematch").clicked();
+            ui.small("V: quit");
+        });
+    if rematch {
+        *state = MatchState::new(MATCH_TIME);
+    }
+}
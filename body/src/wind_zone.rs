@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashMap;
+
+use crate::console::{ConsoleExt, ConsoleResult};
+use crate::core::gesture::{is_pointing, Gesture};
+use crate::core::wind_zone::{WindZone, ZoneBrush};
+use crate::display::InteractionSet;
+use crate::packet::Landmark;
+use crate::palm::{PalmPoseSet, PalmPoses};
+use crate::{HandKey, HandPoint, HandSide, HandStates};
+
+// 手のひらの通り道をこれだけ太らせて領域にする
+const BRUSH_RADIUS: f32 = 2.0;
+// 領域の中の物体に与える加速度
+const WIND_ACCELERATION: f32 = 12.0;
+// 形が一瞬崩れても描き終わりにしない
+const STROKE_GRACE: f32 = 0.25;
+
+// 描いて置いた風の領域。消すまで中の物体を流し続ける
+#[derive(Component, Debug, Clone, Copy)]
+pub struct WindVolume(pub WindZone);
+
+pub fn wind_volume_bundle(zone: WindZone) -> impl Bundle {
+    (SpatialBundle::from_transform(Transform::from_translation(zone.center())), WindVolume(zone))
+}
+
+// 描いている手ごとの筆と、最後に描いた時刻
+#[derive(Resource, Default)]
+struct ZonePainter {
+    brushes: HashMap<HandKey, (ZoneBrush, f32)>,
+}
+
+pub struct WindZonePlugin;
+
+impl Plugin for WindZonePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ZonePainter::default())
+            .add_console_command("wind", "wind clear (remove all painted wind zones)", wind_command)
+            .add_systems(
+                Update,
+                ((paint_wind_zones.after(PalmPoseSet), blow_wind_zones).in_set(InteractionSet), draw_wind_zones)
+                    .chain(),
+            );
+    }
+}
+
+// もう片方の手で指さしをしている間、開いた手のひらを動かした範囲に手のひらの向きの風を描く。
+// 指さしをしたまま握った手を領域に入れると、その領域を消す
+fn paint_wind_zones(
+    mut commands: Commands,
+    mut painter: ResMut<ZonePainter>,
+    hand_states: Res<HandStates>,
+    palms: Res<PalmPoses>,
+    points: Query<(&HandPoint, &Transform)>,
+    zones: Query<(Entity, &WindVolume)>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed_seconds();
+    let mut hands: HashMap<HandKey, Vec<Landmark>> = HashMap::new();
+    for (point, transform) in points.iter() {
+        let Vec3 { x, y, z } = transform.translation;
+        hands.entry((point.client, point.side)).or_default().push(Landmark { id: point.id, x, y, z });
+    }
+    let pointing = |key: HandKey| hand_states.is_confident(key) && hands.get(&key).is_some_and(|l| is_pointing(l));
+
+    for (&key, hand) in &hand_states.hands {
+        let other = match key.1 {
+            HandSide::Right => HandSide::Left,
+            HandSide::Left => HandSide::Right,
+        };
+        let Some(palm) = palms.poses.get(&key) else {
+            continue;
+        };
+        if !pointing((key.0, other)) || !hand_states.is_confident(key) {
+            continue;
+        }
+        match hand.gesture {
+            Gesture::Open => {
+                let (brush, last) = painter.brushes.entry(key).or_default();
+                brush.stroke(palm.position(), palm.orientation() * Vec3::Z);
+                *last = now;
+            }
+            Gesture::Fist => {
+                for (entity, volume) in zones.iter().filter(|(_, v)| v.0.contains(palm.position())) {
+                    info!(center = ?volume.0.center(), "wind zone erased");
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    painter.brushes.retain(|_, (brush, last)| {
+        if now - *last <= STROKE_GRACE {
+            return true;
+        }
+        if let Some(zone) = brush.finish(BRUSH_RADIUS, WIND_ACCELERATION) {
+            info!(center = ?zone.center(), size = ?zone.size(), wind = ?zone.wind(), "wind zone painted");
+            commands.spawn(wind_volume_bundle(zone));
+        }
+        false
+    });
+}
+
+// 物体の重さによらず同じように流す
+fn blow_wind_zones(
+    zones: Query<&WindVolume>,
+    mut bodies: Query<(&Transform, &RigidBody, &mut Velocity)>,
+    time: Res<Time>,
+) {
+    if zones.is_empty() {
+        return;
+    }
+    let dt = time.delta_seconds();
+    for (transform, body, mut velocity) in bodies.iter_mut() {
+        if *body != RigidBody::Dynamic {
+            continue;
+        }
+        let wind: Vec3 = zones.iter().filter(|z| z.0.contains(transform.translation)).map(|z| z.0.wind()).sum();
+        if wind != Vec3::ZERO {
+            velocity.linvel += wind * dt;
+        }
+    }
+}
+
+// 置いた領域の枠と風向き、描いている途中の範囲
+fn draw_wind_zones(zones: Query<&WindVolume>, painter: Res<ZonePainter>, mut gizmos: Gizmos) {
+    let color = Color::srgba(0.5, 0.9, 1.0, 0.6);
+    for volume in zones.iter() {
+        let zone = volume.0;
+        gizmos.cuboid(Transform::from_translation(zone.center()).with_scale(zone.size()), color);
+        let direction = zone.wind().normalize_or_zero();
+        let reach = (zone.size() * direction.abs()).max_element() * 0.4;
+        gizmos.arrow(zone.center() - direction * reach, zone.center() + direction * reach, color);
+    }
+    for (brush, _) in painter.brushes.values() {
+        if let Some((min, max)) = brush.bounds(BRUSH_RADIUS) {
+            gizmos.cuboid(Transform::from_translation((min + max) / 2.0).with_scale(max - min), Color::WHITE);
+        }
+    }
+}
+
+fn wind_command(
+    In(args): In<Vec<String>>,
+    mut commands: Commands,
+    zones: Query<Entity, With<WindVolume>>,
+) -> ConsoleResult {
+    if args.first().map(String::as_str) != Some("clear") {
+        return Err("usage: wind clear".to_string());
+    }
+    let count = zones.iter().count();
+    for entity in zones.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    Ok(format!("removed {count} wind zones"))
+}
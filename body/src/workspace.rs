@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::core::mapping::{Bounds, Region, LEGACY_REGION};
+use crate::packet::{ClientId, ClientRegistry};
+use crate::{update_hands_and_physics, HandPoint, HandStates};
+
+const WORKSPACES_PATH: &str = "workspaces.json";
+const FLOOR_Y: f32 = -5.0;
+const FLOOR_HALF_EXTENT: f32 = 15.0;
+const BOUNDARY_HEIGHT: f32 = 8.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct WorkspaceConfig {
+    #[serde(default)]
+    isolate: bool,
+    #[serde(default)]
+    regions: Vec<Region>,
+    #[serde(default)]
+    bounds: Bounds,
+}
+
+#[derive(Resource)]
+pub struct Workspaces {
+    // 他の領域の物体に手や力が及ばないようにする
+    pub isolate: bool,
+    pub show_boundaries: bool,
+    // workspaces.json で指定された領域 (空なら床を等分する)
+    pub configured: Vec<Region>,
+    // 手の位置はこの範囲に収める
+    pub bounds: Bounds,
+    client_count: usize,
+}
+
+impl Default for Workspaces {
+    fn default() -> Self {
+        Self {
+            isolate: false,
+            show_boundaries: true,
+            configured: Vec::new(),
+            bounds: Bounds::default(),
+            client_count: 1,
+        }
+    }
+}
+
+impl Workspaces {
+    fn load() -> Self {
+        let config = match fs::read_to_string(WORKSPACES_PATH) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+                warn!("failed to parse {WORKSPACES_PATH}: {e}");
+                WorkspaceConfig::default()
+            }),
+            Err(_) => WorkspaceConfig::default(),
+        };
+        Self {
+            isolate: config.isolate,
+            configured: config.regions,
+            bounds: config.bounds,
+            ..default()
+        }
+    }
+
+    pub fn is_partitioned(&self) -> bool {
+        self.client_count > 1
+    }
+
+    pub fn is_isolated(&self) -> bool {
+        self.isolate && self.is_partitioned()
+    }
+
+    pub fn region(&self, client: ClientId) -> Region {
+        if let Some(region) = self.configured.get(client as usize) {
+            return *region;
+        }
+        if !self.is_partitioned() {
+            return LEGACY_REGION;
+        }
+        let width = FLOOR_HALF_EXTENT * 2.0 / self.client_count as f32;
+        let min_x = -FLOOR_HALF_EXTENT + width * client as f32;
+        Region { min_x, max_x: min_x + width }
+    }
+}
+
+pub struct WorkspacePlugin;
+
+impl Plugin for WorkspacePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Workspaces::load())
+            .add_systems(Update, (track_client_count, workspace_keys, draw_boundaries))
+            .add_systems(Update, suspend_out_of_bounds_hands.after(update_hands_and_physics));
+    }
+}
+
+fn track_client_count(registry: Res<ClientRegistry>, mut workspaces: ResMut<Workspaces>) {
+    let count = registry.count();
+    if workspaces.client_count != count {
+        workspaces.client_count = count;
+    }
+}
+
+fn workspace_keys(keys: Res<ButtonInput<KeyCode>>, mut workspaces: ResMut<Workspaces>) {
+    if keys.just_pressed(KeyCode::KeyI) {
+        workspaces.isolate = !workspaces.isolate;
+        info!("workspace isolation: {}", workspaces.isolate);
+    }
+    if keys.just_pressed(KeyCode::F4) {
+        workspaces.show_boundaries = !workspaces.show_boundaries;
+    }
+}
+
+fn draw_boundaries(workspaces: Res<Workspaces>, mut gizmos: Gizmos) {
+    if !workspaces.show_boundaries || !workspaces.is_partitioned() {
+        return;
+    }
+    // 分離中は赤、領域が共有されているときは白で境界を描く
+    let color = if workspaces.is_isolated() {
+        Color::srgba(1.0, 0.3, 0.3, 0.8)
+    } else {
+        Color::srgba(1.0, 1.0, 1.0, 0.4)
+    };
+    for client in 0..workspaces.client_count {
+        let region = workspaces.region(client as ClientId);
+        for x in [region.min_x, region.max_x] {
+            let near = Vec3::new(x, FLOOR_Y, FLOOR_HALF_EXTENT);
+            let far = Vec3::new(x, FLOOR_Y, -FLOOR_HALF_EXTENT);
+            gizmos.line(near, far, color);
+            gizmos.line(far, far + Vec3::Y * BOUNDARY_HEIGHT, color);
+            gizmos.line(near, near + Vec3::Y * BOUNDARY_HEIGHT, color);
+        }
+    }
+}
+
+// 範囲外の手は当たり判定を外し、押し戻した位置で物体を押しのけないようにする。速すぎる手も同じく外す
+fn suspend_out_of_bounds_hands(
+    mut commands: Commands,
+    hand_states: Res<HandStates>,
+    workspaces: Res<Workspaces>,
+    points: Query<(Entity, &HandPoint, Has<ColliderDisabled>)>,
+    mut gizmos: Gizmos,
+) {
+    for (entity, point, disabled) in points.iter() {
+        let suspended = hand_states.is_suspended((point.client, point.side));
+        if suspended && !disabled {
+            commands.entity(entity).insert(ColliderDisabled);
+        } else if !suspended && disabled {
+            commands.entity(entity).remove::<ColliderDisabled>();
+        }
+    }
+    if !hand_states.out_of_bounds.is_empty() {
+        let min = Vec3::from_array(workspaces.bounds.min);
+        let max = Vec3::from_array(workspaces.bounds.max);
+        let transform = Transform::from_translation((min + max) / 2.0).with_scale(max - min);
+        gizmos.cuboid(transform, Color::srgba(1.0, 0.3, 0.3, 0.6));
+    }
+}
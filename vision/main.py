@@ -1,8 +1,14 @@
+import argparse
 import cv2
+import hashlib
+import hmac
 import mediapipe as mp
+import platform
 import socket
 import json
 import math
+import sys
+import time
 
 mp_hands = mp.solutions.hands
 hands = mp_hands.Hands(
@@ -13,9 +19,89 @@ hands = mp_hands.Hands(
 mp_drawing = mp.solutions.drawing_utils
 
 sock = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+# 最初に送る前に受け取ろうとしても Windows で失敗しないよう、先にポートを決めておく
+sock.bind(("", 0))
+# 受信側からの間引きの希望を、撮影を止めずに読む
+sock.setblocking(False)
 server_address = ('127.0.0.1', 5005)
 
-cap = cv2.VideoCapture(0)
+# 受信側が希望する 1 秒あたりのパケット数 (0 なら制限なし) と z を送るかどうか
+send_rate = 0.0
+include_z = True
+last_sent_at = 0.0
+# 受信側の設定画面で選ばれたカメラ番号。変わったときだけ切り替える (c キーで選んだものを上書きし続けない)
+requested_camera = None
+pending_camera = None
+
+# 探すカメラ番号の上限
+MAX_CAMERAS = 10
+
+
+def list_cameras():
+    found = []
+    for index in range(MAX_CAMERAS):
+        probe = cv2.VideoCapture(index)
+        if probe.isOpened():
+            width = int(probe.get(cv2.CAP_PROP_FRAME_WIDTH))
+            height = int(probe.get(cv2.CAP_PROP_FRAME_HEIGHT))
+            found.append((index, f"{width}x{height}"))
+        probe.release()
+    return found
+
+
+def permission_hint():
+    system = platform.system()
+    if system == "Darwin":
+        return "Allow camera access for your terminal in System Settings > Privacy & Security > Camera, then restart it."
+    if system == "Windows":
+        return "Turn on 'Let desktop apps access your camera' in Settings > Privacy & security > Camera."
+    return "Check that /dev/video* exists and that your user is in the 'video' group."
+
+
+def open_camera(index):
+    capture = cv2.VideoCapture(index)
+    if not capture.isOpened():
+        print(f"Could not open camera {index}. {permission_hint()}", file=sys.stderr)
+        return None
+    return capture
+
+
+parser = argparse.ArgumentParser(description="Send MediaPipe hand landmarks to the body over UDP")
+parser.add_argument("--camera", type=int, default=0, help="camera index (see --list-cameras)")
+parser.add_argument("--list-cameras", action="store_true", help="print available cameras and exit")
+parser.add_argument("--port", type=int, default=5005,
+                    help="body port to send to (one-hand trackers send to a port given in the body's --split-inputs)")
+parser.add_argument("--secret-file",
+                    help="sign packets with the shared secret in this file (match the body's --secret; "
+                         "the body rejects packets whose clock is more than 30 s off)")
+args = parser.parse_args()
+server_address = (server_address[0], args.port)
+
+# 受信側と同じく前後の空白と改行は除く
+secret = None
+# 最後に署名した通し番号。時計が戻っても増やし続ける
+sequence = 0
+if args.secret_file:
+    with open(args.secret_file, encoding="utf-8") as f:
+        secret = f.read().strip().encode("utf-8")
+    if not secret:
+        print(f"Secret file {args.secret_file} is empty", file=sys.stderr)
+        sys.exit(1)
+
+if args.list_cameras:
+    cameras = list_cameras()
+    if not cameras:
+        print(f"No cameras found. {permission_hint()}")
+    for index, size in cameras:
+        print(f"{index}: {size}")
+    sys.exit(0)
+
+cap = open_camera(args.camera)
+if cap is None:
+    sys.exit(1)
+camera_index = args.camera
+# 切り替えるときに初めて探す (開いたままのカメラは探せない環境がある)
+cameras = None
 
 pinch_state = {'Right': False, 'Left': False}
 prev_middle_y = {'Right': 0.0, 'Left': 0.0}
@@ -29,6 +115,7 @@ def get_gesture(landmarks):
     finger_mcps = [5, 9, 13, 17]
     
     folded_count = 0
+    claw_count = 0
     for tip_idx, mcp_idx in zip(finger_tips, finger_mcps):
         tip = landmarks[tip_idx]
         mcp = landmarks[mcp_idx]
@@ -40,18 +127,75 @@ def get_gesture(landmarks):
         # 指先の方が手首に近い＝折れ曲がっている
         if dist_tip < dist_mcp:
             folded_count += 1
+        else:
+            # 伸びた指の第二・第三関節だけが曲がっている＝鷲づかみ
+            joints = [landmarks[mcp_idx + k] for k in range(4)]
+            along = sum(math.dist((a.x, a.y, a.z), (b.x, b.y, b.z)) for a, b in zip(joints, joints[1:]))
+            straight = math.dist((mcp.x, mcp.y, mcp.z), (tip.x, tip.y, tip.z))
+            if along > 0 and straight / along < 0.75:
+                claw_count += 1
             
     if folded_count >= 3:
         return "Fist"
+    elif claw_count >= 3:
+        return "Claw"
     elif folded_count == 0:
         return "Open"
     else:
         return "Neutral"
 
+def read_thinning_requests():
+    global send_rate, include_z, requested_camera, pending_camera
+    while True:
+        try:
+            data, addr = sock.recvfrom(1024)
+        except OSError:
+            # まだ何も届いていない (BlockingIOError) か、受信側が閉じている (ConnectionResetError)
+            return
+        # 送り先の受信側以外からの希望は聞かない
+        if addr != server_address:
+            continue
+        try:
+            request = json.loads(data)
+            rate = float(request['rate'])
+            z = bool(request['include_z'])
+            camera = request.get('camera')
+            camera = None if camera is None else int(camera)
+        except (ValueError, KeyError, TypeError, AttributeError):
+            continue
+        if (rate, z) != (send_rate, include_z):
+            print(f"Receiver asked for {rate or 'unlimited'} packets/s, z: {z}")
+        send_rate, include_z = rate, z
+        if camera is not None and camera != requested_camera:
+            print(f"Receiver asked for camera {camera}")
+            pending_camera = camera
+        requested_camera = camera
+
+
+def switch_camera(index):
+    global cap, camera_index
+    cap.release()
+    next_cap = open_camera(index)
+    if next_cap is None:
+        next_cap = open_camera(camera_index)
+    else:
+        camera_index = index
+    cap = next_cap
+    return cap is not None
+
 while cap.isOpened():
+    read_thinning_requests()
+    if pending_camera is not None:
+        index, pending_camera = pending_camera, None
+        if index != camera_index:
+            if not switch_camera(index):
+                break
+            print(f"Switched to camera {camera_index}")
     success, image = cap.read()
     if not success:
         continue
+    # 受信側の補間はこの撮影時刻を基準に進める
+    captured_at = time.time()
 
     image = cv2.flip(image, 1)
     image_rgb = cv2.cvtColor(image, cv2.COLOR_BGR2RGB)
@@ -67,12 +211,14 @@ while cap.isOpened():
             
             landmark_list = []
             for id, lm in enumerate(hand_landmarks.landmark):
-                landmark_list.append({
+                point = {
                     'id': id,
                     'x': lm.x,
-                    'y': lm.y,
-                    'z': lm.z
-                })
+                    'y': lm.y
+                }
+                if include_z:
+                    point['z'] = lm.z
+                landmark_list.append(point)
             
             gesture = get_gesture(hand_landmarks.landmark)
 
@@ -109,16 +255,42 @@ while cap.isOpened():
                 mp_hands.HAND_CONNECTIONS
             )
 
-    if hand_data_list:
+    # 間引くときも指パッチンのフレームは落とさない
+    due = send_rate <= 0 or snap_detected or captured_at - last_sent_at >= 1.0 / send_rate
+    if hand_data_list and due:
+        last_sent_at = captured_at
         data = json.dumps({
             'hands': hand_data_list,
-            'snap': snap_detected 
+            'snap': snap_detected,
+            'timestamp': captured_at
         })
-        sock.sendto(data.encode('utf-8'), server_address)
+        payload = data.encode('utf-8')
+        # 署名は HMAC-SHA256 の 16 進表記を先頭に付ける。受信側が古いものや二度目のものを捨てられるよう、
+        # 送った時刻 (マイクロ秒) を通し番号として本文の前に付け、署名に含める
+        if secret is not None:
+            sequence = max(sequence + 1, time.time_ns() // 1000)
+            signed = f"{sequence:016x}".encode('ascii') + payload
+            payload = hmac.new(secret, signed, hashlib.sha256).hexdigest().encode('ascii') + signed
+        try:
+            sock.sendto(payload, server_address)
+        except BlockingIOError:
+            pass
 
+    cv2.putText(image, f"camera {camera_index} (c: next)", (10, 24), cv2.FONT_HERSHEY_SIMPLEX, 0.6, (255, 255, 255), 1)
     cv2.imshow('MasterHand Vision', image)
-    if cv2.waitKey(5) & 0xFF == 27:
+    key = cv2.waitKey(5) & 0xFF
+    if key == 27:
         break
+    # プレビューを見ながら次のカメラに切り替える
+    if key == ord('c'):
+        if cameras is None:
+            cap.release()
+            cameras = list_cameras()
+        indices = [index for index, _ in cameras] or [camera_index]
+        position = indices.index(camera_index) if camera_index in indices else -1
+        if not switch_camera(indices[(position + 1) % len(indices)]):
+            break
+        print(f"Switched to camera {camera_index}")
 
 cap.release()
 cv2.destroyAllWindows()